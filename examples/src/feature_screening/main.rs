@@ -0,0 +1,21 @@
+use std::error::Error;
+
+use log::*;
+
+use rune_data::load_wine_quality;
+use rune_metrics::correlation::{correlation_matrix, Correlation};
+use rune_metrics::feature_scoring::{f_classif, mutual_info_classif};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let (x, y) = load_wine_quality();
+    let quality = y.mapv(|quality| quality.round() as i64);
+
+    info!("f_classif: {}", f_classif(x.view(), quality.view()));
+    info!("mutual_info_classif: {}", mutual_info_classif(x.view(), quality.view(), 10));
+    info!("pearson: {}", correlation_matrix(x.view(), Correlation::Pearson));
+    info!("spearman: {}", correlation_matrix(x.view(), Correlation::Spearman));
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use std::error::Error;
+
+use log::*;
+
+use rune_core::estimator::{Fit, Score};
+use rune_core::random::RngSource;
+use rune_data::load_wine_quality;
+use rune_ensemble::gradient_boosting_regressor::{GradientBoostingRegressor, RegressionLoss};
+use rune_metrics::regression::root_mean_squared_error::root_mean_squared_error;
+use rune_model_selection::splitting::train_test_split::train_test_split;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let (x, y) = load_wine_quality();
+
+    let (x_train, x_test, y_train, y_test) = train_test_split(x.view(), y.view(), 0.8, RngSource::Seed(42));
+
+    for &loss in &[RegressionLoss::Squared, RegressionLoss::Absolute, RegressionLoss::Huber { alpha: 0.9 }] {
+        let regressor = GradientBoostingRegressor::new(loss, 200, 0.1)
+            .subsample(0.8, RngSource::Seed(42));
+
+        let model = regressor.fit(x_train.view(), y_train.view())?;
+
+        for (stage, y_pred) in model.staged_predict(x_test.view()).enumerate().step_by(50) {
+            info!("{:?} after {} trees: test rmse {:.4}", loss, stage + 1, root_mean_squared_error(y_test.view(), y_pred.view(), None));
+        }
+        info!("{:?}: test r2 {:.4}", loss, model.score(x_test.view(), y_test.view()));
+    }
+
+    Ok(())
+}
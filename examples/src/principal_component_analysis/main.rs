@@ -1,13 +1,12 @@
 use log::*;
-use ndarray::{s, Array1, ArrayView1, Axis};
+use ndarray::{Array1, ArrayView1, Axis};
 
-use rune_data::{read_banknote_authentication_dataset, read_iris_dataset};
+use rune_data::load_iris;
 use rune_metrics::confusion_matrix::ConfusionMatrix;
 use rune_model_selection::splitting::train_test_split::train_test_split;
 use rune_tree::DecisionTreeClassifier;
 use rune_tree::feature_selector::greedy_feature_selector::GreedyFeatureSelector;
 use rune_tree::measures::entropy::EntropySelectionMeasure;
-use ndarray_type_conversion::MapTypeExt;
 use itertools::Itertools;
 use rune_decomposition::principal_component_analysis::PrincipalComponentAnalysis;
 use rune_preprocessing::standard_scaler::StandardScaler;
@@ -16,22 +15,17 @@ use std::error::Error;
 fn main() -> Result<(), Box<dyn Error>>{
     env_logger::init();
 
-    let df = read_iris_dataset().unwrap();
-
-    // info!("df.map_type::<f64>(): {:?}", df.map_type::<f64>());
-
-    let x = df.slice(s![.., ..4]).map_type::<f64>();
-    let y: Array1<String> = df.slice(s![.., 4]).map_type::<String>();
+    let (x, y) = load_iris();
 
     let scaler = StandardScaler::new();
 
-    let x = scaler.fit(x.view()).transform(x.view());
+    let x = scaler.fit(x.view(), y.view())?.transform(x.view());
     info!("transformed: {:?}", x);
     info!("transformed mean: {:?}", x.mean_axis(Axis(0)));
     info!("transformed std: {:?}", x.std_axis(Axis(0), 1.));
 
     let pca = PrincipalComponentAnalysis::new(1);
-    let x = pca.fit(x.view())?.transform(x.view());
+    let x = pca.fit(x.view(), y.view())?.transform(x.view());
 
     info!("x: {:?}", x);
     info!("x mean: {:?}", x.mean_axis(Axis(0)));
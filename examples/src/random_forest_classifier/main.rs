@@ -1,17 +1,13 @@
 use log::*;
-use ndarray::{s, Array1, ArrayView1};
+use ndarray::{Array1, ArrayView1};
 
-use rune_data::{read_iris_dataset};
-use ndarray_type_conversion::MapTypeExt;
+use rune_data::load_iris;
 use itertools::Itertools;
 
 fn main() {
     env_logger::init();
 
-    let df = read_iris_dataset().unwrap();
-
-    let x = df.slice(s![.., ..4]).map_type::<f64>();
-    let y: Array1<String> = df.slice(s![.., 4]).map_type::<String>();
+    let (x, y) = load_iris();
 
     info!("x: {:?}", x);
     info!("y: {:?}", y);
@@ -24,7 +20,7 @@ fn main() {
 
     // let mut cm = ConfusionMatrix::from_labels(y.view());
 
-    // let (x_train, x_test, y_train, y_test) = train_test_split(x.view(), y.view(), 0.8);
+    // let (x_train, x_test, y_train, y_test) = train_test_split(x.view(), y.view(), 0.8, RngSource::Entropy);
     //
     // info!("x_train: {:?}", x_train);
     // info!("x_test: {:?}", x_test);
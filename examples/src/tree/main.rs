@@ -1,25 +1,24 @@
 use log::*;
-use ndarray::{Array, array, Array1, Array2, azip, s};
+use ndarray::{Array, array, Array1, Array2, azip};
 
-use rune_data::read_banknote_authentication_dataset;
+use rune_data::load_banknote_authentication;
 use rune_metrics::confusion_matrix::ConfusionMatrix;
 use rune_model_selection::splitting::train_test_split::train_test_split;
 use rune_tree::DecisionTreeClassifier;
 use rune_tree::feature_selector::greedy_feature_selector::GreedyFeatureSelector;
 use rune_tree::measures::entropy::EntropySelectionMeasure;
 use ndarray_type_conversion::MapTypeExt;
+use rune_core::estimator::Predictor;
+use rune_core::random::RngSource;
 
 fn main() {
     env_logger::init();
 
-    let df = read_banknote_authentication_dataset().unwrap();
-
-    let x = df.slice(s![.., ..4]);
-    let y = df.slice(s![.., 4]).map(|x| if *x == 1. { true } else { false });
+    let (x, y) = load_banknote_authentication();
 
     let mut cm = ConfusionMatrix::from_labels(y.view());
 
-    let (x_train, x_test, y_train, y_test) = train_test_split(x.view(), y.view(), 0.8);
+    let (x_train, x_test, y_train, y_test) = train_test_split(x.view(), y.view(), 0.8, RngSource::Entropy);
 
     info!("x_train: {:?}", x_train);
     info!("x_test: {:?}", x_test);
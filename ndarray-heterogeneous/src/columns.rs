@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fmt;
+
+use chrono::NaiveDateTime;
+use ndarray::{Array1, ArrayBase, Data, Ix2};
+
+use crate::Scalar;
+
+/// The type of a `Scalar`, or the common type of a column of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DType {
+    I64,
+    F64,
+    BOOL,
+    STRING,
+    DATETIME,
+}
+
+impl DType {
+    /// The narrowest type values of both types convert to: integers widen to floats and any
+    /// other mix can only be represented as strings.
+    fn unify(self, other: DType) -> DType {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (DType::I64, DType::F64) | (DType::F64, DType::I64) => DType::F64,
+            _ => DType::STRING,
+        }
+    }
+}
+
+/// A value that can't be converted to the type a column was extracted as.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionError {
+    pub value: Scalar,
+    pub target: &'static str,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cannot convert {:?} to {}", self.value, self.target)
+    }
+}
+
+impl Error for ConversionError {}
+
+impl Scalar {
+    pub fn dtype(&self) -> DType {
+        match self {
+            Scalar::I64(_) => DType::I64,
+            Scalar::F64(_) => DType::F64,
+            Scalar::BOOL(_) => DType::BOOL,
+            Scalar::STRING(_) => DType::STRING,
+            Scalar::DATETIME(_) => DType::DATETIME,
+        }
+    }
+
+    /// Numbers as they are, booleans as 1 or 0, datetimes as seconds since the Unix epoch and
+    /// strings parsed, with empty strings as NaN.
+    pub fn to_f64(&self) -> Option<f64> {
+        match self {
+            Scalar::STRING(value) if value.is_empty() => Some(f64::NAN),
+            Scalar::STRING(value) => value.parse().ok(),
+            value => Some(f64::from(value.clone())),
+        }
+    }
+
+    /// Integers, floats without a fractional part, booleans as 1 or 0 and parsed strings.
+    pub fn to_i64(&self) -> Option<i64> {
+        match self {
+            Scalar::I64(value) => Some(*value),
+            Scalar::F64(value) if value.fract() == 0. && value.abs() <= i64::MAX as f64 => Some(*value as i64),
+            Scalar::BOOL(value) => Some(*value as i64),
+            Scalar::STRING(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Booleans, the numbers 0 and 1 and the strings `true` and `false`.
+    pub fn to_bool(&self) -> Option<bool> {
+        match self {
+            Scalar::BOOL(value) => Some(*value),
+            Scalar::I64(0) => Some(false),
+            Scalar::I64(1) => Some(true),
+            Scalar::F64(value) if *value == 0. || *value == 1. => Some(*value == 1.),
+            Scalar::STRING(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Column-wise inspection and typed extraction of a table of `Scalar`s, e.g. splitting the
+/// measurements from the species of iris:
+///
+/// `let y = dataset.column_as_string(4);`
+pub trait ScalarColumnsExt {
+    /// The common type of every column, see `DType`. Empty columns are `F64`.
+    fn dtypes(&self) -> Vec<DType>;
+
+    fn column_as_f64(&self, index: usize) -> Result<Array1<f64>, ConversionError>;
+
+    fn column_as_i64(&self, index: usize) -> Result<Array1<i64>, ConversionError>;
+
+    fn column_as_bool(&self, index: usize) -> Result<Array1<bool>, ConversionError>;
+
+    fn column_as_datetime(&self, index: usize) -> Result<Array1<NaiveDateTime>, ConversionError>;
+
+    /// Every value has a string form, so this can't fail.
+    fn column_as_string(&self, index: usize) -> Array1<String>;
+}
+
+fn convert_column<S, T, F>(x: &ArrayBase<S, Ix2>, index: usize, target: &'static str, convert: F) -> Result<Array1<T>, ConversionError>
+    where
+        S: Data<Elem=Scalar>,
+        F: Fn(&Scalar) -> Option<T> {
+    x.column(index).iter()
+        .map(|value| convert(value).ok_or_else(|| ConversionError { value: value.clone(), target }))
+        .collect()
+}
+
+impl<S: Data<Elem=Scalar>> ScalarColumnsExt for ArrayBase<S, Ix2> {
+    fn dtypes(&self) -> Vec<DType> {
+        self.gencolumns().into_iter()
+            .map(|column| {
+                let mut dtypes = column.iter().map(Scalar::dtype);
+                let first = dtypes.next().unwrap_or(DType::F64);
+                dtypes.fold(first, DType::unify)
+            })
+            .collect()
+    }
+
+    fn column_as_f64(&self, index: usize) -> Result<Array1<f64>, ConversionError> {
+        convert_column(self, index, "f64", Scalar::to_f64)
+    }
+
+    fn column_as_i64(&self, index: usize) -> Result<Array1<i64>, ConversionError> {
+        convert_column(self, index, "i64", Scalar::to_i64)
+    }
+
+    fn column_as_bool(&self, index: usize) -> Result<Array1<bool>, ConversionError> {
+        convert_column(self, index, "bool", Scalar::to_bool)
+    }
+
+    fn column_as_datetime(&self, index: usize) -> Result<Array1<NaiveDateTime>, ConversionError> {
+        convert_column(self, index, "datetime", Scalar::as_datetime)
+    }
+
+    fn column_as_string(&self, index: usize) -> Array1<String> {
+        self.column(index).mapv(String::from)
+    }
+}
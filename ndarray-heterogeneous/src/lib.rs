@@ -1,13 +1,61 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use ndarray::{Array, RemoveAxis};
 use ndarray::{ArrayBase, DataOwned, Dimension};
-use serde::{Deserialize, Deserializer};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-#[derive(Debug, Clone)]
+pub use crate::columns::{ConversionError, DType, ScalarColumnsExt};
+
+mod columns;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Scalar {
     I64(i64),
     F64(f64),
     BOOL(bool),
     STRING(String),
+    DATETIME(NaiveDateTime),
+}
+
+/// The format datetimes are written in, ISO 8601 with fractional seconds only when there are any.
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+const DATETIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%d/%m/%Y %H:%M:%S",
+];
+
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%d/%m/%Y"];
+
+impl Scalar {
+    /// Parses ISO 8601 / RFC 3339 datetimes (converted to UTC when they carry an offset), the same
+    /// with a space instead of the `T`, and dates such as `2020-01-31`, `2020/01/31` or
+    /// `31/01/2020`, which are taken to be at midnight.
+    pub fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+            return Some(datetime.naive_utc());
+        }
+
+        DATETIME_FORMATS.iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .or_else(|| DATE_FORMATS.iter()
+                .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+                .and_then(|date| date.and_hms_opt(0, 0, 0)))
+    }
+
+    /// This value as a datetime, parsing strings with `parse_datetime`.
+    pub fn as_datetime(&self) -> Option<NaiveDateTime> {
+        match self {
+            Scalar::DATETIME(datetime) => Some(*datetime),
+            Scalar::STRING(value) => Scalar::parse_datetime(value),
+            _ => None,
+        }
+    }
 }
 
 use std::fmt;
@@ -20,7 +68,33 @@ impl<'de> Visitor<'de> for ScalarVisitor {
     type Value = Scalar;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("an integer between -2^31 and 2^31")
+        formatter.write_str("a number, a boolean, a string or a datetime")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+    {
+        Ok(Scalar::BOOL(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+    {
+        Ok(Scalar::I64(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+    {
+        // Integers beyond i64 are kept approximately rather than rejected.
+        if value <= i64::MAX as u64 {
+            Ok(Scalar::I64(value as i64))
+        } else {
+            Ok(Scalar::F64(value as f64))
+        }
     }
 
     fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
@@ -34,14 +108,14 @@ impl<'de> Visitor<'de> for ScalarVisitor {
         where
             E: de::Error,
     {
-        Ok(Scalar::STRING(value.to_owned()))
+        Ok(Scalar::parse_datetime(value).map_or_else(|| Scalar::STRING(value.to_owned()), Scalar::DATETIME))
     }
 
     fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
         where
             E: de::Error,
     {
-        Ok(Scalar::STRING(value))
+        Ok(Scalar::parse_datetime(&value).map_or(Scalar::STRING(value), Scalar::DATETIME))
     }
 }
 
@@ -54,13 +128,29 @@ impl<'de> Deserialize<'de> for Scalar {
     }
 }
 
+impl Serialize for Scalar {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+    {
+        match self {
+            Scalar::I64(value) => serializer.serialize_i64(*value),
+            Scalar::F64(value) => serializer.serialize_f64(*value),
+            Scalar::BOOL(value) => serializer.serialize_bool(*value),
+            Scalar::STRING(value) => serializer.serialize_str(value),
+            Scalar::DATETIME(value) => serializer.collect_str(&value.format(DATETIME_FORMAT)),
+        }
+    }
+}
+
 impl From<Scalar> for String {
     fn from(scalar: Scalar) -> Self {
         match scalar {
             Scalar::I64(i) => i.to_string(),
             Scalar::F64(i) => i.to_string(),
             Scalar::BOOL(i) => i.to_string(),
-            Scalar::STRING(i) => i
+            Scalar::STRING(i) => i,
+            Scalar::DATETIME(i) => i.format(DATETIME_FORMAT).to_string(),
         }
     }
 }
@@ -71,7 +161,9 @@ impl From<Scalar> for f64 {
             Scalar::I64(i) => i as f64,
             Scalar::F64(i) => i,
             Scalar::BOOL(i) => if i { 1. } else { 0. },
-            Scalar::STRING(i) => i.parse().unwrap()
+            Scalar::STRING(i) => i.parse().unwrap(),
+            // Seconds since the Unix epoch.
+            Scalar::DATETIME(i) => i.and_utc().timestamp() as f64 + i.and_utc().timestamp_subsec_nanos() as f64 * 1e-9,
         }
     }
 }
@@ -89,6 +181,12 @@ impl From<Scalar> for bool {
     }
 }
 
+impl From<Scalar> for NaiveDateTime {
+    fn from(scalar: Scalar) -> Self {
+        scalar.as_datetime().expect("not a datetime")
+    }
+}
+
 impl Scalar {
     pub fn unwrap_as<B: From<Scalar>>(self) -> B where Self: Sized {
         B::from(self)
@@ -103,6 +201,13 @@ pub trait ScalarExt<S, D>
     fn map_scalar_type<T: From<Scalar>>(&self) -> Array<T, D>
         where
             D: RemoveAxis;
+
+    /// Like `map_scalar_type`, but converting the elements on the rayon thread pool, e.g. for
+    /// frames of millions of rows.
+    #[cfg(feature = "rayon")]
+    fn par_map_scalar_type<T: From<Scalar> + Send>(&self) -> Array<T, D>
+        where
+            D: RemoveAxis;
 }
 
 impl<S, D> ScalarExt<S, D> for ArrayBase<S, D>
@@ -115,6 +220,16 @@ impl<S, D> ScalarExt<S, D> for ArrayBase<S, D>
             D: RemoveAxis {
         self.mapv(|v| v.unwrap_as::<T>())
     }
+
+    #[cfg(feature = "rayon")]
+    fn par_map_scalar_type<T: From<Scalar> + Send>(&self) -> Array<T, D>
+        where
+            D: RemoveAxis {
+        let x = self.as_standard_layout();
+        let values: Vec<T> = x.as_slice().unwrap().par_iter().map(|v| v.clone().unwrap_as::<T>()).collect();
+
+        Array::from_shape_vec(self.raw_dim(), values).unwrap()
+    }
 }
 
 // fn main() {
@@ -133,4 +248,83 @@ impl<S, D> ScalarExt<S, D> for ArrayBase<S, D>
 //
 //     info!("df: {:?}", df);
 //     info!("df.map_type::<f64>(): {:?}", df.map_type::<f64>());
-// }
\ No newline at end of file
+// }
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use ndarray::array;
+
+    use crate::{ConversionError, DType, Scalar, ScalarColumnsExt};
+
+    #[test]
+    fn test_json_round_trip() {
+        let row = vec![Scalar::I64(-3), Scalar::F64(2.5), Scalar::BOOL(true), Scalar::STRING("setosa".to_owned())];
+
+        let json = serde_json::to_string(&row).unwrap();
+        assert_eq!(json, r#"[-3,2.5,true,"setosa"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Scalar>>(&json).unwrap(), row);
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let rows = array![
+            [Scalar::I64(1), Scalar::F64(0.5), Scalar::BOOL(false), Scalar::STRING("a".to_owned())],
+            [Scalar::I64(2), Scalar::F64(1.5), Scalar::BOOL(true), Scalar::STRING("b".to_owned())]
+        ];
+
+        let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(vec![]);
+        for row in rows.genrows() {
+            writer.serialize(row.to_vec()).unwrap();
+        }
+        let written = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert_eq!(written, "1,0.5,false,a\n2,1.5,true,b\n");
+
+        let mut reader = csv::ReaderBuilder::new().has_headers(false).from_reader(written.as_bytes());
+        let read: Vec<Vec<Scalar>> = reader.deserialize().collect::<Result<_, _>>().unwrap();
+        assert_eq!(read, rows.genrows().into_iter().map(|row| row.to_vec()).collect::<Vec<Vec<Scalar>>>());
+    }
+
+    #[test]
+    fn test_datetimes_round_trip_through_strings() {
+        let datetime = NaiveDate::from_ymd_opt(2020, 1, 31).unwrap().and_hms_opt(13, 45, 10).unwrap();
+
+        let json = serde_json::to_string(&Scalar::DATETIME(datetime)).unwrap();
+        assert_eq!(json, r#""2020-01-31T13:45:10""#);
+        assert_eq!(serde_json::from_str::<Scalar>(&json).unwrap(), Scalar::DATETIME(datetime));
+
+        assert_eq!(Scalar::parse_datetime("2020-01-31 13:45:10"), Some(datetime));
+        assert_eq!(Scalar::parse_datetime("2020-01-31T14:45:10+01:00"), Some(datetime));
+        assert_eq!(Scalar::parse_datetime("31/01/2020"), datetime.date().and_hms_opt(0, 0, 0));
+        assert_eq!(Scalar::parse_datetime("2020"), None);
+    }
+
+    #[test]
+    fn test_column_dtypes_and_extraction() {
+        let x = array![
+            [Scalar::I64(1), Scalar::F64(5.1), Scalar::STRING("setosa".to_owned())],
+            [Scalar::F64(2.5), Scalar::F64(4.9), Scalar::BOOL(true)]
+        ];
+
+        assert_eq!(x.dtypes(), vec![DType::F64, DType::F64, DType::STRING]);
+        assert_eq!(x.column_as_f64(0).unwrap(), array![1., 2.5]);
+        assert_eq!(x.column_as_string(2), array!["setosa".to_owned(), "true".to_owned()]);
+        assert_eq!(x.column_as_i64(0).unwrap_err(), ConversionError { value: Scalar::F64(2.5), target: "i64" });
+    }
+
+    #[test]
+    fn test_large_unsigned_integers_become_floats() {
+        assert_eq!(serde_json::from_str::<Scalar>("18446744073709551615").unwrap(), Scalar::F64(18446744073709551615.));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_map_scalar_type_matches_map_scalar_type() {
+        use crate::ScalarExt;
+
+        let x = array![[Scalar::I64(1), Scalar::F64(2.5)], [Scalar::BOOL(true), Scalar::STRING("4".to_owned())]];
+
+        assert_eq!(x.par_map_scalar_type::<f64>(), x.map_scalar_type::<f64>());
+        assert_eq!(x.par_map_scalar_type::<String>(), x.map_scalar_type::<String>());
+    }
+}
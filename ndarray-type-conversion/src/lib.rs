@@ -1,6 +1,28 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::mem::{align_of, size_of, ManuallyDrop};
+use std::ptr;
+
 use ndarray::{Array, Data};
 use ndarray::{ArrayBase, Dimension};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// An element `try_map_type` couldn't convert, by its position in logical (row-major) order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cannot convert element {}: {}", self.position, self.message)
+    }
+}
 
+impl Error for ConversionError {}
 
 pub trait MapTypeExt<A, S, D>
     where
@@ -9,6 +31,25 @@ pub trait MapTypeExt<A, S, D>
         A: Clone
 {
     fn map_type<T: From<A>>(&self) -> Array<T, D>;
+
+    /// Like `map_type`, but for fallible conversions, stopping at the first element that fails.
+    fn try_map_type<T>(&self) -> Result<Array<T, D>, ConversionError>
+        where
+            T: TryFrom<A>,
+            T::Error: fmt::Display;
+
+    /// Like `map_type`, but consumes the array. When `T` has the size and alignment of `A` and the
+    /// array is contiguous in standard layout, the elements are converted in place, reusing the
+    /// allocation instead of copying it.
+    fn map_type_into<T: From<A>>(self) -> Array<T, D> where Self: Sized;
+
+    /// Like `map_type`, but converting the elements on the rayon thread pool, which pays off on
+    /// arrays of millions of elements.
+    #[cfg(feature = "rayon")]
+    fn par_map_type<T>(&self) -> Array<T, D>
+        where
+            A: Sync,
+            T: From<A> + Send;
 }
 
 impl<A, S, D> MapTypeExt<A, S, D> for ArrayBase<S, D> where
@@ -20,4 +61,106 @@ impl<A, S, D> MapTypeExt<A, S, D> for ArrayBase<S, D> where
         let z: Array<T, D> = self.mapv(|v| T::from(v));
         z
     }
-}
\ No newline at end of file
+
+    fn try_map_type<T>(&self) -> Result<Array<T, D>, ConversionError>
+        where
+            T: TryFrom<A>,
+            T::Error: fmt::Display {
+        let values = self.iter()
+            .enumerate()
+            .map(|(position, v)| T::try_from(v.clone()).map_err(|e| ConversionError { position, message: e.to_string() }))
+            .collect::<Result<Vec<T>, ConversionError>>()?;
+
+        Ok(Array::from_shape_vec(self.raw_dim(), values).unwrap())
+    }
+
+    fn map_type_into<T: From<A>>(self) -> Array<T, D> {
+        if size_of::<A>() != size_of::<T>() || align_of::<A>() != align_of::<T>() || size_of::<A>() == 0 || !self.is_standard_layout() {
+            return self.map_type();
+        }
+
+        let owned = self.into_owned();
+        let dim = owned.raw_dim();
+        let n = owned.len();
+        if n == 0 {
+            return Array::from_shape_vec(dim, vec![]).unwrap();
+        }
+
+        // An owned array may be a window on a larger allocation, e.g. after `slice_move`; being in
+        // standard layout, its elements are the `n` starting at its first one.
+        let first = owned.as_ptr() as usize;
+        let mut values = owned.into_raw_vec();
+        let offset = (first - values.as_ptr() as usize) / size_of::<A>();
+        values.truncate(offset + n);
+        values.drain(..offset);
+
+        // Should a conversion panic, the allocation is leaked rather than dropped as a mix of `A`s
+        // and `T`s.
+        let mut values = ManuallyDrop::new(values);
+        let (elements, length, capacity) = (values.as_mut_ptr(), values.len(), values.capacity());
+
+        unsafe {
+            for i in 0..length {
+                let value = ptr::read(elements.add(i));
+                ptr::write(elements.add(i) as *mut T, T::from(value));
+            }
+
+            Array::from_shape_vec(dim, Vec::from_raw_parts(elements as *mut T, length, capacity)).unwrap()
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    fn par_map_type<T>(&self) -> Array<T, D>
+        where
+            A: Sync,
+            T: From<A> + Send {
+        let x = self.as_standard_layout();
+        let values: Vec<T> = x.as_slice().unwrap().par_iter().map(|v| T::from(v.clone())).collect();
+
+        Array::from_shape_vec(self.raw_dim(), values).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, s};
+
+    use crate::{ConversionError, MapTypeExt};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Wrapped(u64);
+
+    impl From<f64> for Wrapped {
+        fn from(value: f64) -> Self {
+            Wrapped(value as u64)
+        }
+    }
+
+    #[test]
+    fn test_try_map_type_reports_the_failing_element() {
+        let x = array![[1i64, 2], [300, 4]];
+
+        assert_eq!(x.try_map_type::<i16>().unwrap(), array![[1i16, 2], [300, 4]]);
+        assert_eq!(x.try_map_type::<u8>().unwrap_err().position, 2);
+        assert_eq!(array![-1i64].try_map_type::<u8>(), Err(ConversionError { position: 0, message: "out of range integral type conversion attempted".to_owned() }));
+    }
+
+    #[test]
+    fn test_map_type_into_matches_map_type() {
+        let x = array![[1., 2., 3.], [4., 5., 6.], [7., 8., 9.]];
+
+        assert_eq!(x.clone().map_type_into::<Wrapped>(), x.map_type::<Wrapped>());
+        assert_eq!(x.clone().slice_move(s![1.., ..]).map_type_into::<Wrapped>(), x.slice(s![1.., ..]).map_type::<Wrapped>());
+        assert_eq!(x.clone().reversed_axes().map_type_into::<Wrapped>(), x.t().map_type::<Wrapped>());
+        assert_eq!(array![[1u32, 2], [3, 4]].map_type_into::<f64>(), array![[1., 2.], [3., 4.]]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_map_type_matches_map_type() {
+        let x = array![[1u32, 2, 3], [4, 5, 6]];
+
+        assert_eq!(x.par_map_type::<f64>(), x.map_type::<f64>());
+        assert_eq!(x.t().par_map_type::<f64>(), x.t().map_type::<f64>());
+    }
+}
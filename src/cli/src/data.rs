@@ -0,0 +1,47 @@
+use std::path::Path;
+
+use ndarray::{Array1, Array2};
+use ndarray_heterogeneous::Scalar;
+use rune_core::{Error, Result};
+use rune_data::{read_csv_file, CsvOptions, Dataset};
+
+use crate::model::Target;
+
+/// Reads a CSV file with a header row, moving the `target` column, when given, into `y`.
+pub fn read_data<P: AsRef<Path>>(path: P, delimiter: u8, target: Option<&str>) -> Result<Dataset<Scalar>> {
+    let options = CsvOptions { delimiter, target_column: target.map(|target| target.into()), ..Default::default() };
+    Ok(read_csv_file(path, &options)?)
+}
+
+/// The columns of `dataset` named by `feature_names`, in that order, so files may order their
+/// columns differently from the training data or carry extra ones.
+pub fn features(dataset: &Dataset<Scalar>, feature_names: &[String]) -> Result<Array2<f64>> {
+    let columns = feature_names.iter()
+        .map(|name| {
+            dataset.feature_names.iter()
+                .position(|existing| existing == name)
+                .ok_or_else(|| Error::InvalidInput(format!("missing feature column {}", name)))
+        })
+        .collect::<Result<Vec<usize>>>()?;
+
+    for (&column, name) in columns.iter().zip(feature_names) {
+        if dataset.x.column(column).iter().any(|value| matches!(value, Scalar::STRING(_))) {
+            return Err(Error::InvalidInput(format!("feature column {} is not numeric", name)));
+        }
+    }
+
+    Ok(Array2::from_shape_fn((dataset.n_samples(), columns.len()), |(i, j)| dataset.x[[i, columns[j]]].clone().into()))
+}
+
+/// The target column read as class names for classifiers, as numbers otherwise.
+pub fn target(dataset: &Dataset<Scalar>, classification: bool) -> Result<Target> {
+    let y = dataset.y.as_ref().ok_or_else(|| Error::InvalidInput("the data has no target column".to_owned()))?;
+
+    if classification {
+        Ok(Target::Classes(y.mapv(|label| label.into())))
+    } else if y.iter().any(|value| matches!(value, Scalar::STRING(_))) {
+        Err(Error::InvalidInput("a regressor's target column must be numeric".to_owned()))
+    } else {
+        Ok(Target::Values(y.iter().cloned().map(f64::from).collect::<Array1<f64>>()))
+    }
+}
@@ -0,0 +1,151 @@
+//! `rune train`, `rune predict` and `rune evaluate`: fit a pipeline described by a spec file on
+//! a CSV file, save it, and use it to predict or score other CSV files. With the `serve` feature,
+//! `rune serve` answers prediction requests over HTTP.
+
+mod data;
+mod model;
+mod report;
+#[cfg(feature = "serve")]
+mod serve;
+mod spec;
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process;
+
+use clap::{Parser, Subcommand};
+use log::info;
+use rune_core::{Error, Result};
+
+use crate::data::{features, read_data, target};
+use crate::model::{Target, TrainedModel};
+use crate::report::report;
+use crate::spec::Spec;
+
+#[derive(Parser)]
+#[command(name = "rune", about = "Train, apply and evaluate rune models on CSV files")]
+struct Cli {
+    /// The field delimiter of the CSV files.
+    #[arg(long, global = true, default_value_t = ',')]
+    delimiter: char,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Fits the pipeline of a TOML or YAML spec and saves it.
+    Train {
+        #[arg(long)]
+        spec: PathBuf,
+        /// Training data, with a header row and the spec's target column.
+        #[arg(long)]
+        data: PathBuf,
+        /// Where to save the model: bincode for .bin or .bincode, JSON otherwise.
+        #[arg(long)]
+        model: PathBuf,
+    },
+    /// Writes the predictions of a saved model as CSV.
+    Predict {
+        #[arg(long)]
+        model: PathBuf,
+        #[arg(long)]
+        data: PathBuf,
+        /// Defaults to standard output.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Scores a saved model on data with the target column.
+    Evaluate {
+        #[arg(long)]
+        model: PathBuf,
+        #[arg(long)]
+        data: PathBuf,
+    },
+    /// Answers prediction requests over HTTP with a saved model.
+    #[cfg(feature = "serve")]
+    Serve {
+        #[arg(long)]
+        model: PathBuf,
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: std::net::SocketAddr,
+    },
+}
+
+fn main() {
+    env_logger::init();
+
+    if let Err(error) = run(Cli::parse()) {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    if !cli.delimiter.is_ascii() {
+        return Err(Error::InvalidParameter(format!("delimiter {:?} is not an ASCII character", cli.delimiter)));
+    }
+    let delimiter = cli.delimiter as u8;
+
+    match cli.command {
+        Command::Train { spec, data, model } => {
+            let spec = Spec::from_path(spec)?;
+            let dataset = read_data(data, delimiter, Some(&spec.target))?;
+            let x = features(&dataset, &dataset.feature_names)?;
+            let y = target(&dataset, spec.model.is_classifier())?;
+
+            let trained = TrainedModel::fit(&spec, dataset.feature_names.clone(), x.view(), &y)?;
+            trained.save(&model)?;
+            info!("Saved model to {}", model.display());
+
+            println!("training scores\n{}", report(&y, &trained.predict(x.view())?)?);
+        }
+        Command::Predict { model, data, output } => {
+            let trained = TrainedModel::load(model)?;
+            let dataset = read_data(data, delimiter, None)?;
+            let predictions = trained.predict(features(&dataset, &trained.feature_names)?.view())?;
+
+            match output {
+                Some(path) => write_predictions(File::create(path)?, &trained.target, &predictions, delimiter)?,
+                None => write_predictions(io::stdout().lock(), &trained.target, &predictions, delimiter)?,
+            }
+        }
+        Command::Evaluate { model, data } => {
+            let trained = TrainedModel::load(model)?;
+            let dataset = read_data(data, delimiter, Some(&trained.target))?;
+            let x = features(&dataset, &trained.feature_names)?;
+            let y = target(&dataset, trained.is_classifier())?;
+
+            println!("{}", report(&y, &trained.predict(x.view())?)?);
+        }
+        #[cfg(feature = "serve")]
+        Command::Serve { model, address } => serve::serve(TrainedModel::load(model)?, address)?,
+    }
+
+    Ok(())
+}
+
+/// One prediction per row, under a header naming the target column.
+fn write_predictions<W: Write>(writer: W, target: &str, predictions: &Target, delimiter: u8) -> Result<()> {
+    let csv_error = |e: csv::Error| Error::Io(e.into());
+    let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(writer);
+
+    writer.write_record([target]).map_err(csv_error)?;
+    match predictions {
+        Target::Classes(classes) => {
+            for class in classes {
+                writer.write_record([class]).map_err(csv_error)?;
+            }
+        }
+        Target::Values(values) => {
+            for value in values {
+                writer.write_record([value.to_string()]).map_err(csv_error)?;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
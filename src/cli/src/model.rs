@@ -0,0 +1,230 @@
+use std::path::Path;
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+use log::info;
+use rune_core::{Error, Result};
+use rune_linear::multiple_linear_regression::{MultipleLinearRegression, MultipleLinearRegressionModel};
+use rune_core::estimator::{Fit, FitTransform, Predictor, ProbabilisticPredictor, Transformer};
+use rune_persist::{load_model, save_model};
+use rune_pipeline::dyn_pipeline::{step, DynPipeline, DynPipelineModel, SavedDynPipeline, StepRegistry};
+use rune_pipeline::params::Parameterized;
+use rune_preprocessing::standard_scaler::{StandardScaler, StandardScalerTransformer};
+use rune_tree::feature_selector::greedy_feature_selector::GreedyFeatureSelector;
+use rune_tree::measures::entropy::EntropySelectionMeasure;
+use rune_tree::{DecisionTreeClassifier, DecisionTreeModel};
+use serde::{Deserialize, Serialize};
+
+use crate::spec::{ModelSpec, Spec, StepSpec};
+
+/// Everything `rune predict` needs: the columns the model was trained on, the fitted steps and
+/// the fitted estimator.
+pub struct TrainedModel {
+    pub target: String,
+    pub feature_names: Vec<String>,
+    steps: DynPipelineModel,
+    estimator: FittedEstimator,
+}
+
+/// A `TrainedModel` as saved with `rune_persist`, so it's JSON or bincode by file extension. The
+/// fitted steps are stored through the `StepRegistry` of `registry`.
+#[derive(Serialize, Deserialize)]
+struct SavedModel<E> {
+    target: String,
+    feature_names: Vec<String>,
+    steps: SavedDynPipeline,
+    estimator: E,
+}
+
+#[derive(Serialize, Deserialize)]
+enum FittedEstimator {
+    /// Trees predict the index of a class in `classes`.
+    DecisionTree { classes: Vec<String>, model: DecisionTreeModel<usize> },
+    LinearRegression(MultipleLinearRegressionModel),
+}
+
+/// The target column, as read: class names for classifiers and numbers for regressors.
+pub enum Target {
+    Classes(Array1<String>),
+    Values(Array1<f64>),
+}
+
+/// The steps of a spec as a `DynPipeline`, each named after its type and position, e.g.
+/// `standard_scaler_0`, and configured through its parameters.
+fn pipeline<Y: 'static>(specs: &[StepSpec]) -> Result<DynPipeline<Y>> {
+    let mut pipeline = DynPipeline::new();
+
+    for (i, spec) in specs.iter().enumerate() {
+        match *spec {
+            StepSpec::StandardScaler { with_mean, with_std } => {
+                let name = format!("standard_scaler_{}", i);
+                pipeline = pipeline.then_boxed(&name, step(StandardScaler::new()))?;
+                pipeline.set_param(&format!("{}__with_mean", name), with_mean.into())?;
+                pipeline.set_param(&format!("{}__with_std", name), with_std.into())?;
+            }
+            #[cfg(feature = "decomposition")]
+            StepSpec::Pca { n_components, whiten } => {
+                use rune_decomposition::principal_component_analysis::PrincipalComponentAnalysis;
+
+                let name = format!("pca_{}", i);
+                pipeline = pipeline.then_boxed(&name, step(PrincipalComponentAnalysis::new(n_components)))?;
+                pipeline.set_param(&format!("{}__whiten", name), whiten.into())?;
+            }
+            #[cfg(not(feature = "decomposition"))]
+            StepSpec::Pca { .. } => return Err(Error::InvalidParameter("pca steps need rune built with the decomposition feature".to_owned())),
+        }
+    }
+
+    Ok(pipeline)
+}
+
+/// Every transformer `pipeline` can fit, so the fitted steps can be saved and loaded.
+fn registry() -> StepRegistry {
+    let registry = StepRegistry::new().register::<StandardScalerTransformer>("standard_scaler");
+    #[cfg(feature = "decomposition")]
+    let registry = registry.register::<rune_decomposition::principal_component_analysis::PrincipalComponentAnalysisTransformer>("pca");
+    registry
+}
+
+/// Fits the steps of `specs` in order, each on the output of the one before, returning the
+/// fitted steps and the output of the last.
+fn fit_steps<Y: 'static>(specs: &[StepSpec], x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<(DynPipelineModel, Array2<f64>)> {
+    info!("Fitting {:?}", specs);
+    let (steps, transformed) = pipeline(specs)?.fit_transform(x, y)?;
+    info!("Fitting on {} samples of {} features", transformed.nrows(), transformed.ncols());
+    Ok((steps, transformed))
+}
+
+impl TrainedModel {
+    /// Fits the steps of `spec`, then the model on the output of the last step.
+    pub fn fit(spec: &Spec, feature_names: Vec<String>, x: ArrayView2<f64>, y: &Target) -> Result<TrainedModel> {
+        let (steps, estimator) = match (&spec.model, y) {
+            (&ModelSpec::DecisionTree { max_depth, min_size }, Target::Classes(labels)) => {
+                let classes = distinct(labels.view());
+                let y = labels.mapv(|label| classes.binary_search(&label).unwrap());
+                let (steps, transformed) = fit_steps(&spec.steps, x, y.view())?;
+
+                info!("Fitting {:?}", spec.model);
+                let classifier = DecisionTreeClassifier::new(max_depth, min_size, GreedyFeatureSelector::new(EntropySelectionMeasure::new()));
+                (steps, FittedEstimator::DecisionTree { model: classifier.fit(transformed.view(), y.view())?, classes })
+            }
+            (&ModelSpec::LinearRegression { alpha, iterations }, Target::Values(values)) => {
+                let (steps, transformed) = fit_steps(&spec.steps, x, values.view())?;
+
+                info!("Fitting {:?}", spec.model);
+                (steps, FittedEstimator::LinearRegression(MultipleLinearRegression::new(alpha, iterations).fit(transformed.view(), values.view())?))
+            }
+            _ => return Err(Error::InvalidInput("the target column doesn't suit the model".to_owned())),
+        };
+
+        Ok(TrainedModel { target: spec.target.clone(), feature_names, steps, estimator })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let saved = SavedModel {
+            target: self.target.clone(),
+            feature_names: self.feature_names.clone(),
+            steps: self.steps.save(&registry())?,
+            estimator: &self.estimator,
+        };
+        save_model(&saved, path)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<TrainedModel> {
+        let saved: SavedModel<FittedEstimator> = load_model(path)?;
+        let steps = DynPipelineModel::load(&saved.steps, &registry())?;
+
+        Ok(TrainedModel { target: saved.target, feature_names: saved.feature_names, steps, estimator: saved.estimator })
+    }
+
+    /// Whether this model predicts classes, so its target column should be read as class names.
+    pub fn is_classifier(&self) -> bool {
+        matches!(self.estimator, FittedEstimator::DecisionTree { .. })
+    }
+
+    pub fn predict(&self, x: ArrayView2<f64>) -> Result<Target> {
+        let transformed = self.transform(x)?;
+
+        Ok(match &self.estimator {
+            FittedEstimator::DecisionTree { classes, model } => {
+                Target::Classes(model.predict(transformed.view()).mapv(|class| classes[class].clone()))
+            }
+            FittedEstimator::LinearRegression(model) => Target::Values(model.predict(transformed.view())),
+        })
+    }
+
+    /// The probability of each class for every row, one column per class in the returned names.
+    #[cfg_attr(not(feature = "serve"), allow(dead_code))]
+    pub fn predict_proba(&self, x: ArrayView2<f64>) -> Result<(Vec<String>, Array2<f64>)> {
+        match &self.estimator {
+            FittedEstimator::DecisionTree { classes, model } => {
+                let probabilities = model.predict_proba(self.transform(x)?.view())?;
+                let names = model.classes().iter().map(|&class| classes[class].clone()).collect();
+                Ok((names, probabilities))
+            }
+            FittedEstimator::LinearRegression(_) => Err(Error::InvalidInput("regressors don't predict class probabilities".to_owned())),
+        }
+    }
+
+    /// Runs `x` through the fitted steps.
+    fn transform(&self, x: ArrayView2<f64>) -> Result<Array2<f64>> {
+        if x.ncols() != self.feature_names.len() {
+            return Err(Error::ShapeMismatch(format!("model expects {} features, got {}", self.feature_names.len(), x.ncols())));
+        }
+
+        Ok(self.steps.transform(x))
+    }
+}
+
+/// The distinct labels in `labels`, sorted.
+fn distinct(labels: ArrayView1<String>) -> Vec<String> {
+    let mut classes: Vec<String> = labels.to_vec();
+    classes.sort();
+    classes.dedup();
+    classes
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::model::{Target, TrainedModel};
+    use crate::spec::Spec;
+
+    #[test]
+    fn test_saved_models_predict_like_the_original() {
+        let spec: Spec = toml::from_str(r#"
+            target = "species"
+
+            [[steps]]
+            type = "standard_scaler"
+
+            [[steps]]
+            type = "standard_scaler"
+            with_std = false
+
+            [model]
+            type = "decision_tree"
+            max_depth = 2
+        "#).unwrap();
+        let x = array![[1., 10.], [2., 20.], [3., 10.], [4., 20.]];
+        let y = Target::Classes(array!["a", "a", "b", "b"].mapv(str::to_owned));
+        let trained = TrainedModel::fit(&spec, vec!["length".to_owned(), "width".to_owned()], x.view(), &y).unwrap();
+
+        for name in ["rune-cli-test.json", "rune-cli-test.bin"].iter() {
+            let path = std::env::temp_dir().join(name);
+            trained.save(&path).unwrap();
+            let loaded = TrainedModel::load(&path).unwrap();
+            std::fs::remove_file(path).unwrap();
+
+            assert_eq!(loaded.feature_names, trained.feature_names);
+            assert_eq!(loaded.steps.step_names(), vec!["standard_scaler_0", "standard_scaler_1"]);
+            match (loaded.predict(x.view()).unwrap(), trained.predict(x.view()).unwrap()) {
+                (Target::Classes(loaded), Target::Classes(original)) => {
+                    assert_eq!(loaded, original);
+                    assert_eq!(loaded, array!["a", "a", "b", "b"].mapv(str::to_owned));
+                }
+                _ => panic!("a tree predicts classes"),
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use rune_core::{Error, Result};
+use rune_metrics::metrics::{accuracy_score, balanced_accuracy_score, f1, precision, recall};
+use rune_metrics::regression::explained_variance::explained_variance;
+use rune_metrics::regression::mean_absolute_error::mean_absolute_error;
+use rune_metrics::regression::mean_squared_error::mean_squared_error;
+use rune_metrics::regression::r2::r2;
+
+use crate::model::Target;
+
+/// Scores predictions against the true targets: accuracy and per-class precision, recall and F1
+/// for classifiers, error and explained variance for regressors.
+pub fn report(y_true: &Target, y_pred: &Target) -> Result<String> {
+    let mut lines = vec![];
+
+    match (y_true, y_pred) {
+        (Target::Classes(y_true), Target::Classes(y_pred)) => {
+            lines.push(format!("accuracy           {:.4}", accuracy_score(y_true.view(), y_pred.view(), None)));
+            lines.push(format!("balanced accuracy  {:.4}", balanced_accuracy_score(y_true.view(), y_pred.view())));
+            lines.push(String::new());
+            lines.push(format!("{:<20} {:>9} {:>9} {:>9} {:>9}", "class", "precision", "recall", "f1", "support"));
+
+            let mut classes: Vec<&String> = y_true.iter().chain(y_pred.iter()).collect();
+            classes.sort();
+            classes.dedup();
+            for class in classes {
+                lines.push(format!("{:<20} {:>9.4} {:>9.4} {:>9.4} {:>9}",
+                                   class,
+                                   precision(y_true.view(), y_pred.view(), class, None),
+                                   recall(y_true.view(), y_pred.view(), class, None),
+                                   f1(y_true.view(), y_pred.view(), class, None),
+                                   y_true.iter().filter(|&label| label == class).count()));
+            }
+        }
+        (Target::Values(y_true), Target::Values(y_pred)) => {
+            let mse = mean_squared_error(y_true.view(), y_pred.view());
+            lines.push(format!("mean squared error       {:.4}", mse));
+            lines.push(format!("root mean squared error  {:.4}", mse.sqrt()));
+            lines.push(format!("mean absolute error      {:.4}", mean_absolute_error(y_true.view(), y_pred.view())));
+            lines.push(format!("r2                       {:.4}", r2(y_true.view(), y_pred.view(), None)));
+            lines.push(format!("explained variance       {:.4}", explained_variance(y_true.view(), y_pred.view())));
+        }
+        _ => return Err(Error::InvalidInput("predictions and targets are of different kinds".to_owned())),
+    }
+
+    Ok(lines.join("\n"))
+}
@@ -0,0 +1,138 @@
+//! `rune serve`: answers prediction requests over HTTP with a saved model.
+//!
+//! Both endpoints take a JSON body of rows, each either an array of feature values in the order
+//! the model was trained on or an object keyed by feature name:
+//!
+//! `POST /predict {"instances": [[5.1, 3.5, 1.4, 0.2], {"sepal_length": 6.7, ...}]}`
+//!
+//! `/predict` answers `{"predictions": [...]}`, and `/predict_proba` answers
+//! `{"classes": [...], "probabilities": [[...], ...]}` for models that estimate probabilities.
+//! Invalid requests get a 400 with `{"error": "..."}`.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use log::info;
+use ndarray::Array2;
+use rune_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::model::{Target, TrainedModel};
+
+#[derive(Deserialize)]
+struct PredictRequest {
+    instances: Vec<Instance>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Instance {
+    Values(Vec<f64>),
+    Named(HashMap<String, f64>),
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Predictions {
+    Classes(Vec<String>),
+    Values(Vec<f64>),
+}
+
+struct ServeError(Error);
+
+impl IntoResponse for ServeError {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(json!({ "error": self.0.to_string() }))).into_response()
+    }
+}
+
+/// The routes of the server, predicting with `model`.
+fn app(model: Arc<TrainedModel>) -> Router {
+    Router::new()
+        .route("/predict", post(predict))
+        .route("/predict_proba", post(predict_proba))
+        .with_state(model)
+}
+
+pub fn serve(model: TrainedModel, address: SocketAddr) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        info!("Serving predictions of {} on {}", model.target, listener.local_addr()?);
+
+        axum::serve(listener, app(Arc::new(model))).await?;
+        Ok(())
+    })
+}
+
+async fn predict(State(model): State<Arc<TrainedModel>>, Json(request): Json<PredictRequest>) -> std::result::Result<Json<serde_json::Value>, ServeError> {
+    let x = instances_to_matrix(&request.instances, &model.feature_names).map_err(ServeError)?;
+
+    let predictions = match model.predict(x.view()).map_err(ServeError)? {
+        Target::Classes(classes) => Predictions::Classes(classes.to_vec()),
+        Target::Values(values) => Predictions::Values(values.to_vec()),
+    };
+
+    Ok(Json(json!({ "predictions": predictions })))
+}
+
+async fn predict_proba(State(model): State<Arc<TrainedModel>>, Json(request): Json<PredictRequest>) -> std::result::Result<Json<serde_json::Value>, ServeError> {
+    let x = instances_to_matrix(&request.instances, &model.feature_names).map_err(ServeError)?;
+
+    let (classes, probabilities) = model.predict_proba(x.view()).map_err(ServeError)?;
+    let probabilities: Vec<Vec<f64>> = probabilities.genrows().into_iter().map(|row| row.to_vec()).collect();
+
+    Ok(Json(json!({ "classes": classes, "probabilities": probabilities })))
+}
+
+/// One row per instance, with the features in the order of `feature_names`.
+fn instances_to_matrix(instances: &[Instance], feature_names: &[String]) -> Result<Array2<f64>> {
+    let mut x = Array2::zeros((instances.len(), feature_names.len()));
+
+    for (i, instance) in instances.iter().enumerate() {
+        match instance {
+            Instance::Values(values) if values.len() == feature_names.len() => {
+                x.row_mut(i).iter_mut().zip(values).for_each(|(cell, &value)| *cell = value);
+            }
+            Instance::Values(values) => {
+                return Err(Error::ShapeMismatch(format!("instance {} has {} values, the model expects {}", i, values.len(), feature_names.len())));
+            }
+            Instance::Named(values) => {
+                for (j, name) in feature_names.iter().enumerate() {
+                    x[[i, j]] = *values.get(name)
+                        .ok_or_else(|| Error::InvalidInput(format!("instance {} is missing feature {}", i, name)))?;
+                }
+            }
+        }
+    }
+
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::serve::{instances_to_matrix, Instance};
+
+    #[test]
+    fn test_instances_are_arranged_in_feature_order() {
+        let names = vec!["a".to_owned(), "b".to_owned()];
+        let instances = vec![
+            Instance::Values(vec![1., 2.]),
+            Instance::Named(vec![("b".to_owned(), 4.), ("a".to_owned(), 3.), ("c".to_owned(), 5.)].into_iter().collect()),
+        ];
+
+        assert_eq!(instances_to_matrix(&instances, &names).unwrap(), array![[1., 2.], [3., 4.]]);
+        assert!(instances_to_matrix(&[Instance::Values(vec![1.])], &names).is_err());
+        assert!(instances_to_matrix(&[Instance::Named(Default::default())], &names).is_err());
+    }
+}
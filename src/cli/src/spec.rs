@@ -0,0 +1,163 @@
+use std::fs;
+use std::path::Path;
+
+use rune_core::{Error, Result};
+use serde::Deserialize;
+
+/// What `rune train` fits, read from TOML or YAML:
+///
+/// ```toml
+/// target = "species"
+///
+/// [[steps]]
+/// type = "standard_scaler"
+///
+/// [[steps]]
+/// type = "pca"
+/// n_components = 2
+///
+/// [model]
+/// type = "decision_tree"
+/// max_depth = 5
+/// ```
+///
+/// The steps transform the features in order before the model sees them. Hyperparameters left
+/// out take the defaults noted on each variant.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Spec {
+    /// The column of the training data to predict; every other column is a feature.
+    pub target: String,
+    #[serde(default)]
+    pub steps: Vec<StepSpec>,
+    pub model: ModelSpec,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum StepSpec {
+    /// `with_mean` and `with_std` default to true.
+    StandardScaler {
+        #[serde(default = "default_true")]
+        with_mean: bool,
+        #[serde(default = "default_true")]
+        with_std: bool,
+    },
+    /// `whiten` defaults to false. Only available when rune is built with the `decomposition`
+    /// feature.
+    #[cfg_attr(not(feature = "decomposition"), allow(dead_code))]
+    Pca {
+        n_components: usize,
+        #[serde(default)]
+        whiten: bool,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ModelSpec {
+    /// A classifier over the distinct values of the target column, split on entropy. `max_depth`
+    /// defaults to 10 and `min_size` to 1.
+    DecisionTree {
+        #[serde(default = "default_max_depth")]
+        max_depth: u32,
+        #[serde(default = "default_min_size")]
+        min_size: usize,
+    },
+    /// A regressor of the numeric target column, fitted by gradient descent. `alpha`
+    /// defaults to 0.01 and `iterations` to 1000.
+    LinearRegression {
+        #[serde(default = "default_alpha")]
+        alpha: f64,
+        #[serde(default = "default_iterations")]
+        iterations: usize,
+    },
+}
+
+impl ModelSpec {
+    pub fn is_classifier(&self) -> bool {
+        matches!(self, ModelSpec::DecisionTree { .. })
+    }
+}
+
+impl Spec {
+    /// Reads `path` as YAML when it ends in `.yaml` or `.yml`, as TOML otherwise.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Spec> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+        let invalid = |message: String| Error::InvalidInput(format!("invalid spec {}: {}", path.display(), message));
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&contents).map_err(|e| invalid(e.to_string())),
+            _ => toml::from_str(&contents).map_err(|e| invalid(e.to_string())),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_depth() -> u32 {
+    10
+}
+
+fn default_min_size() -> usize {
+    1
+}
+
+fn default_alpha() -> f64 {
+    0.01
+}
+
+fn default_iterations() -> usize {
+    1000
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spec::{ModelSpec, Spec, StepSpec};
+
+    #[test]
+    fn test_toml_and_yaml_specs_agree() {
+        let toml: Spec = toml::from_str(r#"
+            target = "price"
+
+            [[steps]]
+            type = "standard_scaler"
+            with_mean = false
+
+            [model]
+            type = "linear_regression"
+            iterations = 50
+        "#).unwrap();
+        let yaml: Spec = serde_yaml::from_str("
+            target: price
+            steps:
+              - type: standard_scaler
+                with_mean: false
+            model:
+              type: linear_regression
+              iterations: 50
+        ").unwrap();
+
+        for spec in [toml, yaml].iter() {
+            assert_eq!(spec.target, "price");
+            assert!(matches!(spec.steps[..], [StepSpec::StandardScaler { with_mean: false, with_std: true }]));
+            assert!(matches!(spec.model, ModelSpec::LinearRegression { iterations: 50, .. }));
+        }
+    }
+
+    #[test]
+    fn test_misspelt_hyperparameters_are_rejected() {
+        let spec = toml::from_str::<Spec>(r#"
+            target = "species"
+
+            [model]
+            type = "decision_tree"
+            max_dept = 3
+        "#);
+
+        assert!(spec.is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Hooks called while an estimator fits, e.g. to drive a progress bar or report to an experiment
+/// tracker. Every hook does nothing by default and gets the time elapsed since the fit started.
+/// Estimators that fit on several threads, such as grid search, may call them from any of them.
+pub trait FitCallback: Send + Sync {
+    /// After every full pass over the training data, e.g. a gradient descent step, with the
+    /// training loss after it.
+    fn on_epoch(&self, _epoch: usize, _loss: f64, _elapsed: Duration) {}
+
+    /// After every step of an estimator that works in steps other than epochs, e.g. a boosting
+    /// stage, a KMeans iteration or a grid search candidate, with its loss.
+    fn on_iteration(&self, _iteration: usize, _loss: f64, _elapsed: Duration) {}
+
+    /// After a tree node at `depth` is split on `feature` at `threshold`, with the impurity of
+    /// the node before the split.
+    fn on_split(&self, _depth: usize, _feature: usize, _threshold: f64, _impurity: f64, _elapsed: Duration) {}
+}
+
+/// The callbacks registered on an estimator, called in the order they were added. Cloning shares
+/// them, so every copy of an estimator reports to the same callbacks.
+#[derive(Clone, Default)]
+pub struct Callbacks {
+    callbacks: Vec<Arc<dyn FitCallback>>,
+}
+
+impl Callbacks {
+    pub fn new() -> Self {
+        Callbacks::default()
+    }
+
+    pub fn push<C: FitCallback + 'static>(&mut self, callback: C) {
+        self.callbacks.push(Arc::new(callback));
+    }
+}
+
+impl FitCallback for Callbacks {
+    fn on_epoch(&self, epoch: usize, loss: f64, elapsed: Duration) {
+        self.callbacks.iter().for_each(|callback| callback.on_epoch(epoch, loss, elapsed));
+    }
+
+    fn on_iteration(&self, iteration: usize, loss: f64, elapsed: Duration) {
+        self.callbacks.iter().for_each(|callback| callback.on_iteration(iteration, loss, elapsed));
+    }
+
+    fn on_split(&self, depth: usize, feature: usize, threshold: f64, impurity: f64, elapsed: Duration) {
+        self.callbacks.iter().for_each(|callback| callback.on_split(depth, feature, threshold, impurity, elapsed));
+    }
+}
+
+impl fmt::Debug for Callbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Callbacks({})", self.callbacks.len())
+    }
+}
@@ -0,0 +1,83 @@
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::validation::check_finite;
+
+/// What each kind of mistake costs, for classifiers to predict the class with the least expected
+/// cost under their class probabilities rather than the most probable one, e.g. when missing a
+/// fraud costs far more than flagging an honest transaction. `costs[[actual, predicted]]` is the
+/// cost of predicting class `predicted` for a row of class `actual`, classes being numbered as
+/// the model numbers them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostMatrix {
+    costs: Array2<f64>,
+}
+
+impl CostMatrix {
+    /// `costs` must be square, with a row and a column per class, and finite.
+    pub fn new(costs: Array2<f64>) -> Result<Self> {
+        if costs.nrows() != costs.ncols() || costs.nrows() < 2 {
+            return Err(Error::ShapeMismatch(format!("a cost matrix needs a row and a column per class, got {:?}", costs.dim())));
+        }
+        check_finite("costs", costs.view())?;
+
+        Ok(CostMatrix { costs })
+    }
+
+    /// Every mistake costs 1 and every correct prediction nothing, so the least costly class is
+    /// the most probable one.
+    pub fn zero_one(n_classes: usize) -> Self {
+        assert!(n_classes >= 2, "a cost matrix needs at least 2 classes");
+
+        CostMatrix { costs: Array2::from_shape_fn((n_classes, n_classes), |(actual, predicted)| if actual == predicted { 0. } else { 1. }) }
+    }
+
+    /// Binary costs, with class 0 the negative and class 1 the positive: a false positive costs
+    /// `false_positive` and a false negative `false_negative`, correct predictions nothing.
+    pub fn binary(false_positive: f64, false_negative: f64) -> Result<Self> {
+        CostMatrix::new(Array2::from_shape_vec((2, 2), vec![0., false_positive, false_negative, 0.])?)
+    }
+
+    pub fn n_classes(&self) -> usize {
+        self.costs.nrows()
+    }
+
+    pub fn costs(&self) -> ArrayView2<'_, f64> {
+        self.costs.view()
+    }
+
+    /// The expected cost of predicting each class for a row with the class probabilities
+    /// `probabilities`.
+    pub fn expected_costs(&self, probabilities: ArrayView1<f64>) -> Array1<f64> {
+        assert_eq!(probabilities.len(), self.n_classes(), "one probability is required per class");
+
+        probabilities.dot(&self.costs)
+    }
+
+    /// The class with the least expected cost for a row with the class probabilities
+    /// `probabilities`, the first of equals.
+    pub fn decide(&self, probabilities: ArrayView1<f64>) -> usize {
+        let expected = self.expected_costs(probabilities);
+
+        (0..expected.len()).fold(0, |best, class| if expected[class] < expected[best] { class } else { best })
+    }
+
+    /// `decide` for every row of `probabilities`, which has a column per class.
+    pub fn decide_rows(&self, probabilities: ArrayView2<f64>) -> Array1<usize> {
+        probabilities.axis_iter(Axis(0)).map(|row| self.decide(row)).collect()
+    }
+
+    /// For two classes, the positive probability at or above which predicting the positive class
+    /// costs no more than predicting the negative one: the operating point of the ROC curve these
+    /// costs pick. `0.5` for equal costs.
+    pub fn binary_threshold(&self) -> f64 {
+        assert_eq!(self.n_classes(), 2, "a probability threshold only exists for 2 classes");
+
+        let c = &self.costs;
+        let negative_regret = c[[0, 1]] - c[[0, 0]];
+        let positive_regret = c[[1, 0]] - c[[1, 1]];
+
+        negative_regret / (negative_regret + positive_regret)
+    }
+}
@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+/// Everything that can go wrong while fitting or persisting a rune estimator.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// A hyperparameter is outside the range the estimator supports.
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
+
+    /// The data can't be used by the estimator, e.g. non-positive values for Box-Cox.
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
+    /// Two inputs that must line up don't, e.g. `x` and `y` with a different number of samples.
+    #[error("shape mismatch: {0}")]
+    ShapeMismatch(String),
+
+    #[error("linear algebra failure: {0}")]
+    LinearAlgebra(String),
+
+    #[error(transparent)]
+    Shape(#[from] ndarray::ShapeError),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("serialization failure: {0}")]
+    Serialization(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
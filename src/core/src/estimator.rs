@@ -0,0 +1,81 @@
+//! The traits every estimator implements, so generic utilities such as pipelines, cross
+//! validation, grid search and ensembles can take any of them.
+
+use std::any::type_name;
+
+use ndarray::{Array2, ArrayView1, ArrayView2};
+
+use crate::error::Result;
+
+pub trait Transformer<In, Out> {
+    fn transform(&self, x: In) -> Out;
+
+    /// Maps transformed features back to the original feature space, e.g. to read reduced
+    /// representations in terms of the input features. Not every transformer is invertible; those
+    /// that are not panic.
+    fn inverse_transform(&self, _x: ArrayView2<f64>) -> Array2<f64> {
+        unimplemented!("{} does not support inverse_transform", type_name::<Self>())
+    }
+}
+
+/// Maps the output `Out` of a transformer back to its input `In`, for transformers whose input
+/// isn't a feature matrix of `f64`, such as encoders of labels or categories, which
+/// `Transformer::inverse_transform` can't express. Fails on values the transformer can't have
+/// produced.
+pub trait InverseTransformer<Out, In> {
+    fn inverse_transform(&self, x: Out) -> Result<In>;
+}
+
+/// A fitted estimator mapping the rows of `X` to predictions `Y`, e.g. the class labels of a
+/// decision tree or the decision scores of a binary model.
+pub trait Predictor<X, Y> {
+    fn predict(&self, x: X) -> Y;
+}
+
+/// A fitted classifier that can also report how confident it is, as the probability `P` of
+/// each row belonging to the positive class.
+pub trait ProbabilisticPredictor<X, P> {
+    fn predict_proba(&self, x: X) -> P;
+}
+
+/// How well a fitted estimator predicts the labels `y` of `x`, higher being better: accuracy for
+/// classifiers and R² for regressors.
+pub trait Score<X, Y> {
+    fn score(&self, x: X, y: ArrayView1<Y>) -> f64;
+}
+
+/// Learns from `x` and the labels `y`, producing the fitted transformer `Out`. `Y` is the label
+/// type: `bool` for binary classifiers, `f64` for regressors, and so on. Steps that ignore the
+/// labels, such as scalers, implement `Fit` for every `Y`.
+pub trait Fit<In, Out, Y = bool> {
+    fn fit(&self, x: In, y: ArrayView1<Y>) -> Result<Out>;
+}
+
+/// Keeps learning from further batches of rows after `fit` has been called on the first one, so
+/// data that does not fit in memory can be streamed through the model chunk by chunk.
+pub trait IncrementalFit<In, Out, Y = bool>: Fit<In, Out, Y> {
+    fn partial_fit(&self, model: &mut Out, x: In, y: ArrayView1<Y>) -> Result<()>;
+}
+
+/// Fits a step without labels. Only available for steps that ignore `y`, i.e. that implement
+/// `Fit` for every label type; supervised steps such as `SelectKBest` must be given their labels.
+pub trait UnsupervisedFit<In, Out> {
+    fn fit_unsupervised(&self, x: In) -> Result<Out>;
+}
+
+impl<F, In, Out> UnsupervisedFit<In, Out> for F where F: Fit<In, Out, ()> {
+    fn fit_unsupervised(&self, x: In) -> Result<Out> {
+        self.fit(x, ArrayView1::from(&[]))
+    }
+}
+
+/// Fits and transforms the training data in one go, returning the fitted transformer together
+/// with the transformed `x`. The default fits and then transforms; fitters that already compute
+/// the transformed data while fitting override it to skip the second pass.
+pub trait FitTransform<In, T, Out, Y = bool>: Fit<In, T, Y> where In: Clone, T: Transformer<In, Out> {
+    fn fit_transform(&self, x: In, y: ArrayView1<Y>) -> Result<(T, Out)> {
+        let transformer = self.fit(x.clone(), y)?;
+        let transformed = transformer.transform(x);
+        Ok((transformer, transformed))
+    }
+}
@@ -0,0 +1,14 @@
+pub mod callback;
+pub mod cost_matrix;
+pub mod error;
+pub mod estimator;
+pub mod math;
+pub mod parallel;
+pub mod random;
+pub mod validation;
+
+pub use crate::callback::{Callbacks, FitCallback};
+pub use crate::cost_matrix::CostMatrix;
+pub use crate::error::{Error, Result};
+pub use crate::estimator::{Fit, FitTransform, IncrementalFit, InverseTransformer, Predictor, ProbabilisticPredictor, Score, Transformer, UnsupervisedFit};
+pub use crate::random::RngSource;
@@ -0,0 +1,142 @@
+use std::cmp::Ordering;
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+use serde::{Deserialize, Serialize};
+
+use crate::math::quantile::{quantile_sorted, Interpolation};
+
+/// The compact integer type bin indexes are stored in: `u8` for up to 256 bins, `u16` for up to
+/// 65536.
+pub trait BinIndex: Copy + Default + Send + Sync {
+    const MAX_BINS: usize;
+
+    fn from_bin(bin: usize) -> Self;
+
+    fn bin(self) -> usize;
+}
+
+impl BinIndex for u8 {
+    const MAX_BINS: usize = 1 << 8;
+
+    fn from_bin(bin: usize) -> Self {
+        bin as u8
+    }
+
+    fn bin(self) -> usize {
+        self as usize
+    }
+}
+
+impl BinIndex for u16 {
+    const MAX_BINS: usize = 1 << 16;
+
+    fn from_bin(bin: usize) -> Self {
+        bin as u16
+    }
+
+    fn bin(self) -> usize {
+        self as usize
+    }
+}
+
+/// Computes quantile bin edges for every feature once, so that data can be replaced by small
+/// integer bin indexes, e.g. for histogram split finding in trees that see the same rows many
+/// times over.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Binner {
+    max_bins: usize,
+}
+
+/// The edges of every feature's bins, including the outer minimum and maximum, as fitted by a
+/// `Binner`. A value falls in the bin whose lower edge is the last inner edge at or below it, so
+/// values beyond the fitted range fall in the first or last bin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BinEdges {
+    edges: Vec<Array1<f64>>,
+}
+
+impl Binner {
+    /// At most `max_bins` bins per feature; features with fewer distinct values get fewer.
+    pub fn new(max_bins: usize) -> Self {
+        assert!(max_bins >= 1, "at least 1 bin is required");
+
+        Binner {
+            max_bins,
+        }
+    }
+
+    pub fn fit(&self, x: ArrayView2<f64>) -> BinEdges {
+        let edges = x.gencolumns()
+            .into_iter()
+            .map(|column| {
+                let mut sorted = column.to_vec();
+                sorted.sort_by(|l, r| l.partial_cmp(r).unwrap_or(Ordering::Equal));
+
+                let mut edges = quantile_edges(&sorted, self.max_bins);
+                edges.dedup_by(|l, r| (*l - *r).abs() < 1e-8);
+                if edges.len() < 2 {
+                    edges = vec![sorted[0], sorted[0]];
+                }
+
+                Array1::from(edges)
+            })
+            .collect();
+
+        BinEdges { edges }
+    }
+}
+
+/// The `n_bins + 1` edges splitting `sorted` into bins of (roughly) equal counts, from its
+/// minimum to its maximum. Repeated values can make consecutive edges equal.
+pub fn quantile_edges(sorted: &[f64], n_bins: usize) -> Vec<f64> {
+    (0..=n_bins)
+        .map(|i| quantile_sorted(sorted, i as f64 / n_bins as f64, Interpolation::Linear))
+        .collect()
+}
+
+/// The bin of `edges`, laid out as in `BinEdges`, that `value` falls in.
+pub fn bin_index(edges: ArrayView1<f64>, value: f64) -> usize {
+    let n_bins = edges.len() - 1;
+
+    // The inner edges are sorted, so the count of those at or below the value is a partition point.
+    let (mut low, mut high) = (1, n_bins);
+    while low < high {
+        let middle = (low + high) / 2;
+        if edges[middle] <= value {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+
+    low - 1
+}
+
+impl BinEdges {
+    /// Edges of every feature, including the outer minimum and maximum.
+    pub fn edges(&self) -> &[Array1<f64>] {
+        &self.edges
+    }
+
+    pub fn n_features(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn n_bins(&self, feature: usize) -> usize {
+        self.edges[feature].len() - 1
+    }
+
+    /// The edge between `bin` and the bin after it: values below it fall in `bin` or before.
+    pub fn upper_edge(&self, feature: usize, bin: usize) -> f64 {
+        self.edges[feature][bin + 1]
+    }
+
+    /// The bin of every value of `x`, which must have as many columns as the fitted data and at
+    /// most `B::MAX_BINS` bins per feature.
+    pub fn transform<B: BinIndex>(&self, x: ArrayView2<f64>) -> Array2<B> {
+        assert_eq!(x.ncols(), self.edges.len(), "x must have a column per binned feature");
+        assert!((0..self.edges.len()).all(|feature| self.n_bins(feature) <= B::MAX_BINS), "too many bins for the bin index type");
+
+        Array2::from_shape_fn(x.dim(), |(row, column)| B::from_bin(bin_index(self.edges[column].view(), x[[row, column]])))
+    }
+}
@@ -0,0 +1,153 @@
+use std::cmp::Ordering;
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2};
+
+/// Solves `a x = b` for a symmetric positive definite `a` through its Cholesky factorization,
+/// e.g. the regularized normal equations of ridge regression. `None` when `a` isn't positive
+/// definite.
+pub fn cholesky_solve(a: ArrayView2<f64>, b: ArrayView1<f64>) -> Option<Array1<f64>> {
+    let n = a.nrows();
+    assert_eq!(a.ncols(), n, "a must be square");
+    assert_eq!(b.len(), n, "b must have a value per row of a");
+
+    // a = l lᵀ, l lower triangular.
+    let mut l = Array2::<f64>::zeros((n, n));
+    for i in 0..n {
+        for j in 0..=i {
+            let dot: f64 = (0..j).map(|k| l[[i, k]] * l[[j, k]]).sum();
+            if i == j {
+                let pivot = a[[i, i]] - dot;
+                if !(pivot > 0. && pivot.is_finite()) {
+                    return None;
+                }
+                l[[i, i]] = pivot.sqrt();
+            } else {
+                l[[i, j]] = (a[[i, j]] - dot) / l[[j, j]];
+            }
+        }
+    }
+
+    // Forward substitution for l z = b, then back substitution for lᵀ x = z.
+    let mut x = b.to_owned();
+    for i in 0..n {
+        let dot: f64 = (0..i).map(|k| l[[i, k]] * x[k]).sum();
+        x[i] = (x[i] - dot) / l[[i, i]];
+    }
+    for i in (0..n).rev() {
+        let dot: f64 = (i + 1..n).map(|k| l[[k, i]] * x[k]).sum();
+        x[i] = (x[i] - dot) / l[[i, i]];
+    }
+
+    Some(x)
+}
+
+/// The eigenvalues of the symmetric matrix `a`, ascending, and its eigenvectors as the columns
+/// in the same order, by cyclic Jacobi rotations. Accurate to working precision, and quick
+/// enough for matrices of up to a few hundred rows, such as the Gram matrix of the features.
+pub fn symmetric_eigen(a: ArrayView2<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = a.nrows();
+    assert_eq!(a.ncols(), n, "a must be square");
+
+    let mut a = a.to_owned();
+    let mut vectors = Array2::<f64>::eye(n);
+    let norm: f64 = a.iter().map(|v| v * v).sum();
+
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..n).flat_map(|p| (p + 1..n).map(move |q| (p, q))).map(|(p, q)| a[[p, q]] * a[[p, q]]).sum();
+        if off_diagonal <= f64::EPSILON * f64::EPSILON * norm {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[[p, q]] == 0. {
+                    continue;
+                }
+
+                // The rotation in the (p, q) plane that zeroes a[[p, q]], taking the smaller
+                // angle for stability.
+                let theta = (a[[q, q]] - a[[p, p]]) / (2. * a[[p, q]]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+                let c = 1. / (t * t + 1.).sqrt();
+                let s = t * c;
+
+                for k in 0..n {
+                    let (kp, kq) = (a[[k, p]], a[[k, q]]);
+                    a[[k, p]] = c * kp - s * kq;
+                    a[[k, q]] = s * kp + c * kq;
+                }
+                for k in 0..n {
+                    let (pk, qk) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = c * pk - s * qk;
+                    a[[q, k]] = s * pk + c * qk;
+                }
+                for k in 0..n {
+                    let (kp, kq) = (vectors[[k, p]], vectors[[k, q]]);
+                    vectors[[k, p]] = c * kp - s * kq;
+                    vectors[[k, q]] = s * kp + c * kq;
+                }
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&l, &r| a[[l, l]].partial_cmp(&a[[r, r]]).unwrap_or(Ordering::Equal));
+
+    let values = order.iter().map(|&i| a[[i, i]]).collect();
+    let vectors = Array2::from_shape_fn((n, n), |(row, column)| vectors[[row, order[column]]]);
+
+    (values, vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2, Array2};
+
+    use crate::math::linalg::{cholesky_solve, symmetric_eigen};
+
+    #[test]
+    fn test_cholesky_solve() {
+        let a = arr2(&[[4., 2., 0.6], [2., 5., 1.], [0.6, 1., 3.]]);
+        let b = arr1(&[1., -2., 3.]);
+        let x = cholesky_solve(a.view(), b.view()).unwrap();
+
+        for (product, expected) in a.dot(&x).iter().zip(b.iter()) {
+            assert!((product - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_cholesky_solve_rejects_matrices_that_are_not_positive_definite() {
+        let singular = arr2(&[[1., 2.], [2., 4.]]);
+        assert!(cholesky_solve(singular.view(), arr1(&[1., 1.]).view()).is_none());
+
+        let indefinite = arr2(&[[1., 0.], [0., -1.]]);
+        assert!(cholesky_solve(indefinite.view(), arr1(&[1., 1.]).view()).is_none());
+    }
+
+    #[test]
+    fn test_symmetric_eigen_reconstructs_the_matrix() {
+        let a = arr2(&[[2., -1., 0., 0.5], [-1., 2., -1., 0.], [0., -1., 2., 1.], [0.5, 0., 1., -3.]]);
+        let (values, vectors) = symmetric_eigen(a.view());
+
+        assert!(values.windows(2).into_iter().all(|pair| pair[0] <= pair[1]));
+
+        let identity = vectors.t().dot(&vectors);
+        for (product, expected) in identity.iter().zip(Array2::<f64>::eye(4).iter()) {
+            assert!((product - expected).abs() < 1e-12);
+        }
+
+        let reconstructed = vectors.dot(&Array2::from_diag(&values)).dot(&vectors.t());
+        for (reconstructed, expected) in reconstructed.iter().zip(a.iter()) {
+            assert!((reconstructed - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_symmetric_eigen_of_a_diagonal_matrix() {
+        let (values, vectors) = symmetric_eigen(arr2(&[[3., 0.], [0., 1.]]).view());
+
+        assert_eq!(values, arr1(&[1., 3.]));
+        assert_eq!(vectors, arr2(&[[0., 1.], [1., 0.]]));
+    }
+}
@@ -0,0 +1,7 @@
+//! Numerical helpers shared by the estimators, so each doesn't reimplement its own.
+
+pub mod binning;
+pub mod linalg;
+pub mod quantile;
+pub mod special;
+pub mod stats;
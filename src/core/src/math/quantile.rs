@@ -0,0 +1,73 @@
+use std::cmp::Ordering;
+
+use ndarray::{Array1, ArrayView1, ArrayView2, Axis};
+
+/// How a quantile falling between two sorted values `lower` and `upper` is estimated, as in
+/// numpy's `quantile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Linearly between `lower` and `upper`.
+    #[default]
+    Linear,
+    Lower,
+    Higher,
+    /// Whichever of `lower` and `upper` is closest, `lower` on a tie.
+    Nearest,
+    /// Halfway between `lower` and `upper`.
+    Midpoint,
+}
+
+impl Interpolation {
+    fn interpolate(self, lower: f64, upper: f64, fraction: f64) -> f64 {
+        match self {
+            Interpolation::Linear => lower + (upper - lower) * fraction,
+            Interpolation::Lower => lower,
+            Interpolation::Higher => upper,
+            Interpolation::Nearest => if fraction > 0.5 { upper } else { lower },
+            Interpolation::Midpoint => if fraction > 0. { (lower + upper) / 2. } else { lower },
+        }
+    }
+}
+
+/// The `q`th quantile of `values`, for `q` between 0 and 1.
+pub fn quantile(values: ArrayView1<f64>, q: f64, interpolation: Interpolation) -> f64 {
+    quantile_sorted(&sorted(values), q, interpolation)
+}
+
+/// `quantile` for every `q` in `qs`, sorting `values` only once.
+pub fn quantiles(values: ArrayView1<f64>, qs: ArrayView1<f64>, interpolation: Interpolation) -> Array1<f64> {
+    let sorted = sorted(values);
+    qs.mapv(|q| quantile_sorted(&sorted, q, interpolation))
+}
+
+/// `quantile` of values that are already sorted in increasing order.
+pub fn quantile_sorted(sorted: &[f64], q: f64, interpolation: Interpolation) -> f64 {
+    assert!(!sorted.is_empty(), "the quantile of no values is undefined");
+    assert!((0. ..=1.).contains(&q), "quantiles must be between 0 and 1, got {}", q);
+
+    let position = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+
+    interpolation.interpolate(sorted[lower], sorted[upper], position - lower as f64)
+}
+
+/// The `p`th percentile, for `p` between 0 and 100, of every lane of `x` along `axis`: of each
+/// column for `Axis(0)` and of each row for `Axis(1)`.
+pub fn percentile_axis(x: ArrayView2<f64>, axis: Axis, p: f64, interpolation: Interpolation) -> Array1<f64> {
+    x.lanes(axis).into_iter().map(|lane| quantile(lane, p / 100., interpolation)).collect()
+}
+
+pub fn median(values: ArrayView1<f64>) -> f64 {
+    quantile(values, 0.5, Interpolation::Linear)
+}
+
+/// The median of every lane of `x` along `axis`, as in `percentile_axis`.
+pub fn median_axis(x: ArrayView2<f64>, axis: Axis) -> Array1<f64> {
+    percentile_axis(x, axis, 50., Interpolation::Linear)
+}
+
+fn sorted(values: ArrayView1<f64>) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|l, r| l.partial_cmp(r).unwrap_or(Ordering::Equal));
+    sorted
+}
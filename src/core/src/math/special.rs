@@ -0,0 +1,238 @@
+use std::f64::consts::PI;
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis, Zip};
+
+/// The logistic function `1 / (1 + exp(-z))`, which never overflows: `exp` is only taken of
+/// non-positive numbers.
+pub fn sigmoid(z: f64) -> f64 {
+    if z >= 0. {
+        1. / (1. + (-z).exp())
+    } else {
+        z.exp() / (1. + z.exp())
+    }
+}
+
+/// `ln(1 + exp(z))`, without overflowing for large `z` or losing precision for very negative
+/// `z`. It's the negative log likelihood of a logistic model scoring `-z` for the true class.
+pub fn softplus(z: f64) -> f64 {
+    if z >= 0. {
+        z + (-z).exp().ln_1p()
+    } else {
+        z.exp().ln_1p()
+    }
+}
+
+/// `ln(sum(exp(values)))`, shifting by the largest value so no `exp` overflows. It's negative
+/// infinity for no values.
+pub fn logsumexp(values: ArrayView1<f64>) -> f64 {
+    let max = values.fold(f64::NEG_INFINITY, |max, &v| max.max(v));
+    if max.is_infinite() {
+        return max;
+    }
+
+    max + values.fold(0., |sum, &v| sum + (v - max).exp()).ln()
+}
+
+/// `logsumexp` of every lane of `x` along `axis`, as in `quantile::percentile_axis`.
+pub fn logsumexp_axis(x: ArrayView2<f64>, axis: Axis) -> Array1<f64> {
+    x.lanes(axis).into_iter().map(logsumexp).collect()
+}
+
+/// `exp(values)` normalized to sum to one, e.g. class probabilities from per-class scores.
+pub fn softmax(values: ArrayView1<f64>) -> Array1<f64> {
+    let normalizer = logsumexp(values);
+    values.mapv(|v| (v - normalizer).exp())
+}
+
+/// `softmax` of every lane of `x` along `axis`: of each row, for per-class scores in columns,
+/// with `Axis(1)`.
+pub fn softmax_axis(x: ArrayView2<f64>, axis: Axis) -> Array2<f64> {
+    let mut probabilities = x.to_owned();
+    Zip::from(probabilities.lanes_mut(axis))
+        .and(x.lanes(axis))
+        .apply(|mut lane, values| lane.assign(&softmax(values)));
+    probabilities
+}
+
+/// The natural logarithm of the gamma function of a positive `x`, by the Lanczos
+/// approximation, accurate to about 15 significant digits.
+///
+/// # Panics
+///
+/// If `x` isn't positive.
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    assert!(x > 0., "ln_gamma is only defined here for positive x, got {}", x);
+
+    if x < 0.5 {
+        // The reflection formula Γ(x) Γ(1 - x) = π / sin(πx), as the series is poor near 0.
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1. - x);
+    }
+
+    let x = x - 1.;
+    let sum = COEFFICIENTS[1..].iter().enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, &c)| sum + c / (x + (i + 1) as f64));
+    let t = x + 7.5;
+
+    0.5 * (2. * PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The regularized lower incomplete gamma function `P(a, x)`, the probability that a gamma
+/// variable of shape `a` and unit scale is at most `x`: by its series below `a + 1` and by the
+/// continued fraction of its complement above, each converging quickly there.
+///
+/// # Panics
+///
+/// If `a` isn't positive.
+pub fn gamma_p(a: f64, x: f64) -> f64 {
+    assert!(a > 0., "the shape must be positive, got {}", a);
+    if x <= 0. {
+        return 0.;
+    }
+    let prefactor = (a * x.ln() - x - ln_gamma(a)).exp();
+
+    if x < a + 1. {
+        let (mut term, mut sum) = (1. / a, 1. / a);
+        for n in 1..1000 {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * f64::EPSILON {
+                break;
+            }
+        }
+        (sum * prefactor).min(1.)
+    } else {
+        // Lentz's method for the continued fraction of Q(a, x) = 1 - P(a, x).
+        const TINY: f64 = 1e-300;
+        let mut b = x + 1. - a;
+        let mut c = 1. / TINY;
+        let mut d = 1. / b;
+        let mut fraction = d;
+        for i in 1..1000 {
+            let an = -(i as f64) * (i as f64 - a);
+            b += 2.;
+            d = an * d + b;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = b + an / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1. / d;
+            let delta = d * c;
+            fraction *= delta;
+            if (delta - 1.).abs() < f64::EPSILON {
+                break;
+            }
+        }
+        (1. - prefactor * fraction).max(0.)
+    }
+}
+
+/// The probability that a chi-squared variable with `k` degrees of freedom is at most `x`.
+///
+/// # Panics
+///
+/// If `k` isn't positive.
+pub fn chi_squared_cdf(x: f64, k: f64) -> f64 {
+    gamma_p(k / 2., x / 2.)
+}
+
+/// The `q`th quantile, for `q` in `[0, 1)`, of the chi-squared distribution with `k` degrees of
+/// freedom, e.g. the squared Mahalanobis distance that a fraction `q` of normal data falls
+/// within. Found by bisection, to working precision.
+///
+/// # Panics
+///
+/// If `q` is outside `[0, 1)` or `k` isn't positive.
+pub fn chi_squared_quantile(q: f64, k: f64) -> f64 {
+    assert!((0. ..1.).contains(&q), "q must be in [0, 1), got {}", q);
+    assert!(k > 0., "the degrees of freedom must be positive, got {}", k);
+
+    let mut upper = k.max(1.);
+    while chi_squared_cdf(upper, k) < q {
+        upper *= 2.;
+    }
+    let mut lower = 0.;
+    while upper - lower > f64::EPSILON * upper {
+        let middle = (lower + upper) / 2.;
+        if middle <= lower || middle >= upper {
+            break;
+        }
+        if chi_squared_cdf(middle, k) < q {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+
+    (lower + upper) / 2.
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::math::special::{chi_squared_cdf, chi_squared_quantile, gamma_p, ln_gamma};
+
+    #[test]
+    fn test_ln_gamma_known_values() {
+        assert!((ln_gamma(0.5) - PI.sqrt().ln()).abs() < 1e-12);
+        assert!(ln_gamma(1.).abs() < 1e-12);
+        assert!(ln_gamma(2.).abs() < 1e-12);
+        assert!((ln_gamma(10.) - 362_880_f64.ln()).abs() < 1e-10);
+        // Through the reflection formula: Γ(0.25) = 3.625609908221908.
+        assert!((ln_gamma(0.25) - 3.625_609_908_221_908_f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gamma_p_known_values() {
+        // Shape 1 is the exponential distribution, on both sides of the series/fraction switch.
+        for &x in &[0.1, 1., 2.5, 10.] {
+            assert!((gamma_p(1., x) - (1. - (-x).exp())).abs() < 1e-12);
+        }
+        assert_eq!(gamma_p(3., 0.), 0.);
+        assert_eq!(gamma_p(3., -1.), 0.);
+    }
+
+    #[test]
+    fn test_chi_squared_quantile_known_values() {
+        assert!((chi_squared_quantile(0.5, 1.) - 0.454_936_423).abs() < 1e-8);
+        assert!((chi_squared_quantile(0.5, 2.) - 4_f64.ln()).abs() < 1e-12);
+        assert!((chi_squared_quantile(0.975, 2.) - 7.377_758_908).abs() < 1e-8);
+        assert!((chi_squared_quantile(0.975, 5.) - 12.832_501_994).abs() < 1e-8);
+        assert_eq!(chi_squared_quantile(0., 3.), 0.);
+    }
+
+    #[test]
+    fn test_chi_squared_quantile_inverts_cdf() {
+        for &k in &[1., 3., 10.] {
+            for &q in &[0.05, 0.5, 0.9, 0.999] {
+                assert!((chi_squared_cdf(chi_squared_quantile(q, k), k) - q).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ln_gamma_panics_on_non_positive() {
+        ln_gamma(0.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_chi_squared_quantile_panics_on_one() {
+        chi_squared_quantile(1., 2.);
+    }
+}
@@ -0,0 +1,116 @@
+use ndarray::{Array1, ArrayView1, ArrayView2, Axis};
+use serde::{Deserialize, Serialize};
+
+/// The per-feature mean and variance of rows seen one at a time or in batches, using Welford's
+/// algorithm so the data never has to be held in memory at once. Rows may carry weights, which
+/// count as that many repetitions of the row.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunningStats {
+    n_samples: usize,
+    sum_weights: f64,
+    mean: Array1<f64>,
+    /// The weighted sum of squared deviations from `mean`.
+    m2: Array1<f64>,
+}
+
+impl RunningStats {
+    pub fn new(n_features: usize) -> Self {
+        RunningStats {
+            n_samples: 0,
+            sum_weights: 0.,
+            mean: Array1::zeros(n_features),
+            m2: Array1::zeros(n_features),
+        }
+    }
+
+    /// The statistics of the rows of `x`, computed in one pass over the whole batch.
+    pub fn of(x: ArrayView2<f64>) -> Self {
+        if x.nrows() == 0 {
+            return RunningStats::new(x.ncols());
+        }
+
+        let mean = x.mean_axis(Axis(0)).unwrap();
+        let m2 = (&x - &mean).mapv(|v| v * v).sum_axis(Axis(0));
+
+        RunningStats {
+            n_samples: x.nrows(),
+            sum_weights: x.nrows() as f64,
+            mean,
+            m2,
+        }
+    }
+
+    /// `of` with a weight per row of `x`.
+    pub fn of_weighted(x: ArrayView2<f64>, weights: ArrayView1<f64>) -> Self {
+        let mut stats = RunningStats::new(x.ncols());
+        for (row, &weight) in x.genrows().into_iter().zip(weights.iter()) {
+            stats.push_weighted(row, weight);
+        }
+        stats
+    }
+
+    pub fn push(&mut self, row: ArrayView1<f64>) {
+        self.push_weighted(row, 1.);
+    }
+
+    /// West's weighted form of Welford's update.
+    pub fn push_weighted(&mut self, row: ArrayView1<f64>, weight: f64) {
+        assert_eq!(row.len(), self.mean.len(), "expected a row of {} features", self.mean.len());
+
+        self.n_samples += 1;
+        if weight == 0. {
+            return;
+        }
+        self.sum_weights += weight;
+
+        let delta = &row - &self.mean;
+        self.mean.scaled_add(weight / self.sum_weights, &delta);
+        self.m2 += &(&delta * &(&row - &self.mean) * weight);
+    }
+
+    /// Adds every row of `x`, merging them in as a batch.
+    pub fn update(&mut self, x: ArrayView2<f64>) {
+        self.merge(&RunningStats::of(x));
+    }
+
+    /// Combines the statistics of two disjoint sets of rows with Chan et al.'s pairwise update,
+    /// which stays accurate however many batches are merged.
+    pub fn merge(&mut self, other: &RunningStats) {
+        assert_eq!(other.mean.len(), self.mean.len(), "cannot merge statistics of {} and {} features", self.mean.len(), other.mean.len());
+
+        if other.sum_weights == 0. {
+            self.n_samples += other.n_samples;
+            return;
+        }
+
+        let sum_weights = self.sum_weights + other.sum_weights;
+        let delta = &other.mean - &self.mean;
+
+        self.m2 = &self.m2 + &other.m2 + &(delta.mapv(|d| d * d) * (self.sum_weights * other.sum_weights / sum_weights));
+        self.mean.scaled_add(other.sum_weights / sum_weights, &delta);
+        self.n_samples += other.n_samples;
+        self.sum_weights = sum_weights;
+    }
+
+    pub fn n_samples(&self) -> usize {
+        self.n_samples
+    }
+
+    pub fn sum_weights(&self) -> f64 {
+        self.sum_weights
+    }
+
+    pub fn mean(&self) -> ArrayView1<'_, f64> {
+        self.mean.view()
+    }
+
+    /// The variance of every feature, dividing the squared deviations by the sum of the weights
+    /// less `ddof`: 0 for the population variance, 1 for the unbiased sample variance.
+    pub fn variance(&self, ddof: f64) -> Array1<f64> {
+        &self.m2 / (self.sum_weights - ddof)
+    }
+
+    pub fn std_dev(&self, ddof: f64) -> Array1<f64> {
+        self.variance(ddof).mapv(f64::sqrt)
+    }
+}
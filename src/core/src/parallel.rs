@@ -0,0 +1,54 @@
+#[cfg(feature = "parallel")]
+use {
+    rayon::prelude::*,
+    rayon::{ThreadPool, ThreadPoolBuilder},
+    std::sync::{Arc, Mutex},
+};
+
+/// The pools started so far, one per number of threads. Starting threads costs more than many of
+/// the tasks run on them, so every call with the same `n_jobs` shares a pool.
+#[cfg(feature = "parallel")]
+static POOLS: Mutex<Vec<(usize, Arc<ThreadPool>)>> = Mutex::new(Vec::new());
+
+#[cfg(feature = "parallel")]
+fn pool(n_jobs: usize) -> Arc<ThreadPool> {
+    let mut pools = POOLS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((_, pool)) = pools.iter().find(|(threads, _)| *threads == n_jobs) {
+        return pool.clone();
+    }
+
+    let pool = Arc::new(ThreadPoolBuilder::new()
+        .num_threads(n_jobs)
+        .build()
+        .expect("failed to start worker threads"));
+    pools.push((n_jobs, pool.clone()));
+    pool
+}
+
+/// Applies `f` to every item on a pool of `n_jobs` worker threads, returning the results in the
+/// order of `items`. The pool is started by the first call with this `n_jobs` and reused by the
+/// rest. With `n_jobs <= 1` everything runs on the calling thread.
+#[cfg(feature = "parallel")]
+pub fn parallel_map<T, R, F>(items: &[T], n_jobs: usize, f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync + Send {
+    if n_jobs <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    pool(n_jobs).install(|| items.par_iter().map(f).collect())
+}
+
+/// Without the `parallel` feature, e.g. on wasm32 where threads can't be spawned, `n_jobs` is
+/// ignored and everything runs on the calling thread.
+#[cfg(not(feature = "parallel"))]
+pub fn parallel_map<T, R, F>(items: &[T], _n_jobs: usize, f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync + Send {
+    items.iter().map(f).collect()
+}
+
@@ -0,0 +1,68 @@
+use rand::{Error, RngCore, SeedableRng};
+use rand_isaac::isaac64::Isaac64Rng;
+
+/// Where a stochastic component draws its randomness from. Every shuffle, split, sampler and
+/// random initialisation takes one, so an experiment seeded throughout is exactly reproducible.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RngSource {
+    /// The same stream of numbers on every run.
+    Seed(u64),
+    /// A fresh stream on every run, seeded from the operating system.
+    #[default]
+    Entropy,
+}
+
+impl RngSource {
+    pub fn rng(self) -> SourceRng {
+        SourceRng(match self {
+            RngSource::Seed(seed) => Isaac64Rng::seed_from_u64(seed),
+            RngSource::Entropy => Isaac64Rng::from_entropy(),
+        })
+    }
+
+    /// The source for the `index`th of several components drawing from this one, e.g. the
+    /// estimators of an ensemble, so each gets its own stream while the whole stays reproducible.
+    pub fn derive(self, index: u64) -> RngSource {
+        match self {
+            // SplitMix64's increment spreads the seeds of neighbouring components apart.
+            RngSource::Seed(seed) => RngSource::Seed(seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15))),
+            RngSource::Entropy => RngSource::Entropy,
+        }
+    }
+}
+
+impl From<u64> for RngSource {
+    fn from(seed: u64) -> Self {
+        RngSource::Seed(seed)
+    }
+}
+
+impl From<Option<u64>> for RngSource {
+    fn from(seed: Option<u64>) -> Self {
+        seed.map_or(RngSource::Entropy, RngSource::Seed)
+    }
+}
+
+/// The generator an `RngSource` draws from: ISAAC-64, with every `u32` taken from a whole `u64`.
+/// rand_core 0.5 hands out the halves of ISAAC-64's words through an index past the length of
+/// the slice it reads them from, which debug builds abort on once half a block has been drawn.
+#[derive(Debug, Clone)]
+pub struct SourceRng(Isaac64Rng);
+
+impl RngCore for SourceRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
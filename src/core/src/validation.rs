@@ -0,0 +1,57 @@
+use log::warn;
+use ndarray::{ArrayView, ArrayView1, ArrayView2, Axis, Dimension};
+
+use crate::error::{Error, Result};
+
+/// The usual check for supervised estimators: one label per row of `x`.
+pub fn check_n_samples(n_rows: usize, n_labels: usize) -> Result<()> {
+    if n_rows == n_labels {
+        Ok(())
+    } else {
+        Err(Error::ShapeMismatch(format!("x has {} samples but y has {}", n_rows, n_labels)))
+    }
+}
+
+/// The checks every supervised `fit` runs before training: one label per row of `x`, at least
+/// `min_samples` rows and only finite features. Constant features are logged as warnings, since
+/// nothing can be learnt from them.
+pub fn check_x_y<Y>(x: ArrayView2<f64>, y: ArrayView1<Y>, min_samples: usize) -> Result<()> {
+    check_n_samples(x.nrows(), y.len())?;
+    check_x(x, min_samples)
+}
+
+/// `check_x_y` for estimators that learn from the features alone.
+pub fn check_x(x: ArrayView2<f64>, min_samples: usize) -> Result<()> {
+    check_min_samples(x.nrows(), min_samples)?;
+    check_finite("x", x)?;
+    warn_constant_features(x);
+    Ok(())
+}
+
+pub fn check_min_samples(n_samples: usize, min_samples: usize) -> Result<()> {
+    if n_samples >= min_samples {
+        Ok(())
+    } else {
+        Err(Error::InvalidInput(format!("at least {} samples are needed to fit, got {}", min_samples, n_samples)))
+    }
+}
+
+/// Rejects NaN and infinite values, naming the first one found, e.g. regression targets.
+pub fn check_finite<D: Dimension>(name: &str, values: ArrayView<f64, D>) -> Result<()> {
+    match values.indexed_iter().find(|(_, value)| !value.is_finite()) {
+        Some((index, value)) => Err(Error::InvalidInput(format!("{} has {} at {:?}", name, value, index))),
+        None => Ok(()),
+    }
+}
+
+fn warn_constant_features(x: ArrayView2<f64>) {
+    if x.nrows() < 2 {
+        return;
+    }
+
+    for (column, values) in x.axis_iter(Axis(1)).enumerate() {
+        if values.iter().all(|&value| value == values[0]) {
+            warn!("feature {} is constant", column);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use log::*;
+use ndarray::{Array1, ArrayView1, ArrayView2};
+use rune_core::estimator::{Fit, Predictor};
+use rune_core::math::quantile::{quantile, Interpolation};
+use rune_core::validation::check_x;
+use rune_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::empirical_covariance::{CovarianceModel, EmpiricalCovariance};
+use crate::min_cov_det::MinCovDet;
+
+/// Outlier detection for roughly elliptical, e.g. normal, data: the rows flagged are those
+/// whose Mahalanobis distance from a covariance estimate of the training data exceeds that of
+/// all but a `contamination` fraction of the training rows. Estimated with `MinCovDet`, the
+/// outliers among the training rows can't stretch the ellipsoid that should expose them.
+#[derive(Debug, Clone)]
+pub struct EllipticEnvelope {
+    contamination: f64,
+    robust: Option<MinCovDet>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EllipticEnvelopeModel {
+    covariance: CovarianceModel,
+    threshold: f64,
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, EllipticEnvelopeModel, Y> for EllipticEnvelope {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<EllipticEnvelopeModel> {
+        check_x(x, 2)?;
+        if !(self.contamination > 0. && self.contamination <= 0.5) {
+            return Err(Error::InvalidParameter(format!("contamination must be in (0, 0.5], got {}", self.contamination)));
+        }
+
+        let covariance = match &self.robust {
+            Some(mcd) => mcd.fit(x, y)?,
+            None => EmpiricalCovariance::new().fit(x, y)?,
+        };
+        let threshold = quantile(covariance.mahalanobis(x).view(), 1. - self.contamination, Interpolation::Linear);
+        info!("Flagging rows beyond a squared Mahalanobis distance of {}", threshold);
+
+        Ok(EllipticEnvelopeModel { covariance, threshold })
+    }
+}
+
+/// Whether each row is an outlier: `true` for rows beyond the threshold.
+impl Predictor<ArrayView2<'_, f64>, Array1<bool>> for EllipticEnvelopeModel {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<bool> {
+        self.decision_function(x).mapv(|score| score < 0.)
+    }
+}
+
+impl EllipticEnvelope {
+    /// Expects a `contamination` fraction of the training rows to be outliers, up to a half,
+    /// and estimates the covariance from every row unless made `robust`.
+    pub fn new(contamination: f64) -> Self {
+        EllipticEnvelope { contamination, robust: None }
+    }
+
+    /// Estimates the covariance with `mcd`, which disregards the outlying rows.
+    pub fn robust(mut self, mcd: MinCovDet) -> Self {
+        self.robust = Some(mcd);
+        self
+    }
+}
+
+impl EllipticEnvelopeModel {
+    /// The squared Mahalanobis distance threshold less that of every row: negative for
+    /// outliers, and larger the more typical the row.
+    pub fn decision_function(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        self.threshold - self.covariance.mahalanobis(x)
+    }
+
+    /// The squared Mahalanobis distance of every row from the estimated location.
+    pub fn mahalanobis(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        self.covariance.mahalanobis(x)
+    }
+
+    pub fn covariance(&self) -> &CovarianceModel {
+        &self.covariance
+    }
+
+    /// The squared Mahalanobis distance beyond which rows are outliers.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::Array1;
+    use rune_core::estimator::{Fit, Predictor};
+    use rune_core::random::RngSource;
+    use rune_core::Error;
+
+    use crate::elliptic_envelope::EllipticEnvelope;
+    use crate::min_cov_det::tests::contaminated;
+    use crate::min_cov_det::MinCovDet;
+
+    #[test]
+    fn test_flags_contamination_fraction_of_training_rows() {
+        let x = contaminated(200, 50);
+        let y = Array1::<f64>::zeros(0);
+
+        let model = EllipticEnvelope::new(0.2).robust(MinCovDet::new(RngSource::Seed(1))).fit(x.view(), y.view()).unwrap();
+        let outliers = model.predict(x.view());
+
+        assert_eq!(outliers.iter().filter(|&&outlier| outlier).count(), 50);
+        assert!(outliers.iter().skip(200).all(|&outlier| outlier));
+        assert_eq!(model.decision_function(x.view()).mapv(|score| score < 0.), outliers);
+    }
+
+    #[test]
+    fn test_flags_contamination_fraction_without_robust_estimate() {
+        let x = contaminated(300, 0);
+        let y = Array1::<f64>::zeros(0);
+
+        let model = EllipticEnvelope::new(0.1).fit(x.view(), y.view()).unwrap();
+        let flagged = model.predict(x.view()).iter().filter(|&&outlier| outlier).count();
+
+        assert!((29..=31).contains(&flagged), "{}", flagged);
+    }
+
+    #[test]
+    fn test_rejects_invalid_contamination() {
+        let x = contaminated(20, 0);
+        let y = Array1::<f64>::zeros(0);
+
+        for &contamination in &[0., 0.6] {
+            let result = EllipticEnvelope::new(contamination).fit(x.view(), y.view());
+            assert!(matches!(result, Err(Error::InvalidParameter(_))));
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rune_core::estimator::Fit;
+use rune_core::math::linalg::symmetric_eigen;
+use rune_core::validation::check_x;
+use rune_core::Result;
+use serde::{Deserialize, Serialize};
+
+/// The maximum likelihood estimate of where the rows lie and how the features vary together:
+/// their mean, and their covariance about it dividing by the number of rows. A handful of
+/// outliers can drag both arbitrarily far; see `MinCovDet` for an estimate that resists them.
+#[derive(Debug, Clone, Default)]
+pub struct EmpiricalCovariance;
+
+/// A location and covariance of the features, with the precision, the covariance's
+/// pseudo-inverse, that distances from the location are measured with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CovarianceModel {
+    location: Array1<f64>,
+    covariance: Array2<f64>,
+    precision: Array2<f64>,
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, CovarianceModel, Y> for EmpiricalCovariance {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<CovarianceModel> {
+        check_x(x, 2)?;
+
+        Ok(estimate(x).0)
+    }
+}
+
+impl EmpiricalCovariance {
+    pub fn new() -> Self {
+        EmpiricalCovariance
+    }
+}
+
+impl CovarianceModel {
+    /// A singular `covariance`, of features that are linear combinations of others, measures
+    /// distances within the subspace the data spans.
+    pub fn new(location: Array1<f64>, covariance: Array2<f64>) -> Self {
+        decompose(location, covariance).0
+    }
+
+    pub fn location(&self) -> ArrayView1<'_, f64> {
+        self.location.view()
+    }
+
+    pub fn covariance(&self) -> ArrayView2<'_, f64> {
+        self.covariance.view()
+    }
+
+    pub fn precision(&self) -> ArrayView2<'_, f64> {
+        self.precision.view()
+    }
+
+    /// The squared Mahalanobis distance of every row of `x` from the location,
+    /// `(x - location)ᵀ precision (x - location)`. For normal data it's chi-squared distributed
+    /// with as many degrees of freedom as there are features.
+    pub fn mahalanobis(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        let centred = &x - &self.location;
+        (centred.dot(&self.precision) * &centred).sum_axis(Axis(1))
+    }
+}
+
+/// The empirical estimate from the rows of `x`, with the log of the covariance's determinant.
+pub(crate) fn estimate(x: ArrayView2<f64>) -> (CovarianceModel, f64) {
+    let location = x.mean_axis(Axis(0)).unwrap_or_else(|| Array1::zeros(x.ncols()));
+    let centred = &x - &location;
+    let covariance = centred.t().dot(&centred) / x.nrows() as f64;
+
+    decompose(location, covariance)
+}
+
+/// The model of `covariance`, with the log of its determinant, negative infinity when it's
+/// singular. Eigenvalues too small to tell from rounding count as zero.
+fn decompose(location: Array1<f64>, covariance: Array2<f64>) -> (CovarianceModel, f64) {
+    let (eigenvalues, eigenvectors) = symmetric_eigen(covariance.view());
+    let largest = eigenvalues.fold(0., |max: f64, &v| max.max(v));
+    let tolerance = largest * eigenvalues.len() as f64 * f64::EPSILON;
+
+    let inverse = eigenvalues.mapv(|v| if v > tolerance { 1. / v } else { 0. });
+    let precision = (&eigenvectors * &inverse).dot(&eigenvectors.t());
+    let log_det = eigenvalues.iter().map(|&v| if v > tolerance { v.ln() } else { f64::NEG_INFINITY }).sum();
+
+    (CovarianceModel { location, covariance, precision }, log_det)
+}
@@ -0,0 +1,3 @@
+pub mod elliptic_envelope;
+pub mod empirical_covariance;
+pub mod min_cov_det;
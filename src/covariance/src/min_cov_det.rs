@@ -0,0 +1,215 @@
+use std::cmp::Ordering;
+
+use log::*;
+use ndarray::{Array1, ArrayView1, ArrayView2, Axis};
+use rand::seq::index::sample;
+use rune_core::estimator::Fit;
+use rune_core::math::quantile::median;
+use rune_core::math::special::chi_squared_quantile;
+use rune_core::random::RngSource;
+use rune_core::validation::check_x;
+use rune_core::{Error, Result};
+
+use crate::empirical_covariance::{estimate, CovarianceModel};
+
+/// The most concentration steps taken from any start; they usually converge in a few.
+const MAX_C_STEPS: usize = 30;
+
+/// The Minimum Covariance Determinant estimate of location and covariance, by FastMCD
+/// (Rousseeuw and Van Driessen, 1999): the mean and covariance of the subset of rows whose
+/// covariance has the smallest determinant, so the rows outside it can lie arbitrarily far away
+/// without moving the estimate. The raw estimate is rescaled to be consistent for normal data,
+/// then recomputed from every row inside its 97.5% ellipsoid, which recovers most of the
+/// efficiency of the empirical estimate.
+#[derive(Debug, Clone)]
+pub struct MinCovDet {
+    support_fraction: Option<f64>,
+    n_trials: usize,
+    rng: RngSource,
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, CovarianceModel, Y> for MinCovDet {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<CovarianceModel> {
+        check_x(x, 2)?;
+        let (n, p) = x.dim();
+        if n <= p {
+            return Err(Error::InvalidInput(format!("the covariance of {} features needs more than {} rows, got {}", p, p, n)));
+        }
+        if self.n_trials == 0 {
+            return Err(Error::InvalidParameter("n_trials must be at least 1".to_owned()));
+        }
+        let support_size = match self.support_fraction {
+            Some(fraction) if !(fraction > 0. && fraction <= 1.) => {
+                return Err(Error::InvalidParameter(format!("support_fraction must be in (0, 1], got {}", fraction)));
+            }
+            Some(fraction) => ((fraction * n as f64).ceil() as usize).clamp(p + 1, n),
+            None => (n + p).div_ceil(2),
+        };
+
+        let mut rng = self.rng.rng();
+        let mut best: Option<(Vec<usize>, f64)> = None;
+        for trial in 0..self.n_trials {
+            let (support, log_det) = concentrate(x, sample(&mut rng, n, p + 1).into_vec(), support_size);
+            debug!("[{}] - log determinant: {}", trial, log_det);
+
+            let better = match best {
+                Some((_, best_log_det)) => log_det < best_log_det,
+                None => true,
+            };
+            if better {
+                best = Some((support, log_det));
+            }
+        }
+
+        let (support, log_det) = best.expect("n_trials is positive");
+        if log_det == f64::NEG_INFINITY {
+            return Err(Error::LinearAlgebra(format!(
+                "{} of the rows lie on a hyperplane, so their covariance is singular; try a larger support fraction", support_size)));
+        }
+
+        // The most concentrated rows underestimate the spread of the rest; rescale the distances
+        // so that their median matches that of the chi-squared distribution.
+        let raw = estimate(x.select(Axis(0), &support).view()).0;
+        let distances = raw.mahalanobis(x);
+        let correction = median(distances.view()) / chi_squared_quantile(0.5, p as f64);
+
+        let cutoff = chi_squared_quantile(0.975, p as f64);
+        let inliers: Vec<usize> = (0..n).filter(|&row| distances[row] / correction <= cutoff).collect();
+        info!("Estimating the covariance from {} of {} rows", inliers.len(), n);
+
+        Ok(estimate(x.select(Axis(0), &inliers).view()).0)
+    }
+}
+
+impl MinCovDet {
+    /// Defaults to estimating from `(n + p + 1) / 2` of the `n` rows of `p` features, the most
+    /// outliers tolerated, picking the best of 30 random starts.
+    pub fn new(rng: RngSource) -> Self {
+        MinCovDet {
+            support_fraction: None,
+            n_trials: 30,
+            rng,
+        }
+    }
+
+    /// The fraction of the rows the raw estimate comes from. Larger fractions are more
+    /// efficient on clean data but tolerate fewer outliers.
+    pub fn support_fraction(mut self, support_fraction: f64) -> Self {
+        self.support_fraction = Some(support_fraction);
+        self
+    }
+
+    /// The random starts to concentrate from, each from `p + 1` rows, keeping the one ending
+    /// with the smallest determinant.
+    pub fn n_trials(mut self, n_trials: usize) -> Self {
+        self.n_trials = n_trials;
+        self
+    }
+}
+
+/// Concentration steps from the rows `support`: re-estimating from the `support_size` rows
+/// nearest the last estimate until the determinant, which never rises, stops falling. The final
+/// rows, with the log of the determinant of their covariance.
+fn concentrate(x: ArrayView2<f64>, mut support: Vec<usize>, support_size: usize) -> (Vec<usize>, f64) {
+    let mut log_det = f64::INFINITY;
+
+    for _ in 0..MAX_C_STEPS {
+        let (model, next_log_det) = estimate(x.select(Axis(0), &support).view());
+        if support.len() == support_size && (next_log_det == f64::NEG_INFINITY || next_log_det >= log_det) {
+            return (support, next_log_det);
+        }
+
+        log_det = next_log_det;
+        support = nearest(model.mahalanobis(x), support_size);
+    }
+
+    let log_det = estimate(x.select(Axis(0), &support).view()).1;
+    (support, log_det)
+}
+
+/// The `k` rows with the smallest distances.
+fn nearest(distances: Array1<f64>, k: usize) -> Vec<usize> {
+    let mut rows: Vec<usize> = (0..distances.len()).collect();
+    rows.sort_by(|&l, &r| distances[l].partial_cmp(&distances[r]).unwrap_or(Ordering::Equal));
+    rows.truncate(k);
+    rows
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::f64::consts::PI;
+
+    use ndarray::{arr1, arr2, Array1, Array2};
+    use rand::Rng;
+    use rune_core::estimator::Fit;
+    use rune_core::random::RngSource;
+    use rune_core::Error;
+
+    use crate::empirical_covariance::EmpiricalCovariance;
+    use crate::min_cov_det::MinCovDet;
+
+    /// `n_inliers` rows from a normal distribution with mean `(1, -2)` and covariance
+    /// `[[2, 0.8], [0.8, 1]]`, followed by `n_outliers` rows scattered around `(15, 15)`.
+    pub(crate) fn contaminated(n_inliers: usize, n_outliers: usize) -> Array2<f64> {
+        let mut rng = RngSource::Seed(7).rng();
+        let mut normal = move || {
+            // Box-Muller, with the first uniform in (0, 1] so the logarithm is finite.
+            let u: f64 = 1. - rng.gen::<f64>();
+            let v: f64 = rng.gen();
+            (-2. * u.ln()).sqrt() * (2. * PI * v).cos()
+        };
+
+        let (l11, l21, l22) = (2_f64.sqrt(), 0.8 / 2_f64.sqrt(), 0.68_f64.sqrt());
+        Array2::from_shape_fn((n_inliers + n_outliers, 2), |_| normal())
+            .outer_iter()
+            .enumerate()
+            .flat_map(|(row, z)| {
+                if row < n_inliers {
+                    vec![1. + l11 * z[0], -2. + l21 * z[0] + l22 * z[1]]
+                } else {
+                    vec![15. + 0.5 * z[0], 15. + 0.5 * z[1]]
+                }
+            })
+            .collect::<Array1<f64>>()
+            .into_shape((n_inliers + n_outliers, 2))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_min_cov_det_ignores_outliers() {
+        let x = contaminated(400, 100);
+        let y = Array1::<f64>::zeros(0);
+        let location = arr1(&[1., -2.]);
+        let covariance = arr2(&[[2., 0.8], [0.8, 1.]]);
+
+        let robust = MinCovDet::new(RngSource::Seed(1)).fit(x.view(), y.view()).unwrap();
+        assert!(robust.location().iter().zip(location.iter()).all(|(a, b)| (a - b).abs() < 0.25), "{}", robust.location());
+        assert!(robust.covariance().iter().zip(covariance.iter()).all(|(a, b)| (a - b).abs() < 0.4), "{}", robust.covariance());
+
+        let empirical = EmpiricalCovariance::new().fit(x.view(), y.view()).unwrap();
+        assert!(empirical.location().iter().zip(location.iter()).all(|(a, b)| (a - b).abs() > 2.), "{}", empirical.location());
+        assert!(empirical.covariance().iter().zip(covariance.iter()).all(|(a, b)| (a - b).abs() > 10.), "{}", empirical.covariance());
+    }
+
+    #[test]
+    fn test_min_cov_det_is_reproducible() {
+        let x = contaminated(100, 20);
+        let y = Array1::<f64>::zeros(0);
+
+        let fit = |seed| MinCovDet::new(RngSource::Seed(seed)).n_trials(5).fit(x.view(), y.view()).unwrap();
+        assert_eq!(fit(3).covariance(), fit(3).covariance());
+    }
+
+    #[test]
+    fn test_min_cov_det_rejects_invalid_parameters() {
+        let x = contaminated(20, 0);
+        let y = Array1::<f64>::zeros(0);
+
+        let result = MinCovDet::new(RngSource::Seed(0)).n_trials(0).fit(x.view(), y.view());
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+        let result = MinCovDet::new(RngSource::Seed(0)).support_fraction(1.5).fit(x.view(), y.view());
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+        let result = MinCovDet::new(RngSource::Seed(0)).fit(x.slice(ndarray::s![..2, ..]), y.view());
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,104 @@
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Float64Type, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type, UInt64Type, UInt8Type};
+use arrow_array::{Array, ArrowPrimitiveType, PrimitiveArray, RecordBatch};
+use arrow_buffer::ArrowNativeType;
+use arrow_schema::DataType;
+use log::debug;
+use ndarray_heterogeneous::Scalar;
+
+use crate::csv_reader::Column;
+use crate::dataset::Dataset;
+use crate::error::DataError;
+
+/// Stacks Arrow record batches sharing a schema into a dataset, keeping the schema's field
+/// names. Missing values follow `read_csv`: integer columns with nulls are read as floats with
+/// NaN in their place, and boolean and string columns with nulls as strings with empty ones.
+pub fn read_record_batches(batches: &[RecordBatch], target_column: Option<&Column>) -> Result<Dataset<Scalar>, DataError> {
+    let names: Vec<String> = match batches.first() {
+        Some(batch) => batch.schema().fields().iter().map(|field| field.name().clone()).collect(),
+        None => vec![],
+    };
+
+    let columns = (0..names.len())
+        .map(|j| {
+            let arrays: Vec<&dyn Array> = batches.iter().map(|batch| batch.column(j).as_ref()).collect();
+            column_values(&names[j], &arrays)
+        })
+        .collect::<Result<Vec<Vec<Scalar>>, DataError>>()?;
+
+    debug!("Read {} columns from {} record batches", names.len(), batches.len());
+
+    Dataset::from_columns(names, columns, target_column)
+}
+
+fn column_values(name: &str, arrays: &[&dyn Array]) -> Result<Vec<Scalar>, DataError> {
+    let data_type = arrays[0].data_type();
+    let has_nulls = arrays.iter().any(|array| array.null_count() > 0);
+
+    let values = match data_type {
+        DataType::Int8 => integers::<Int8Type>(name, arrays, has_nulls)?,
+        DataType::Int16 => integers::<Int16Type>(name, arrays, has_nulls)?,
+        DataType::Int32 => integers::<Int32Type>(name, arrays, has_nulls)?,
+        DataType::Int64 => integers::<Int64Type>(name, arrays, has_nulls)?,
+        DataType::UInt8 => integers::<UInt8Type>(name, arrays, has_nulls)?,
+        DataType::UInt16 => integers::<UInt16Type>(name, arrays, has_nulls)?,
+        DataType::UInt32 => integers::<UInt32Type>(name, arrays, has_nulls)?,
+        DataType::UInt64 => integers::<UInt64Type>(name, arrays, has_nulls)?,
+        DataType::Float32 => floats::<Float32Type, _>(arrays, f64::from),
+        DataType::Float64 => floats::<Float64Type, _>(arrays, |value| value),
+        DataType::Boolean => arrays.iter()
+            .flat_map(|array| array.as_boolean().iter())
+            .map(|value| match (value, has_nulls) {
+                (Some(value), false) => Scalar::BOOL(value),
+                (value, true) => Scalar::STRING(value.map(|value| value.to_string()).unwrap_or_default()),
+                (None, false) => unreachable!(),
+            })
+            .collect(),
+        DataType::Utf8 => arrays.iter()
+            .flat_map(|array| array.as_string::<i32>().iter())
+            .map(|value| Scalar::STRING(value.unwrap_or("").to_owned()))
+            .collect(),
+        DataType::LargeUtf8 => arrays.iter()
+            .flat_map(|array| array.as_string::<i64>().iter())
+            .map(|value| Scalar::STRING(value.unwrap_or("").to_owned()))
+            .collect(),
+        data_type => return Err(DataError::UnsupportedType { column: name.to_owned(), data_type: data_type.to_string() }),
+    };
+
+    Ok(values)
+}
+
+fn primitives<'a, T: ArrowPrimitiveType>(arrays: &'a [&dyn Array]) -> impl Iterator<Item=Option<T::Native>> + 'a {
+    arrays.iter().flat_map(|array| {
+        let array: &PrimitiveArray<T> = array.as_primitive();
+        array.iter()
+    })
+}
+
+fn integers<T: ArrowPrimitiveType>(name: &str, arrays: &[&dyn Array], has_nulls: bool) -> Result<Vec<Scalar>, DataError> {
+    primitives::<T>(arrays)
+        .map(|value| match value.map(|value| value.to_i64()) {
+            Some(Some(value)) if has_nulls => Ok(Scalar::F64(value as f64)),
+            Some(Some(value)) => Ok(Scalar::I64(value)),
+            Some(None) => Err(DataError::UnsupportedType { column: name.to_owned(), data_type: "integers beyond the range of i64".to_owned() }),
+            None => Ok(Scalar::F64(f64::NAN)),
+        })
+        .collect()
+}
+
+fn floats<T: ArrowPrimitiveType, F: Fn(T::Native) -> f64>(arrays: &[&dyn Array], to_f64: F) -> Vec<Scalar> {
+    primitives::<T>(arrays)
+        .map(|value| Scalar::F64(value.map(&to_f64).unwrap_or(f64::NAN)))
+        .collect()
+}
+
+/// Reads every row group of a Parquet file into a dataset, see `read_record_batches`.
+#[cfg(feature = "parquet")]
+pub fn read_parquet_file<P: AsRef<std::path::Path>>(path: P, target_column: Option<&Column>) -> Result<Dataset<Scalar>, DataError> {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(path)?)?.build()?;
+    let batches = reader.collect::<Result<Vec<RecordBatch>, _>>()?;
+
+    read_record_batches(&batches, target_column)
+}
@@ -0,0 +1,238 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use csv::{ReaderBuilder, StringRecord, Trim};
+use log::debug;
+use ndarray::{Array1, Array2};
+use ndarray_heterogeneous::Scalar;
+
+use crate::dataset::Dataset;
+use crate::error::DataError;
+
+/// Refers to a column by its position or, for files with headers, by its name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl Column {
+    /// The position of this column among `names`.
+    pub(crate) fn resolve(&self, names: &[String]) -> Result<usize, DataError> {
+        match self {
+            Column::Index(index) if *index < names.len() => Ok(*index),
+            Column::Index(index) => Err(DataError::MissingColumn(index.to_string())),
+            Column::Name(name) => names.iter().position(|n| n == name).ok_or_else(|| DataError::MissingColumn(name.clone())),
+        }
+    }
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Column::Name(name.to_owned())
+    }
+}
+
+/// How `read_csv` parses a file. When `target_column` is set that column is returned as the
+/// dataset's `y` instead of being one of its features.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub headers: bool,
+    pub delimiter: u8,
+    pub target_column: Option<Column>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            headers: true,
+            delimiter: b',',
+            target_column: None,
+        }
+    }
+}
+
+/// The type every cell of a column parses as. Empty cells are missing values: they become NaN
+/// in float columns and keep a column that has them from being read as integers, booleans or
+/// datetimes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnType {
+    Integer,
+    Float,
+    Boolean,
+    DateTime,
+    Text,
+}
+
+impl ColumnType {
+    fn infer<'a, I: Iterator<Item=&'a str>>(cells: I) -> ColumnType {
+        let (mut integer, mut float, mut boolean, mut datetime, mut missing) = (true, true, true, true, false);
+
+        for cell in cells {
+            if cell.is_empty() {
+                missing = true;
+                continue;
+            }
+            integer = integer && cell.parse::<i64>().is_ok();
+            float = float && cell.parse::<f64>().is_ok();
+            boolean = boolean && cell.parse::<bool>().is_ok();
+            datetime = datetime && !float && Scalar::parse_datetime(cell).is_some();
+        }
+
+        if integer && !missing {
+            ColumnType::Integer
+        } else if float {
+            ColumnType::Float
+        } else if boolean && !missing {
+            ColumnType::Boolean
+        } else if datetime && !missing {
+            ColumnType::DateTime
+        } else {
+            ColumnType::Text
+        }
+    }
+
+    fn parse(self, cell: &str) -> Scalar {
+        match self {
+            ColumnType::Integer => Scalar::I64(cell.parse().unwrap()),
+            ColumnType::Float => Scalar::F64(cell.parse().unwrap_or(f64::NAN)),
+            ColumnType::Boolean => Scalar::BOOL(cell.parse().unwrap()),
+            ColumnType::DateTime => Scalar::DATETIME(Scalar::parse_datetime(cell).unwrap()),
+            ColumnType::Text => Scalar::STRING(cell.to_owned()),
+        }
+    }
+}
+
+/// Reads a CSV file of any shape from `reader`, inferring the number of rows and the type of
+/// every column from its contents.
+pub fn read_csv<R: Read>(reader: R, options: &CsvOptions) -> Result<Dataset<Scalar>, DataError> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(options.headers)
+        .delimiter(options.delimiter)
+        .trim(Trim::All)
+        .from_reader(reader);
+
+    let records = reader.records().collect::<Result<Vec<StringRecord>, _>>()?;
+
+    let n_columns = match (options.headers, records.first()) {
+        (true, _) => reader.headers()?.len(),
+        (false, Some(record)) => record.len(),
+        (false, None) => 0,
+    };
+
+    let names: Vec<String> = if options.headers {
+        reader.headers()?.iter().map(|name| name.to_owned()).collect()
+    } else {
+        (0..n_columns).map(|i| format!("x{}", i)).collect()
+    };
+
+    let target = match &options.target_column {
+        Some(column) => Some(column.resolve(&names)?),
+        None => None,
+    };
+
+    let types: Vec<ColumnType> = (0..n_columns)
+        .map(|j| ColumnType::infer(records.iter().map(|record| &record[j])))
+        .collect();
+
+    debug!("Read {} rows with column types {:?}", records.len(), types);
+
+    let features: Vec<usize> = (0..n_columns).filter(|&j| Some(j) != target).collect();
+    let types = &types;
+
+    let x = Array2::from_shape_vec((records.len(), features.len()), records.iter()
+        .flat_map(|record| features.iter().map(move |&j| types[j].parse(&record[j])))
+        .collect())?;
+
+    let y = target.map(|j| records.iter().map(|record| types[j].parse(&record[j])).collect::<Array1<Scalar>>());
+
+    Ok(Dataset {
+        x,
+        y,
+        feature_names: features.iter().map(|&j| names[j].clone()).collect(),
+        target_names: vec![],
+    })
+}
+
+pub fn read_csv_file<P: AsRef<Path>>(path: P, options: &CsvOptions) -> Result<Dataset<Scalar>, DataError> {
+    read_csv(File::open(path)?, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray_heterogeneous::Scalar;
+
+    use crate::csv_reader::{read_csv, Column, CsvOptions};
+    use crate::error::DataError;
+
+    #[test]
+    fn test_read_csv_infers_column_types() {
+        let data = "count,ratio,flag,name\n1,0.5,true,a\n2,1.5,false,b\n3,2,true,c\n";
+        let dataset = read_csv(data.as_bytes(), &CsvOptions::default()).unwrap();
+
+        assert_eq!(dataset.feature_names, vec!["count", "ratio", "flag", "name"]);
+        assert_eq!(dataset.x.dim(), (3, 4));
+        assert!(dataset.y.is_none());
+        assert_eq!(dataset.x.row(2).to_vec(), vec![
+            Scalar::I64(3),
+            Scalar::F64(2.),
+            Scalar::BOOL(true),
+            Scalar::STRING("c".to_owned()),
+        ]);
+    }
+
+    #[test]
+    fn test_read_csv_missing_values() {
+        let data = "a,b,c,d\n1,0.5,true,x\n,,,\n3,2.5,false,z\n";
+        let dataset = read_csv(data.as_bytes(), &CsvOptions::default()).unwrap();
+
+        // A missing cell makes an integer column a float one, and a boolean column text.
+        assert_eq!(dataset.x[[0, 0]], Scalar::F64(1.));
+        assert!(matches!(dataset.x[[1, 0]], Scalar::F64(value) if value.is_nan()));
+        assert!(matches!(dataset.x[[1, 1]], Scalar::F64(value) if value.is_nan()));
+        assert_eq!(dataset.x[[2, 1]], Scalar::F64(2.5));
+        assert_eq!(dataset.x[[0, 2]], Scalar::STRING("true".to_owned()));
+        assert_eq!(dataset.x[[1, 2]], Scalar::STRING(String::new()));
+        assert_eq!(dataset.x[[1, 3]], Scalar::STRING(String::new()));
+    }
+
+    #[test]
+    fn test_read_csv_without_headers() {
+        let data = "1;a;0.5\n2;b;1.5\n";
+        let options = CsvOptions { headers: false, delimiter: b';', target_column: Some(Column::from(1)) };
+        let dataset = read_csv(data.as_bytes(), &options).unwrap();
+
+        assert_eq!(dataset.feature_names, vec!["x0", "x2"]);
+        assert_eq!(dataset.x.row(1).to_vec(), vec![Scalar::I64(2), Scalar::F64(1.5)]);
+        assert_eq!(dataset.y.unwrap().to_vec(), vec![Scalar::STRING("a".to_owned()), Scalar::STRING("b".to_owned())]);
+    }
+
+    #[test]
+    fn test_read_csv_target_column_by_name() {
+        let data = "x,label\n1,true\n2,false\n";
+        let options = CsvOptions { target_column: Some(Column::from("label")), ..CsvOptions::default() };
+        let dataset = read_csv(data.as_bytes(), &options).unwrap();
+
+        assert_eq!(dataset.feature_names, vec!["x"]);
+        assert_eq!(dataset.y.unwrap().to_vec(), vec![Scalar::BOOL(true), Scalar::BOOL(false)]);
+
+        let options = CsvOptions { target_column: Some(Column::from("missing")), ..CsvOptions::default() };
+        assert!(matches!(read_csv(data.as_bytes(), &options), Err(DataError::MissingColumn(_))));
+    }
+
+    #[test]
+    fn test_read_csv_rejects_rows_of_the_wrong_length() {
+        let data = "a,b,c\n1,2,3\n4,5\n";
+
+        assert!(matches!(read_csv(data.as_bytes(), &CsvOptions::default()), Err(DataError::Csv(_))));
+        let data = "a,b\n1,2,3\n";
+        assert!(matches!(read_csv(data.as_bytes(), &CsvOptions::default()), Err(DataError::Csv(_))));
+    }
+}
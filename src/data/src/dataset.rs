@@ -0,0 +1,73 @@
+use std::collections::BTreeSet;
+
+use ndarray::{Array1, Array2};
+use ndarray_heterogeneous::{Scalar, ScalarExt};
+
+use crate::csv_reader::Column;
+use crate::error::DataError;
+
+/// A feature matrix together with its targets, when the source had any, and the names of its
+/// feature columns in order. For classification datasets `target_names` holds the distinct
+/// labels found in `y`, sorted; it is empty for regression targets and unlabelled data.
+#[derive(Debug, Clone)]
+pub struct Dataset<A, T = A> {
+    pub x: Array2<A>,
+    pub y: Option<Array1<T>>,
+    pub feature_names: Vec<String>,
+    pub target_names: Vec<String>,
+}
+
+impl<A, T> Dataset<A, T> {
+    pub fn n_samples(&self) -> usize {
+        self.x.nrows()
+    }
+
+    pub fn n_features(&self) -> usize {
+        self.x.ncols()
+    }
+
+    /// Names the classes of `y` after its distinct values.
+    pub fn with_class_names(self) -> Self where T: ToString {
+        let target_names = match &self.y {
+            Some(y) => y.iter().map(|label| label.to_string()).collect::<BTreeSet<String>>().into_iter().collect(),
+            None => vec![],
+        };
+
+        Dataset { target_names, ..self }
+    }
+}
+
+impl Dataset<Scalar, Scalar> {
+    /// Assembles a dataset from equally long `columns` named by `names`, moving `target_column`
+    /// into `y` when it is given.
+    pub(crate) fn from_columns(names: Vec<String>, columns: Vec<Vec<Scalar>>, target_column: Option<&Column>) -> Result<Self, DataError> {
+        let target = match target_column {
+            Some(column) => Some(column.resolve(&names)?),
+            None => None,
+        };
+
+        let n_samples = columns.first().map_or(0, |column| column.len());
+        let features: Vec<usize> = (0..names.len()).filter(|&j| Some(j) != target).collect();
+
+        let x = Array2::from_shape_fn((n_samples, features.len()), |(i, j)| columns[features[j]][i].clone());
+        let y = target.map(|j| Array1::from(columns[j].clone()));
+
+        Ok(Dataset {
+            x,
+            y,
+            feature_names: features.iter().map(|&j| names[j].clone()).collect(),
+            target_names: vec![],
+        })
+    }
+
+    /// Converts every feature to `B` and every target to `U`, e.g. `into_typed::<f64, String>()`
+    /// for a dataset of numeric features labelled by name.
+    pub fn into_typed<B: From<Scalar>, U: From<Scalar>>(self) -> Dataset<B, U> {
+        Dataset {
+            x: self.x.map_scalar_type(),
+            y: self.y.map(|y| y.map_scalar_type()),
+            feature_names: self.feature_names,
+            target_names: self.target_names,
+        }
+    }
+}
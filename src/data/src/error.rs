@@ -0,0 +1,60 @@
+use thiserror::Error;
+
+/// Everything that can go wrong while reading a dataset.
+#[derive(Debug, Error)]
+pub enum DataError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The file isn't valid CSV, e.g. a row with a different number of fields than the header.
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+
+    /// `CsvOptions::target_column` names a column the file doesn't have.
+    #[error("missing column: {0}")]
+    MissingColumn(String),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    /// A line of a text format that doesn't follow its syntax.
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+
+    /// A binary file whose contents don't match its format.
+    #[error("invalid format: {0}")]
+    InvalidFormat(String),
+
+    #[cfg(feature = "download")]
+    #[error("download failed: {0}")]
+    Download(String),
+
+    /// A column whose values can't be represented as `Scalar`s.
+    #[error("column {column} has unsupported type {data_type}")]
+    UnsupportedType { column: String, data_type: String },
+
+    #[cfg(feature = "arrow")]
+    #[error(transparent)]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "polars")]
+    #[error(transparent)]
+    Polars(#[from] polars::error::PolarsError),
+
+    #[error(transparent)]
+    Shape(#[from] ndarray::ShapeError),
+}
+
+impl From<DataError> for rune_core::Error {
+    fn from(error: DataError) -> Self {
+        match error {
+            DataError::Io(error) => rune_core::Error::Io(error),
+            DataError::Shape(error) => rune_core::Error::Shape(error),
+            error => rune_core::Error::InvalidInput(error.to_string()),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use log::debug;
+use ndarray::{Array1, Array2};
+
+use crate::error::DataError;
+
+/// A multi-dimensional array read from the IDX format: its dimensions, outermost first, and its
+/// values in row-major order.
+struct Idx {
+    dims: Vec<usize>,
+    values: Vec<f64>,
+}
+
+/// Reads a whole IDX file, gunzipping it first when it is compressed.
+fn read_idx<R: Read>(mut reader: R) -> Result<Idx, DataError> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut decompressed = vec![];
+        GzDecoder::new(&bytes[..]).read_to_end(&mut decompressed)?;
+        bytes = decompressed;
+    }
+
+    let invalid = |message: &str| DataError::InvalidFormat(format!("IDX file {}", message));
+
+    if bytes.len() < 4 || bytes[0] != 0 || bytes[1] != 0 {
+        return Err(invalid("is missing its magic number"));
+    }
+    let (type_code, n_dims) = (bytes[2], bytes[3] as usize);
+
+    let header = 4 + 4 * n_dims;
+    if bytes.len() < header {
+        return Err(invalid("has truncated dimensions"));
+    }
+    let dims: Vec<usize> = bytes[4..header]
+        .chunks(4)
+        .map(|dim| u32::from_be_bytes([dim[0], dim[1], dim[2], dim[3]]) as usize)
+        .collect();
+
+    let size = match type_code {
+        0x08 | 0x09 => 1,
+        0x0B => 2,
+        0x0C | 0x0D => 4,
+        0x0E => 8,
+        _ => return Err(invalid(&format!("has unknown data type 0x{:02x}", type_code))),
+    };
+
+    let data = &bytes[header..];
+    let n_values: usize = dims.iter().product();
+    if data.len() != n_values * size {
+        return Err(invalid(&format!("should hold {} values of {} bytes for dimensions {:?}, holds {} bytes", n_values, size, dims, data.len())));
+    }
+
+    let values = data.chunks(size)
+        .map(|value| match type_code {
+            0x08 => value[0] as f64,
+            0x09 => value[0] as i8 as f64,
+            0x0B => i16::from_be_bytes([value[0], value[1]]) as f64,
+            0x0C => i32::from_be_bytes([value[0], value[1], value[2], value[3]]) as f64,
+            0x0D => f32::from_be_bytes([value[0], value[1], value[2], value[3]]) as f64,
+            _ => f64::from_be_bytes([value[0], value[1], value[2], value[3], value[4], value[5], value[6], value[7]]),
+        })
+        .collect();
+
+    debug!("Read IDX array of type 0x{:02x} with dimensions {:?}", type_code, dims);
+
+    Ok(Idx { dims, values })
+}
+
+/// Reads an IDX file of images, e.g. `train-images-idx3-ubyte`, flattening each image into a row
+/// of pixel values.
+pub fn read_idx_images<R: Read>(reader: R) -> Result<Array2<f64>, DataError> {
+    let Idx { dims, values } = read_idx(reader)?;
+
+    let n_samples = dims.first().cloned().unwrap_or(0);
+    let n_pixels = dims.iter().skip(1).product();
+
+    Ok(Array2::from_shape_vec((n_samples, n_pixels), values)?)
+}
+
+/// Reads an IDX file of byte labels, e.g. `train-labels-idx1-ubyte`.
+pub fn read_idx_labels<R: Read>(reader: R) -> Result<Array1<u8>, DataError> {
+    let Idx { dims, values } = read_idx(reader)?;
+
+    if dims.len() != 1 {
+        return Err(DataError::InvalidFormat(format!("IDX labels should have one dimension, got {:?}", dims)));
+    }
+
+    Ok(values.into_iter().map(|label| label as u8).collect())
+}
+
+/// Datasets published as four IDX files, training and test images and labels, under the names
+/// MNIST introduced.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IdxDataset {
+    Mnist,
+    FashionMnist,
+}
+
+impl IdxDataset {
+    fn name(self) -> &'static str {
+        match self {
+            IdxDataset::Mnist => "mnist",
+            IdxDataset::FashionMnist => "fashion-mnist",
+        }
+    }
+
+    fn files(train: bool) -> [&'static str; 2] {
+        if train {
+            ["train-images-idx3-ubyte.gz", "train-labels-idx1-ubyte.gz"]
+        } else {
+            ["t10k-images-idx3-ubyte.gz", "t10k-labels-idx1-ubyte.gz"]
+        }
+    }
+
+    #[cfg(feature = "download")]
+    fn url(self) -> &'static str {
+        match self {
+            IdxDataset::Mnist => "https://ossci-datasets.s3.amazonaws.com/mnist",
+            IdxDataset::FashionMnist => "http://fashion-mnist.s3-website.eu-central-1.amazonaws.com",
+        }
+    }
+}
+
+/// Reads the training or test split of `dataset` from the gzipped IDX files in `dir`, as
+/// `(images, labels)`.
+pub fn load_idx_dataset<P: AsRef<Path>>(dataset: IdxDataset, dir: P, train: bool) -> Result<(Array2<f64>, Array1<u8>), DataError> {
+    let [images, labels] = IdxDataset::files(train);
+    debug!("Loading {} {} split from {}", dataset.name(), if train { "training" } else { "test" }, dir.as_ref().display());
+
+    let x = read_idx_images(File::open(dir.as_ref().join(images))?)?;
+    let y = read_idx_labels(File::open(dir.as_ref().join(labels))?)?;
+
+    Ok((x, y))
+}
+
+/// Like `load_idx_dataset`, but first downloads any of the split's files missing from
+/// `cache_dir/<dataset name>`, so only the first call needs the network.
+#[cfg(feature = "download")]
+pub fn fetch_idx_dataset<P: AsRef<Path>>(dataset: IdxDataset, cache_dir: P, train: bool) -> Result<(Array2<f64>, Array1<u8>), DataError> {
+    let dir = cache_dir.as_ref().join(dataset.name());
+    std::fs::create_dir_all(&dir)?;
+
+    for file in IdxDataset::files(train).iter() {
+        let path = dir.join(file);
+        if path.exists() {
+            continue;
+        }
+
+        let url = format!("{}/{}", dataset.url(), file);
+        log::info!("Downloading {}", url);
+
+        let response = ureq::get(&url).call().map_err(|e| DataError::Download(e.to_string()))?;
+        let mut bytes = vec![];
+        response.into_reader().read_to_end(&mut bytes)?;
+
+        // Write to a temporary name first so an interrupted download isn't mistaken for a cached file.
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, bytes)?;
+        std::fs::rename(partial, path)?;
+    }
+
+    load_idx_dataset(dataset, dir, train)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use ndarray::array;
+
+    use crate::error::DataError;
+    use crate::idx::{load_idx_dataset, read_idx_images, read_idx_labels, IdxDataset};
+
+    /// An IDX file of `type_code` with dimensions `dims` holding the big-endian `data`.
+    fn idx(type_code: u8, dims: &[u32], data: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, type_code, dims.len() as u8];
+        for dim in dims {
+            bytes.extend_from_slice(&dim.to_be_bytes());
+        }
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_images_are_flattened_into_rows() {
+        let images = idx(0x08, &[2, 2, 2], &[0, 1, 2, 3, 4, 5, 6, 255]);
+        let expected = array![[0., 1., 2., 3.], [4., 5., 6., 255.]];
+
+        assert_eq!(read_idx_images(&images[..]).unwrap(), expected);
+        assert_eq!(read_idx_images(&gzip(&images)[..]).unwrap(), expected);
+        assert_eq!(read_idx_labels(&idx(0x08, &[3], &[7, 0, 9])[..]).unwrap(), array![7, 0, 9]);
+    }
+
+    #[test]
+    fn test_every_data_type_is_read_big_endian() {
+        let mut data = vec![];
+        data.extend_from_slice(&(-2i16).to_be_bytes());
+        assert_eq!(read_idx_images(&idx(0x0B, &[1, 1], &data)[..]).unwrap(), array![[-2.]]);
+
+        assert_eq!(read_idx_images(&idx(0x09, &[1, 1], &[0xff])[..]).unwrap(), array![[-1.]]);
+        assert_eq!(read_idx_images(&idx(0x0C, &[1, 1], &70000i32.to_be_bytes())[..]).unwrap(), array![[70000.]]);
+        assert_eq!(read_idx_images(&idx(0x0D, &[1, 1], &1.5f32.to_be_bytes())[..]).unwrap(), array![[1.5]]);
+        assert_eq!(read_idx_images(&idx(0x0E, &[1, 1], &0.1f64.to_be_bytes())[..]).unwrap(), array![[0.1]]);
+    }
+
+    #[test]
+    fn test_malformed_files_are_errors() {
+        let invalid = |bytes: Vec<u8>| matches!(read_idx_images(&bytes[..]), Err(DataError::InvalidFormat(_)));
+
+        assert!(invalid(vec![1, 0, 8, 1]));
+        assert!(invalid(vec![0, 0, 8, 2, 0, 0, 0, 1]));
+        assert!(invalid(idx(0x0A, &[1], &[0])));
+        assert!(invalid(idx(0x08, &[2, 2], &[0, 1, 2])));
+        assert!(matches!(read_idx_labels(&idx(0x08, &[1, 1], &[0])[..]), Err(DataError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_load_idx_dataset_reads_the_split_from_a_directory() {
+        let dir = std::env::temp_dir().join("rune-data-idx-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("t10k-images-idx3-ubyte.gz"), gzip(&idx(0x08, &[1, 1, 2], &[3, 4]))).unwrap();
+        std::fs::write(dir.join("t10k-labels-idx1-ubyte.gz"), gzip(&idx(0x08, &[1], &[5]))).unwrap();
+
+        let (x, y) = load_idx_dataset(IdxDataset::Mnist, &dir, false).unwrap();
+        assert_eq!(x, array![[3., 4.]]);
+        assert_eq!(y, array![5]);
+        assert!(matches!(load_idx_dataset(IdxDataset::Mnist, &dir, true), Err(DataError::Io(_))));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,147 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use log::debug;
+use ndarray_heterogeneous::Scalar;
+use serde_json::{Map, Value};
+
+use crate::csv_reader::Column;
+use crate::dataset::Dataset;
+use crate::error::DataError;
+
+/// Reads JSON records, either a single array of objects or newline-delimited JSON with one object
+/// per line, told apart by whether the input starts with `[`. Every field becomes a named column,
+/// in the order the fields first appear. Fields that are null or left out of a record are
+/// missing values, handled as in `read_csv`: integer columns with missing values are read as
+/// floats with NaN in their place, boolean, datetime and string columns with empty strings, and
+/// columns mixing types as strings.
+pub fn read_json<R: Read>(mut reader: R, target_column: Option<&Column>) -> Result<Dataset<Scalar>, DataError> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+
+    let records: Vec<Map<String, Value>> = if content.trim_start().starts_with('[') {
+        serde_json::from_str(&content)?
+    } else {
+        content.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| serde_json::from_str(line).map_err(|e| DataError::Parse { line: i + 1, message: e.to_string() }))
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut names: Vec<String> = vec![];
+    for record in &records {
+        for name in record.keys() {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+
+    debug!("Read {} JSON records with fields {:?}", records.len(), names);
+
+    let columns = names.iter()
+        .map(|name| column_values(name, records.iter().map(|record| record.get(name).filter(|value| !value.is_null()))))
+        .collect::<Result<Vec<Vec<Scalar>>, DataError>>()?;
+
+    Dataset::from_columns(names, columns, target_column)
+}
+
+pub fn read_json_file<P: AsRef<Path>>(path: P, target_column: Option<&Column>) -> Result<Dataset<Scalar>, DataError> {
+    read_json(File::open(path)?, target_column)
+}
+
+fn column_values<'a, I: Iterator<Item=Option<&'a Value>> + Clone>(name: &str, values: I) -> Result<Vec<Scalar>, DataError> {
+    let (mut integer, mut number, mut boolean, mut datetime, mut missing) = (true, true, true, true, false);
+
+    for value in values.clone() {
+        match value {
+            None => missing = true,
+            Some(Value::Number(n)) => {
+                integer = integer && n.is_i64();
+                boolean = false;
+                datetime = false;
+            }
+            Some(Value::Bool(_)) => {
+                integer = false;
+                number = false;
+                datetime = false;
+            }
+            Some(Value::String(s)) => {
+                integer = false;
+                number = false;
+                boolean = false;
+                datetime = datetime && Scalar::parse_datetime(s).is_some();
+            }
+            Some(value) => return Err(DataError::UnsupportedType {
+                column: name.to_owned(),
+                data_type: if value.is_array() { "array" } else { "object" }.to_owned(),
+            }),
+        }
+    }
+
+    let values = values.map(|value| match value {
+        Some(Value::Number(n)) if integer && !missing => Scalar::I64(n.as_i64().unwrap()),
+        Some(Value::Number(n)) if number => Scalar::F64(n.as_f64().unwrap_or(f64::NAN)),
+        None if number => Scalar::F64(f64::NAN),
+        Some(Value::Bool(b)) if boolean && !missing => Scalar::BOOL(*b),
+        Some(Value::String(s)) if datetime && !missing => Scalar::DATETIME(Scalar::parse_datetime(s).unwrap()),
+        Some(Value::String(s)) => Scalar::STRING(s.clone()),
+        Some(value) => Scalar::STRING(value.to_string()),
+        None => Scalar::STRING(String::new()),
+    });
+
+    Ok(values.collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray_heterogeneous::Scalar;
+
+    use crate::csv_reader::Column;
+    use crate::error::DataError;
+    use crate::json::read_json;
+
+    #[test]
+    fn test_read_json_arrays_and_lines_agree() {
+        let array = r#"[{"a": 1, "b": "x"}, {"a": 2, "b": "y"}]"#;
+        let lines = "{\"a\": 1, \"b\": \"x\"}\n\n{\"a\": 2, \"b\": \"y\"}\n";
+
+        let from_array = read_json(array.as_bytes(), Some(&Column::from("b"))).unwrap();
+        let from_lines = read_json(lines.as_bytes(), Some(&Column::from("b"))).unwrap();
+
+        assert_eq!(from_array.feature_names, vec!["a"]);
+        assert_eq!(from_array.x, from_lines.x);
+        assert_eq!(from_array.y, from_lines.y);
+        assert_eq!(from_array.y.unwrap().to_vec(), vec![Scalar::STRING("x".to_owned()), Scalar::STRING("y".to_owned())]);
+    }
+
+    #[test]
+    fn test_read_json_infers_types_with_missing_fields() {
+        let data = concat!(
+            "{\"count\": 1, \"ratio\": 1, \"flag\": true, \"day\": \"2024-01-02\", \"mixed\": 1}\n",
+            "{\"ratio\": 2.5, \"flag\": null, \"mixed\": \"a\", \"late\": false}\n",
+        );
+        let dataset = read_json(data.as_bytes(), None).unwrap();
+
+        // Columns follow the first appearance of their fields.
+        assert_eq!(dataset.feature_names, vec!["count", "ratio", "flag", "day", "mixed", "late"]);
+        assert_eq!(dataset.x[[0, 0]], Scalar::F64(1.));
+        assert!(matches!(dataset.x[[1, 0]], Scalar::F64(value) if value.is_nan()));
+        assert_eq!(dataset.x.column(1).to_vec(), vec![Scalar::F64(1.), Scalar::F64(2.5)]);
+        assert_eq!(dataset.x.column(2).to_vec(), vec![Scalar::STRING("true".to_owned()), Scalar::STRING(String::new())]);
+        assert_eq!(dataset.x.column(3).to_vec(), vec![Scalar::STRING("2024-01-02".to_owned()), Scalar::STRING(String::new())]);
+        assert_eq!(dataset.x.column(4).to_vec(), vec![Scalar::STRING("1".to_owned()), Scalar::STRING("a".to_owned())]);
+        assert_eq!(dataset.x.column(5).to_vec(), vec![Scalar::STRING(String::new()), Scalar::STRING("false".to_owned())]);
+
+        let complete = read_json("{\"n\": 3, \"on\": false, \"day\": \"2024-01-02\"}".as_bytes(), None).unwrap();
+        assert_eq!(complete.x.row(0).to_vec(), vec![Scalar::I64(3), Scalar::BOOL(false), Scalar::DATETIME(Scalar::parse_datetime("2024-01-02").unwrap())]);
+    }
+
+    #[test]
+    fn test_read_json_rejects_nested_values_and_bad_lines() {
+        assert!(matches!(read_json(r#"[{"a": [1, 2]}]"#.as_bytes(), None), Err(DataError::UnsupportedType { .. })));
+        assert!(matches!(read_json("{\"a\": 1}\n{\"a\": \n".as_bytes(), None), Err(DataError::Parse { line: 2, .. })));
+    }
+}
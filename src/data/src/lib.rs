@@ -1,76 +1,128 @@
-use csv::ReaderBuilder;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod csv_reader;
+pub mod dataset;
+pub mod error;
+pub mod idx;
+pub mod json;
+pub mod libsvm;
+#[cfg(feature = "polars")]
+pub mod polars;
+pub mod resample;
+pub mod sampling;
+pub mod synthetic;
+
+pub use crate::csv_reader::{read_csv, read_csv_file, Column, CsvOptions};
+pub use crate::dataset::Dataset;
+pub use crate::error::DataError;
+pub use crate::idx::{load_idx_dataset, read_idx_images, read_idx_labels, IdxDataset};
+#[cfg(feature = "download")]
+pub use crate::idx::fetch_idx_dataset;
+pub use crate::json::{read_json, read_json_file};
+pub use crate::libsvm::{read_libsvm, read_libsvm_file};
+#[cfg(feature = "polars")]
+pub use crate::polars::{array_to_dataframe, dataframe_to_array, read_dataframe};
+pub use crate::resample::{RandomOverSampler, RandomUnderSampler, Smote};
+pub use crate::sampling::{shuffle, subsample, SampleSize};
+pub use crate::synthetic::{make_blobs, make_circles, make_classification, make_moons};
+
 use ndarray::{Array, Array1, Array2, azip, array};
-use ndarray_csv::{Array2Reader, ReadError};
-use ndarray_rand::rand::SeedableRng;
 use ndarray_rand::rand_distr::Uniform;
 use ndarray_rand::RandomExt;
-use ndarray_heterogeneous::Scalar;
-use rand_isaac::isaac64::Isaac64Rng;
-
-pub fn read_static_dataset() -> Array2<f64> {
-    return array![
-    [1., 2., 3.],
-    [2., 1., 3.]
-    ];
+use rune_core::random::RngSource;
+
+pub fn read_static_dataset() -> Dataset<f64> {
+    Dataset {
+        x: array![
+            [1., 2., 3.],
+            [2., 1., 3.]
+        ],
+        y: None,
+        feature_names: vec!["x0".to_owned(), "x1".to_owned(), "x2".to_owned()],
+        target_names: vec![],
+    }
 }
 
-pub fn read_iris_dataset() -> Result<Array2<Scalar>, ndarray_csv::ReadError> {
+pub fn read_iris_dataset() -> Result<Dataset<f64, String>, DataError> {
     let csv = include_str!("../iris.csv");
 
-    let mut reader = ReaderBuilder::new().has_headers(true).from_reader(csv.as_bytes());
-    let dataset: Array2<Scalar> = reader.deserialize_array2_dynamic()?;
-
-    // let r: Array2<f64> = dataset.mapv(|v| f(v));
+    let options = CsvOptions { target_column: Some("species".into()), ..Default::default() };
+    let dataset = read_csv(csv.as_bytes(), &options)?;
 
-    // fn f(s: String) -> f64 {
-    //     s.parse().unwrap()
-    // }
-
-    Ok(dataset.into_owned())
+    Ok(dataset.into_typed().with_class_names())
 }
 
-pub fn read_headbrain_dataset() -> Result<Array2<f64>, ndarray_csv::ReadError> {
+pub fn read_headbrain_dataset() -> Result<Dataset<f64>, DataError> {
     let csv = include_str!("../headbrain.csv");
 
-    let mut reader = ReaderBuilder::new().has_headers(true).from_reader(csv.as_bytes());
-    let dataset: Array2<f64> = reader.deserialize_array2((237, 4))?;
+    let options = CsvOptions { target_column: Some("Brain Weight(grams)".into()), ..Default::default() };
+    let dataset = read_csv(csv.as_bytes(), &options)?;
 
-    Ok(dataset.into_owned())
+    Ok(dataset.into_typed())
 }
 
-pub fn read_student() -> Result<Array2<f64>, ReadError> {
+pub fn read_student() -> Result<Dataset<f64>, DataError> {
     let csv = include_str!("../student.csv");
 
-    let mut reader = ReaderBuilder::new().has_headers(true).from_reader(csv.as_bytes());
-    let dataset: Array2<f64> = reader.deserialize_array2((1000, 3))?;
+    let dataset = read_csv(csv.as_bytes(), &CsvOptions::default())?;
 
-    Ok(dataset.into_owned())
+    Ok(dataset.into_typed())
 }
 
-pub fn read_banknote_authentication_dataset() -> Result<Array2<f64>, ReadError> {
+pub fn read_banknote_authentication_dataset() -> Result<Dataset<f64>, DataError> {
     let csv = include_str!("../data_banknote_authentication.csv");
 
-    let mut reader = ReaderBuilder::new().has_headers(false).from_reader(csv.as_bytes());
-    let dataset: Array2<f64> = reader.deserialize_array2((1372, 5))?;
+    let options = CsvOptions { headers: false, target_column: Some(4.into()), ..Default::default() };
+    let dataset = read_csv(csv.as_bytes(), &options)?;
+
+    // The file has no header row; these are the wavelet statistics described with the dataset.
+    let feature_names = vec!["variance", "skewness", "curtosis", "entropy"];
 
-    Ok(dataset)
+    Ok(Dataset {
+        feature_names: feature_names.into_iter().map(|name| name.to_owned()).collect(),
+        ..dataset.into_typed()
+    }.with_class_names())
 }
 
-pub fn read_wine_quality_dataset() -> Result<Array2<f64>, ReadError> {
+pub fn read_wine_quality_dataset() -> Result<Dataset<f64>, DataError> {
     let csv = include_str!("../winequality-white.csv");
 
-    let mut reader = ReaderBuilder::new()
-        .has_headers(true)
-        .delimiter(b';')
-        .from_reader(csv.as_bytes());
+    let options = CsvOptions { delimiter: b';', target_column: Some("quality".into()), ..Default::default() };
+    let dataset = read_csv(csv.as_bytes(), &options)?;
+
+    Ok(dataset.into_typed())
+}
+
+/// The iris measurements with the species of every flower.
+pub fn load_iris() -> (Array2<f64>, Array1<String>) {
+    split(read_iris_dataset().expect("the bundled iris dataset is valid"))
+}
+
+/// Gender, age range and head size with the brain weight, in grams, to predict from them.
+pub fn load_headbrain() -> (Array2<f64>, Array1<f64>) {
+    split(read_headbrain_dataset().expect("the bundled headbrain dataset is valid"))
+}
+
+/// Wavelet statistics of banknote images, labelled `true` for class 1.
+pub fn load_banknote_authentication() -> (Array2<f64>, Array1<bool>) {
+    let (x, y) = split(read_banknote_authentication_dataset().expect("the bundled banknote dataset is valid"));
 
-    let dataset: Array2<f64> = reader.deserialize_array2((4898, 12))?;
+    (x, y.mapv(|label| label == 1.))
+}
+
+/// Physicochemical measurements of white wines with their quality score.
+pub fn load_wine_quality() -> (Array2<f64>, Array1<f64>) {
+    split(read_wine_quality_dataset().expect("the bundled wine quality dataset is valid"))
+}
 
-    Ok(dataset.into_owned())
+fn split<A, T>(dataset: Dataset<A, T>) -> (Array2<A>, Array1<T>) {
+    (dataset.x, dataset.y.expect("bundled datasets have a target column"))
 }
 
-pub fn xor_dataset(count: usize) -> (Array2<f64>, Array1<bool>) {
-    let mut rng = Isaac64Rng::seed_from_u64(42);
+/// `count` points drawn uniformly from the unit square, labelled `true` in the bottom left and
+/// top right quadrants.
+pub fn xor_dataset(count: usize, rng: RngSource) -> (Array2<f64>, Array1<bool>) {
+    let mut rng = rng.rng();
 
     let x = Array::random_using((count, 2), Uniform::new(0., 1.), &mut rng);
     let mut y = Array1::default(x.nrows());
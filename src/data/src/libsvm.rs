@@ -0,0 +1,118 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use log::debug;
+use ndarray::{Array1, Array2};
+
+use crate::dataset::Dataset;
+use crate::error::DataError;
+
+/// Reads the libsvm / svmlight text format, one sample per line:
+///
+/// `<label> <index>:<value> <index>:<value> ... # comment`
+///
+/// Indices start at 1 and features left out of a line are zero. The number of features is the
+/// largest index in the file unless `n_features` is given, e.g. to read a test split with the
+/// same width as its training split. `qid:` entries are ignored.
+pub fn read_libsvm<R: BufRead>(reader: R, n_features: Option<usize>) -> Result<Dataset<f64>, DataError> {
+    let mut labels = vec![];
+    let mut rows = vec![];
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let parse_error = |message: String| DataError::Parse { line: i + 1, message };
+
+        let content = line.split('#').next().unwrap_or("").trim();
+        if content.is_empty() {
+            continue;
+        }
+
+        let mut tokens = content.split_whitespace();
+        let label = tokens.next().unwrap();
+        labels.push(label.parse::<f64>().map_err(|_| parse_error(format!("invalid label {:?}", label)))?);
+
+        let mut entries = vec![];
+        for token in tokens {
+            let (index, value) = match token.find(':') {
+                Some(position) => (&token[..position], &token[position + 1..]),
+                None => return Err(parse_error(format!("expected <index>:<value>, got {:?}", token))),
+            };
+            if index == "qid" {
+                continue;
+            }
+
+            let index = index.parse::<usize>().ok().filter(|&index| index > 0)
+                .ok_or_else(|| parse_error(format!("invalid feature index {:?}, indices start at 1", index)))?;
+            let value = value.parse::<f64>().map_err(|_| parse_error(format!("invalid value {:?}", value)))?;
+
+            if let Some(n_features) = n_features {
+                if index > n_features {
+                    return Err(parse_error(format!("feature index {} exceeds n_features = {}", index, n_features)));
+                }
+            }
+
+            entries.push((index - 1, value));
+        }
+        rows.push(entries);
+    }
+
+    let n_features = n_features.unwrap_or_else(|| rows.iter().flatten().map(|&(j, _)| j + 1).max().unwrap_or(0));
+
+    debug!("Read {} libsvm samples with {} features", rows.len(), n_features);
+
+    let mut x = Array2::zeros((rows.len(), n_features));
+    for (i, entries) in rows.into_iter().enumerate() {
+        for (j, value) in entries {
+            x[[i, j]] = value;
+        }
+    }
+
+    Ok(Dataset {
+        x,
+        y: Some(Array1::from(labels)),
+        feature_names: (0..n_features).map(|j| format!("x{}", j)).collect(),
+        target_names: vec![],
+    })
+}
+
+pub fn read_libsvm_file<P: AsRef<Path>>(path: P, n_features: Option<usize>) -> Result<Dataset<f64>, DataError> {
+    read_libsvm(BufReader::new(File::open(path)?), n_features)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::error::DataError;
+    use crate::libsvm::read_libsvm;
+
+    #[test]
+    fn test_read_libsvm_skips_comments_and_qids() {
+        let data = "# a header comment\n1 qid:3 1:0.5 3:2 # trailing comment\n\n-1 2:-1.5\n0 qid:4\n";
+        let dataset = read_libsvm(data.as_bytes(), None).unwrap();
+
+        assert_eq!(dataset.x, array![[0.5, 0., 2.], [0., -1.5, 0.], [0., 0., 0.]]);
+        assert_eq!(dataset.y.unwrap(), array![1., -1., 0.]);
+        assert_eq!(dataset.feature_names, vec!["x0", "x1", "x2"]);
+    }
+
+    #[test]
+    fn test_read_libsvm_pads_to_n_features_and_rejects_wider_lines() {
+        let dataset = read_libsvm("1 1:1\n".as_bytes(), Some(3)).unwrap();
+        assert_eq!(dataset.x, array![[1., 0., 0.]]);
+
+        let data = "1 1:1\n0 2:1 4:1\n";
+        match read_libsvm(data.as_bytes(), Some(3)) {
+            Err(DataError::Parse { line, .. }) => assert_eq!(line, 2),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_libsvm_rejects_malformed_entries() {
+        for data in ["a 1:1\n", "1 0:1\n", "1 1:x\n", "1 1\n"].iter() {
+            assert!(matches!(read_libsvm(data.as_bytes(), None), Err(DataError::Parse { line: 1, .. })), "{:?}", data);
+        }
+    }
+}
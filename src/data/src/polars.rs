@@ -0,0 +1,229 @@
+use std::convert::TryFrom;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use log::debug;
+use ndarray::{Array2, ArrayView1, ArrayView2};
+use ndarray_heterogeneous::Scalar;
+use polars::prelude::{DataFrame, DataType, IntoColumn, NamedFrom, Series, TimeUnit};
+
+use crate::csv_reader::Column;
+use crate::dataset::Dataset;
+use crate::error::DataError;
+
+/// Reads every column of a polars data frame into a dataset, keeping the column names. Integer,
+/// float, boolean, string, date and datetime columns are supported; missing values follow
+/// `read_record_batches`, and datetime columns with nulls are read as strings.
+pub fn read_dataframe(frame: &DataFrame, target_column: Option<&Column>) -> Result<Dataset<Scalar>, DataError> {
+    let names: Vec<String> = frame.get_column_names_str().into_iter().map(|name| name.to_owned()).collect();
+
+    let columns = frame.get_columns().iter()
+        .map(|column| series_values(column.as_materialized_series()))
+        .collect::<Result<Vec<Vec<Scalar>>, DataError>>()?;
+
+    debug!("Read {} columns of {} rows from a data frame", names.len(), frame.height());
+
+    Dataset::from_columns(names, columns, target_column)
+}
+
+/// The cells of `frame` as a matrix, one column per column of the frame.
+pub fn dataframe_to_array(frame: &DataFrame) -> Result<Array2<Scalar>, DataError> {
+    Ok(read_dataframe(frame, None)?.x)
+}
+
+/// A data frame with a column named after each of `names` per column of `x`. Every column must
+/// hold a single kind of scalar, except that integers and floats mix into a float column.
+pub fn array_to_dataframe(x: ArrayView2<Scalar>, names: &[String]) -> Result<DataFrame, DataError> {
+    if names.len() != x.ncols() {
+        return Err(DataError::InvalidFormat(format!("{} column names for {} columns", names.len(), x.ncols())));
+    }
+
+    let columns = names.iter()
+        .zip(x.gencolumns())
+        .map(|(name, values)| column_series(name, values).map(|series| series.into_column()))
+        .collect::<Result<Vec<_>, DataError>>()?;
+
+    Ok(DataFrame::new(columns)?)
+}
+
+impl TryFrom<&DataFrame> for Dataset<Scalar> {
+    type Error = DataError;
+
+    fn try_from(frame: &DataFrame) -> Result<Self, Self::Error> {
+        read_dataframe(frame, None)
+    }
+}
+
+/// The features of the dataset under their names, followed by its targets, when it has any, in
+/// a column named `target`.
+impl TryFrom<&Dataset<Scalar>> for DataFrame {
+    type Error = DataError;
+
+    fn try_from(dataset: &Dataset<Scalar>) -> Result<Self, Self::Error> {
+        let mut frame = array_to_dataframe(dataset.x.view(), &dataset.feature_names)?;
+
+        if let Some(y) = &dataset.y {
+            frame.with_column(column_series("target", y.view())?)?;
+        }
+
+        Ok(frame)
+    }
+}
+
+fn series_values(series: &Series) -> Result<Vec<Scalar>, DataError> {
+    let has_nulls = series.null_count() > 0;
+
+    let values = match series.dtype() {
+        data_type if data_type.is_integer() => series.strict_cast(&DataType::Int64)?.i64()?
+            .into_iter()
+            .map(|value| match (value, has_nulls) {
+                (Some(value), false) => Scalar::I64(value),
+                (value, true) => Scalar::F64(value.map_or(f64::NAN, |value| value as f64)),
+                (None, false) => unreachable!(),
+            })
+            .collect(),
+        data_type if data_type.is_float() => series.cast(&DataType::Float64)?.f64()?
+            .into_iter()
+            .map(|value| Scalar::F64(value.unwrap_or(f64::NAN)))
+            .collect(),
+        DataType::Boolean => series.bool()?
+            .into_iter()
+            .map(|value| match (value, has_nulls) {
+                (Some(value), false) => Scalar::BOOL(value),
+                (value, true) => Scalar::STRING(value.map(|value| value.to_string()).unwrap_or_default()),
+                (None, false) => unreachable!(),
+            })
+            .collect(),
+        DataType::String => series.str()?
+            .into_iter()
+            .map(|value| Scalar::STRING(value.unwrap_or("").to_owned()))
+            .collect(),
+        DataType::Date => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+            let days = series.date()?.physical().into_iter();
+            datetimes(days.map(|days| days.map(|days| epoch + chrono::Duration::days(days.into()))), has_nulls)
+        }
+        DataType::Datetime(unit, _) => {
+            // Time zoned datetimes are stored in UTC, which is what datetimes are parsed to.
+            let to_datetime = match unit {
+                TimeUnit::Nanoseconds => |value: i64| Some(DateTime::from_timestamp_nanos(value)),
+                TimeUnit::Microseconds => DateTime::from_timestamp_micros,
+                TimeUnit::Milliseconds => DateTime::from_timestamp_millis,
+            };
+            let values = series.datetime()?.physical().into_iter();
+            datetimes(values.map(|value| value.and_then(to_datetime).map(|datetime| datetime.naive_utc())), has_nulls)
+        }
+        data_type => return Err(DataError::UnsupportedType { column: series.name().to_string(), data_type: data_type.to_string() }),
+    };
+
+    Ok(values)
+}
+
+fn datetimes<I: Iterator<Item=Option<NaiveDateTime>>>(values: I, has_nulls: bool) -> Vec<Scalar> {
+    values
+        .map(|value| match (value, has_nulls) {
+            (Some(value), false) => Scalar::DATETIME(value),
+            (value, true) => Scalar::STRING(value.map(|value| Scalar::DATETIME(value).into()).unwrap_or_default()),
+            (None, false) => unreachable!(),
+        })
+        .collect()
+}
+
+fn column_series(name: &str, values: ArrayView1<Scalar>) -> Result<Series, DataError> {
+    let unsupported = |data_type: &str| DataError::UnsupportedType { column: name.to_owned(), data_type: data_type.to_owned() };
+    let all = |matches: fn(&Scalar) -> bool| values.iter().all(matches);
+
+    let series = if all(|value| matches!(value, Scalar::I64(_))) {
+        Series::new(name.into(), values.iter().map(|value| if let Scalar::I64(value) = value { *value } else { unreachable!() }).collect::<Vec<i64>>())
+    } else if all(|value| matches!(value, Scalar::I64(_) | Scalar::F64(_))) {
+        Series::new(name.into(), values.iter().map(|value| f64::from(value.clone())).collect::<Vec<f64>>())
+    } else if all(|value| matches!(value, Scalar::BOOL(_))) {
+        Series::new(name.into(), values.iter().map(|value| value == &Scalar::BOOL(true)).collect::<Vec<bool>>())
+    } else if all(|value| matches!(value, Scalar::STRING(_))) {
+        Series::new(name.into(), values.iter().map(|value| String::from(value.clone())).collect::<Vec<String>>())
+    } else if all(|value| matches!(value, Scalar::DATETIME(_))) {
+        let micros = values.iter()
+            .map(|value| if let Scalar::DATETIME(value) = value { value.and_utc().timestamp_micros() } else { unreachable!() })
+            .collect::<Vec<i64>>();
+        Series::new(name.into(), micros).cast(&DataType::Datetime(TimeUnit::Microseconds, None))?
+    } else {
+        return Err(unsupported("mixed scalar types"));
+    };
+
+    Ok(series)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use chrono::NaiveDate;
+    use ndarray::{array, Array1, Axis};
+    use ndarray_heterogeneous::Scalar;
+    use polars::prelude::{DataFrame, DataType, IntoColumn, NamedFrom, Series};
+
+    use crate::csv_reader::Column;
+    use crate::dataset::Dataset;
+    use crate::error::DataError;
+    use crate::polars::{array_to_dataframe, dataframe_to_array, read_dataframe};
+
+    fn dataset() -> Dataset<Scalar> {
+        let noon = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap().and_hms_opt(12, 0, 0).unwrap();
+
+        Dataset {
+            x: array![
+                [Scalar::I64(1), Scalar::F64(0.5), Scalar::BOOL(true), Scalar::STRING("a".to_owned()), Scalar::DATETIME(noon)],
+                [Scalar::I64(-2), Scalar::F64(1e-300), Scalar::BOOL(false), Scalar::STRING("".to_owned()), Scalar::DATETIME(noon + chrono::Duration::microseconds(1))],
+            ],
+            y: Some(array![Scalar::STRING("yes".to_owned()), Scalar::STRING("no".to_owned())]),
+            feature_names: vec!["count".to_owned(), "ratio".to_owned(), "flag".to_owned(), "name".to_owned(), "seen".to_owned()],
+            target_names: vec![],
+        }
+    }
+
+    #[test]
+    fn test_datasets_round_trip_through_data_frames() {
+        let dataset = dataset();
+
+        let frame = DataFrame::try_from(&dataset).unwrap();
+        assert_eq!(frame.get_column_names_str(), vec!["count", "ratio", "flag", "name", "seen", "target"]);
+        assert_eq!(frame.dtypes()[..4], [DataType::Int64, DataType::Float64, DataType::Boolean, DataType::String]);
+
+        let read = read_dataframe(&frame, Some(&Column::from("target"))).unwrap();
+        assert_eq!(read.feature_names, dataset.feature_names);
+        assert_eq!(read.x, dataset.x);
+        assert_eq!(read.y, dataset.y);
+
+        let without_target = Dataset::try_from(&frame).unwrap();
+        assert_eq!(without_target.feature_names.last().unwrap(), "target");
+        assert!(without_target.y.is_none());
+        assert_eq!(dataframe_to_array(&frame).unwrap(), without_target.x);
+    }
+
+    #[test]
+    fn test_nulls_are_read_as_read_record_batches_reads_them() {
+        let frame = DataFrame::new(vec![
+            Series::new("count".into(), &[Some(1i64), None]).into_column(),
+            Series::new("flag".into(), &[Some(true), None]).into_column(),
+            Series::new("name".into(), &[None, Some("b")]).into_column(),
+        ]).unwrap();
+
+        let x = dataframe_to_array(&frame).unwrap();
+        assert_eq!(x[[0, 0]], Scalar::F64(1.));
+        assert!(matches!(x[[1, 0]], Scalar::F64(value) if value.is_nan()));
+        assert_eq!(x.index_axis(Axis(1), 1).to_vec(), vec![Scalar::STRING("true".to_owned()), Scalar::STRING(String::new())]);
+        assert_eq!(x.index_axis(Axis(1), 2).to_vec(), vec![Scalar::STRING(String::new()), Scalar::STRING("b".to_owned())]);
+    }
+
+    #[test]
+    fn test_integers_and_floats_mix_but_other_types_do_not() {
+        let x = array![[Scalar::I64(1)], [Scalar::F64(2.5)]];
+        let frame = array_to_dataframe(x.view(), &["value".to_owned()]).unwrap();
+        assert_eq!(frame.dtypes(), vec![DataType::Float64]);
+        assert_eq!(dataframe_to_array(&frame).unwrap().column(0).to_owned(), Array1::from(vec![Scalar::F64(1.), Scalar::F64(2.5)]));
+
+        let mixed = array![[Scalar::I64(1)], [Scalar::STRING("a".to_owned())]];
+        assert!(matches!(array_to_dataframe(mixed.view(), &["value".to_owned()]), Err(DataError::UnsupportedType { .. })));
+        assert!(matches!(array_to_dataframe(x.view(), &[]), Err(DataError::InvalidFormat(_))));
+    }
+}
@@ -0,0 +1,253 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rand::prelude::*;
+use rune_core::random::RngSource;
+use rune_core::validation::{check_n_samples, check_x_y};
+use rune_core::{Error, Result};
+
+/// The sample indexes of every class in `y`, classes in order of first appearance.
+pub(crate) fn class_indexes<T: Eq + Hash>(y: ArrayView1<T>) -> Vec<Vec<usize>> {
+    let mut positions: HashMap<&T, usize> = HashMap::new();
+    let mut classes: Vec<Vec<usize>> = vec![];
+
+    for (index, label) in y.iter().enumerate() {
+        let position = *positions.entry(label).or_insert_with(|| {
+            classes.push(vec![]);
+            classes.len() - 1
+        });
+        classes[position].push(index);
+    }
+
+    classes
+}
+
+pub(crate) fn select_labels<T: Clone>(y: ArrayView1<T>, indexes: &[usize]) -> Array1<T> {
+    indexes.iter().map(|&index| y[index].clone()).collect()
+}
+
+/// Balances classes by drawing extra samples of every minority class, with replacement, until
+/// each class is as large as the majority class. The drawn samples are appended after the
+/// original ones.
+#[derive(Debug, Clone)]
+pub struct RandomOverSampler {
+    rng: RngSource,
+}
+
+impl RandomOverSampler {
+    pub fn new(rng: RngSource) -> Self {
+        RandomOverSampler { rng }
+    }
+
+    pub fn fit_resample<X: Copy, T: Clone + Eq + Hash>(&self, x: ArrayView2<X>, y: ArrayView1<T>) -> Result<(Array2<X>, Array1<T>)> {
+        check_n_samples(x.nrows(), y.len())?;
+        let mut rng = self.rng.rng();
+
+        let classes = class_indexes(y);
+        let largest = classes.iter().map(|indexes| indexes.len()).max().unwrap_or(0);
+
+        let mut indexes: Vec<usize> = (0..y.len()).collect();
+        for class in classes.iter() {
+            indexes.extend((class.len()..largest).map(|_| *class.choose(&mut rng).unwrap()));
+        }
+
+        Ok((x.select(Axis(0), &indexes), select_labels(y, &indexes)))
+    }
+}
+
+/// Balances classes by keeping a random subset, without replacement, of every class the size of
+/// the minority class. The kept samples stay in their original order.
+#[derive(Debug, Clone)]
+pub struct RandomUnderSampler {
+    rng: RngSource,
+}
+
+impl RandomUnderSampler {
+    pub fn new(rng: RngSource) -> Self {
+        RandomUnderSampler { rng }
+    }
+
+    pub fn fit_resample<X: Copy, T: Clone + Eq + Hash>(&self, x: ArrayView2<X>, y: ArrayView1<T>) -> Result<(Array2<X>, Array1<T>)> {
+        check_n_samples(x.nrows(), y.len())?;
+        let mut rng = self.rng.rng();
+
+        let classes = class_indexes(y);
+        let smallest = classes.iter().map(|indexes| indexes.len()).min().unwrap_or(0);
+
+        let mut indexes: Vec<usize> = classes.iter()
+            .flat_map(|class| class.choose_multiple(&mut rng, smallest).cloned().collect::<Vec<usize>>())
+            .collect();
+        indexes.sort_unstable();
+
+        Ok((x.select(Axis(0), &indexes), select_labels(y, &indexes)))
+    }
+}
+
+/// SMOTE (Chawla et al., 2002): balances classes by synthesising minority samples instead of
+/// repeating them. Each synthetic sample lies at a random point on the segment between a random
+/// sample of its class and one of that sample's `k_neighbors` nearest (Euclidean) neighbours in
+/// the same class. The synthetic samples are appended after the original ones.
+#[derive(Debug, Clone)]
+pub struct Smote {
+    k_neighbors: usize,
+    rng: RngSource,
+}
+
+impl Smote {
+    pub fn new(k_neighbors: usize, rng: RngSource) -> Self {
+        Smote { k_neighbors, rng }
+    }
+
+    /// Fails when a minority class has a single sample, which has no neighbour to interpolate
+    /// towards.
+    pub fn fit_resample<T: Clone + Eq + Hash>(&self, x: ArrayView2<f64>, y: ArrayView1<T>) -> Result<(Array2<f64>, Array1<T>)> {
+        if self.k_neighbors == 0 {
+            return Err(Error::InvalidParameter("SMOTE needs at least one neighbour".to_owned()));
+        }
+        check_x_y(x, y, 1)?;
+        let mut rng = self.rng.rng();
+
+        let classes = class_indexes(y);
+        let largest = classes.iter().map(|indexes| indexes.len()).max().unwrap_or(0);
+        if let Some(class) = classes.iter().find(|class| class.len() < 2 && class.len() < largest) {
+            return Err(Error::InvalidInput(format!("SMOTE needs at least 2 samples of every minority class, got {}", class.len())));
+        }
+
+        let mut rows: Vec<f64> = x.iter().cloned().collect();
+        let mut sources: Vec<usize> = (0..y.len()).collect();
+
+        for class in classes.iter().filter(|class| class.len() < largest) {
+            let neighbours = self.nearest_neighbours(x, class);
+
+            for _ in class.len()..largest {
+                let sample = rng.gen_range(0, class.len());
+                let neighbour = *neighbours[sample].choose(&mut rng).unwrap();
+                let gap: f64 = rng.gen();
+
+                let from = x.row(class[sample]);
+                let to = x.row(neighbour);
+                rows.extend(from.iter().zip(to.iter()).map(|(a, b)| a + gap * (b - a)));
+                sources.push(class[sample]);
+            }
+        }
+
+        let x = Array2::from_shape_vec((sources.len(), x.ncols()), rows).unwrap();
+
+        Ok((x, select_labels(y, &sources)))
+    }
+
+    /// The `k_neighbors` nearest other samples of `class` to each of its samples.
+    fn nearest_neighbours(&self, x: ArrayView2<f64>, class: &[usize]) -> Vec<Vec<usize>> {
+        let k = self.k_neighbors.min(class.len() - 1);
+
+        class.iter()
+            .map(|&sample| {
+                let mut others: Vec<(f64, usize)> = class.iter()
+                    .filter(|&&other| other != sample)
+                    .map(|&other| {
+                        let distance: f64 = x.row(sample).iter().zip(x.row(other).iter()).map(|(a, b)| (a - b).powi(2)).sum();
+                        (distance, other)
+                    })
+                    .collect();
+                others.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+                others.into_iter().take(k).map(|(_, other)| other).collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2, s, Array1};
+    use rune_core::random::RngSource;
+    use rune_core::Error;
+
+    use crate::resample::{RandomOverSampler, RandomUnderSampler, Smote};
+
+    fn count(y: &Array1<u8>, label: u8) -> usize {
+        y.iter().filter(|&&l| l == label).count()
+    }
+
+    #[test]
+    fn test_random_over_sampler_balances_classes() {
+        let x = arr2(&[[0.], [1.], [2.], [3.], [4.], [5.]]);
+        let y = arr1(&[0u8, 0, 0, 0, 1, 2]);
+        let (xr, yr) = RandomOverSampler::new(RngSource::Seed(0)).fit_resample(x.view(), y.view()).unwrap();
+
+        assert_eq!(yr.len(), 12);
+        assert_eq!((count(&yr, 0), count(&yr, 1), count(&yr, 2)), (4, 4, 4));
+        assert_eq!(xr.slice(s![..6, ..]), x);
+        // Every drawn sample repeats one of its class.
+        for (row, &label) in xr.outer_iter().zip(yr.iter()).skip(6) {
+            assert_eq!(row[0], if label == 1 { 4. } else { 5. });
+        }
+    }
+
+    #[test]
+    fn test_random_under_sampler_balances_classes() {
+        let x = arr2(&[[0.], [1.], [2.], [3.], [4.], [5.]]);
+        let y = arr1(&[0u8, 0, 0, 1, 1, 0]);
+        let (xr, yr) = RandomUnderSampler::new(RngSource::Seed(0)).fit_resample(x.view(), y.view()).unwrap();
+
+        assert_eq!((count(&yr, 0), count(&yr, 1)), (2, 2));
+        // The kept samples stay in their original order, with their labels.
+        assert!(xr.column(0).windows(2).into_iter().all(|pair| pair[0] < pair[1]));
+        for (row, &label) in xr.outer_iter().zip(yr.iter()) {
+            assert_eq!(label, y[row[0] as usize]);
+        }
+    }
+
+    #[test]
+    fn test_random_samplers_reject_mismatched_labels() {
+        let x = arr2(&[[0.], [1.]]);
+        let y = arr1(&[0u8]);
+
+        assert!(matches!(RandomOverSampler::new(RngSource::Seed(0)).fit_resample(x.view(), y.view()), Err(Error::ShapeMismatch(_))));
+        assert!(matches!(RandomUnderSampler::new(RngSource::Seed(0)).fit_resample(x.view(), y.view()), Err(Error::ShapeMismatch(_))));
+    }
+
+    #[test]
+    fn test_smote_interpolates_between_neighbours_of_a_class() {
+        let x = arr2(&[
+            [0., 0.], [10., 0.], [0., 10.], [10., 10.], [5., 5.], [6., 4.],
+            [100., 100.], [101., 100.], [100., 102.],
+        ]);
+        let y = arr1(&[0u8, 0, 0, 0, 0, 0, 1, 1, 1]);
+        let minority = [[100., 100.], [101., 100.], [100., 102.]];
+
+        let (xr, yr) = Smote::new(1, RngSource::Seed(0)).fit_resample(x.view(), y.view()).unwrap();
+
+        assert_eq!((count(&yr, 0), count(&yr, 1)), (6, 6));
+        assert_eq!(xr.slice(s![..9, ..]), x);
+
+        // With one neighbour, [100, 100] and [101, 100] are each other's nearest, and
+        // [100, 102] is nearest to [100, 100], so every synthetic sample lies on one of the
+        // segments from a sample towards its nearest neighbour.
+        let segments = [(minority[0], minority[1]), (minority[1], minority[0]), (minority[2], minority[0])];
+        for (row, &label) in xr.outer_iter().zip(yr.iter()).skip(9) {
+            assert_eq!(label, 1);
+            let on_segment = segments.iter().any(|(from, to)| {
+                let gap = if to[0] != from[0] { (row[0] - from[0]) / (to[0] - from[0]) } else { (row[1] - from[1]) / (to[1] - from[1]) };
+                (0. ..=1.).contains(&gap)
+                    && (0..2).all(|j| (from[j] + gap * (to[j] - from[j]) - row[j]).abs() < 1e-12)
+            });
+            assert!(on_segment, "{} is not between neighbours of its class", row);
+        }
+    }
+
+    #[test]
+    fn test_smote_rejects_a_single_minority_sample_and_nan() {
+        let x = arr2(&[[0.], [1.], [2.]]);
+        let y = arr1(&[0u8, 0, 1]);
+        assert!(matches!(Smote::new(5, RngSource::Seed(0)).fit_resample(x.view(), y.view()), Err(Error::InvalidInput(_))));
+
+        let x = arr2(&[[0.], [f64::NAN], [2.], [3.]]);
+        let y = arr1(&[0u8, 0, 0, 1]);
+        assert!(matches!(Smote::new(5, RngSource::Seed(0)).fit_resample(x.view(), y.view()), Err(Error::InvalidInput(_))));
+
+        assert!(matches!(Smote::new(0, RngSource::Seed(0)).fit_resample(x.view(), y.view()), Err(Error::InvalidParameter(_))));
+    }
+}
@@ -0,0 +1,82 @@
+use std::hash::Hash;
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rand::prelude::*;
+use rune_core::random::RngSource;
+
+use crate::resample::{class_indexes, select_labels};
+
+/// How many samples `subsample` keeps: an exact count, or a fraction of the dataset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleSize {
+    Count(usize),
+    Fraction(f64),
+}
+
+impl SampleSize {
+    fn of(self, n_samples: usize) -> usize {
+        match self {
+            SampleSize::Count(count) => {
+                assert!(count <= n_samples, "Cannot subsample {} of {} samples", count, n_samples);
+                count
+            }
+            SampleSize::Fraction(fraction) => {
+                assert!((0. ..=1.).contains(&fraction), "subsample fraction must be between 0 and 1, got {}", fraction);
+                (n_samples as f64 * fraction).round() as usize
+            }
+        }
+    }
+}
+
+impl From<usize> for SampleSize {
+    fn from(count: usize) -> Self {
+        SampleSize::Count(count)
+    }
+}
+
+impl From<f64> for SampleSize {
+    fn from(fraction: f64) -> Self {
+        SampleSize::Fraction(fraction)
+    }
+}
+
+/// Shuffles the rows of `x` and `y` together.
+pub fn shuffle<X: Copy, T: Clone>(x: ArrayView2<X>, y: ArrayView1<T>, rng: RngSource) -> (Array2<X>, Array1<T>) {
+    let mut indexes: Vec<usize> = (0..x.nrows()).collect();
+    indexes.shuffle(&mut rng.rng());
+
+    (x.select(Axis(0), &indexes), select_labels(y, &indexes))
+}
+
+/// Keeps a random subset of `size` rows, e.g. `10_000` or `0.1`, in their original order. With
+/// `stratify` every class keeps its share of the subset, rounded so the shares add up to `size`.
+pub fn subsample<X: Copy, T: Clone + Eq + Hash, S: Into<SampleSize>>(x: ArrayView2<X>, y: ArrayView1<T>, size: S, stratify: bool, rng: RngSource) -> (Array2<X>, Array1<T>) {
+    let mut rng = rng.rng();
+    let n_samples = x.nrows();
+    let n_kept = size.into().of(n_samples);
+
+    let mut indexes: Vec<usize> = if stratify {
+        let classes = class_indexes(y);
+
+        // Largest remainder: every class gets the floor of its share, then the classes losing the
+        // most to rounding get one more until the counts add up.
+        let shares: Vec<f64> = classes.iter().map(|class| class.len() as f64 * n_kept as f64 / n_samples as f64).collect();
+        let mut counts: Vec<usize> = shares.iter().map(|share| share.floor() as usize).collect();
+
+        let mut by_remainder: Vec<usize> = (0..classes.len()).collect();
+        by_remainder.sort_by(|&a, &b| (shares[b] - shares[b].floor()).partial_cmp(&(shares[a] - shares[a].floor())).unwrap());
+        for &class in by_remainder.iter().take(n_kept - counts.iter().sum::<usize>()) {
+            counts[class] += 1;
+        }
+
+        classes.iter()
+            .zip(counts)
+            .flat_map(|(class, count)| class.choose_multiple(&mut rng, count).cloned().collect::<Vec<usize>>())
+            .collect()
+    } else {
+        (0..n_samples).choose_multiple(&mut rng, n_kept)
+    };
+    indexes.sort_unstable();
+
+    (x.select(Axis(0), &indexes), select_labels(y, &indexes))
+}
@@ -0,0 +1,97 @@
+use std::f64::consts::PI;
+
+use ndarray::{Array, Array1, Array2, s};
+use ndarray_rand::rand_distr::{Normal, Uniform};
+use ndarray_rand::RandomExt;
+use rune_core::random::RngSource;
+
+/// Gaussian clusters of `n_features` dimensions with standard deviation `cluster_std` around
+/// `centers` centres drawn uniformly from `[-10, 10)`. Sample `i` belongs to cluster
+/// `i % centers`, so the clusters are as close to equal in size as possible.
+pub fn make_blobs(n_samples: usize, centers: usize, n_features: usize, cluster_std: f64, rng: RngSource) -> (Array2<f64>, Array1<usize>) {
+    assert!(centers > 0, "make_blobs needs at least one centre");
+
+    let mut rng = rng.rng();
+
+    let centres = Array::random_using((centers, n_features), Uniform::new(-10., 10.), &mut rng);
+    let y = Array1::from_shape_fn(n_samples, |i| i % centers);
+
+    let mut x = Array::random_using((n_samples, n_features), Normal::new(0., cluster_std).unwrap(), &mut rng);
+    for (mut row, &label) in x.genrows_mut().into_iter().zip(y.iter()) {
+        row += &centres.row(label);
+    }
+
+    (x, y)
+}
+
+/// Two interleaving half circles in two dimensions. The lower one, shifted right, is labelled
+/// `true`. `noise` is the standard deviation of the Gaussian noise added to every point.
+pub fn make_moons(n_samples: usize, noise: f64, rng: RngSource) -> (Array2<f64>, Array1<bool>) {
+    let n_outer = n_samples / 2;
+    let n_inner = n_samples - n_outer;
+
+    let outer = Array1::linspace(0., PI, n_outer);
+    let inner = Array1::linspace(0., PI, n_inner);
+
+    let mut x = Array2::zeros((n_samples, 2));
+    x.slice_mut(s![..n_outer, 0]).assign(&outer.mapv(f64::cos));
+    x.slice_mut(s![..n_outer, 1]).assign(&outer.mapv(f64::sin));
+    x.slice_mut(s![n_outer.., 0]).assign(&inner.mapv(|t| 1. - t.cos()));
+    x.slice_mut(s![n_outer.., 1]).assign(&inner.mapv(|t| 0.5 - t.sin()));
+
+    let y = Array1::from_shape_fn(n_samples, |i| i >= n_outer);
+
+    (x + noise_for(n_samples, noise, rng), y)
+}
+
+/// A small circle of radius `factor` inside a unit circle, both centred on the origin. Points on
+/// the inner circle are labelled `true`. `noise` is the standard deviation of the Gaussian noise
+/// added to every point.
+pub fn make_circles(n_samples: usize, noise: f64, factor: f64, rng: RngSource) -> (Array2<f64>, Array1<bool>) {
+    assert!((0. ..1.).contains(&factor), "make_circles needs 0 <= factor < 1, got {}", factor);
+
+    let n_outer = n_samples / 2;
+    let n_inner = n_samples - n_outer;
+
+    // Angles spaced evenly without repeating the starting point at 2 pi.
+    let outer = Array1::range(0., n_outer as f64, 1.) * (2. * PI / n_outer.max(1) as f64);
+    let inner = Array1::range(0., n_inner as f64, 1.) * (2. * PI / n_inner.max(1) as f64);
+
+    let mut x = Array2::zeros((n_samples, 2));
+    x.slice_mut(s![..n_outer, 0]).assign(&outer.mapv(f64::cos));
+    x.slice_mut(s![..n_outer, 1]).assign(&outer.mapv(f64::sin));
+    x.slice_mut(s![n_outer.., 0]).assign(&inner.mapv(|t| factor * t.cos()));
+    x.slice_mut(s![n_outer.., 1]).assign(&inner.mapv(|t| factor * t.sin()));
+
+    let y = Array1::from_shape_fn(n_samples, |i| i >= n_outer);
+
+    (x + noise_for(n_samples, noise, rng), y)
+}
+
+/// A `n_classes` classification problem where only the first `n_informative` features carry
+/// signal. Each class is a standard normal cluster around its own vertex of the hypercube with
+/// side `2 * class_sep`; the remaining features are standard normal noise. Labels are assigned
+/// round robin, so the classes are balanced.
+pub fn make_classification(n_samples: usize, n_features: usize, n_informative: usize, n_classes: usize, class_sep: f64, rng: RngSource) -> (Array2<f64>, Array1<usize>) {
+    assert!(n_informative <= n_features, "make_classification needs n_informative <= n_features");
+    assert!(n_classes >= 2 && n_classes <= 1 << n_informative, "make_classification needs 2 <= n_classes <= 2^n_informative");
+
+    let mut rng = rng.rng();
+
+    let mut x = Array::random_using((n_samples, n_features), Normal::new(0., 1.).unwrap(), &mut rng);
+    let y = Array1::from_shape_fn(n_samples, |i| i % n_classes);
+
+    for (mut row, &label) in x.genrows_mut().into_iter().zip(y.iter()) {
+        for j in 0..n_informative {
+            row[j] += if label >> j & 1 == 1 { class_sep } else { -class_sep };
+        }
+    }
+
+    (x, y)
+}
+
+fn noise_for(n_samples: usize, noise: f64, rng: RngSource) -> Array2<f64> {
+    let mut rng = rng.rng();
+
+    Array::random_using((n_samples, 2), Normal::new(0., noise).unwrap(), &mut rng)
+}
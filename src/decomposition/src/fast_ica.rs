@@ -0,0 +1,277 @@
+use ndarray::{s, Axis, ArrayView2, Array2, ArrayView1, Array1, Array, Dimension};
+
+use log::{debug, warn};
+use ndarray_stats::CorrelationExt;
+use ndarray_linalg::{Eigh, UPLO};
+use rand::Rng;
+use std::cmp::Ordering;
+use rune_core::random::RngSource;
+use rune_core::validation::check_x;
+use rune_core::{Error, Result};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::estimator::{Transformer, Fit, FitTransform};
+use serde::{Deserialize, Serialize};
+
+/// How FastICA finds the components: all at once, decorrelating them symmetrically after every
+/// step, or one at a time, each kept orthogonal to the ones already found.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IcaAlgorithm {
+    Parallel,
+    Deflation,
+}
+
+/// Independent component analysis by FastICA (Hyvärinen & Oja, 2000): separates signals that
+/// were linearly mixed, e.g. overlapping recordings, by finding the directions along which the
+/// whitened data is least Gaussian, measured with the log cosh contrast. Where PCA finds
+/// uncorrelated directions of most variance, ICA finds statistically independent ones.
+#[derive(Debug)]
+pub struct FastICA {
+    n_components: usize,
+    algorithm: IcaAlgorithm,
+    max_iterations: usize,
+    tolerance: f64,
+    rng: RngSource,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FastICATransformer {
+    mean: Array1<f64>,
+    components: Array2<f64>,
+    mixing: Array2<f64>,
+}
+
+impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for FastICATransformer {
+    fn transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_transform(x)
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
+}
+
+impl Transformer<Array2<f64>, Array2<f64>> for FastICATransformer {
+    fn transform(&self, x: Array2<f64>) -> Array2<f64> {
+        self.internal_transform(x.view())
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, FastICATransformer, Y> for FastICA {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<FastICATransformer> {
+        self.internal_fit(x)
+    }
+}
+
+impl<Y> Fit<Array2<f64>, FastICATransformer, Y> for FastICA {
+    fn fit(&self, x: Array2<f64>, _y: ArrayView1<Y>) -> Result<FastICATransformer> {
+        self.internal_fit(x.view())
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, f64>, FastICATransformer, Array2<f64>, Y> for FastICA {}
+
+impl<Y> FitTransform<Array2<f64>, FastICATransformer, Array2<f64>, Y> for FastICA {}
+
+impl FastICATransformer {
+    pub fn new(mean: Array1<f64>, components: Array2<f64>, mixing: Array2<f64>) -> Self {
+        FastICATransformer { mean, components, mixing }
+    }
+
+    /// The unmixing matrix, one row per independent component, applied to the centred data.
+    pub fn components(&self) -> ArrayView2<'_, f64> {
+        self.components.view()
+    }
+
+    /// The estimated mixing matrix, one column per independent component: how much each source
+    /// contributes to each feature.
+    pub fn mixing(&self) -> ArrayView2<'_, f64> {
+        self.mixing.view()
+    }
+
+    pub fn internal_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        (&x - &self.mean).dot(&self.components.t())
+    }
+
+    /// Remixes sources into the original features, which recovers the part of the data in the
+    /// whitened subspace.
+    pub fn internal_inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        x.dot(&self.mixing.t()) + &self.mean
+    }
+}
+
+impl FastICA {
+    pub fn new(n_components: usize, algorithm: IcaAlgorithm, rng: RngSource) -> Self {
+        FastICA {
+            n_components,
+            algorithm,
+            max_iterations: 200,
+            tolerance: 1e-4,
+            rng,
+        }
+    }
+
+    /// The most fixed-point iterations per run, or per component when deflating.
+    pub fn with_max_iterations(self, max_iterations: usize) -> Self {
+        FastICA { max_iterations, ..self }
+    }
+
+    /// How close consecutive unmixing vectors must be to stop, as `1 - |cos|` of the angle
+    /// between them.
+    pub fn with_tolerance(self, tolerance: f64) -> Self {
+        FastICA { tolerance, ..self }
+    }
+
+    pub fn internal_fit(&self, x: ArrayView2<f64>) -> Result<FastICATransformer> {
+        check_x(x, 2)?;
+
+        let k = self.n_components;
+        if k == 0 || k > x.ncols() {
+            return Err(Error::InvalidParameter(format!(
+                "n_components should be between 1 and the number of features ({}), got {}", x.ncols(), k)));
+        }
+
+        let mean = x.mean_axis(Axis(0))
+            .ok_or_else(|| Error::InvalidInput("cannot fit FastICA to no samples".to_owned()))?;
+        let co_variance_matrix = x.t().cov(1.)
+            .map_err(|_| Error::InvalidInput("cannot compute the covariance of fewer than 2 samples".to_owned()))?;
+
+        // Whiten with PCA: project onto the top principal directions and scale them to unit
+        // variance, leaving only a rotation for ICA to find.
+        let (eig_val, eig_vec) = co_variance_matrix.eigh(UPLO::Upper)
+            .map_err(|e| Error::LinearAlgebra(e.to_string()))?;
+        let mut order: Vec<usize> = (0..eig_val.len()).collect();
+        order.sort_by(|&a, &b| eig_val[b].partial_cmp(&eig_val[a]).unwrap_or(Ordering::Equal));
+
+        let scale: Array1<f64> = order[..k].iter().map(|&i| eig_val[i].max(0.).sqrt()).collect();
+        if scale[k - 1] < 1e-10 {
+            return Err(Error::InvalidInput(format!("x varies in fewer than {} independent directions", k)));
+        }
+        let directions = eig_vec.select(Axis(1), &order[..k]);
+        let whitening = &directions / &scale;
+        let whitened = (&x - &mean).dot(&whitening);
+
+        let mut rng = self.rng.rng();
+        let initial = Array2::from_shape_fn((k, k), |_| rng.gen_range(-1., 1.));
+
+        let unmixing = match self.algorithm {
+            IcaAlgorithm::Parallel => self.parallel(whitened.view(), initial)?,
+            IcaAlgorithm::Deflation => self.deflation(whitened.view(), initial),
+        };
+        debug!("unmixing: {}", unmixing);
+
+        // The unmixing matrix is orthogonal, so undoing it and the whitening is a transpose and
+        // a rescale.
+        let components = unmixing.dot(&whitening.t());
+        let mixing = (&unmixing * &scale).dot(&directions.t()).reversed_axes();
+
+        Ok(FastICATransformer::new(mean, components, mixing))
+    }
+
+    /// Updates every unmixing vector at once, then makes them orthonormal again.
+    fn parallel(&self, x: ArrayView2<f64>, initial: Array2<f64>) -> Result<Array2<f64>> {
+        let n_samples = x.nrows() as f64;
+        let mut w = symmetric_decorrelation(initial)?;
+
+        for iteration in 0..self.max_iterations {
+            let (g, g_prime) = log_cosh(x.dot(&w.t()));
+            let g_prime = g_prime.mean_axis(Axis(0)).unwrap().insert_axis(Axis(1));
+
+            let updated = symmetric_decorrelation(g.t().dot(&x) / n_samples - &w * &g_prime)?;
+            let change = updated.dot(&w.t()).diag().iter().map(|d| (d.abs() - 1.).abs()).fold(0., f64::max);
+            w = updated;
+
+            if change < self.tolerance {
+                debug!("FastICA converged after {} iterations", iteration + 1);
+                return Ok(w);
+            }
+        }
+
+        warn!("FastICA did not converge in {} iterations", self.max_iterations);
+        Ok(w)
+    }
+
+    /// Finds the unmixing vectors one after the other, removing the directions already found
+    /// from each.
+    fn deflation(&self, x: ArrayView2<f64>, initial: Array2<f64>) -> Array2<f64> {
+        let n_samples = x.nrows() as f64;
+        let mut w = Array2::zeros(initial.dim());
+
+        for component in 0..initial.nrows() {
+            let found = w.slice(s![..component, ..]);
+            let mut wc = orthonormalise(initial.row(component).to_owned(), found);
+            let mut converged = false;
+
+            for _ in 0..self.max_iterations {
+                let (g, g_prime) = log_cosh(x.dot(&wc));
+
+                let updated = orthonormalise(x.t().dot(&g) / n_samples - &wc * g_prime.mean().unwrap(), found);
+                let change = (updated.dot(&wc).abs() - 1.).abs();
+                wc = updated;
+
+                if change < self.tolerance {
+                    converged = true;
+                    break;
+                }
+            }
+
+            if !converged {
+                warn!("FastICA did not converge on component {} in {} iterations", component, self.max_iterations);
+            }
+            w.row_mut(component).assign(&wc);
+        }
+
+        w
+    }
+}
+
+/// The log cosh contrast's derivative, tanh, and second derivative at every element.
+fn log_cosh<D: Dimension>(x: Array<f64, D>) -> (Array<f64, D>, Array<f64, D>) {
+    let g = x.mapv(f64::tanh);
+    let g_prime = g.mapv(|g| 1. - g * g);
+    (g, g_prime)
+}
+
+/// `(W Wᵀ)^(-1/2) W`, the orthonormal matrix closest to `W`.
+fn symmetric_decorrelation(w: Array2<f64>) -> Result<Array2<f64>> {
+    let (values, vectors) = w.dot(&w.t()).eigh(UPLO::Upper)
+        .map_err(|e| Error::LinearAlgebra(e.to_string()))?;
+    let inverse_sqrt = values.mapv(|v| 1. / v.max(f64::EPSILON).sqrt());
+
+    Ok((&vectors * &inverse_sqrt).dot(&vectors.t()).dot(&w))
+}
+
+/// Removes from `w` its projection onto each of the orthonormal rows of `found`, then scales
+/// it to unit length (Gram-Schmidt).
+fn orthonormalise(mut w: Array1<f64>, found: ArrayView2<f64>) -> Array1<f64> {
+    for row in found.genrows() {
+        let projection = w.dot(&row);
+        w.scaled_add(-projection, &row);
+    }
+
+    let norm = w.dot(&w).sqrt();
+    w / norm
+}
+
+impl Parameterized for FastICA {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("n_components".to_owned(), self.n_components.into());
+        params.insert("max_iterations".to_owned(), self.max_iterations.into());
+        params.insert("tolerance".to_owned(), self.tolerance.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "n_components" => self.n_components = value.as_usize()?,
+            "max_iterations" => self.max_iterations = value.as_usize()?,
+            "tolerance" => self.tolerance = value.as_f64()?,
+            _ => return Err(Error::InvalidParameter(format!("FastICA has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
@@ -1 +1,4 @@
+pub mod fast_ica;
 pub mod principal_component_analysis;
+pub mod randomized_svd;
+pub mod truncated_svd;
@@ -1,109 +1,265 @@
-use ndarray::{Axis, ArrayView2, Array2, stack, Array, ArrayView1};
+use ndarray::{s, Axis, ArrayView2, Array2, ArrayView1, Array1};
 
 use log::debug;
 use ndarray_stats::CorrelationExt;
 use ndarray_linalg::{Eigh, UPLO};
-use std::error::Error;
 use std::cmp::Ordering;
-use rune_pipeline::pipeline::{Transformer, Fit};
+use rune_core::validation::check_x;
+use rune_core::{Error, Result};
+use crate::randomized_svd::{randomized_svd, SvdSolver};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::estimator::{Transformer, Fit, FitTransform};
+use serde::{Deserialize, Serialize};
+
+/// How many components to keep: a fixed number, or the fewest that explain a fraction of the
+/// variance.
+#[derive(Debug, Clone, Copy)]
+enum Components {
+    Count(usize),
+    Variance(f64),
+}
 
 #[derive(Debug)]
 pub struct PrincipalComponentAnalysis {
-    number_of_features: usize
+    n_components: Components,
+    whiten: bool,
+    solver: SvdSolver,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct PrincipalComponentAnalysisTransformer {
+    mean: Array1<f64>,
     projection: Array2<f64>,
+    whiten: bool,
+    explained_variance: Array1<f64>,
+    explained_variance_ratio: Array1<f64>,
 }
 
 impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for PrincipalComponentAnalysisTransformer {
     fn transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
         self.internal_transform(x)
     }
+
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
 }
 
 impl Transformer<Array2<f64>, Array2<f64>> for PrincipalComponentAnalysisTransformer {
     fn transform(&self, x: Array2<f64>) -> Array2<f64> {
         self.internal_transform(x.view())
     }
+
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
 }
 
 
-impl Fit<ArrayView2<'_, f64>, PrincipalComponentAnalysisTransformer> for PrincipalComponentAnalysis {
-    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<bool>) -> PrincipalComponentAnalysisTransformer {
-        self.internal_fit(x).unwrap()
+impl<Y> Fit<ArrayView2<'_, f64>, PrincipalComponentAnalysisTransformer, Y> for PrincipalComponentAnalysis {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<PrincipalComponentAnalysisTransformer> {
+        self.internal_fit(x)
     }
 }
 
-impl Fit<Array2<f64>, PrincipalComponentAnalysisTransformer> for PrincipalComponentAnalysis {
-    fn fit(&self, x: Array2<f64>, y: ArrayView1<bool>) -> PrincipalComponentAnalysisTransformer {
-        self.internal_fit(x.view()).unwrap()
+impl<Y> Fit<Array2<f64>, PrincipalComponentAnalysisTransformer, Y> for PrincipalComponentAnalysis {
+    fn fit(&self, x: Array2<f64>, _y: ArrayView1<Y>) -> Result<PrincipalComponentAnalysisTransformer> {
+        self.internal_fit(x.view())
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, f64>, PrincipalComponentAnalysisTransformer, Array2<f64>, Y> for PrincipalComponentAnalysis {}
+
+/// Fits and projects through a view of `x`, so the default implementation's copy of the
+/// training data is not needed.
+impl<Y> FitTransform<Array2<f64>, PrincipalComponentAnalysisTransformer, Array2<f64>, Y> for PrincipalComponentAnalysis {
+    fn fit_transform(&self, x: Array2<f64>, _y: ArrayView1<Y>) -> Result<(PrincipalComponentAnalysisTransformer, Array2<f64>)> {
+        let transformer = self.internal_fit(x.view())?;
+        let transformed = transformer.internal_transform(x.view());
+        Ok((transformer, transformed))
     }
 }
 
 impl PrincipalComponentAnalysisTransformer {
-    pub fn new(projection: Array2<f64>) -> Self {
-        PrincipalComponentAnalysisTransformer { projection }
+    pub fn new(mean: Array1<f64>, projection: Array2<f64>, whiten: bool, explained_variance: Array1<f64>, explained_variance_ratio: Array1<f64>) -> Self {
+        PrincipalComponentAnalysisTransformer { mean, projection, whiten, explained_variance, explained_variance_ratio }
+    }
+
+    /// The variance of the training data along each component, largest first.
+    pub fn explained_variance(&self) -> ArrayView1<'_, f64> {
+        self.explained_variance.view()
+    }
+
+    /// The share of the training data's total variance along each component.
+    pub fn explained_variance_ratio(&self) -> ArrayView1<'_, f64> {
+        self.explained_variance_ratio.view()
     }
 
     pub fn internal_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
-        return x.dot(&self.projection);
+        let projected = (&x - &self.mean).dot(&self.projection);
+
+        if self.whiten {
+            projected / &self.component_scale()
+        } else {
+            projected
+        }
+    }
+
+    /// The components are orthonormal, so projecting back onto them recovers the part of the
+    /// original features that lies in the retained subspace.
+    pub fn internal_inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let projected = if self.whiten {
+            &x * &self.component_scale()
+        } else {
+            x.to_owned()
+        };
+
+        projected.dot(&self.projection.t()) + &self.mean
+    }
+
+    /// The standard deviation along each component, which whitening divides by, with 1 in place
+    /// of 0 so components without variance are left as they are.
+    fn component_scale(&self) -> Array1<f64> {
+        self.explained_variance.mapv(|v| if v > 0. { v.sqrt() } else { 1. })
     }
 }
 
 impl PrincipalComponentAnalysis {
-    pub fn new(number_of_features: usize) -> Self {
+    pub fn new(n_components: usize) -> Self {
         PrincipalComponentAnalysis {
-            number_of_features
+            n_components: Components::Count(n_components),
+            whiten: false,
+            solver: SvdSolver::Full,
         }
     }
 
-    pub fn internal_fit(&self, x: ArrayView2<f64>) -> Result<PrincipalComponentAnalysisTransformer, Box<dyn Error>> {
-        let co_variance_matrix = x.t().cov(1.)?;
-        debug!("co_variance_matrix: \n {}", co_variance_matrix);
+    /// Keeps the fewest components that together explain at least `fraction` of the variance,
+    /// e.g. 0.95.
+    pub fn with_variance(fraction: f64) -> Self {
+        PrincipalComponentAnalysis {
+            n_components: Components::Variance(fraction),
+            whiten: false,
+            solver: SvdSolver::Full,
+        }
+    }
+
+    /// Whether to scale the projected components to unit variance, e.g. before a model that
+    /// assumes uncorrelated features of equal scale.
+    pub fn whiten(self, whiten: bool) -> Self {
+        PrincipalComponentAnalysis { whiten, ..self }
+    }
 
-        // eig_vec: The vector which is only stretched or squashed
-        // eig_val: The amount that vector is stretched or squashed
-        let (eig_val, eig_vec) = co_variance_matrix.eigh(UPLO::Upper)?;
-        debug!("eig_val: {}", eig_val);
-        debug!("eig_vec: {}", eig_vec);
+    /// How to find the components. The randomized solver only finds the ones asked for, so
+    /// can't be combined with `with_variance`.
+    pub fn with_solver(self, solver: SvdSolver) -> Self {
+        PrincipalComponentAnalysis { solver, ..self }
+    }
 
-        let mut arr = Vec::new();
+    pub fn internal_fit(&self, x: ArrayView2<f64>) -> Result<PrincipalComponentAnalysisTransformer> {
+        check_x(x, 2)?;
 
-        for i in 0..eig_val.len() {
-            arr.push((eig_val[i], eig_vec.column(i)))
+        match self.n_components {
+            Components::Count(n) if n == 0 || n > x.ncols() => return Err(Error::InvalidParameter(format!(
+                "n_components should be between 1 and the number of features ({}), got {}", x.ncols(), n))),
+            Components::Variance(fraction) if !(fraction > 0. && fraction <= 1.) => return Err(Error::InvalidParameter(format!(
+                "the variance to explain should be a fraction in (0, 1], got {}", fraction))),
+            Components::Variance(_) if self.solver != SvdSolver::Full => return Err(Error::InvalidParameter(
+                "choosing components by variance needs the full solver".to_owned())),
+            _ => {}
         }
 
-        arr.sort_by(|o1, o2|
-            if o1.0 < o2.0 {
-                Ordering::Greater
-            } else {
-                Ordering::Less
+        let mean = x.mean_axis(Axis(0))
+            .ok_or_else(|| Error::InvalidInput("cannot fit PCA to no samples".to_owned()))?;
+
+        let (directions, variance, total) = match (self.solver, self.n_components) {
+            (SvdSolver::Randomized { n_iter, rng }, Components::Count(n)) => {
+                if x.nrows() < 2 {
+                    return Err(Error::InvalidInput("cannot compute the covariance of fewer than 2 samples".to_owned()));
+                }
+
+                let (singular_values, vt) = randomized_svd((&x - &mean).view(), n, n_iter, rng)?;
+                let variance = singular_values.mapv(|s| s * s / (x.nrows() - 1) as f64);
+                (vt.reversed_axes(), variance, x.var_axis(Axis(0), 1.).sum())
             }
-        );
+            _ => {
+                let (directions, variance) = covariance_directions(x)?;
+                let total = variance.sum();
+                (directions, variance, total)
+            }
+        };
+        let ratio = if total > 0. { &variance / total } else { Array1::zeros(variance.len()) };
 
-        for i in 0..arr.len() {
-            debug!("arr[{}]: {:?}", i, arr[i]);
-        }
+        let n_components = match self.n_components {
+            Components::Count(n) => n,
+            Components::Variance(fraction) => {
+                let mut explained = 0.;
+                // Tolerate rounding in the sum, so asking for all of the variance doesn't miss by an ulp.
+                ratio.iter().take_while(|&&r| {
+                    let short = explained < fraction - 1e-12;
+                    explained += r;
+                    short
+                }).count().max(1)
+            }
+        };
 
-        let mut z: Vec<Array2<f64>> = Vec::new();
+        let projection = directions.slice(s![.., ..n_components]).to_owned();
+        debug!("feature_projection: {:?}", projection);
 
-        for i in &arr[0..2] {
-            let v = i.1;
-            let x = Array::from_shape_vec((4, 1), v.to_vec())?;
-            z.push(x);
-        }
+        Ok(PrincipalComponentAnalysisTransformer::new(
+            mean,
+            projection,
+            self.whiten,
+            variance.slice(s![..n_components]).to_owned(),
+            ratio.slice(s![..n_components]).to_owned(),
+        ))
+    }
+}
 
-        debug!("arr[]: {:?}", z);
+/// Every principal direction of `x`, as columns, and the variance along it, most variance first.
+fn covariance_directions(x: ArrayView2<f64>) -> Result<(Array2<f64>, Array1<f64>)> {
+    let co_variance_matrix = x.t().cov(1.)
+        .map_err(|_| Error::InvalidInput("cannot compute the covariance of fewer than 2 samples".to_owned()))?;
+    debug!("co_variance_matrix: \n {}", co_variance_matrix);
 
-        let x = z.iter().map(|x| x.view()).collect::<Vec<ArrayView2<f64>>>();
+    // eig_vec: The vector which is only stretched or squashed
+    // eig_val: The amount that vector is stretched or squashed
+    let (eig_val, eig_vec) = co_variance_matrix.eigh(UPLO::Upper)
+        .map_err(|e| Error::LinearAlgebra(e.to_string()))?;
+    debug!("eig_val: {}", eig_val);
+    debug!("eig_vec: {}", eig_vec);
 
+    // The components are the eigenvectors of the largest eigenvalues, the directions of the
+    // most variance.
+    let mut order: Vec<usize> = (0..eig_val.len()).collect();
+    order.sort_by(|&a, &b| eig_val[b].partial_cmp(&eig_val[a]).unwrap_or(Ordering::Equal));
 
-        let projection: Array2<f64> = stack(Axis(1), &x[0..self.number_of_features])?;
+    // Rounding can leave the eigenvalues of a singular covariance matrix slightly negative.
+    let variance = order.iter().map(|&i| eig_val[i].max(0.)).collect();
 
-        debug!("feature_projection: {:?}", projection);
+    Ok((eig_vec.select(Axis(1), &order), variance))
+}
 
+impl Parameterized for PrincipalComponentAnalysis {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        let n_components = match self.n_components {
+            Components::Count(n) => n.into(),
+            Components::Variance(fraction) => fraction.into(),
+        };
+        params.insert("n_components".to_owned(), n_components);
+        params.insert("whiten".to_owned(), self.whiten.into());
+        params
+    }
 
-        Ok(PrincipalComponentAnalysisTransformer::new(projection))
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "n_components" => self.n_components = match value {
+                ParamValue::Float(fraction) => Components::Variance(fraction),
+                value => Components::Count(value.as_usize()?),
+            },
+            "whiten" => self.whiten = value.as_bool()?,
+            _ => return Err(Error::InvalidParameter(format!("PrincipalComponentAnalysis has no parameter {}", name))),
+        }
+        Ok(())
     }
-}
\ No newline at end of file
+}
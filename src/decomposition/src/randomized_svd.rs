@@ -0,0 +1,54 @@
+use ndarray::{s, ArrayView2, Array2, Array1};
+
+use log::debug;
+use ndarray_linalg::{QR, SVD};
+use rand_distr::{Distribution, StandardNormal};
+use rune_core::random::RngSource;
+use rune_core::{Error, Result};
+
+/// How PCA and `TruncatedSVD` factorise the data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SvdSolver {
+    /// An exact, full decomposition.
+    Full,
+    /// Only the top components, approximated through random projections (Halko et al., 2011),
+    /// which is far faster on wide matrices. Each of the `n_iter` power iterations sharpens the
+    /// approximation when the singular values decay slowly.
+    Randomized { n_iter: usize, rng: RngSource },
+}
+
+/// Extra random directions sampled beyond the components asked for, which makes the range
+/// found much more likely to contain the top singular vectors.
+const N_OVERSAMPLES: usize = 10;
+
+/// The `k` largest singular values of `x` and their right singular vectors, as the rows of the
+/// second matrix.
+pub(crate) fn randomized_svd(x: ArrayView2<f64>, k: usize, n_iter: usize, rng: RngSource) -> Result<(Array1<f64>, Array2<f64>)> {
+    let rank = x.nrows().min(x.ncols());
+    if k > rank {
+        return Err(Error::InvalidParameter(format!(
+            "the randomized solver can find at most {} components of a {}x{} matrix, got {}", rank, x.nrows(), x.ncols(), k)));
+    }
+
+    let n_random = (k + N_OVERSAMPLES).min(rank);
+    let qr = |a: Array2<f64>| a.qr().map(|(q, _)| q).map_err(|e| Error::LinearAlgebra(e.to_string()));
+
+    let mut rng = rng.rng();
+    let omega = Array2::from_shape_fn((x.ncols(), n_random), |_| StandardNormal.sample(&mut rng));
+
+    // An orthonormal basis for the range of x, re-orthonormalised between power iterations so
+    // rounding doesn't collapse it onto the top singular vector.
+    let mut q = qr(x.dot(&omega))?;
+    for _ in 0..n_iter {
+        q = qr(x.t().dot(&q))?;
+        q = qr(x.dot(&q))?;
+    }
+
+    // x is close to q qᵀ x, so the SVD of the small matrix qᵀ x has nearly the same singular
+    // values and right singular vectors.
+    let (_, singular_values, vt) = q.t().dot(&x).svd(false, true)
+        .map_err(|e| Error::LinearAlgebra(e.to_string()))?;
+    debug!("randomized singular_values: {}", singular_values);
+
+    Ok((singular_values.slice(s![..k]).to_owned(), vt.unwrap().slice(s![..k, ..]).to_owned()))
+}
@@ -0,0 +1,159 @@
+use ndarray::{s, Axis, ArrayView2, Array2, ArrayView1, Array1};
+
+use log::debug;
+use ndarray_linalg::SVD;
+use rune_core::validation::check_x;
+use rune_core::{Error, Result};
+use crate::randomized_svd::{randomized_svd, SvdSolver};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::estimator::{Transformer, Fit, FitTransform};
+use serde::{Deserialize, Serialize};
+
+/// Reduces dimensionality by projecting onto the top right singular vectors of the data itself,
+/// without centring it first. Unlike PCA, this keeps zeros at zero, which suits term-document
+/// matrices such as TF-IDF weights (latent semantic analysis). The data is taken as a dense
+/// matrix.
+#[derive(Debug)]
+pub struct TruncatedSVD {
+    n_components: usize,
+    solver: SvdSolver,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TruncatedSVDTransformer {
+    components: Array2<f64>,
+    singular_values: Array1<f64>,
+    explained_variance: Array1<f64>,
+    explained_variance_ratio: Array1<f64>,
+}
+
+impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for TruncatedSVDTransformer {
+    fn transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_transform(x)
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
+}
+
+impl Transformer<Array2<f64>, Array2<f64>> for TruncatedSVDTransformer {
+    fn transform(&self, x: Array2<f64>) -> Array2<f64> {
+        self.internal_transform(x.view())
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, TruncatedSVDTransformer, Y> for TruncatedSVD {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<TruncatedSVDTransformer> {
+        self.internal_fit(x)
+    }
+}
+
+impl<Y> Fit<Array2<f64>, TruncatedSVDTransformer, Y> for TruncatedSVD {
+    fn fit(&self, x: Array2<f64>, _y: ArrayView1<Y>) -> Result<TruncatedSVDTransformer> {
+        self.internal_fit(x.view())
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, f64>, TruncatedSVDTransformer, Array2<f64>, Y> for TruncatedSVD {}
+
+impl<Y> FitTransform<Array2<f64>, TruncatedSVDTransformer, Array2<f64>, Y> for TruncatedSVD {}
+
+impl TruncatedSVDTransformer {
+    pub fn new(components: Array2<f64>, singular_values: Array1<f64>, explained_variance: Array1<f64>, explained_variance_ratio: Array1<f64>) -> Self {
+        TruncatedSVDTransformer { components, singular_values, explained_variance, explained_variance_ratio }
+    }
+
+    /// The right singular vectors kept, one per row.
+    pub fn components(&self) -> ArrayView2<'_, f64> {
+        self.components.view()
+    }
+
+    pub fn singular_values(&self) -> ArrayView1<'_, f64> {
+        self.singular_values.view()
+    }
+
+    /// The variance of the training data along each component.
+    pub fn explained_variance(&self) -> ArrayView1<'_, f64> {
+        self.explained_variance.view()
+    }
+
+    /// The share of the training data's total variance along each component.
+    pub fn explained_variance_ratio(&self) -> ArrayView1<'_, f64> {
+        self.explained_variance_ratio.view()
+    }
+
+    pub fn internal_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        x.dot(&self.components.t())
+    }
+
+    pub fn internal_inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        x.dot(&self.components)
+    }
+}
+
+impl TruncatedSVD {
+    pub fn new(n_components: usize) -> Self {
+        TruncatedSVD {
+            n_components,
+            solver: SvdSolver::Full,
+        }
+    }
+
+    pub fn with_solver(self, solver: SvdSolver) -> Self {
+        TruncatedSVD { solver, ..self }
+    }
+
+    pub fn internal_fit(&self, x: ArrayView2<f64>) -> Result<TruncatedSVDTransformer> {
+        check_x(x, 1)?;
+
+        let rank = x.nrows().min(x.ncols());
+        if self.n_components == 0 || self.n_components > rank {
+            return Err(Error::InvalidParameter(format!(
+                "n_components should be between 1 and the smaller dimension of x ({}), got {}", rank, self.n_components)));
+        }
+
+        let (singular_values, components) = match self.solver {
+            SvdSolver::Full => {
+                // The singular values come sorted largest first, so the top components lead.
+                let (_, singular_values, vt) = x.svd(false, true)
+                    .map_err(|e| Error::LinearAlgebra(e.to_string()))?;
+                debug!("singular_values: {}", singular_values);
+
+                (singular_values.slice(s![..self.n_components]).to_owned(), vt.unwrap().slice(s![..self.n_components, ..]).to_owned())
+            }
+            SvdSolver::Randomized { n_iter, rng } => randomized_svd(x, self.n_components, n_iter, rng)?,
+        };
+
+        let explained_variance = x.dot(&components.t()).var_axis(Axis(0), 0.);
+        let total_variance = x.var_axis(Axis(0), 0.).sum();
+        let explained_variance_ratio = if total_variance > 0. {
+            &explained_variance / total_variance
+        } else {
+            Array1::zeros(self.n_components)
+        };
+        debug!("explained_variance_ratio: {}", explained_variance_ratio);
+
+        Ok(TruncatedSVDTransformer::new(components, singular_values, explained_variance, explained_variance_ratio))
+    }
+}
+
+impl Parameterized for TruncatedSVD {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("n_components".to_owned(), self.n_components.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "n_components" => self.n_components = value.as_usize()?,
+            _ => return Err(Error::InvalidParameter(format!("TruncatedSVD has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
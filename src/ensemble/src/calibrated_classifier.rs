@@ -0,0 +1,316 @@
+use std::cmp::Ordering;
+
+use log::*;
+use ndarray::{Array1, ArrayView1, ArrayView2, Axis};
+use rune_core::cost_matrix::CostMatrix;
+use rune_core::validation::check_x_y;
+use rune_core::math::special::{sigmoid, softplus};
+use rune_core::Result;
+use rune_model_selection::splitting::k_fold::KFold;
+use rune_core::estimator::{Fit, Predictor, ProbabilisticPredictor};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalibrationMethod {
+    /// Platt scaling: a sigmoid fitted to the decision scores.
+    Sigmoid,
+    /// A non-decreasing step function fitted with pool adjacent violators.
+    Isotonic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Calibrator {
+    Sigmoid {
+        a: f64,
+        b: f64,
+    },
+    Isotonic {
+        thresholds: Vec<f64>,
+        values: Vec<f64>,
+    },
+}
+
+/// Turns the decision scores of a binary estimator into probabilities. The calibrator is fitted
+/// on out-of-fold scores so it never sees scores the base model produced for its own training rows.
+#[derive(Debug)]
+pub struct CalibratedClassifier<E> {
+    estimator: E,
+    method: CalibrationMethod,
+    cv: KFold,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CalibratedModel<M> {
+    model: M,
+    calibrator: Calibrator,
+}
+
+impl<E> CalibratedClassifier<E> {
+    pub fn new(estimator: E, method: CalibrationMethod, cv: KFold) -> Self {
+        CalibratedClassifier {
+            estimator,
+            method,
+            cv,
+        }
+    }
+}
+
+impl<'a, E, M> Fit<ArrayView2<'a, f64>, CalibratedModel<M>> for CalibratedClassifier<E>
+    where
+        E: for<'b> Fit<ArrayView2<'b, f64>, M>,
+        M: for<'b> Predictor<ArrayView2<'b, f64>, Array1<f64>> {
+    fn fit(&self, x: ArrayView2<'a, f64>, y: ArrayView1<bool>) -> Result<CalibratedModel<M>> {
+        check_x_y(x, y, self.cv.n_splits())?;
+
+        let mut scores = Array1::zeros(x.nrows());
+
+        for (train, test) in self.cv.split(x.nrows())? {
+            let model = self.estimator.fit(x.select(Axis(0), &train).view(), y.select(Axis(0), &train).view())?;
+            let fold_scores = model.predict(x.select(Axis(0), &test).view());
+
+            for (&row, &score) in test.iter().zip(fold_scores.iter()) {
+                scores[row] = score;
+            }
+        }
+
+        let calibrator = Calibrator::fit(self.method, scores.view(), y);
+        info!("Fitted calibrator: {:?}", calibrator);
+
+        Ok(CalibratedModel {
+            model: self.estimator.fit(x, y)?,
+            calibrator,
+        })
+    }
+}
+
+impl<'a, M> ProbabilisticPredictor<ArrayView2<'a, f64>, Array1<f64>> for CalibratedModel<M>
+    where M: for<'b> Predictor<ArrayView2<'b, f64>, Array1<f64>> {
+    fn predict_proba(&self, x: ArrayView2<'a, f64>) -> Array1<f64> {
+        self.model.predict(x).mapv(|score| self.calibrator.apply(score))
+    }
+}
+
+impl<'a, M> Predictor<ArrayView2<'a, f64>, Array1<bool>> for CalibratedModel<M>
+    where M: for<'b> Predictor<ArrayView2<'b, f64>, Array1<f64>> {
+    fn predict(&self, x: ArrayView2<'a, f64>) -> Array1<bool> {
+        self.predict_proba(x).mapv(|p| p >= 0.5)
+    }
+}
+
+impl<M> CalibratedModel<M> where M: for<'b> Predictor<ArrayView2<'b, f64>, Array1<f64>> {
+    /// Predicts the class with the least expected cost under the binary `costs`, class 0 being
+    /// `false`, i.e. the positive class wherever its probability reaches `costs.binary_threshold()`
+    /// rather than 0.5.
+    pub fn predict_with_costs(&self, x: ArrayView2<f64>, costs: &CostMatrix) -> Array1<bool> {
+        let threshold = costs.binary_threshold();
+        self.predict_proba(x).mapv(|p| p >= threshold)
+    }
+}
+
+impl Calibrator {
+    pub fn fit(method: CalibrationMethod, scores: ArrayView1<f64>, y: ArrayView1<bool>) -> Calibrator {
+        match method {
+            CalibrationMethod::Sigmoid => {
+                let (a, b) = platt_scaling(scores, y);
+                Calibrator::Sigmoid { a, b }
+            }
+            CalibrationMethod::Isotonic => {
+                let (thresholds, values) = isotonic_regression(scores, y);
+                Calibrator::Isotonic { thresholds, values }
+            }
+        }
+    }
+
+    pub fn apply(&self, score: f64) -> f64 {
+        match self {
+            Calibrator::Sigmoid { a, b } => sigmoid(-(a * score + b)),
+            Calibrator::Isotonic { thresholds, values } => interpolate(thresholds, values, score),
+        }
+    }
+}
+
+/// Fits `P(y = 1 | f) = 1 / (1 + exp(a * f + b))` by Newton's method with backtracking, using
+/// Platt's smoothed targets to avoid overfitting the extremes.
+fn platt_scaling(scores: ArrayView1<f64>, y: ArrayView1<bool>) -> (f64, f64) {
+    const MAX_ITERATIONS: usize = 100;
+    const MIN_STEP: f64 = 1e-10;
+    const SIGMA: f64 = 1e-12;
+    const EPSILON: f64 = 1e-5;
+
+    let positives = y.iter().filter(|&&label| label).count() as f64;
+    let negatives = y.len() as f64 - positives;
+
+    let high_target = (positives + 1.) / (positives + 2.);
+    let low_target = 1. / (negatives + 2.);
+    let targets: Vec<f64> = y.iter().map(|&label| if label { high_target } else { low_target }).collect();
+
+    let objective = |a: f64, b: f64| -> f64 {
+        scores.iter()
+            .zip(targets.iter())
+            .map(|(&f, &t)| {
+                let z = f * a + b;
+                (t - 1.) * z + softplus(z)
+            })
+            .sum()
+    };
+
+    let mut a = 0.;
+    let mut b = ((negatives + 1.) / (positives + 1.)).ln();
+    let mut value = objective(a, b);
+
+    for iteration in 0..MAX_ITERATIONS {
+        let (mut h11, mut h22, mut h21, mut g1, mut g2) = (SIGMA, SIGMA, 0., 0., 0.);
+
+        for (&f, &t) in scores.iter().zip(targets.iter()) {
+            let p = sigmoid(-(f * a + b));
+            let d1 = t - p;
+            let d2 = p * (1. - p);
+
+            h11 += f * f * d2;
+            h22 += d2;
+            h21 += f * d2;
+            g1 += f * d1;
+            g2 += d1;
+        }
+
+        if g1.abs() < EPSILON && g2.abs() < EPSILON {
+            debug!("Platt scaling converged after {} iterations", iteration);
+            break;
+        }
+
+        let det = h11 * h22 - h21 * h21;
+        let da = -(h22 * g1 - h21 * g2) / det;
+        let db = -(-h21 * g1 + h11 * g2) / det;
+        let gd = g1 * da + g2 * db;
+
+        let mut step = 1.;
+        while step >= MIN_STEP {
+            let (new_a, new_b) = (a + step * da, b + step * db);
+            let new_value = objective(new_a, new_b);
+
+            if new_value < value + 1e-4 * step * gd {
+                a = new_a;
+                b = new_b;
+                value = new_value;
+                break;
+            }
+
+            step /= 2.;
+        }
+
+        if step < MIN_STEP {
+            warn!("Platt scaling line search failed after {} iterations", iteration);
+            break;
+        }
+    }
+
+    (a, b)
+}
+
+/// Pool adjacent violators over the scores sorted in increasing order, returning the sorted
+/// scores and the fitted non-decreasing probability for each of them.
+fn isotonic_regression(scores: ArrayView1<f64>, y: ArrayView1<bool>) -> (Vec<f64>, Vec<f64>) {
+    let mut pairs: Vec<(f64, f64)> = scores.iter()
+        .zip(y.iter())
+        .map(|(&score, &label)| (score, if label { 1. } else { 0. }))
+        .collect();
+    pairs.sort_by(|l, r| l.0.partial_cmp(&r.0).unwrap_or(Ordering::Equal));
+
+    // (sum of targets, number of samples) for each pooled block
+    let mut blocks: Vec<(f64, usize)> = Vec::new();
+
+    for &(_, target) in &pairs {
+        blocks.push((target, 1));
+
+        while blocks.len() > 1 {
+            let (last_sum, last_count) = blocks[blocks.len() - 1];
+            let (prev_sum, prev_count) = blocks[blocks.len() - 2];
+
+            if prev_sum / prev_count as f64 <= last_sum / last_count as f64 {
+                break;
+            }
+
+            blocks.pop();
+            let len = blocks.len();
+            blocks[len - 1] = (prev_sum + last_sum, prev_count + last_count);
+        }
+    }
+
+    // `repeat_n` needs Rust 1.82.
+    #[allow(clippy::manual_repeat_n)]
+    let values = blocks.iter()
+        .flat_map(|&(sum, count)| std::iter::repeat(sum / count as f64).take(count))
+        .collect();
+    let thresholds = pairs.into_iter().map(|(score, _)| score).collect();
+
+    (thresholds, values)
+}
+
+fn interpolate(thresholds: &[f64], values: &[f64], score: f64) -> f64 {
+    let upper = thresholds.iter().position(|&t| t >= score);
+
+    match upper {
+        None => values[values.len() - 1],
+        Some(0) => values[0],
+        Some(i) => {
+            let (x0, x1) = (thresholds[i - 1], thresholds[i]);
+            let (y0, y1) = (values[i - 1], values[i]);
+
+            if x1 == x0 {
+                y1
+            } else {
+                y0 + (y1 - y0) * (score - x0) / (x1 - x0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::calibrated_classifier::{isotonic_regression, platt_scaling, CalibrationMethod, Calibrator};
+
+    #[test]
+    fn test_platt_scaling_separates_separable_scores() {
+        let scores = array![-3., -2., -1.5, -1., 1., 1.5, 2., 3.];
+        let y = array![false, false, false, false, true, true, true, true];
+
+        let (a, b) = platt_scaling(scores.view(), y.view());
+        // P(y = 1 | f) = 1 / (1 + exp(a * f + b)) increases with the score, and symmetric
+        // classes put the boundary at 0.
+        assert!(a < 0., "{}", a);
+        assert!(b.abs() < 1e-3, "{}", b);
+
+        let calibrator = Calibrator::fit(CalibrationMethod::Sigmoid, scores.view(), y.view());
+        assert!((calibrator.apply(0.) - 0.5).abs() < 1e-3);
+        assert!(calibrator.apply(-3.) < calibrator.apply(-1.) && calibrator.apply(1.) < calibrator.apply(3.));
+        assert!(calibrator.apply(-1.) < 1. / 3. && calibrator.apply(1.) > 2. / 3.);
+        // Platt's smoothed targets, 1/6 and 5/6 here, keep the extremes off 0 and 1.
+        assert!(calibrator.apply(-3.) > 0.01 && calibrator.apply(3.) < 0.99);
+
+        // At the optimum the residuals against the targets sum to zero, also when weighted by
+        // the scores.
+        let residuals = y.mapv(|label| if label { 5. / 6. } else { 1. / 6. }) - scores.mapv(|f| calibrator.apply(f));
+        assert!(residuals.sum().abs() < 1e-4);
+        assert!((&residuals * &scores).sum().abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_isotonic_regression_pools_adjacent_violators() {
+        let scores = array![0.9, 0.1, 0.5, 0.3, 0.7, 0.6];
+        let y = array![true, false, false, true, true, false];
+
+        let (thresholds, values) = isotonic_regression(scores.view(), y.view());
+        // Sorted by score the targets are 0, 1, 0, 0, 1, 1: the 1 at 0.3 is pooled with the two
+        // 0s after it into a block of mean 1/3.
+        assert_eq!(thresholds, vec![0.1, 0.3, 0.5, 0.6, 0.7, 0.9]);
+        assert_eq!(values, vec![0., 1. / 3., 1. / 3., 1. / 3., 1., 1.]);
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let calibrator = Calibrator::fit(CalibrationMethod::Isotonic, scores.view(), y.view());
+        assert_eq!(calibrator.apply(0.), 0.);
+        assert!((calibrator.apply(0.65) - 2. / 3.).abs() < 1e-12);
+        assert_eq!(calibrator.apply(2.), 1.);
+    }
+}
@@ -0,0 +1,485 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Instant;
+
+use log::*;
+use ndarray::{Array1, ArrayView1, ArrayView2, Axis};
+use rand::seq::index::sample;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rune_core::callback::{Callbacks, FitCallback};
+use rune_core::estimator::{Fit, Predictor, Score};
+use rune_core::math::binning::{BinIndex, Binner};
+use rune_core::math::quantile::{median, quantile, Interpolation};
+use rune_core::random::RngSource;
+use rune_core::validation::{check_finite, check_x_y};
+use rune_core::{Error, Result};
+use rune_metrics::regression::r2::r2;
+use rune_tree::regressor::{DecisionTreeRegressor, RegressionTreeModel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RegressionLoss {
+    /// Half the squared error, fitting the conditional mean.
+    Squared,
+    /// The absolute error, fitting the conditional median and ignoring how far outliers are.
+    Absolute,
+    /// Squared for residuals up to the `alpha` quantile of their absolute values and absolute
+    /// beyond it, so the bulk of the data is fitted like `Squared` while outliers are damped.
+    Huber { alpha: f64 },
+}
+
+/// Fits a sum of shallow regression trees in stages, each to the negative gradient of the loss
+/// of the stages before it, shrunk by the learning rate. With a `subsample` below one, every
+/// stage sees a different random fraction of the rows, which is stochastic gradient boosting.
+#[derive(Debug, Clone)]
+pub struct GradientBoostingRegressor {
+    loss: RegressionLoss,
+    n_estimators: usize,
+    learning_rate: f64,
+    subsample: f64,
+    tree: DecisionTreeRegressor,
+    max_bins: Option<usize>,
+    early_stopping: Option<EarlyStopping>,
+    scoring: Option<Scoring>,
+    rng: RngSource,
+    callbacks: Callbacks,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EarlyStopping {
+    validation_fraction: f64,
+    n_iter_no_change: usize,
+}
+
+type ScoringFn = dyn Fn(ArrayView1<f64>, ArrayView1<f64>) -> f64 + Send + Sync;
+
+/// Scores predictions of held out rows as `scoring(y_true, y_pred)`, higher being better.
+#[derive(Clone)]
+struct Scoring(Arc<ScoringFn>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GradientBoostingRegressorModel {
+    initial_prediction: f64,
+    learning_rate: f64,
+    trees: Vec<RegressionTreeModel>,
+    best_iteration: Option<usize>,
+}
+
+impl Fit<ArrayView2<'_, f64>, GradientBoostingRegressorModel, f64> for GradientBoostingRegressor {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<GradientBoostingRegressorModel> {
+        check_x_y(x, y, if self.early_stopping.is_some() { 2 } else { 1 })?;
+        check_finite("y", y)?;
+        self.check_params()?;
+        self.tree.check_constraints(x.ncols())?;
+
+        Ok(self.fit_internal(x, y))
+    }
+}
+
+impl Predictor<ArrayView2<'_, f64>, Array1<f64>> for GradientBoostingRegressorModel {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        self.trees.iter().fold(Array1::from_elem(x.nrows(), self.initial_prediction), |mut predictions, tree| {
+            predictions.scaled_add(self.learning_rate, &tree.predict(x));
+            predictions
+        })
+    }
+}
+
+/// The coefficient of determination of the predictions.
+impl Score<ArrayView2<'_, f64>, f64> for GradientBoostingRegressorModel {
+    fn score(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> f64 {
+        r2(y, self.predict(x).view(), None)
+    }
+}
+
+impl GradientBoostingRegressor {
+    /// Boosts `n_estimators` trees of depth 3 under `loss`, each scaled by `learning_rate`.
+    pub fn new(loss: RegressionLoss, n_estimators: usize, learning_rate: f64) -> Self {
+        GradientBoostingRegressor {
+            loss,
+            n_estimators,
+            learning_rate,
+            subsample: 1.,
+            tree: DecisionTreeRegressor::new(3, 1),
+            max_bins: None,
+            early_stopping: None,
+            scoring: None,
+            rng: RngSource::default(),
+            callbacks: Callbacks::new(),
+        }
+    }
+
+    /// Fits every stage on a `fraction` of the rows, drawn without replacement from `rng`.
+    pub fn subsample(mut self, fraction: f64, rng: RngSource) -> Self {
+        self.subsample = fraction;
+        self.rng = rng;
+        self
+    }
+
+    /// The tree every stage fits, `DecisionTreeRegressor::new(3, 1)` by default.
+    pub fn tree(mut self, tree: DecisionTreeRegressor) -> Self {
+        self.tree = tree;
+        self
+    }
+
+    /// Constrains every tree, and so the sum of them, to be monotonic in each feature as in
+    /// `DecisionTreeRegressor::monotonic_constraints`. Set it after `tree`, which replaces it.
+    pub fn monotonic_constraints(mut self, constraints: Vec<i8>) -> Self {
+        self.tree = self.tree.monotonic_constraints(constraints);
+        self
+    }
+
+    /// Bins every feature into at most `max_bins` quantiles, up to 256, once before the first
+    /// stage, and has every tree split between bins, which is much faster on many rows.
+    pub fn histogram(mut self, max_bins: usize) -> Self {
+        assert!((2..=u8::MAX_BINS).contains(&max_bins), "histogram trees need between 2 and 256 bins");
+        self.max_bins = Some(max_bins);
+        self
+    }
+
+    /// Holds out a `validation_fraction` of the rows, drawn from the same source as `subsample`,
+    /// and stops once `n_iter_no_change` stages in a row haven't improved the score on them. The
+    /// fitted model keeps the stages up to the best scoring one.
+    pub fn early_stopping(mut self, validation_fraction: f64, n_iter_no_change: usize) -> Self {
+        self.early_stopping = Some(EarlyStopping { validation_fraction, n_iter_no_change });
+        self
+    }
+
+    /// How early stopping scores the held out rows, as `scoring(y_true, y_pred)` with higher
+    /// being better, the negated loss by default.
+    pub fn scoring<S: Fn(ArrayView1<f64>, ArrayView1<f64>) -> f64 + Send + Sync + 'static>(mut self, scoring: S) -> Self {
+        self.scoring = Some(Scoring(Arc::new(scoring)));
+        self
+    }
+
+    /// Calls `callback` after every stage with the training loss.
+    pub fn with_callback<C: FitCallback + 'static>(mut self, callback: C) -> Self {
+        self.callbacks.push(callback);
+        self
+    }
+
+    fn check_params(&self) -> Result<()> {
+        if !(self.learning_rate > 0. && self.learning_rate.is_finite()) {
+            return Err(Error::InvalidParameter(format!("learning rate must be positive, got {}", self.learning_rate)));
+        }
+        if !(self.subsample > 0. && self.subsample <= 1.) {
+            return Err(Error::InvalidParameter(format!("subsample must be in (0, 1], got {}", self.subsample)));
+        }
+        if let RegressionLoss::Huber { alpha } = self.loss {
+            if !(alpha > 0. && alpha < 1.) {
+                return Err(Error::InvalidParameter(format!("huber alpha must be in (0, 1), got {}", alpha)));
+            }
+        }
+        if let Some(EarlyStopping { validation_fraction, n_iter_no_change }) = self.early_stopping {
+            if !(validation_fraction > 0. && validation_fraction < 1.) {
+                return Err(Error::InvalidParameter(format!("validation fraction must be in (0, 1), got {}", validation_fraction)));
+            }
+            if n_iter_no_change == 0 {
+                return Err(Error::InvalidParameter("early stopping needs at least 1 stage without change".to_owned()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn fit_internal(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> GradientBoostingRegressorModel {
+        let mut rng = self.rng.rng();
+
+        match self.early_stopping {
+            Some(EarlyStopping { validation_fraction, .. }) => {
+                let mut rows: Vec<usize> = (0..x.nrows()).collect();
+                rows.shuffle(&mut rng);
+
+                let n_validation = ((validation_fraction * rows.len() as f64).round() as usize).max(1).min(rows.len() - 1);
+                let (validation, train) = rows.split_at(n_validation);
+                info!("Holding out {} rows for early stopping", n_validation);
+
+                let (x_validation, y_validation) = (x.select(Axis(0), validation), y.select(Axis(0), validation));
+                self.boost(x.select(Axis(0), train).view(), y.select(Axis(0), train).view(), Some((x_validation.view(), y_validation.view())), &mut rng)
+            }
+            None => self.boost(x, y, None, &mut rng),
+        }
+    }
+
+    fn boost<R: Rng>(&self, x: ArrayView2<f64>, y: ArrayView1<f64>, validation: Option<(ArrayView2<f64>, ArrayView1<f64>)>, rng: &mut R) -> GradientBoostingRegressorModel {
+        let start = Instant::now();
+        let n_rows = x.nrows();
+        let n_sampled = ((self.subsample * n_rows as f64).round() as usize).max(1);
+
+        let initial_prediction = match self.loss {
+            RegressionLoss::Squared => y.mean().unwrap(),
+            RegressionLoss::Absolute | RegressionLoss::Huber { .. } => median(y),
+        };
+        let mut predictions = Array1::from_elem(n_rows, initial_prediction);
+        let binned = self.max_bins.map(|max_bins| {
+            let edges = Binner::new(max_bins).fit(x);
+            (edges.transform::<u8>(x), edges)
+        });
+        let mut trees = Vec::with_capacity(self.n_estimators);
+
+        let mut validation = validation.map(|(x, y)| (x, y, Array1::from_elem(x.nrows(), initial_prediction)));
+        let mut best: Option<(usize, f64)> = None;
+
+        for stage in 0..self.n_estimators {
+            let rows = if n_sampled < n_rows {
+                sample(rng, n_rows, n_sampled).into_vec()
+            } else {
+                (0..n_rows).collect()
+            };
+
+            let residuals: Array1<f64> = rows.iter().map(|&row| y[row] - predictions[row]).collect();
+            let delta = self.huber_delta(residuals.view());
+
+            let gradients = residuals.mapv(|r| self.negative_gradient(r, delta));
+            let mut tree = match &binned {
+                Some((binned, edges)) => self.tree.fit_binned(binned.select(Axis(0), &rows).view(), edges, gradients.view()),
+                None => self.tree.fit_internal(x.select(Axis(0), &rows).view(), gradients.view()),
+            };
+
+            if self.loss != RegressionLoss::Squared {
+                let leaves = tree.apply(x.select(Axis(0), &rows).view());
+                tree.set_leaf_values(&self.leaf_values(&tree, leaves.view(), residuals.view(), delta));
+            }
+
+            let update = tree.predict(x);
+            predictions.scaled_add(self.learning_rate, &update);
+
+            let loss = self.loss_value(y, predictions.view());
+            debug!("Stage {} has a training loss of {:.5}", stage, loss);
+            self.callbacks.on_iteration(stage, loss, start.elapsed());
+
+            if let Some((x_validation, y_validation, validation_predictions)) = validation.as_mut() {
+                validation_predictions.scaled_add(self.learning_rate, &tree.predict(*x_validation));
+
+                let score = self.validation_score(*y_validation, validation_predictions.view());
+                debug!("Stage {} has a validation score of {:.5}", stage, score);
+
+                let improved = match best {
+                    Some((_, best_score)) => score > best_score,
+                    None => true,
+                };
+                if improved {
+                    best = Some((stage, score));
+                }
+            }
+
+            trees.push(tree);
+
+            if let (Some((best_stage, _)), Some(early_stopping)) = (best, self.early_stopping) {
+                if stage - best_stage >= early_stopping.n_iter_no_change {
+                    info!("Stopping after stage {}, the validation score last improved at stage {}", stage, best_stage);
+                    break;
+                }
+            }
+        }
+
+        let best_iteration = best.map(|(stage, _)| stage);
+        if let Some(stage) = best_iteration {
+            trees.truncate(stage + 1);
+        }
+
+        info!("Fitted {} stages", trees.len());
+
+        GradientBoostingRegressorModel {
+            initial_prediction,
+            learning_rate: self.learning_rate,
+            trees,
+            best_iteration,
+        }
+    }
+
+    fn validation_score(&self, y: ArrayView1<f64>, predictions: ArrayView1<f64>) -> f64 {
+        match &self.scoring {
+            Some(Scoring(scoring)) => scoring(y, predictions),
+            None => -self.loss_value(y, predictions),
+        }
+    }
+
+    /// The residual beyond which the Huber loss is linear: the `alpha` quantile of the absolute
+    /// residuals of the stage.
+    fn huber_delta(&self, residuals: ArrayView1<f64>) -> f64 {
+        match self.loss {
+            RegressionLoss::Huber { alpha } => quantile(residuals.mapv(f64::abs).view(), alpha, Interpolation::Linear),
+            _ => f64::INFINITY,
+        }
+    }
+
+    fn negative_gradient(&self, residual: f64, delta: f64) -> f64 {
+        match self.loss {
+            RegressionLoss::Squared => residual,
+            RegressionLoss::Absolute => residual.signum(),
+            RegressionLoss::Huber { .. } => residual.max(-delta).min(delta),
+        }
+    }
+
+    /// The value of each leaf minimizing the loss of the residuals falling in it, since the mean
+    /// gradient the tree was fitted to is only the best step for the squared loss.
+    fn leaf_values(&self, tree: &RegressionTreeModel, leaves: ArrayView1<usize>, residuals: ArrayView1<f64>, delta: f64) -> Vec<f64> {
+        let mut leaf_residuals = vec![vec![]; tree.n_leaves()];
+        for (&leaf, &residual) in leaves.iter().zip(residuals.iter()) {
+            leaf_residuals[leaf].push(residual);
+        }
+
+        leaf_residuals.into_iter()
+            .map(|residuals| {
+                let residuals = Array1::from(residuals);
+                let median = median(residuals.view());
+
+                match self.loss {
+                    RegressionLoss::Huber { .. } => {
+                        median + residuals.mapv(|r| (r - median).max(-delta).min(delta)).mean().unwrap()
+                    }
+                    _ => median,
+                }
+            })
+            .collect()
+    }
+
+    fn loss_value(&self, y: ArrayView1<f64>, predictions: ArrayView1<f64>) -> f64 {
+        let delta = self.huber_delta((&y - &predictions).view());
+
+        let total: f64 = y.iter()
+            .zip(predictions.iter())
+            .map(|(&y, &prediction)| {
+                let residual = (y - prediction).abs();
+                match self.loss {
+                    RegressionLoss::Squared => residual * residual / 2.,
+                    RegressionLoss::Absolute => residual,
+                    RegressionLoss::Huber { .. } if residual <= delta => residual * residual / 2.,
+                    RegressionLoss::Huber { .. } => delta * (residual - delta / 2.),
+                }
+            })
+            .sum();
+
+        total / y.len() as f64
+    }
+}
+
+impl fmt::Debug for Scoring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Scoring")
+    }
+}
+
+impl GradientBoostingRegressorModel {
+    /// The predictions after each stage in turn, to monitor how the fit converges, e.g. how the
+    /// error on held-out data changes with the number of trees.
+    pub fn staged_predict<'a, 'x: 'a>(&'a self, x: ArrayView2<'x, f64>) -> impl Iterator<Item=Array1<f64>> + 'a {
+        // Views are invariant in their lifetime, so this shortens it to the model's borrow.
+        let x: ArrayView2<'a, f64> = x.reborrow();
+        let mut predictions = Array1::from_elem(x.nrows(), self.initial_prediction);
+
+        self.trees.iter().map(move |tree| {
+            predictions.scaled_add(self.learning_rate, &tree.predict(x));
+            predictions.clone()
+        })
+    }
+
+    pub fn n_estimators(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// The stage, counting from 0, that scored best on the rows held out for early stopping,
+    /// which is the last stage kept, or none when early stopping is off.
+    pub fn best_iteration(&self) -> Option<usize> {
+        self.best_iteration
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, Array1, Array2};
+    use rune_core::estimator::{Fit, Predictor};
+    use rune_core::random::RngSource;
+    use rune_tree::regressor::DecisionTreeRegressor;
+
+    use crate::gradient_boosting_regressor::{GradientBoostingRegressor, RegressionLoss};
+
+    fn data() -> (Array2<f64>, Array1<f64>) {
+        let x = Array2::from_shape_fn((60, 2), |(i, j)| if j == 0 { i as f64 / 10. } else { (i % 7) as f64 });
+        let y = x.outer_iter().map(|row| (row[0] * 2.).sin() * 3. + row[1] * row[1] / 10.).collect();
+        (x, y)
+    }
+
+    fn mean_squared_error(y: &Array1<f64>, predictions: &Array1<f64>) -> f64 {
+        (y - predictions).mapv(|r| r * r).mean().unwrap()
+    }
+
+    #[test]
+    fn test_squared_loss_training_error_falls_every_stage() {
+        let (x, y) = data();
+        let model = GradientBoostingRegressor::new(RegressionLoss::Squared, 20, 0.3).fit(x.view(), y.view()).unwrap();
+
+        let mut last = mean_squared_error(&y, &Array1::from_elem(y.len(), y.mean().unwrap()));
+        for predictions in model.staged_predict(x.view()) {
+            let error = mean_squared_error(&y, &predictions);
+            assert!(error < last, "training error rose from {} to {}", last, error);
+            last = error;
+        }
+        assert!(last < 0.05);
+    }
+
+    #[test]
+    fn test_last_stage_is_the_prediction() {
+        let (x, y) = data();
+        let model = GradientBoostingRegressor::new(RegressionLoss::Huber { alpha: 0.9 }, 15, 0.1).fit(x.view(), y.view()).unwrap();
+
+        let stages: Vec<Array1<f64>> = model.staged_predict(x.view()).collect();
+
+        assert_eq!(stages.len(), model.n_estimators());
+        assert_eq!(stages.last().unwrap(), &model.predict(x.view()));
+    }
+
+    #[test]
+    fn test_early_stopping_keeps_the_stages_up_to_the_plateau() {
+        // A step the first full-rate stage fits, leaving residuals no later stage improves on.
+        let x = Array2::from_shape_fn((60, 1), |(i, _)| i as f64 / 10.);
+        let y = x.column(0).mapv(|v| if v < 3. { 0. } else { 1. });
+        let model = GradientBoostingRegressor::new(RegressionLoss::Squared, 50, 1.)
+            .subsample(1., RngSource::Seed(0))
+            .early_stopping(0.2, 3)
+            .fit(x.view(), y.view())
+            .unwrap();
+
+        let best_iteration = model.best_iteration().unwrap();
+        assert!(best_iteration < 50, "no plateau before the last stage");
+        assert_eq!(model.n_estimators(), best_iteration + 1);
+
+        let stages: Vec<Array1<f64>> = model.staged_predict(x.view()).collect();
+        assert_eq!(stages.len(), best_iteration + 1);
+        assert_eq!(stages[best_iteration], model.predict(x.view()));
+    }
+
+    #[test]
+    fn test_absolute_and_huber_leaf_values_are_medians() {
+        let x = Array2::from_shape_fn((6, 1), |(i, _)| i as f64);
+        let tree = DecisionTreeRegressor::new(1, 1).fit_internal(x.view(), arr1(&[0., 0., 0., 1., 1., 1.]).view());
+        let leaves = tree.apply(x.view());
+        let residuals = arr1(&[1., 2., 100., -5., 3., 4.]);
+
+        let absolute = GradientBoostingRegressor::new(RegressionLoss::Absolute, 1, 1.);
+        let values = absolute.leaf_values(&tree, leaves.view(), residuals.view(), 0.);
+        assert_eq!((values[leaves[0]], values[leaves[3]]), (2., 3.));
+
+        // Huber steps from the median by the mean of the deviations clipped to `delta`, which
+        // cancel here, and tends to the mean as `delta` grows.
+        let huber = GradientBoostingRegressor::new(RegressionLoss::Huber { alpha: 0.9 }, 1, 1.);
+        let values = huber.leaf_values(&tree, leaves.view(), residuals.view(), 1.);
+        assert_eq!((values[leaves[0]], values[leaves[3]]), (2., 3.));
+        let values = huber.leaf_values(&tree, leaves.view(), residuals.view(), 1000.);
+        assert!((values[leaves[0]] - 103. / 3.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_subsampling_is_reproducible_with_a_seed() {
+        let (x, y) = data();
+        let fit = |seed| GradientBoostingRegressor::new(RegressionLoss::Absolute, 5, 0.5)
+            .subsample(0.3, RngSource::Seed(seed))
+            .fit(x.view(), y.view())
+            .unwrap()
+            .predict(x.view());
+
+        assert_eq!(fit(7), fit(7));
+        assert_ne!(fit(7), fit(8));
+    }
+}
@@ -1 +1,5 @@
-pub mod random_forest_classifier;
\ No newline at end of file
+pub mod calibrated_classifier;
+pub mod gradient_boosting_regressor;
+pub mod one_vs_rest_classifier;
+pub mod random_forest_classifier;
+pub mod stacking_classifier;
@@ -0,0 +1,114 @@
+use std::hash::Hash;
+#[cfg(feature = "parallel")]
+use std::thread;
+
+use itertools::Itertools;
+use log::*;
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rune_core::validation::check_x_y;
+use rune_core::Result;
+use rune_metrics::metrics::accuracy_score;
+use rune_core::estimator::{Fit, Predictor, Score};
+use serde::{Deserialize, Serialize};
+
+/// Wraps a binary estimator so it can be trained on a multi-class target. One copy of the
+/// estimator is fitted per class (that class against every other) and the class with the
+/// highest decision score wins at prediction time.
+#[derive(Debug)]
+pub struct OneVsRestClassifier<E> {
+    estimator: E,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OneVsRestModel<M, T> {
+    classes: Vec<T>,
+    models: Vec<M>,
+}
+
+impl<E> OneVsRestClassifier<E> {
+    pub fn new(estimator: E) -> Self {
+        OneVsRestClassifier { estimator }
+    }
+}
+
+impl<E, M, T> Fit<ArrayView2<'_, f64>, OneVsRestModel<M, T>, T> for OneVsRestClassifier<E>
+    where
+        E: for<'a> Fit<ArrayView2<'a, f64>, M> + Clone + Send,
+        M: Send,
+        T: Clone + Eq + Hash + Sync {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<T>) -> Result<OneVsRestModel<M, T>> {
+        check_x_y(x, y, 1)?;
+
+        let classes: Vec<T> = y.iter().unique().cloned().collect();
+        info!("Training {} one-vs-rest estimators", classes.len());
+
+        #[cfg(feature = "parallel")]
+        let models = thread::scope(|scope| {
+            let handles = classes.iter()
+                .map(|class| {
+                    let estimator = self.estimator.clone();
+                    scope.spawn(move || {
+                        let binary_y = y.mapv(|label| label == *class);
+                        estimator.fit(x, binary_y.view())
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles.into_iter()
+                .map(|handle| handle.join().expect("one-vs-rest estimator panicked"))
+                .collect::<Result<Vec<M>>>()
+        })?;
+
+        // Without threads, e.g. on wasm32, the estimators are fitted one after another.
+        #[cfg(not(feature = "parallel"))]
+        let models = classes.iter()
+            .map(|class| {
+                let binary_y = y.mapv(|label| label == *class);
+                self.estimator.fit(x, binary_y.view())
+            })
+            .collect::<Result<Vec<M>>>()?;
+
+        Ok(OneVsRestModel { classes, models })
+    }
+}
+
+impl<M, T> OneVsRestModel<M, T> where M: for<'a> Predictor<ArrayView2<'a, f64>, Array1<f64>>, T: Clone {
+    pub fn classes(&self) -> &[T] {
+        &self.classes
+    }
+
+    /// Decision score of every class for each row, one column per entry in `classes()`.
+    pub fn decision_function(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let mut scores = Array2::zeros((x.nrows(), self.models.len()));
+
+        for (column, model) in self.models.iter().enumerate() {
+            scores.column_mut(column).assign(&model.predict(x));
+        }
+
+        scores
+    }
+}
+
+impl<'a, M, T> Predictor<ArrayView2<'a, f64>, Array1<T>> for OneVsRestModel<M, T> where M: for<'b> Predictor<ArrayView2<'b, f64>, Array1<f64>>, T: Clone {
+    fn predict(&self, x: ArrayView2<'a, f64>) -> Array1<T> {
+        let scores = self.decision_function(x);
+
+        scores.axis_iter(Axis(0))
+            .map(|row| {
+                let (best, _) = row.iter()
+                    .enumerate()
+                    .fold((0, f64::NEG_INFINITY), |(best, best_score), (index, &score)| {
+                        if score > best_score { (index, score) } else { (best, best_score) }
+                    });
+                self.classes[best].clone()
+            })
+            .collect()
+    }
+}
+
+/// The fraction of rows whose class is predicted correctly.
+impl<'a, M, T> Score<ArrayView2<'a, f64>, T> for OneVsRestModel<M, T> where M: for<'b> Predictor<ArrayView2<'b, f64>, Array1<f64>>, T: Clone + PartialEq {
+    fn score(&self, x: ArrayView2<'a, f64>, y: ArrayView1<T>) -> f64 {
+        accuracy_score(y, self.predict(x).view(), None)
+    }
+}
@@ -1,2 +1,6 @@
+use std::marker::PhantomData;
+
 #[derive(Debug)]
-pub struct RandomForestClassifier<FS> {}
\ No newline at end of file
+pub struct RandomForestClassifier<FS> {
+    _feature_selector: PhantomData<FS>,
+}
@@ -0,0 +1,108 @@
+use log::*;
+use ndarray::{stack, Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rune_core::validation::check_x_y;
+use rune_core::Result;
+use rune_model_selection::splitting::k_fold::KFold;
+use rune_core::estimator::{Fit, Predictor};
+use serde::{Deserialize, Serialize};
+
+/// Trains a meta-learner on the out-of-fold decision scores of a set of base estimators. With
+/// `passthrough` enabled the original features are appended to the base scores before they
+/// reach the meta-learner.
+#[derive(Debug)]
+pub struct StackingClassifier<E, F> {
+    estimators: Vec<E>,
+    final_estimator: F,
+    cv: KFold,
+    passthrough: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StackingModel<M, FM> {
+    models: Vec<M>,
+    final_model: FM,
+    passthrough: bool,
+}
+
+impl<E, F> StackingClassifier<E, F> {
+    pub fn new(estimators: Vec<E>, final_estimator: F, cv: KFold, passthrough: bool) -> Self {
+        StackingClassifier {
+            estimators,
+            final_estimator,
+            cv,
+            passthrough,
+        }
+    }
+
+    fn out_of_fold_scores<M>(&self, x: ArrayView2<f64>, y: ArrayView1<bool>) -> Result<Array2<f64>>
+        where
+            E: for<'a> Fit<ArrayView2<'a, f64>, M>,
+            M: for<'a> Predictor<ArrayView2<'a, f64>, Array1<f64>> {
+        let folds = self.cv.split(x.nrows())?;
+        let mut scores = Array2::zeros((x.nrows(), self.estimators.len()));
+
+        for (column, estimator) in self.estimators.iter().enumerate() {
+            for (fold, (train, test)) in folds.iter().enumerate() {
+                debug!("Fitting base estimator {} on fold {}", column, fold);
+
+                let model = estimator.fit(x.select(Axis(0), train).view(), y.select(Axis(0), train).view())?;
+                let fold_scores = model.predict(x.select(Axis(0), test).view());
+
+                for (&row, &score) in test.iter().zip(fold_scores.iter()) {
+                    scores[[row, column]] = score;
+                }
+            }
+        }
+
+        Ok(scores)
+    }
+}
+
+fn with_passthrough(scores: Array2<f64>, x: ArrayView2<f64>, passthrough: bool) -> Array2<f64> {
+    if passthrough {
+        stack(Axis(1), &[scores.view(), x.view()]).unwrap()
+    } else {
+        scores
+    }
+}
+
+impl<'a, E, F, M, FM> Fit<ArrayView2<'a, f64>, StackingModel<M, FM>> for StackingClassifier<E, F>
+    where
+        E: for<'b> Fit<ArrayView2<'b, f64>, M>,
+        M: for<'b> Predictor<ArrayView2<'b, f64>, Array1<f64>>,
+        F: for<'b> Fit<ArrayView2<'b, f64>, FM> {
+    fn fit(&self, x: ArrayView2<'a, f64>, y: ArrayView1<bool>) -> Result<StackingModel<M, FM>> {
+        check_x_y(x, y, self.cv.n_splits())?;
+
+        let meta_x = with_passthrough(self.out_of_fold_scores(x, y)?, x, self.passthrough);
+
+        info!("Fitting final estimator on {} meta features", meta_x.ncols());
+        let final_model = self.final_estimator.fit(meta_x.view(), y)?;
+
+        let models = self.estimators.iter()
+            .map(|estimator| estimator.fit(x, y))
+            .collect::<Result<_>>()?;
+
+        Ok(StackingModel {
+            models,
+            final_model,
+            passthrough: self.passthrough,
+        })
+    }
+}
+
+impl<'a, M, FM, Out> Predictor<ArrayView2<'a, f64>, Out> for StackingModel<M, FM>
+    where
+        M: for<'b> Predictor<ArrayView2<'b, f64>, Array1<f64>>,
+        FM: for<'b> Predictor<ArrayView2<'b, f64>, Out> {
+    fn predict(&self, x: ArrayView2<'a, f64>) -> Out {
+        let mut scores = Array2::zeros((x.nrows(), self.models.len()));
+
+        for (column, model) in self.models.iter().enumerate() {
+            scores.column_mut(column).assign(&model.predict(x));
+        }
+
+        let meta_x = with_passthrough(scores, x, self.passthrough);
+        self.final_model.predict(meta_x.view())
+    }
+}
@@ -0,0 +1,92 @@
+use std::hash::Hash;
+
+use ndarray::{Array1, Array2, ArrayView2};
+use rune_core::estimator::Predictor;
+use rune_tree::DecisionTreeModel;
+use serde::{Deserialize, Serialize};
+
+/// A forest of decision trees, e.g. the trees of a scikit-learn forest read with
+/// `rune_tree::sklearn::read_sklearn_forest`, predicting the class most of its trees vote for.
+/// Ties go to the class voted for by the earliest tree.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForestModel<T> {
+    trees: Vec<DecisionTreeModel<T>>,
+}
+
+impl<T> ForestModel<T> {
+    pub fn new(trees: Vec<DecisionTreeModel<T>>) -> Self {
+        ForestModel { trees }
+    }
+
+    pub fn trees(&self) -> &[DecisionTreeModel<T>] {
+        &self.trees
+    }
+}
+
+impl<T: Eq + Hash + Default + Copy> ForestModel<T> {
+    fn predict_rows(&self, x: ArrayView2<f64>) -> Array1<T> {
+        assert!(!self.trees.is_empty(), "a forest needs at least one tree to predict");
+
+        let votes: Vec<Array1<T>> = self.trees.iter().map(|tree| tree.predict(x)).collect();
+
+        (0..x.nrows())
+            .map(|row| {
+                let mut counts: Vec<(T, usize)> = vec![];
+                for vote in votes.iter().map(|votes| votes[row]) {
+                    match counts.iter_mut().find(|(class, _)| *class == vote) {
+                        Some((_, count)) => *count += 1,
+                        None => counts.push((vote, 1)),
+                    }
+                }
+
+                counts.iter()
+                    .fold(counts[0], |best, &candidate| if candidate.1 > best.1 { candidate } else { best })
+                    .0
+            })
+            .collect()
+    }
+}
+
+impl<T: Eq + Hash + Default + Copy> Predictor<Array2<f64>, Array1<T>> for ForestModel<T> {
+    fn predict(&self, x: Array2<f64>) -> Array1<T> {
+        self.predict_rows(x.view())
+    }
+}
+
+impl<T: Eq + Hash + Default + Copy> Predictor<ArrayView2<'_, f64>, Array1<T>> for ForestModel<T> {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<T> {
+        self.predict_rows(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array2};
+    use rune_core::estimator::Predictor;
+    use rune_tree::sklearn::read_sklearn_tree;
+    use rune_tree::DecisionTreeModel;
+
+    use crate::forest::ForestModel;
+
+    /// A tree sending `x[0] <= threshold` to class 0 and everything else to class 1.
+    fn stump(threshold: f64) -> DecisionTreeModel<i64> {
+        let json = format!(r#"{{"classes": [0, 1], "children_left": [1, -1, -1], "children_right": [2, -1, -1],
+            "feature": [0, -2, -2], "threshold": [{}, -2.0, -2.0], "value": [[[1.0, 1.0]], [[1.0, 0.0]], [[0.0, 1.0]]]}}"#, threshold);
+        read_sklearn_tree(json.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_forest_predicts_the_majority_vote() {
+        let forest = ForestModel::new(vec![stump(1.), stump(2.), stump(3.)]);
+        let x: Array2<f64> = array![[0.5], [1.5], [2.5], [3.5]];
+
+        assert_eq!(forest.predict(x.view()), array![0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_forest_ties_go_to_the_earliest_tree() {
+        let forest = ForestModel::new(vec![stump(2.), stump(1.)]);
+
+        assert_eq!(forest.predict(array![[1.5]]), array![0]);
+    }
+}
@@ -0,0 +1,15 @@
+//! The prediction side of rune, for serving models trained elsewhere in places without threads,
+//! a filesystem or LAPACK, such as a browser or an edge worker through wasm32-unknown-unknown.
+//! Models are fitted with the full crates, written with `rune_persist::write_model`, and read
+//! back here from bytes with `read_model`.
+
+pub mod forest;
+
+pub use rune_linear::linear_regression::LinearRegressionModel;
+pub use rune_linear::multiple_linear_regression::MultipleLinearRegressionModel;
+pub use rune_persist::{read_model, write_model, Format};
+pub use rune_pipeline::pipeline::{PipelineModel, Predictor, Transformer};
+pub use rune_preprocessing::standard_scaler::StandardScalerTransformer;
+pub use rune_tree::DecisionTreeModel;
+
+pub use crate::forest::ForestModel;
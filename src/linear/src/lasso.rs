@@ -0,0 +1,303 @@
+use log::*;
+use ndarray::{Array1, ArrayView1, ArrayView2, Axis};
+use rune_core::estimator::{Fit, Predictor};
+use rune_core::validation::{check_finite, check_x_y};
+use rune_core::{Error, Result};
+use rune_model_selection::splitting::k_fold::KFold;
+use rune_model_selection::splitting::CrossValidator;
+
+use crate::linear_model::{Centred, LinearModel, LinearModelCV, LinearOptions};
+use crate::ridge::best_alpha;
+
+/// Least squares with the L1 penalty `alpha ||w||₁`, minimizing `||y - x w||² / (2 n) +
+/// alpha ||w||₁` by coordinate descent. The penalty drives the coefficients of uninformative
+/// features to exactly zero. The intercept isn't penalized.
+#[derive(Debug, Clone)]
+pub struct Lasso {
+    alpha: f64,
+    max_iter: usize,
+    tol: f64,
+    options: LinearOptions,
+}
+
+/// `Lasso` with `alpha` picked by cross-validation on the folds of `cv` along a path of
+/// decreasing strengths, each fit starting from the coefficients of the one before, so the whole
+/// path costs little more than a single fit.
+#[derive(Debug, Clone)]
+pub struct LassoCV<C = KFold> {
+    cv: C,
+    alphas: Option<Vec<f64>>,
+    n_alphas: usize,
+    max_iter: usize,
+    tol: f64,
+    options: LinearOptions,
+}
+
+impl Fit<ArrayView2<'_, f64>, LinearModel, f64> for Lasso {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<LinearModel> {
+        check_x_y(x, y, 1)?;
+        check_finite("y", y)?;
+        check_alpha(self.alpha)?;
+
+        let centred = Centred::new(x, y, self.options);
+        let mut coef = Array1::zeros(x.ncols());
+        coordinate_descent(centred.x.view(), centred.y.view(), self.alpha, &mut coef, self.max_iter, self.tol);
+
+        Ok(centred.model(coef))
+    }
+}
+
+impl<C: CrossValidator<f64>> Fit<ArrayView2<'_, f64>, LinearModelCV, f64> for LassoCV<C> {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<LinearModelCV> {
+        check_x_y(x, y, 2)?;
+        check_finite("y", y)?;
+
+        let alphas = match &self.alphas {
+            Some(alphas) => {
+                for &alpha in alphas {
+                    check_alpha(alpha)?;
+                }
+                let mut alphas = alphas.clone();
+                alphas.sort_by(|l, r| r.partial_cmp(l).unwrap());
+                alphas
+            }
+            None => alpha_path(&Centred::new(x, y, self.options), self.n_alphas),
+        };
+        if alphas.is_empty() {
+            return Err(Error::InvalidParameter("at least one alpha is required".to_owned()));
+        }
+
+        let folds = self.cv.folds(y)?;
+        let mut errors = vec![0.; alphas.len()];
+
+        for (train, test) in &folds {
+            let centred = Centred::new(x.select(Axis(0), train).view(), y.select(Axis(0), train).view(), self.options);
+            let (x_test, y_test) = (x.select(Axis(0), test), y.select(Axis(0), test));
+            let mut coef = Array1::zeros(x.ncols());
+
+            for (error, &alpha) in errors.iter_mut().zip(&alphas) {
+                coordinate_descent(centred.x.view(), centred.y.view(), alpha, &mut coef, self.max_iter, self.tol);
+                let residuals = &y_test - &centred.model(coef.clone()).predict(x_test.view());
+                *error += residuals.mapv(|r| r * r).mean().unwrap_or(0.) / folds.len() as f64;
+            }
+        }
+
+        let cv_results: Vec<(f64, f64)> = alphas.into_iter().zip(errors).collect();
+        let alpha = best_alpha(&cv_results)?;
+        info!("Picked alpha {}", alpha);
+
+        let model = Lasso {
+            alpha,
+            max_iter: self.max_iter,
+            tol: self.tol,
+            options: self.options,
+        }.fit(x, y)?;
+        Ok(LinearModelCV::new(model, alpha, cv_results))
+    }
+}
+
+impl Lasso {
+    /// Defaults to at most 1000 passes over the features, stopping once no coefficient moves by
+    /// more than `1e-4` of the largest.
+    pub fn new(alpha: f64) -> Self {
+        Lasso {
+            alpha,
+            max_iter: 1000,
+            tol: 1e-4,
+            options: LinearOptions::new(),
+        }
+    }
+
+    pub fn max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    pub fn options(mut self, options: LinearOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl<C> LassoCV<C> {
+    /// Defaults to 100 strengths spaced evenly on a log scale over three decades, from the
+    /// smallest that zeroes every coefficient, and the `Lasso` defaults otherwise.
+    pub fn new(cv: C) -> Self {
+        LassoCV {
+            cv,
+            alphas: None,
+            n_alphas: 100,
+            max_iter: 1000,
+            tol: 1e-4,
+            options: LinearOptions::new(),
+        }
+    }
+
+    /// Tries exactly these strengths, largest first, instead of a path.
+    pub fn alphas(mut self, alphas: Vec<f64>) -> Self {
+        self.alphas = Some(alphas);
+        self
+    }
+
+    pub fn n_alphas(mut self, n_alphas: usize) -> Self {
+        self.n_alphas = n_alphas;
+        self
+    }
+
+    pub fn max_iter(mut self, max_iter: usize) -> Self {
+        self.max_iter = max_iter;
+        self
+    }
+
+    pub fn tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    pub fn options(mut self, options: LinearOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+fn check_alpha(alpha: f64) -> Result<()> {
+    if alpha >= 0. && alpha.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!("alpha must be non-negative, got {}", alpha)))
+    }
+}
+
+/// `n_alphas` strengths from the smallest that zeroes every coefficient of `centred` down to a
+/// thousandth of it, evenly spaced on a log scale.
+fn alpha_path(centred: &Centred, n_alphas: usize) -> Vec<f64> {
+    let n = centred.y.len() as f64;
+    let alpha_max = centred.x.t().dot(&centred.y).iter().fold(0., |max: f64, &v| max.max(v.abs() / n));
+    if alpha_max == 0. || n_alphas < 2 {
+        return vec![alpha_max];
+    }
+
+    (0..n_alphas)
+        .map(|i| alpha_max * 10f64.powf(-3. * i as f64 / (n_alphas - 1) as f64))
+        .collect()
+}
+
+/// Minimizes the lasso objective on centred data by cyclic coordinate descent, starting from and
+/// updating `coef`.
+fn coordinate_descent(x: ArrayView2<f64>, y: ArrayView1<f64>, alpha: f64, coef: &mut Array1<f64>, max_iter: usize, tol: f64) {
+    let n = y.len() as f64;
+    let norms: Array1<f64> = x.gencolumns().into_iter().map(|column| column.dot(&column) / n).collect();
+    let mut residuals = &y - &x.dot(coef);
+
+    for iteration in 0..max_iter {
+        let mut max_change: f64 = 0.;
+
+        for feature in 0..x.ncols() {
+            if norms[feature] == 0. {
+                continue;
+            }
+
+            let column = x.column(feature);
+            let old = coef[feature];
+            let rho = column.dot(&residuals) / n + norms[feature] * old;
+            let new = rho.signum() * (rho.abs() - alpha).max(0.) / norms[feature];
+
+            if new != old {
+                residuals.scaled_add(old - new, &column);
+                coef[feature] = new;
+                max_change = max_change.max((new - old).abs());
+            }
+        }
+
+        let max_coef = coef.iter().fold(0., |max: f64, &w| max.max(w.abs()));
+        if max_change <= tol * max_coef {
+            debug!("Coordinate descent converged after {} passes at alpha {}", iteration + 1, alpha);
+            return;
+        }
+    }
+
+    warn!("Coordinate descent did not converge in {} passes at alpha {}", max_iter, alpha);
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{Array1, Array2};
+    use rune_core::estimator::{Fit, Score};
+    use rune_model_selection::splitting::k_fold::KFold;
+    use rune_model_selection::splitting::leave_one_out::LeaveOneOut;
+
+    use crate::lasso::{Lasso, LassoCV};
+    use crate::linear_model::LinearOptions;
+
+    /// The target depends on the first two features only.
+    fn data() -> (Array2<f64>, Array1<f64>) {
+        let x = Array2::from_shape_fn((40, 3), |(i, j)| ((i * (j + 2) * 7) % 13) as f64 / 6. - 1.);
+        let y = x.outer_iter().map(|row| 2. * row[0] - row[1] + 0.5).collect();
+        (x, y)
+    }
+
+    #[test]
+    fn test_lasso_zeroes_an_uninformative_feature() {
+        let (x, y) = data();
+        let model = Lasso::new(0.05).fit(x.view(), y.view()).unwrap();
+
+        assert_eq!(model.coef()[2], 0.);
+        assert!(model.coef()[0] > 1.5);
+        assert!(model.coef()[1] < -0.5);
+    }
+
+    #[test]
+    fn test_lasso_zeroes_everything_above_the_largest_alpha() {
+        let (x, y) = data();
+        let model = Lasso::new(100.).fit(x.view(), y.view()).unwrap();
+
+        assert!(model.coef().iter().all(|&c| c == 0.));
+        assert!((model.intercept() - y.mean().unwrap()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_lasso_cv_picks_the_least_penalty_for_noiseless_data() {
+        let (x, y) = data();
+        let model = LassoCV::new(KFold::new(5)).n_alphas(20).fit(x.view(), y.view()).unwrap();
+
+        let (smallest, _) = *model.cv_results().last().unwrap();
+        let (largest, largest_error) = model.cv_results()[0];
+        assert!(smallest < largest);
+        assert_eq!(model.alpha(), smallest);
+        assert!(model.cv_results().iter().all(|&(_, error)| error <= largest_error));
+        assert!(model.score(x.view(), y.view()) > 0.99);
+    }
+
+    #[test]
+    fn test_lasso_cv_takes_any_splitter() {
+        let (x, y) = data();
+        let alphas = vec![1., 0.1, 0.001];
+
+        let k_fold = LassoCV::new(KFold::new(40)).alphas(alphas.clone()).fit(x.view(), y.view()).unwrap();
+        let leave_one_out = LassoCV::new(LeaveOneOut::new()).alphas(alphas).fit(x.view(), y.view()).unwrap();
+
+        assert_eq!(leave_one_out.alpha(), 0.001);
+        assert_eq!(k_fold.cv_results(), leave_one_out.cv_results());
+    }
+
+    #[test]
+    fn test_normalized_coefficients_are_in_original_units() {
+        let (x, y) = data();
+        let lasso = Lasso::new(0.05).tol(1e-10).options(LinearOptions::new().normalize(true));
+
+        let model = lasso.fit(x.view(), y.view()).unwrap();
+        let mut rescaled = x.clone();
+        rescaled.column_mut(0).mapv_inplace(|v| v * 1000.);
+        let rescaled = lasso.fit(rescaled.view(), y.view()).unwrap();
+
+        assert!((rescaled.coef()[0] * 1000. - model.coef()[0]).abs() < 1e-6);
+        assert!((rescaled.coef()[1] - model.coef()[1]).abs() < 1e-6);
+        assert_eq!(rescaled.coef()[2], 0.);
+        assert!((rescaled.intercept() - model.intercept()).abs() < 1e-6);
+    }
+}
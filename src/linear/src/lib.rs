@@ -1,2 +1,6 @@
+pub mod lasso;
 pub mod linear_regression;
-pub mod multiple_linear_regression;
\ No newline at end of file
+pub mod linear_model;
+pub mod multiple_linear_regression;
+pub mod ridge;
+pub mod sgd;
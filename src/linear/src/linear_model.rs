@@ -0,0 +1,172 @@
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rune_core::estimator::{Predictor, Score};
+use rune_metrics::regression::r2::r2;
+use rune_preprocessing::standard_scaler::{StandardScaler, StandardScalerTransformer};
+use serde::{Deserialize, Serialize};
+
+/// A fitted linear regression, `x · coef + intercept`, as the regularized estimators produce it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinearModel {
+    coef: Array1<f64>,
+    intercept: f64,
+}
+
+/// A `LinearModel` refitted with the regularization strength cross-validation picked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinearModelCV {
+    model: LinearModel,
+    alpha: f64,
+    cv_results: Vec<(f64, f64)>,
+}
+
+impl LinearModel {
+    pub fn new(coef: Array1<f64>, intercept: f64) -> Self {
+        LinearModel { coef, intercept }
+    }
+
+    /// The weight of every feature.
+    pub fn coef(&self) -> ArrayView1<'_, f64> {
+        self.coef.view()
+    }
+
+    pub fn intercept(&self) -> f64 {
+        self.intercept
+    }
+}
+
+impl Predictor<ArrayView2<'_, f64>, Array1<f64>> for LinearModel {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        x.dot(&self.coef) + self.intercept
+    }
+}
+
+/// The coefficient of determination R² of the predictions.
+impl Score<ArrayView2<'_, f64>, f64> for LinearModel {
+    fn score(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> f64 {
+        r2(y, self.predict(x).view(), None)
+    }
+}
+
+impl LinearModelCV {
+    pub fn new(model: LinearModel, alpha: f64, cv_results: Vec<(f64, f64)>) -> Self {
+        LinearModelCV { model, alpha, cv_results }
+    }
+
+    pub fn model(&self) -> &LinearModel {
+        &self.model
+    }
+
+    /// The regularization strength picked.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// The mean squared error of every candidate strength, in the order tried.
+    pub fn cv_results(&self) -> &[(f64, f64)] {
+        &self.cv_results
+    }
+}
+
+impl Predictor<ArrayView2<'_, f64>, Array1<f64>> for LinearModelCV {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        self.model.predict(x)
+    }
+}
+
+/// The coefficient of determination R² of the predictions.
+impl Score<ArrayView2<'_, f64>, f64> for LinearModelCV {
+    fn score(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> f64 {
+        self.model.score(x, y)
+    }
+}
+
+/// How every linear estimator prepares the features it is fitted on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearOptions {
+    pub(crate) fit_intercept: bool,
+    pub(crate) normalize: bool,
+}
+
+impl LinearOptions {
+    /// Defaults to fitting an intercept, on the features as they are.
+    pub fn new() -> Self {
+        LinearOptions { fit_intercept: true, normalize: false }
+    }
+
+    /// Whether to learn an intercept; without one, the model, or a classifier's decision
+    /// boundary, passes through the origin.
+    pub fn fit_intercept(mut self, fit_intercept: bool) -> Self {
+        self.fit_intercept = fit_intercept;
+        self
+    }
+
+    /// Standardizes every feature to zero mean and unit variance with a `StandardScaler` before
+    /// fitting, so that one penalty or learning rate suits features of any units. Estimators
+    /// that learn incrementally keep the scaling of their first fit. The coefficients and
+    /// intercept of the fitted model are still those of the original features.
+    pub fn normalize(mut self, normalize: bool) -> Self {
+        self.normalize = normalize;
+        self
+    }
+}
+
+impl Default for LinearOptions {
+    fn default() -> Self {
+        LinearOptions::new()
+    }
+}
+
+/// The scaler of the `normalize` option fitted on `x`, and `x` scaled. It only centres the
+/// features for models with an intercept, which would otherwise have to absorb the shift.
+pub(crate) fn standardize(x: ArrayView2<f64>, options: LinearOptions) -> (StandardScalerTransformer, Array2<f64>) {
+    StandardScaler::new().with_mean(options.fit_intercept).internal_fit_transform(x)
+}
+
+/// The coefficients and intercept of a model of the features `scaler` standardized as those of
+/// the same model of the original features.
+pub(crate) fn unscale(scaler: &StandardScalerTransformer, coef: ArrayView1<f64>, intercept: f64) -> (Array1<f64>, f64) {
+    let coef = &coef / &scaler.std_dev();
+    let intercept = intercept - scaler.mean().dot(&coef);
+    (coef, intercept)
+}
+
+/// `x` and `y` less their means when fitting an intercept, with `x` also scaled to unit variance
+/// when normalizing, so that a model fitted without an intercept on them maps back to the
+/// original data through `model`.
+pub(crate) struct Centred {
+    pub x: Array2<f64>,
+    pub y: Array1<f64>,
+    x_mean: Array1<f64>,
+    x_scale: Array1<f64>,
+    y_mean: f64,
+}
+
+impl Centred {
+    pub fn new(x: ArrayView2<f64>, y: ArrayView1<f64>, options: LinearOptions) -> Self {
+        let (x, x_mean, x_scale) = if options.normalize {
+            let (scaler, x) = standardize(x, options);
+            (x, scaler.mean().to_owned(), scaler.std_dev().to_owned())
+        } else if options.fit_intercept {
+            let x_mean = x.mean_axis(Axis(0)).unwrap_or_else(|| Array1::zeros(x.ncols()));
+            (&x - &x_mean, x_mean, Array1::ones(x.ncols()))
+        } else {
+            (x.to_owned(), Array1::zeros(x.ncols()), Array1::ones(x.ncols()))
+        };
+        let y_mean = if options.fit_intercept { y.mean().unwrap_or(0.) } else { 0. };
+
+        Centred {
+            x,
+            y: &y - y_mean,
+            x_mean,
+            x_scale,
+            y_mean,
+        }
+    }
+
+    /// The model with `coef` for the centred features.
+    pub fn model(&self, coef: Array1<f64>) -> LinearModel {
+        let coef = coef / &self.x_scale;
+        let intercept = self.y_mean - self.x_mean.dot(&coef);
+        LinearModel::new(coef, intercept)
+    }
+}
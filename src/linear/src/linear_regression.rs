@@ -1,4 +1,9 @@
 use ndarray::{Array1, ArrayView1, Zip};
+use rune_core::estimator::{Fit, Predictor, Score};
+use rune_core::validation::{check_finite, check_min_samples, check_n_samples};
+use rune_core::{Error, Result};
+use rune_metrics::regression::r2::r2;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct LinearRegressionRegressor {}
@@ -7,10 +12,21 @@ impl LinearRegressionRegressor {
     pub fn new() -> Self {
         LinearRegressionRegressor {}
     }
+}
+
+impl Fit<ArrayView1<'_, f64>, LinearRegressionModel, f64> for LinearRegressionRegressor {
+    fn fit(&self, x: ArrayView1<f64>, y: ArrayView1<f64>) -> Result<LinearRegressionModel> {
+        check_n_samples(x.len(), y.len())?;
+        check_min_samples(x.len(), 2)?;
+        check_finite("x", x)?;
+        check_finite("y", y)?;
+
+        let mean_y = y.mean().ok_or_else(|| Error::InvalidInput("cannot fit a regression on no samples".to_owned()))?;
+        let mean_x = x.mean().ok_or_else(|| Error::InvalidInput("cannot fit a regression on no samples".to_owned()))?;
 
-    pub fn fit(&self, x: ArrayView1<f64>, y: ArrayView1<f64>) -> LinearRegressionModel {
-        let mean_y = y.mean().unwrap();
-        let mean_x = x.mean().unwrap();
+        if x.iter().all(|&value| value == x[0]) {
+            return Err(Error::InvalidInput("cannot fit a line to a constant x".to_owned()));
+        }
 
         let (numer, denom) = Zip::from(&y)
             .and(&x)
@@ -21,11 +37,11 @@ impl LinearRegressionRegressor {
         let m = numer / denom;
         let c = mean_y - (m * mean_x);
 
-        LinearRegressionModel::new(m, c)
+        Ok(LinearRegressionModel::new(m, c))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct LinearRegressionModel {
     m: f64,
     c: f64,
@@ -35,7 +51,17 @@ impl LinearRegressionModel {
     pub fn new(m: f64, c: f64) -> Self {
         LinearRegressionModel { m, c }
     }
-    pub fn predict(&self, x: ArrayView1<f64>) -> Array1<f64> {
+}
+
+impl Predictor<ArrayView1<'_, f64>, Array1<f64>> for LinearRegressionModel {
+    fn predict(&self, x: ArrayView1<f64>) -> Array1<f64> {
         x.mapv(|x| self.m * x + self.c)
     }
-}
\ No newline at end of file
+}
+
+/// The coefficient of determination R² of the predictions.
+impl Score<ArrayView1<'_, f64>, f64> for LinearRegressionModel {
+    fn score(&self, x: ArrayView1<f64>, y: ArrayView1<f64>) -> f64 {
+        r2(y, self.predict(x).view(), None)
+    }
+}
@@ -1,29 +1,78 @@
-use ndarray::{ArrayView1, ArrayView2, Array1, Axis, stack, Array2};
+use std::time::Instant;
+
+use ndarray::{ArrayView1, ArrayView2, Array1};
 use log::*;
+use rune_core::callback::{Callbacks, FitCallback};
+use rune_core::validation::{check_finite, check_x_y};
+use rune_core::estimator::{Fit, Predictor, Score};
+use rune_core::Result;
+use rune_metrics::regression::r2::r2;
+use serde::{Deserialize, Serialize};
+
+use crate::linear_model::{standardize, unscale, LinearOptions};
 
 #[derive(Debug)]
 pub struct MultipleLinearRegression {
     alpha: f64,
     iterations: usize,
+    options: LinearOptions,
+    callbacks: Callbacks,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MultipleLinearRegressionModel {
-    beta: Array1<f64>
+    coef: Array1<f64>,
+    intercept: f64,
 }
 
 impl MultipleLinearRegressionModel {
-    pub fn new(beta: Array1<f64>) -> Self {
-        MultipleLinearRegressionModel { beta }
+    pub fn new(coef: Array1<f64>, intercept: f64) -> Self {
+        MultipleLinearRegressionModel { coef, intercept }
+    }
+
+    /// The weight of every feature.
+    pub fn coef(&self) -> ArrayView1<'_, f64> {
+        self.coef.view()
+    }
+
+    pub fn intercept(&self) -> f64 {
+        self.intercept
+    }
+}
+
+impl Predictor<ArrayView2<'_, f64>, Array1<f64>> for MultipleLinearRegressionModel {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        x.dot(&self.coef) + self.intercept
     }
+}
+
+/// The coefficient of determination R² of the predictions.
+impl Score<ArrayView2<'_, f64>, f64> for MultipleLinearRegressionModel {
+    fn score(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> f64 {
+        r2(y, self.predict(x).view(), None)
+    }
+}
+
+impl Fit<ArrayView2<'_, f64>, MultipleLinearRegressionModel, f64> for MultipleLinearRegression {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<MultipleLinearRegressionModel> {
+        check_x_y(x, y, 1)?;
+        check_finite("y", y)?;
 
-    pub fn predict(&self, x: ArrayView2<f64>) -> Array1<f64> {
-        let m = x.nrows();
-        let x0: Array2<f64> = Array2::ones((m, 1));
+        let standardized = if self.options.normalize { Some(standardize(x, self.options)) } else { None };
+        let x = standardized.as_ref().map_or(x.reborrow(), |(_, x)| x.view());
 
-        let x_with_static_coefficient = stack(Axis(1), &[x0.view(), x.view()]).unwrap();
+        let initial_cost = self.cost(x, y, Array1::zeros(x.ncols()).view(), 0.);
+        debug!("initial_cost: {:#?}", initial_cost);
+
+        let (coef, intercept) = self.gradient_descent(x, y);
 
-        x_with_static_coefficient.dot(&self.beta)
+        Ok(match standardized {
+            Some((scaler, _)) => {
+                let (coef, intercept) = unscale(&scaler, coef.view(), intercept);
+                MultipleLinearRegressionModel::new(coef, intercept)
+            }
+            None => MultipleLinearRegressionModel::new(coef, intercept),
+        })
     }
 }
 
@@ -32,53 +81,123 @@ impl MultipleLinearRegression {
         MultipleLinearRegression {
             alpha,
             iterations,
+            options: LinearOptions::new(),
+            callbacks: Callbacks::new(),
         }
     }
 
-    pub fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> MultipleLinearRegressionModel {
-        let number_of_rows = x.nrows();
-        let x0: Array2<f64> = Array2::ones((number_of_rows, 1));
-
-        let x_with_static_coefficient = stack(Axis(1), &[x0.view(), x.view()]).unwrap();
-
-        // # Initial Coefficients
-        let beta: Array1<f64> = Array1::zeros(x.ncols() + 1);
-
-        let initial_cost = self.cost(x_with_static_coefficient.view(), y, beta.view());
-        debug!("initial_cost: {:#?}", initial_cost);
-
-        let beta = self.gradient_descent(x_with_static_coefficient.view(), y, beta.view());
+    pub fn options(mut self, options: LinearOptions) -> Self {
+        self.options = options;
+        self
+    }
 
-        MultipleLinearRegressionModel::new(beta)
+    /// Calls `callback` after every gradient descent step with the cost on the training data.
+    pub fn with_callback<C: FitCallback + 'static>(mut self, callback: C) -> Self {
+        self.callbacks.push(callback);
+        self
     }
 
-    fn gradient_descent(&self, x: ArrayView2<f64>, y: ArrayView1<f64>, beta: ArrayView1<f64>) -> Array1<f64> {
-        let m = y.len();
+    fn gradient_descent(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> (Array1<f64>, f64) {
+        let m = y.len() as f64;
+        let start = Instant::now();
 
-        let mut beta = beta.to_owned();
+        let mut coef: Array1<f64> = Array1::zeros(x.ncols());
+        let mut intercept = 0.;
 
         for iteration in 0..self.iterations {
-            let h = x.dot(&beta);
+            let h = x.dot(&coef) + intercept;
             trace!("[{:?}] - h: {:#?}", iteration, h);
 
             let loss = h - y;
             trace!("[{:?}] - loss: {:#?}", iteration, loss);
 
-            let gradient = x.t().dot(&loss) / (m as f64);
+            let gradient = x.t().dot(&loss) / m;
             trace!("[{:?}] - gradient: {:#?}", iteration, gradient);
 
-            beta = beta.to_owned() - self.alpha * gradient;
-            trace!("[{:?}] - beta: {:#?}", iteration, beta);
+            coef.scaled_add(-self.alpha, &gradient);
+            if self.options.fit_intercept {
+                intercept -= self.alpha * loss.sum() / m;
+            }
+            trace!("[{:?}] - coef: {:#?}, intercept: {:?}", iteration, coef, intercept);
 
-            let cost = self.cost(x, y, beta.view());
-            debug!("[{:?}] - cost: {:#?}", iteration, cost)
+            let cost = self.cost(x, y, coef.view(), intercept);
+            debug!("[{:?}] - cost: {:#?}", iteration, cost);
+            self.callbacks.on_epoch(iteration, cost, start.elapsed());
         }
 
-        return beta;
+        (coef, intercept)
     }
 
-    pub fn cost(&self, x: ArrayView2<f64>, y: ArrayView1<f64>, beta: ArrayView1<f64>) -> f64 {
+    /// Half the mean squared error of the model `x · coef + intercept`.
+    pub fn cost(&self, x: ArrayView2<f64>, y: ArrayView1<f64>, coef: ArrayView1<f64>, intercept: f64) -> f64 {
         let m = y.len();
-        (x.dot(&beta) - y).mapv(|a| a.powi(2)).sum() / (2 * m) as f64
+        (x.dot(&coef) + intercept - y).mapv(|a| a.powi(2)).sum() / (2 * m) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, Array1, Array2};
+    use rune_core::estimator::{Fit, Predictor};
+    use rune_core::math::linalg::cholesky_solve;
+
+    use crate::linear_model::LinearOptions;
+    use crate::multiple_linear_regression::{MultipleLinearRegression, MultipleLinearRegressionModel};
+
+    /// Features a thousand times apart in scale, on which plain gradient descent diverges at
+    /// this learning rate.
+    fn data() -> (Array2<f64>, Array1<f64>) {
+        let x = Array2::from_shape_fn((50, 2), |(i, j)| if j == 0 { (i % 10) as f64 * 100. } else { (i / 10) as f64 / 10. });
+        let y = x.outer_iter().map(|row| 0.02 * row[0] - 3. * row[1] + 4.).collect();
+        (x, y)
+    }
+
+    #[test]
+    fn test_normalized_coefficients_are_in_original_units() {
+        let (x, y) = data();
+        let model = MultipleLinearRegression::new(0.1, 2000)
+            .options(LinearOptions::new().normalize(true))
+            .fit(x.view(), y.view())
+            .unwrap();
+
+        assert!((model.coef()[0] - 0.02).abs() < 1e-6);
+        assert!((model.coef()[1] + 3.).abs() < 1e-4);
+        assert!((model.intercept() - 4.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_without_an_intercept_fits_least_squares_through_the_origin() {
+        let x = Array2::from_shape_fn((20, 2), |(i, j)| ((i * (j + 2)) % 7) as f64 / 7. + j as f64 / 2.);
+        let y: Array1<f64> = x.outer_iter().map(|row| row[0] - 2. * row[1] + 1.).collect();
+
+        let model = MultipleLinearRegression::new(0.5, 20000)
+            .options(LinearOptions::new().fit_intercept(false))
+            .fit(x.view(), y.view())
+            .unwrap();
+        let expected = cholesky_solve(x.t().dot(&x).view(), x.t().dot(&y).view()).unwrap();
+
+        assert_eq!(model.intercept(), 0.);
+        for (coef, expected) in model.coef().iter().zip(expected.iter()) {
+            assert!((coef - expected).abs() < 1e-6, "{} instead of {}", coef, expected);
+        }
+    }
+
+    #[test]
+    fn test_model_serializes_coef_and_intercept() {
+        let model = MultipleLinearRegressionModel::new(arr1(&[1.5, -2.]), 0.25);
+
+        let json = serde_json::to_value(&model).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "coef": { "v": 1, "dim": [2], "data": [1.5, -2.0] },
+            "intercept": 0.25,
+        }));
+
+        let loaded: MultipleLinearRegressionModel = serde_json::from_value(json).unwrap();
+        let x = Array2::from_shape_vec((1, 2), vec![2., 1.]).unwrap();
+        assert_eq!(loaded.predict(x.view()), model.predict(x.view()));
+
+        // Models saved with the intercept stacked into `beta` have to be retrained.
+        let old = serde_json::json!({ "beta": { "v": 1, "dim": [3], "data": [0.25, 1.5, -2.0] } });
+        assert!(serde_json::from_value::<MultipleLinearRegressionModel>(old).is_err());
     }
 }
@@ -0,0 +1,283 @@
+use log::*;
+use ndarray::{Array1, ArrayView1, ArrayView2};
+use rune_core::estimator::Fit;
+use rune_core::math::linalg::{cholesky_solve, symmetric_eigen};
+use rune_core::validation::{check_finite, check_x_y};
+use rune_core::{Error, Result};
+use rune_metrics::regression::mean_squared_error::mean_squared_error;
+use rune_model_selection::cross_validation::cross_val_score;
+use rune_model_selection::splitting::leave_one_out::LeaveOneOut;
+use rune_model_selection::splitting::CrossValidator;
+
+use crate::linear_model::{Centred, LinearModel, LinearModelCV, LinearOptions};
+
+/// Least squares with the L2 penalty `alpha ||w||²` on the coefficients, solved exactly from the
+/// regularized normal equations. The intercept isn't penalized.
+#[derive(Debug, Clone)]
+pub struct Ridge {
+    alpha: f64,
+    options: LinearOptions,
+}
+
+/// `Ridge` with `alpha` picked among candidates by leave-one-out cross-validation. The
+/// leave-one-out errors of every candidate follow from one eigendecomposition of the Gram matrix
+/// of the features, so trying many costs little more than fitting one. Given a splitter with
+/// `cv`, every candidate is instead refitted on its folds by `cross_val_score`.
+#[derive(Debug, Clone)]
+pub struct RidgeCV<C = LeaveOneOut> {
+    alphas: Vec<f64>,
+    options: LinearOptions,
+    cv: Option<C>,
+}
+
+impl Fit<ArrayView2<'_, f64>, LinearModel, f64> for Ridge {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<LinearModel> {
+        check_x_y(x, y, 1)?;
+        check_finite("y", y)?;
+        if !(self.alpha >= 0. && self.alpha.is_finite()) {
+            return Err(Error::InvalidParameter(format!("alpha must be non-negative, got {}", self.alpha)));
+        }
+
+        let centred = Centred::new(x, y, self.options);
+        let mut gram = centred.x.t().dot(&centred.x);
+        gram.diag_mut().mapv_inplace(|v| v + self.alpha);
+
+        let coef = cholesky_solve(gram.view(), centred.x.t().dot(&centred.y).view())
+            .ok_or_else(|| Error::LinearAlgebra("the features are collinear, so alpha must be positive".to_owned()))?;
+
+        Ok(centred.model(coef))
+    }
+}
+
+impl<C: CrossValidator<f64> + Sync> Fit<ArrayView2<'_, f64>, LinearModelCV, f64> for RidgeCV<C> {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<LinearModelCV> {
+        check_x_y(x, y, 2)?;
+        check_finite("y", y)?;
+        if self.alphas.is_empty() || self.alphas.iter().any(|&alpha| !(alpha > 0. && alpha.is_finite())) {
+            return Err(Error::InvalidParameter(format!("alphas must be positive and there must be at least one, got {:?}", self.alphas)));
+        }
+
+        let cv = match &self.cv {
+            Some(cv) => cv,
+            None => return self.fit_leave_one_out(x, y),
+        };
+
+        let negative_mse = |y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>| -mean_squared_error(y_true, y_pred);
+        let cv_results: Vec<(f64, f64)> = self.alphas.iter()
+            .map(|&alpha| {
+                let scores = cross_val_score(&Ridge::new(alpha).options(self.options), x, y, cv, negative_mse, 1)?;
+                let error = -scores.mean().unwrap_or(f64::NAN);
+                debug!("alpha {}: cross-validated mean squared error {}", alpha, error);
+                Ok((alpha, error))
+            })
+            .collect::<Result<_>>()?;
+
+        let alpha = best_alpha(&cv_results)?;
+        info!("Picked alpha {}", alpha);
+
+        let model = Ridge::new(alpha).options(self.options).fit(x, y)?;
+        Ok(LinearModelCV::new(model, alpha, cv_results))
+    }
+}
+
+impl Ridge {
+    pub fn new(alpha: f64) -> Self {
+        Ridge { alpha, options: LinearOptions::new() }
+    }
+
+    pub fn options(mut self, options: LinearOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl RidgeCV {
+    pub fn new(alphas: Vec<f64>) -> Self {
+        RidgeCV { alphas, options: LinearOptions::new(), cv: None }
+    }
+}
+
+impl<C> RidgeCV<C> {
+    pub fn options(mut self, options: LinearOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Cross-validates every candidate on the folds of `cv`, e.g. a `KFold` or a `GroupKFold`,
+    /// instead of leave-one-out in closed form.
+    pub fn cv<D>(self, cv: D) -> RidgeCV<D> {
+        RidgeCV { alphas: self.alphas, options: self.options, cv: Some(cv) }
+    }
+
+    /// The leave-one-out error of every candidate in closed form, and the model for the best.
+    fn fit_leave_one_out(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<LinearModelCV> {
+        let centred = Centred::new(x, y, self.options);
+        let n = x.nrows() as f64;
+        let intercept_leverage = if self.options.fit_intercept { 1. / n } else { 0. };
+
+        // With xᵀx = v diag(s) vᵀ, the coefficients for alpha are v diag(1 / (s + alpha)) vᵀ xᵀy
+        // and the leverage of row i is 1/n for the intercept, if any, plus
+        // Σ_j (x v)_ij² / (s_j + alpha).
+        let (eigenvalues, eigenvectors) = symmetric_eigen(centred.x.t().dot(&centred.x).view());
+        let projected = centred.x.dot(&eigenvectors);
+        let squared = projected.mapv(|v| v * v);
+        let rotated = eigenvectors.t().dot(&centred.x.t().dot(&centred.y));
+        let shrinkage = |alpha: f64| eigenvalues.mapv(|s| 1. / (s.max(0.) + alpha));
+
+        let cv_results: Vec<(f64, f64)> = self.alphas.iter()
+            .map(|&alpha| {
+                let shrinkage = shrinkage(alpha);
+                let fitted = projected.dot(&(&rotated * &shrinkage));
+                let leverage = squared.dot(&shrinkage) + intercept_leverage;
+
+                let error = (0..centred.y.len())
+                    .map(|row| ((centred.y[row] - fitted[row]) / (1. - leverage[row])).powi(2))
+                    .sum::<f64>() / n;
+                debug!("alpha {}: leave-one-out mean squared error {}", alpha, error);
+
+                (alpha, error)
+            })
+            .collect();
+
+        let alpha = best_alpha(&cv_results)?;
+        info!("Picked alpha {}", alpha);
+
+        let coef: Array1<f64> = eigenvectors.dot(&(&rotated * &shrinkage(alpha)));
+        Ok(LinearModelCV::new(centred.model(coef), alpha, cv_results))
+    }
+}
+
+/// The alpha with the least error, the first of equals.
+pub(crate) fn best_alpha(cv_results: &[(f64, f64)]) -> Result<f64> {
+    cv_results.iter()
+        .filter(|(_, error)| !error.is_nan())
+        .fold(None, |best: Option<&(f64, f64)>, candidate| match best {
+            Some(best) if best.1 <= candidate.1 => Some(best),
+            _ => Some(candidate),
+        })
+        .map(|&(alpha, _)| alpha)
+        .ok_or_else(|| Error::InvalidInput("no alpha produced a finite error".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{Array1, Array2, Axis};
+    use rune_core::estimator::{Fit, Predictor};
+    use rune_core::math::linalg::cholesky_solve;
+    use rune_model_selection::splitting::k_fold::KFold;
+
+    use crate::linear_model::LinearOptions;
+    use crate::ridge::{Ridge, RidgeCV};
+
+    fn data() -> (Array2<f64>, Array1<f64>) {
+        let x = Array2::from_shape_fn((30, 3), |(i, j)| ((i * (j + 3) * 7) % 17) as f64 / 4. + j as f64);
+        let y = x.outer_iter().enumerate()
+            .map(|(i, row)| 1.5 * row[0] - 2. * row[1] + 0.5 * row[2] + 3. + ((i * 37) % 11) as f64 / 11. - 0.5)
+            .collect();
+        (x, y)
+    }
+
+    #[test]
+    fn test_ridge_without_penalty_is_least_squares() {
+        let (x, y) = data();
+        let model = Ridge::new(0.).fit(x.view(), y.view()).unwrap();
+
+        // The least squares residuals are orthogonal to every feature and to the intercept.
+        let residuals = &y - &model.predict(x.view());
+        assert!(residuals.sum().abs() < 1e-9);
+        for gradient in x.t().dot(&residuals).iter() {
+            assert!(gradient.abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_ridge_shrinks_the_coefficients() {
+        let (x, y) = data();
+        let norm = |alpha| Ridge::new(alpha).fit(x.view(), y.view()).unwrap().coef().mapv(|c| c * c).sum();
+
+        assert!(norm(0.) > norm(10.));
+        assert!(norm(10.) > norm(1000.));
+    }
+
+    #[test]
+    fn test_ridge_cv_leave_one_out_errors_match_refitting() {
+        let (x, y) = data();
+        let alphas = vec![0.01, 1., 30.];
+
+        for &fit_intercept in [true, false].iter() {
+            let options = LinearOptions::new().fit_intercept(fit_intercept);
+            let model = RidgeCV::new(alphas.clone()).options(options).fit(x.view(), y.view()).unwrap();
+
+            for &(alpha, error) in model.cv_results() {
+                let brute_force = (0..x.nrows())
+                    .map(|left_out| {
+                        let train: Vec<usize> = (0..x.nrows()).filter(|&row| row != left_out).collect();
+                        let ridge = Ridge::new(alpha).options(options)
+                            .fit(x.select(Axis(0), &train).view(), y.select(Axis(0), &train).view())
+                            .unwrap();
+                        (y[left_out] - ridge.predict(x.select(Axis(0), &[left_out]).view())[0]).powi(2)
+                    })
+                    .sum::<f64>() / x.nrows() as f64;
+
+                assert!((error - brute_force).abs() < 1e-9 * brute_force, "alpha {}: {} in closed form, {} refitting", alpha, error, brute_force);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ridge_cv_on_folds_matches_refitting() {
+        let (x, y) = data();
+        let alphas = vec![0.01, 1., 30.];
+        let model = RidgeCV::new(alphas).cv(KFold::new(5)).fit(x.view(), y.view()).unwrap();
+
+        for &(alpha, error) in model.cv_results() {
+            let brute_force = KFold::new(5).split(x.nrows()).unwrap().iter()
+                .map(|(train, test)| {
+                    let ridge = Ridge::new(alpha).fit(x.select(Axis(0), train).view(), y.select(Axis(0), train).view()).unwrap();
+                    let residuals = &y.select(Axis(0), test) - &ridge.predict(x.select(Axis(0), test).view());
+                    residuals.mapv(|r| r * r).mean().unwrap()
+                })
+                .sum::<f64>() / 5.;
+
+            assert!((error - brute_force).abs() < 1e-9 * brute_force, "alpha {}: {} cross-validated, {} refitting", alpha, error, brute_force);
+        }
+        let best = model.cv_results().iter().fold(f64::INFINITY, |best, &(_, error)| best.min(error));
+        assert_eq!(model.cv_results().iter().find(|&&(_, error)| error == best).unwrap().0, model.alpha());
+    }
+
+    #[test]
+    fn test_normalized_coefficients_are_in_original_units() {
+        let (x, y) = data();
+        let normalized = LinearOptions::new().normalize(true);
+
+        // Without a penalty, standardizing changes nothing.
+        let plain = Ridge::new(0.).fit(x.view(), y.view()).unwrap();
+        let model = Ridge::new(0.).options(normalized).fit(x.view(), y.view()).unwrap();
+        for (plain, normalized) in plain.coef().iter().zip(model.coef().iter()) {
+            assert!((plain - normalized).abs() < 1e-9);
+        }
+        assert!((plain.intercept() - model.intercept()).abs() < 1e-9);
+
+        // With one, a feature in a thousandth of the units gets a thousand times the coefficient.
+        let model = Ridge::new(5.).options(normalized).fit(x.view(), y.view()).unwrap();
+        let mut rescaled = x.clone();
+        rescaled.column_mut(1).mapv_inplace(|v| v / 1000.);
+        let rescaled = Ridge::new(5.).options(normalized).fit(rescaled.view(), y.view()).unwrap();
+
+        assert!((rescaled.coef()[1] - 1000. * model.coef()[1]).abs() < 1e-6);
+        assert!((rescaled.coef()[0] - model.coef()[0]).abs() < 1e-9);
+        assert!((rescaled.intercept() - model.intercept()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_without_an_intercept_fits_least_squares_through_the_origin() {
+        let (x, y) = data();
+        let model = Ridge::new(0.).options(LinearOptions::new().fit_intercept(false)).fit(x.view(), y.view()).unwrap();
+        let expected = cholesky_solve(x.t().dot(&x).view(), x.t().dot(&y).view()).unwrap();
+
+        assert_eq!(model.intercept(), 0.);
+        for (coef, expected) in model.coef().iter().zip(expected.iter()) {
+            assert!((coef - expected).abs() < 1e-9);
+        }
+    }
+}
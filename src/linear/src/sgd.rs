@@ -0,0 +1,644 @@
+use std::time::Instant;
+
+use log::*;
+use ndarray::{Array1, ArrayView1, ArrayView2, CowArray, Ix2};
+use rand::seq::SliceRandom;
+use rune_core::callback::{Callbacks, FitCallback};
+use rune_core::cost_matrix::CostMatrix;
+use rune_core::estimator::{Fit, IncrementalFit, Predictor, Score};
+use rune_core::math::special::{sigmoid, softplus};
+use rune_core::random::RngSource;
+use rune_core::validation::{check_finite, check_x_y};
+use rune_core::{Error, Result};
+use rune_metrics::metrics::accuracy_score;
+use rune_metrics::regression::r2::r2;
+use rune_preprocessing::standard_scaler::StandardScalerTransformer;
+use serde::{Deserialize, Serialize};
+
+use crate::linear_model::{standardize, unscale, LinearOptions};
+
+/// What stochastic gradient descent minimizes for each row, given the prediction `p` and the
+/// target `y`, which is -1 or 1 for classifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SgdLoss {
+    /// `max(0, 1 - y p)`, a linear SVM. Classifiers only.
+    Hinge,
+    /// `ln(1 + exp(-y p))`, logistic regression, so classifiers can estimate probabilities.
+    /// Classifiers only.
+    Log,
+    /// `(p - y)² / 2`, ordinary least squares.
+    Squared,
+    /// Squared within `epsilon` of the target and linear beyond, so outliers pull less.
+    Huber { epsilon: f64 },
+    /// `max(0, |p - y| - epsilon)`, a linear support vector regression.
+    EpsilonInsensitive { epsilon: f64 },
+}
+
+/// The regularization added to the loss, scaled by `alpha`. The intercept is never penalized.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Penalty {
+    None,
+    /// `||w||² / 2`, shrinking every coefficient.
+    L2,
+    /// `||w||₁`, driving coefficients to exactly zero.
+    L1,
+    /// `l1_ratio ||w||₁ + (1 - l1_ratio) ||w||² / 2`.
+    ElasticNet { l1_ratio: f64 },
+}
+
+/// How `SGDClassifier` and `SGDRegressor` train: the penalty, the learning rate schedule and the
+/// epochs. Defaults to an L2 penalty with `alpha` 1e-4, 20 shuffled epochs and a learning rate
+/// of `0.01 / t^0.25` at the `t`th update.
+#[derive(Debug, Clone)]
+pub struct SgdOptions {
+    penalty: Penalty,
+    alpha: f64,
+    eta0: f64,
+    power_t: f64,
+    n_epochs: usize,
+    rng: RngSource,
+    callbacks: Callbacks,
+}
+
+/// The settings and training loop shared by `SGDClassifier` and `SGDRegressor`.
+#[derive(Debug, Clone)]
+struct Sgd {
+    loss: SgdLoss,
+    options: SgdOptions,
+    linear: LinearOptions,
+}
+
+/// The weights learnt by `Sgd`, and how many updates were made, so that `partial_fit` carries on
+/// the learning rate schedule where the last fit left it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SgdWeights {
+    coef: Array1<f64>,
+    intercept: f64,
+    t: usize,
+    /// The scaling of the features the weights apply to, when fitted with `normalize`.
+    #[serde(default)]
+    scaler: Option<StandardScalerTransformer>,
+    /// The L1 penalty every coefficient could have been shrunk by so far, and how far each has
+    /// been, for the cumulative L1 penalty.
+    #[serde(default)]
+    l1_total: f64,
+    #[serde(default)]
+    l1_applied: Array1<f64>,
+}
+
+/// A linear classifier of `bool` labels fitted by stochastic gradient descent, one row at a time,
+/// on any of the `SgdLoss`es: a linear SVM with the hinge loss, logistic regression with the log
+/// loss. Updates are cheap enough for data far larger than fits in memory, streamed through
+/// `partial_fit`.
+#[derive(Debug, Clone)]
+pub struct SGDClassifier {
+    sgd: Sgd,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SGDClassifierModel {
+    loss: SgdLoss,
+    weights: SgdWeights,
+}
+
+/// A linear regressor fitted by stochastic gradient descent with the squared, Huber or epsilon
+/// insensitive loss. See `SGDClassifier`.
+#[derive(Debug, Clone)]
+pub struct SGDRegressor {
+    sgd: Sgd,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SGDRegressorModel {
+    weights: SgdWeights,
+}
+
+impl Fit<ArrayView2<'_, f64>, SGDClassifierModel> for SGDClassifier {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<bool>) -> Result<SGDClassifierModel> {
+        check_x_y(x, y, 1)?;
+        self.sgd.check_params()?;
+
+        let targets = y.mapv(|label| if label { 1. } else { -1. });
+        Ok(SGDClassifierModel {
+            loss: self.sgd.loss,
+            weights: self.sgd.fit_internal(x, targets.view()),
+        })
+    }
+}
+
+/// Makes one unshuffled pass over the rows, carrying on from the model's weights.
+impl IncrementalFit<ArrayView2<'_, f64>, SGDClassifierModel> for SGDClassifier {
+    fn partial_fit(&self, model: &mut SGDClassifierModel, x: ArrayView2<f64>, y: ArrayView1<bool>) -> Result<()> {
+        check_x_y(x, y, 1)?;
+        self.sgd.check_params()?;
+        check_n_features(&model.weights, x)?;
+
+        let targets = y.mapv(|label| if label { 1. } else { -1. });
+        let x = model.weights.input(x);
+        self.sgd.epoch(&mut model.weights, x.view(), targets.view(), &(0..x.nrows()).collect::<Vec<_>>());
+        Ok(())
+    }
+}
+
+impl Predictor<ArrayView2<'_, f64>, Array1<bool>> for SGDClassifierModel {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<bool> {
+        self.decision_function(x).mapv(|score| score > 0.)
+    }
+}
+
+/// The fraction of rows whose class is predicted correctly.
+impl Score<ArrayView2<'_, f64>, bool> for SGDClassifierModel {
+    fn score(&self, x: ArrayView2<f64>, y: ArrayView1<bool>) -> f64 {
+        accuracy_score(y, self.predict(x).view(), None)
+    }
+}
+
+impl Fit<ArrayView2<'_, f64>, SGDRegressorModel, f64> for SGDRegressor {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<SGDRegressorModel> {
+        check_x_y(x, y, 1)?;
+        check_finite("y", y)?;
+        self.check_params()?;
+
+        Ok(SGDRegressorModel {
+            weights: self.sgd.fit_internal(x, y),
+        })
+    }
+}
+
+/// Makes one unshuffled pass over the rows, carrying on from the model's weights.
+impl IncrementalFit<ArrayView2<'_, f64>, SGDRegressorModel, f64> for SGDRegressor {
+    fn partial_fit(&self, model: &mut SGDRegressorModel, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<()> {
+        check_x_y(x, y, 1)?;
+        check_finite("y", y)?;
+        self.check_params()?;
+        check_n_features(&model.weights, x)?;
+
+        let x = model.weights.input(x);
+        self.sgd.epoch(&mut model.weights, x.view(), y, &(0..x.nrows()).collect::<Vec<_>>());
+        Ok(())
+    }
+}
+
+impl Predictor<ArrayView2<'_, f64>, Array1<f64>> for SGDRegressorModel {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        self.weights.predict(x)
+    }
+}
+
+/// The coefficient of determination R² of the predictions.
+impl Score<ArrayView2<'_, f64>, f64> for SGDRegressorModel {
+    fn score(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> f64 {
+        r2(y, self.predict(x).view(), None)
+    }
+}
+
+impl SGDClassifier {
+    /// Defaults to the default `SgdOptions` and `LinearOptions`.
+    pub fn new(loss: SgdLoss) -> Self {
+        SGDClassifier { sgd: Sgd::new(loss) }
+    }
+
+    pub fn sgd_options(mut self, options: SgdOptions) -> Self {
+        self.sgd.options = options;
+        self
+    }
+
+    pub fn options(mut self, options: LinearOptions) -> Self {
+        self.sgd.linear = options;
+        self
+    }
+}
+
+impl SGDRegressor {
+    /// Defaults as for `SGDClassifier`.
+    pub fn new(loss: SgdLoss) -> Self {
+        SGDRegressor { sgd: Sgd::new(loss) }
+    }
+
+    pub fn sgd_options(mut self, options: SgdOptions) -> Self {
+        self.sgd.options = options;
+        self
+    }
+
+    pub fn options(mut self, options: LinearOptions) -> Self {
+        self.sgd.linear = options;
+        self
+    }
+
+    fn check_params(&self) -> Result<()> {
+        if let SgdLoss::Hinge | SgdLoss::Log = self.sgd.loss {
+            return Err(Error::InvalidParameter(format!("{:?} is a classification loss", self.sgd.loss)));
+        }
+        self.sgd.check_params()
+    }
+}
+
+impl SGDClassifierModel {
+    /// The signed distance of every row from the separating hyperplane, positive on the side of
+    /// the positive class.
+    pub fn decision_function(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        self.weights.predict(x)
+    }
+
+    /// The probability of the positive class. Only the log loss estimates probabilities, so
+    /// this fails for models fitted with any other.
+    pub fn predict_proba(&self, x: ArrayView2<f64>) -> Result<Array1<f64>> {
+        if self.loss != SgdLoss::Log {
+            return Err(Error::InvalidParameter(format!("only the log loss estimates probabilities, the model was fitted with {:?}", self.loss)));
+        }
+
+        Ok(self.decision_function(x).mapv(sigmoid))
+    }
+
+    /// Predicts the class with the least expected cost under the binary `costs`, class 0 being
+    /// `false`, from the probabilities of `predict_proba`, failing as it does.
+    pub fn predict_with_costs(&self, x: ArrayView2<f64>, costs: &CostMatrix) -> Result<Array1<bool>> {
+        let threshold = costs.binary_threshold();
+        Ok(self.predict_proba(x)?.mapv(|p| p >= threshold))
+    }
+
+    pub fn coef(&self) -> Array1<f64> {
+        self.weights.unscaled().0
+    }
+
+    pub fn intercept(&self) -> f64 {
+        self.weights.unscaled().1
+    }
+}
+
+impl SGDRegressorModel {
+    pub fn coef(&self) -> Array1<f64> {
+        self.weights.unscaled().0
+    }
+
+    pub fn intercept(&self) -> f64 {
+        self.weights.unscaled().1
+    }
+}
+
+impl SgdOptions {
+    pub fn new() -> Self {
+        SgdOptions {
+            penalty: Penalty::L2,
+            alpha: 1e-4,
+            eta0: 0.01,
+            power_t: 0.25,
+            n_epochs: 20,
+            rng: RngSource::Entropy,
+            callbacks: Callbacks::new(),
+        }
+    }
+
+    pub fn penalty(mut self, penalty: Penalty, alpha: f64) -> Self {
+        self.penalty = penalty;
+        self.alpha = alpha;
+        self
+    }
+
+    /// The learning rate at the `t`th update is `eta0 / t^power_t`; a `power_t` of 0 keeps it
+    /// constant.
+    pub fn learning_rate(mut self, eta0: f64, power_t: f64) -> Self {
+        self.eta0 = eta0;
+        self.power_t = power_t;
+        self
+    }
+
+    /// Passes over the training data made by `fit`.
+    pub fn n_epochs(mut self, n_epochs: usize) -> Self {
+        self.n_epochs = n_epochs;
+        self
+    }
+
+    /// Where the order the rows are visited in every epoch of `fit` is drawn from.
+    pub fn shuffle(mut self, rng: RngSource) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// Calls `callback` after every epoch with the mean loss of the rows over it.
+    pub fn with_callback<C: FitCallback + 'static>(mut self, callback: C) -> Self {
+        self.callbacks.push(callback);
+        self
+    }
+}
+
+impl Default for SgdOptions {
+    fn default() -> Self {
+        SgdOptions::new()
+    }
+}
+
+impl Sgd {
+    fn new(loss: SgdLoss) -> Self {
+        Sgd {
+            loss,
+            options: SgdOptions::new(),
+            linear: LinearOptions::new(),
+        }
+    }
+
+    fn check_params(&self) -> Result<()> {
+        let SgdOptions { penalty, alpha, eta0, power_t, .. } = self.options;
+        if !(alpha >= 0. && alpha.is_finite()) {
+            return Err(Error::InvalidParameter(format!("alpha must be non-negative, got {}", alpha)));
+        }
+        if !(eta0 > 0. && eta0.is_finite()) {
+            return Err(Error::InvalidParameter(format!("eta0 must be positive, got {}", eta0)));
+        }
+        if !(power_t >= 0. && power_t.is_finite()) {
+            return Err(Error::InvalidParameter(format!("power_t must be non-negative, got {}", power_t)));
+        }
+        if let Penalty::ElasticNet { l1_ratio } = penalty {
+            if !(0. ..=1.).contains(&l1_ratio) {
+                return Err(Error::InvalidParameter(format!("l1_ratio must be in [0, 1], got {}", l1_ratio)));
+            }
+        }
+        if let SgdLoss::Huber { epsilon } | SgdLoss::EpsilonInsensitive { epsilon } = self.loss {
+            if !(epsilon >= 0. && epsilon.is_finite()) {
+                return Err(Error::InvalidParameter(format!("epsilon must be non-negative, got {}", epsilon)));
+            }
+        }
+        Ok(())
+    }
+
+    fn fit_internal(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> SgdWeights {
+        let mut weights = SgdWeights {
+            coef: Array1::zeros(x.ncols()),
+            intercept: 0.,
+            t: 0,
+            scaler: if self.linear.normalize { Some(standardize(x, self.linear).0) } else { None },
+            l1_total: 0.,
+            l1_applied: Array1::zeros(x.ncols()),
+        };
+        let x = weights.input(x);
+        let mut rows: Vec<usize> = (0..x.nrows()).collect();
+        let mut rng = self.options.rng.rng();
+        let start = Instant::now();
+
+        for epoch in 0..self.options.n_epochs {
+            rows.shuffle(&mut rng);
+            let loss = self.epoch(&mut weights, x.view(), y, &rows);
+
+            debug!("[{}] - mean loss: {:.6}", epoch, loss);
+            self.options.callbacks.on_epoch(epoch, loss, start.elapsed());
+        }
+
+        weights
+    }
+
+    /// Updates `weights` on every row of `rows` in turn, returning the mean loss of the rows,
+    /// each taken just before its update.
+    fn epoch(&self, weights: &mut SgdWeights, x: ArrayView2<f64>, y: ArrayView1<f64>, rows: &[usize]) -> f64 {
+        let SgdOptions { penalty, alpha, eta0, power_t, .. } = self.options;
+        let (l1, l2) = match penalty {
+            Penalty::None => (0., 0.),
+            Penalty::L2 => (0., alpha),
+            Penalty::L1 => (alpha, 0.),
+            Penalty::ElasticNet { l1_ratio } => (alpha * l1_ratio, alpha * (1. - l1_ratio)),
+        };
+
+        if weights.l1_applied.len() != weights.coef.len() {
+            weights.l1_applied = Array1::zeros(weights.coef.len());
+        }
+
+        let mut total_loss = 0.;
+        for &row in rows {
+            weights.t += 1;
+            let eta = eta0 / (weights.t as f64).powf(power_t);
+
+            let features = x.row(row);
+            let prediction = features.dot(&weights.coef) + weights.intercept;
+            let (loss, gradient) = self.loss(prediction, y[row]);
+            total_loss += loss;
+
+            // Shrinking before the step is the L2 gradient. The L1 penalty is cumulative
+            // (Tsuruoka et al., 2009): every coefficient is shrunk by all the penalty it has been
+            // spared so far, clipping at zero, so that one the rows don't pull on consistently
+            // stays exactly zero rather than jittering around it with every row's gradient.
+            if l2 > 0. {
+                weights.coef *= 1. - eta * l2;
+            }
+            weights.coef.scaled_add(-eta * gradient, &features);
+            if self.linear.fit_intercept {
+                weights.intercept -= eta * gradient;
+            }
+            if l1 > 0. {
+                weights.l1_total += eta * l1;
+                let total = weights.l1_total;
+                for (w, applied) in weights.coef.iter_mut().zip(weights.l1_applied.iter_mut()) {
+                    let before = *w;
+                    if before > 0. {
+                        *w = (before - (total + *applied)).max(0.);
+                    } else if before < 0. {
+                        *w = (before + (total - *applied)).min(0.);
+                    }
+                    *applied += *w - before;
+                }
+            }
+        }
+
+        total_loss / rows.len() as f64
+    }
+
+    /// The loss at `prediction` and its derivative with respect to it.
+    fn loss(&self, prediction: f64, y: f64) -> (f64, f64) {
+        let residual = prediction - y;
+
+        match self.loss {
+            SgdLoss::Hinge if prediction * y < 1. => (1. - prediction * y, -y),
+            SgdLoss::Hinge => (0., 0.),
+            SgdLoss::Log => (softplus(-prediction * y), -y * sigmoid(-prediction * y)),
+            SgdLoss::Squared => (residual * residual / 2., residual),
+            SgdLoss::Huber { epsilon } if residual.abs() <= epsilon => (residual * residual / 2., residual),
+            SgdLoss::Huber { epsilon } => (epsilon * (residual.abs() - epsilon / 2.), epsilon * residual.signum()),
+            SgdLoss::EpsilonInsensitive { epsilon } if residual.abs() > epsilon => (residual.abs() - epsilon, residual.signum()),
+            SgdLoss::EpsilonInsensitive { .. } => (0., 0.),
+        }
+    }
+}
+
+impl SgdWeights {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        self.input(x).dot(&self.coef) + self.intercept
+    }
+
+    /// `x` as the weights apply to it, standardized if they were fitted so.
+    fn input<'a>(&self, x: ArrayView2<'a, f64>) -> CowArray<'a, f64, Ix2> {
+        match &self.scaler {
+            Some(scaler) => scaler.internal_transform(x).into(),
+            None => x.into(),
+        }
+    }
+
+    /// The coefficients and intercept for the original features.
+    fn unscaled(&self) -> (Array1<f64>, f64) {
+        match &self.scaler {
+            Some(scaler) => unscale(scaler, self.coef.view(), self.intercept),
+            None => (self.coef.clone(), self.intercept),
+        }
+    }
+}
+
+fn check_n_features(weights: &SgdWeights, x: ArrayView2<f64>) -> Result<()> {
+    if weights.coef.len() == x.ncols() {
+        Ok(())
+    } else {
+        Err(Error::ShapeMismatch(format!("model was fitted on {} features, got {}", weights.coef.len(), x.ncols())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{Array1, Array2};
+    use rune_core::cost_matrix::CostMatrix;
+    use rune_core::estimator::{Fit, IncrementalFit, Predictor, Score};
+    use rune_core::random::RngSource;
+    use rune_core::Error;
+
+    use crate::linear_model::LinearOptions;
+    use crate::sgd::{Penalty, SGDClassifier, SGDRegressor, SgdLoss, SgdOptions};
+
+    const PENALTIES: [Penalty; 4] = [Penalty::None, Penalty::L2, Penalty::L1, Penalty::ElasticNet { l1_ratio: 0.5 }];
+
+    /// Features spread over [-1, 1], the third of which the targets don't depend on.
+    fn features() -> Array2<f64> {
+        Array2::from_shape_fn((200, 3), |(i, j)| match j {
+            0 => (i % 20) as f64 / 10. - 0.95,
+            1 => (i / 20) as f64 / 5. - 0.9,
+            _ => ((i * 7) % 13) as f64 / 6. - 1.,
+        })
+    }
+
+    fn targets(x: &Array2<f64>) -> Array1<f64> {
+        x.outer_iter().map(|row| 2. * row[0] - row[1] + 0.5).collect()
+    }
+
+    fn options(seed: u64) -> SgdOptions {
+        SgdOptions::new().learning_rate(0.05, 0.25).n_epochs(50).shuffle(RngSource::Seed(seed))
+    }
+
+    #[test]
+    fn test_classifier_separates_with_every_loss_and_penalty() {
+        let x = features();
+        let y = targets(&x).mapv(|target| target > 0.);
+        let losses = [SgdLoss::Hinge, SgdLoss::Log, SgdLoss::Squared, SgdLoss::Huber { epsilon: 1. }, SgdLoss::EpsilonInsensitive { epsilon: 0.1 }];
+
+        for &loss in losses.iter() {
+            for &penalty in PENALTIES.iter() {
+                let model = SGDClassifier::new(loss)
+                    .sgd_options(options(0).penalty(penalty, 1e-4))
+                    .fit(x.view(), y.view())
+                    .unwrap();
+
+                let accuracy = model.score(x.view(), y.view());
+                assert!(accuracy > 0.9, "{:?} with {:?} has an accuracy of {}", loss, penalty, accuracy);
+            }
+        }
+    }
+
+    #[test]
+    fn test_regressor_fits_with_every_loss_and_penalty() {
+        let x = features();
+        let y = targets(&x);
+        let losses = [SgdLoss::Squared, SgdLoss::Huber { epsilon: 1. }, SgdLoss::EpsilonInsensitive { epsilon: 0.01 }];
+
+        for &loss in losses.iter() {
+            for &penalty in PENALTIES.iter() {
+                let model = SGDRegressor::new(loss)
+                    .sgd_options(options(0).penalty(penalty, 1e-4))
+                    .fit(x.view(), y.view())
+                    .unwrap();
+
+                let r2 = model.score(x.view(), y.view());
+                assert!(r2 > 0.99, "{:?} with {:?} has an R² of {}", loss, penalty, r2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_regressor_rejects_classification_losses() {
+        let x = features();
+        let y = targets(&x);
+
+        for &loss in [SgdLoss::Hinge, SgdLoss::Log].iter() {
+            assert!(matches!(SGDRegressor::new(loss).fit(x.view(), y.view()), Err(Error::InvalidParameter(_))));
+        }
+    }
+
+    #[test]
+    fn test_partial_fit_converges_to_fit() {
+        let x = features();
+        let y = targets(&x);
+        let regressor = SGDRegressor::new(SgdLoss::Squared).sgd_options(options(0).penalty(Penalty::None, 0.).n_epochs(200));
+
+        let fitted = regressor.fit(x.view(), y.view()).unwrap();
+
+        let mut streamed = regressor.clone().sgd_options(options(0).n_epochs(0)).fit(x.view(), y.view()).unwrap();
+        for _ in 0..200 {
+            regressor.partial_fit(&mut streamed, x.view(), y.view()).unwrap();
+        }
+
+        for (fitted, streamed) in fitted.coef().iter().zip(streamed.coef().iter()) {
+            assert!((fitted - streamed).abs() < 1e-3, "{} from fit, {} from partial_fit", fitted, streamed);
+        }
+        assert!((fitted.intercept() - streamed.intercept()).abs() < 1e-3);
+        assert!((fitted.coef()[0] - 2.).abs() < 1e-3);
+        assert!((fitted.intercept() - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_l1_zeroes_uninformative_features() {
+        let x = features();
+        let y = targets(&x);
+
+        let model = SGDRegressor::new(SgdLoss::Squared)
+            .sgd_options(options(0).penalty(Penalty::L1, 0.01))
+            .fit(x.view(), y.view())
+            .unwrap();
+        assert_eq!(model.coef()[2], 0.);
+        assert!(model.coef()[0] > 1.5);
+
+        let model = SGDRegressor::new(SgdLoss::Squared)
+            .sgd_options(options(0).penalty(Penalty::L2, 0.01))
+            .fit(x.view(), y.view())
+            .unwrap();
+        assert_ne!(model.coef()[2], 0.);
+    }
+
+    #[test]
+    fn test_only_the_log_loss_estimates_probabilities() {
+        let x = features();
+        let y = targets(&x).mapv(|target| target > 0.);
+        let costs = CostMatrix::zero_one(2);
+
+        let log = SGDClassifier::new(SgdLoss::Log).sgd_options(options(0)).fit(x.view(), y.view()).unwrap();
+        let probabilities = log.predict_proba(x.view()).unwrap();
+        assert!(probabilities.iter().all(|p| (0. ..=1.).contains(p)));
+        assert_eq!(log.predict_with_costs(x.view(), &costs).unwrap(), log.predict(x.view()));
+
+        let hinge = SGDClassifier::new(SgdLoss::Hinge).sgd_options(options(0)).fit(x.view(), y.view()).unwrap();
+        assert!(matches!(hinge.predict_proba(x.view()), Err(Error::InvalidParameter(_))));
+        assert!(matches!(hinge.predict_with_costs(x.view(), &costs), Err(Error::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_normalized_coefficients_are_in_original_units() {
+        let mut x = features();
+        let y = targets(&x);
+        x.column_mut(0).mapv_inplace(|v| v * 1000.);
+
+        let regressor = SGDRegressor::new(SgdLoss::Squared)
+            .sgd_options(options(0).penalty(Penalty::None, 0.).n_epochs(200))
+            .options(LinearOptions::new().normalize(true));
+        let model = regressor.fit(x.view(), y.view()).unwrap();
+
+        assert!((model.coef()[0] - 0.002).abs() < 1e-6);
+        assert!((model.coef()[1] + 1.).abs() < 1e-3);
+        assert!((model.intercept() - 0.5).abs() < 1e-3);
+
+        // partial_fit carries on with the scaling of the first fit.
+        let mut streamed = model.clone();
+        regressor.partial_fit(&mut streamed, x.view(), y.view()).unwrap();
+        assert!((streamed.coef()[0] - 0.002).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,45 @@
+use ndarray::{Array1, ArrayView1};
+
+/// Bins the predicted probabilities into `n_bins` equal-width bins over `[0, 1]` and returns, for
+/// every non-empty bin, the fraction of positive samples and the mean predicted probability.
+pub fn calibration_curve(y_true: ArrayView1<bool>, y_prob: ArrayView1<f64>, n_bins: usize) -> (Array1<f64>, Array1<f64>) {
+    let mut positives = vec![0.; n_bins];
+    let mut probabilities = vec![0.; n_bins];
+    let mut counts = vec![0usize; n_bins];
+
+    for (&label, &p) in y_true.iter().zip(y_prob.iter()) {
+        let bin = ((p * n_bins as f64) as usize).min(n_bins - 1);
+
+        counts[bin] += 1;
+        probabilities[bin] += p;
+        if label {
+            positives[bin] += 1.;
+        }
+    }
+
+    let non_empty: Vec<usize> = (0..n_bins).filter(|&bin| counts[bin] > 0).collect();
+
+    let prob_true = non_empty.iter().map(|&bin| positives[bin] / counts[bin] as f64).collect();
+    let prob_pred = non_empty.iter().map(|&bin| probabilities[bin] / counts[bin] as f64).collect();
+
+    (prob_true, prob_pred)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::calibration::calibration_curve;
+
+    #[test]
+    fn test_calibration_curve() {
+        let y_true = array![false, false, true, false, true, true];
+        let y_prob = array![0.1, 0.2, 0.3, 0.6, 0.8, 0.9];
+
+        let (prob_true, prob_pred) = calibration_curve(y_true.view(), y_prob.view(), 2);
+
+        assert_eq!(prob_true, array![1. / 3., 2. / 3.]);
+        assert!((prob_pred[0] - 0.2).abs() < 1e-12);
+        assert!((prob_pred[1] - 2.3 / 3.).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,43 @@
+use std::hash::Hash;
+
+use ndarray::{ArrayView1, Axis};
+
+use crate::clustering::contingency_matrix;
+
+fn pairs(n: f64) -> f64 {
+    n * (n - 1.) / 2.
+}
+
+/// Rand index corrected for chance: 1 for identical clusterings (up to relabelling), around 0
+/// for random ones.
+pub fn adjusted_rand_index<T: Eq + Hash, U: Eq + Hash>(labels_true: ArrayView1<T>, labels_pred: ArrayView1<U>) -> f64 {
+    let contingency = contingency_matrix(labels_true, labels_pred);
+
+    let index: f64 = contingency.iter().map(|&n| pairs(n)).sum();
+    let sum_rows: f64 = contingency.sum_axis(Axis(1)).iter().map(|&n| pairs(n)).sum();
+    let sum_columns: f64 = contingency.sum_axis(Axis(0)).iter().map(|&n| pairs(n)).sum();
+
+    let expected = sum_rows * sum_columns / pairs(labels_true.len() as f64);
+    let maximum = (sum_rows + sum_columns) / 2.;
+
+    if maximum == expected {
+        return 1.;
+    }
+
+    (index - expected) / (maximum - expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::clustering::adjusted_rand_index::adjusted_rand_index;
+
+    #[test]
+    fn test_adjusted_rand_index() {
+        let labels_true = array![0, 0, 1, 1];
+
+        assert_eq!(adjusted_rand_index(labels_true.view(), array!["b", "b", "a", "a"].view()), 1.);
+        assert!((adjusted_rand_index(labels_true.view(), array![0, 1, 0, 1].view()) + 0.5).abs() < 1e-12);
+    }
+}
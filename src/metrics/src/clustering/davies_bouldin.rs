@@ -0,0 +1,37 @@
+use std::hash::Hash;
+
+use ndarray::{Array2, ArrayView1, ArrayView2, Axis};
+
+use crate::clustering::cluster_members;
+use crate::pairwise::euclidean_distance;
+
+/// Mean over clusters of the worst ratio of within-cluster scatter to centroid separation;
+/// lower is better and 0 is the minimum.
+pub fn davies_bouldin_score<T: Eq + Hash>(x: ArrayView2<f64>, labels: ArrayView1<T>) -> f64 {
+    let clusters = cluster_members(labels);
+    let k = clusters.len();
+    assert!(k >= 2, "the Davies-Bouldin index needs at least 2 clusters");
+
+    let mut centroids = Array2::zeros((k, x.ncols()));
+    for (cluster, members) in clusters.iter().enumerate() {
+        centroids.row_mut(cluster).assign(&x.select(Axis(0), members).mean_axis(Axis(0)).unwrap());
+    }
+
+    let scatter: Vec<f64> = clusters.iter()
+        .enumerate()
+        .map(|(cluster, members)| {
+            members.iter().map(|&sample| euclidean_distance(x.row(sample), centroids.row(cluster))).sum::<f64>() / members.len() as f64
+        })
+        .collect();
+
+    let total: f64 = (0..k)
+        .map(|i| {
+            (0..k)
+                .filter(|&j| j != i)
+                .map(|j| (scatter[i] + scatter[j]) / euclidean_distance(centroids.row(i), centroids.row(j)))
+                .fold(f64::NEG_INFINITY, f64::max)
+        })
+        .sum();
+
+    total / k as f64
+}
@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+use std::hash::Hash;
+
+use ndarray::{s, Array1, ArrayView1, ArrayView2, Axis};
+
+use crate::clustering::cluster_members;
+use crate::pairwise::{pairwise_distances, squared_euclidean_distance, Distance};
+
+/// Rows of `x` whose distances to every other row `k_distance` holds at once.
+const BLOCK_SIZE: usize = 256;
+
+/// Inertia against the number of clusters, in increasing order of `k`, for picking `k` at the
+/// elbow of the curve, where more clusters stop paying for themselves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElbowTable {
+    k: Vec<usize>,
+    inertia: Vec<f64>,
+}
+
+impl ElbowTable {
+    pub fn new(k: Vec<usize>, inertia: Vec<f64>) -> Self {
+        assert_eq!(k.len(), inertia.len(), "there must be an inertia for every k");
+        assert!(k.windows(2).all(|pair| pair[0] < pair[1]), "k must be increasing, got {:?}", k);
+
+        ElbowTable { k, inertia }
+    }
+
+    /// Calls `inertia` with every `k` of `ks`, e.g. to fit a clustering of `k` clusters and
+    /// return its `inertia` score.
+    pub fn compute<F: FnMut(usize) -> f64>(ks: &[usize], mut inertia: F) -> Self {
+        let inertia = ks.iter().map(|&k| inertia(k)).collect();
+        ElbowTable::new(ks.to_vec(), inertia)
+    }
+
+    pub fn k(&self) -> &[usize] {
+        &self.k
+    }
+
+    pub fn inertia(&self) -> &[f64] {
+        &self.inertia
+    }
+
+    /// The `k` at the elbow, as `knee` finds it, if the curve has one.
+    pub fn knee(&self) -> Option<usize> {
+        let k: Vec<f64> = self.k.iter().map(|&k| k as f64).collect();
+        knee(ArrayView1::from(&k), ArrayView1::from(&self.inertia)).map(|index| self.k[index])
+    }
+}
+
+/// The within-cluster sum of squared Euclidean distances from every sample to the centroid of
+/// its cluster; lower is tighter, and it only ever falls as clusters are added.
+pub fn inertia<T: Eq + Hash>(x: ArrayView2<f64>, labels: ArrayView1<T>) -> f64 {
+    cluster_members(labels).iter()
+        .map(|members| {
+            let cluster = x.select(Axis(0), members);
+            let centroid = cluster.mean_axis(Axis(0)).unwrap();
+            cluster.genrows().into_iter().map(|row| squared_euclidean_distance(row, centroid.view())).sum::<f64>()
+        })
+        .sum()
+}
+
+/// The distance from every row of `x` to its `k`th nearest other row, sorted in increasing
+/// order. Plotted against the index, the knee of the curve is a good `eps` for DBSCAN with
+/// `min_samples` of `k + 1`: rows to its left are in dense regions and those to its right are
+/// noise.
+pub fn k_distance(x: ArrayView2<f64>, k: usize, metric: Distance) -> Array1<f64> {
+    assert!(k >= 1 && k < x.nrows(), "k must be between 1 and the number of rows less one, got {}", k);
+
+    let mut distances = Vec::with_capacity(x.nrows());
+    for start in (0..x.nrows()).step_by(BLOCK_SIZE) {
+        let end = (start + BLOCK_SIZE).min(x.nrows());
+        let block = pairwise_distances(x.slice(s![start..end, ..]), x, metric);
+
+        for row in block.genrows() {
+            // The row's distance to itself is the smallest, so the kth other row is at k.
+            let mut row = row.to_vec();
+            let (_, kth, _) = row.select_nth_unstable_by(k, |l, r| l.partial_cmp(r).unwrap_or(Ordering::Equal));
+            distances.push(*kth);
+        }
+    }
+
+    distances.sort_by(|l, r| l.partial_cmp(r).unwrap_or(Ordering::Equal));
+    Array1::from(distances)
+}
+
+/// The index of the knee of the convex curve through the points `(x, y)`, with `x` increasing,
+/// by the Kneedle method (Satopää et al., 2011): with both axes scaled to `[0, 1]`, the point
+/// furthest below the chord joining the ends. That's the elbow of falling curves, such as
+/// inertia against `k`, and the knee of rising ones, such as `k_distance`. `None` for fewer than
+/// 3 points, or when no point is below the chord.
+pub fn knee(x: ArrayView1<f64>, y: ArrayView1<f64>) -> Option<usize> {
+    assert_eq!(x.len(), y.len(), "x and y must have the same length");
+    let n = x.len();
+    if n < 3 {
+        return None;
+    }
+
+    let scale = |values: ArrayView1<f64>| {
+        let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| (min.min(v), max.max(v)));
+        values.mapv(|v| if max > min { (v - min) / (max - min) } else { 0. })
+    };
+    let (x, y) = (scale(x), scale(y));
+    let falling = y[n - 1] < y[0];
+
+    (1..n - 1)
+        .map(|i| (i, if falling { 1. - x[i] - y[i] } else { x[i] - y[i] }))
+        .filter(|&(_, below)| below > 0.)
+        .fold(None, |best: Option<(usize, f64)>, (i, below)| match best {
+            Some((_, most)) if most >= below => best,
+            _ => Some((i, below)),
+        })
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::clustering::elbow::{inertia, k_distance, knee, ElbowTable};
+    use crate::pairwise::Distance;
+
+    #[test]
+    fn test_elbow_table_knee() {
+        let table = ElbowTable::compute(&[1, 2, 3, 4, 5, 6], |k| [1000., 400., 120., 100., 90., 85.][k - 1]);
+
+        assert_eq!(table.k(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(table.knee(), Some(3));
+        assert_eq!(ElbowTable::new(vec![1, 2, 3], vec![3., 2., 1.]).knee(), None);
+    }
+
+    #[test]
+    fn test_k_distance_knee() {
+        let x = array![[0.], [1.], [2.], [3.], [4.], [20.], [40.]];
+        let distances = k_distance(x.view(), 1, Distance::Euclidean);
+
+        assert_eq!(distances, array![1., 1., 1., 1., 1., 16., 20.]);
+        assert_eq!(knee(array![0., 1., 2., 3., 4., 5., 6.].view(), distances.view()), Some(4));
+    }
+
+    #[test]
+    fn test_inertia() {
+        let x = array![[0., 0.], [2., 0.], [10., 10.], [10., 12.]];
+
+        assert_eq!(inertia(x.view(), array![0, 0, 1, 1].view()), 4.);
+    }
+}
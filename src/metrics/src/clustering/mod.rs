@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use ndarray::{Array2, ArrayView1};
+
+pub mod adjusted_rand_index;
+pub mod davies_bouldin;
+pub mod elbow;
+pub mod normalized_mutual_info;
+pub mod silhouette;
+
+/// Counts of samples shared by every (true cluster, predicted cluster) pair.
+fn contingency_matrix<T: Eq + Hash, U: Eq + Hash>(labels_true: ArrayView1<T>, labels_pred: ArrayView1<U>) -> Array2<f64> {
+    let mut rows: HashMap<&T, usize> = HashMap::new();
+    let mut columns: HashMap<&U, usize> = HashMap::new();
+
+    for (t, p) in labels_true.iter().zip(labels_pred.iter()) {
+        let next_row = rows.len();
+        rows.entry(t).or_insert(next_row);
+        let next_column = columns.len();
+        columns.entry(p).or_insert(next_column);
+    }
+
+    let mut contingency = Array2::zeros((rows.len(), columns.len()));
+
+    for (t, p) in labels_true.iter().zip(labels_pred.iter()) {
+        contingency[[rows[t], columns[p]]] += 1.;
+    }
+
+    contingency
+}
+
+/// Groups the row indexes of each distinct label, in order of first appearance.
+fn cluster_members<T: Eq + Hash>(labels: ArrayView1<T>) -> Vec<Vec<usize>> {
+    let mut positions: HashMap<&T, usize> = HashMap::new();
+    let mut members: Vec<Vec<usize>> = Vec::new();
+
+    for (index, label) in labels.iter().enumerate() {
+        let next = members.len();
+        let position = *positions.entry(label).or_insert(next);
+        if position == members.len() {
+            members.push(Vec::new());
+        }
+        members[position].push(index);
+    }
+
+    members
+}
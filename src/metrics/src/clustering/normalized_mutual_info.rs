@@ -0,0 +1,35 @@
+use std::hash::Hash;
+
+use ndarray::{ArrayView1, Axis};
+
+use crate::clustering::contingency_matrix;
+
+fn entropy(counts: ArrayView1<f64>, n: f64) -> f64 {
+    -counts.iter()
+        .filter(|&&count| count > 0.)
+        .map(|&count| count / n * (count / n).ln())
+        .sum::<f64>()
+}
+
+/// Mutual information between the two labellings normalised by the arithmetic mean of their
+/// entropies, between 0 (independent) and 1 (identical up to relabelling).
+pub fn normalized_mutual_info<T: Eq + Hash, U: Eq + Hash>(labels_true: ArrayView1<T>, labels_pred: ArrayView1<U>) -> f64 {
+    let contingency = contingency_matrix(labels_true, labels_pred);
+    let n = labels_true.len() as f64;
+
+    let rows = contingency.sum_axis(Axis(1));
+    let columns = contingency.sum_axis(Axis(0));
+
+    let mutual_info: f64 = contingency.indexed_iter()
+        .filter(|(_, &count)| count > 0.)
+        .map(|((row, column), &count)| count / n * (count * n / (rows[row] * columns[column])).ln())
+        .sum();
+
+    let normalizer = (entropy(rows.view(), n) + entropy(columns.view(), n)) / 2.;
+
+    if normalizer == 0. {
+        return 1.;
+    }
+
+    mutual_info / normalizer
+}
@@ -0,0 +1,39 @@
+use std::hash::Hash;
+
+use ndarray::{ArrayView1, ArrayView2};
+
+use crate::clustering::cluster_members;
+use crate::pairwise::euclidean_distance;
+
+/// Mean over all samples of `(b - a) / max(a, b)`, where `a` is the mean distance to the other
+/// members of the sample's cluster and `b` the mean distance to the nearest other cluster.
+/// Samples in singleton clusters score 0.
+pub fn silhouette_score<T: Eq + Hash>(x: ArrayView2<f64>, labels: ArrayView1<T>) -> f64 {
+    let clusters = cluster_members(labels);
+    assert!(clusters.len() >= 2, "the silhouette score needs at least 2 clusters");
+
+    let mut total = 0.;
+
+    for (own, members) in clusters.iter().enumerate() {
+        if members.len() == 1 {
+            continue;
+        }
+
+        for &sample in members {
+            let mean_distance = |cluster: &Vec<usize>| {
+                cluster.iter().map(|&other| euclidean_distance(x.row(sample), x.row(other))).sum::<f64>()
+            };
+
+            let a = mean_distance(members) / (members.len() - 1) as f64;
+            let b = clusters.iter()
+                .enumerate()
+                .filter(|&(other, _)| other != own)
+                .map(|(_, cluster)| mean_distance(cluster) / cluster.len() as f64)
+                .fold(f64::INFINITY, f64::min);
+
+            total += (b - a) / a.max(b);
+        }
+    }
+
+    total / x.nrows() as f64
+}
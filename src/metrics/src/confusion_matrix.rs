@@ -6,17 +6,30 @@ use log::*;
 use ndarray::{Array1, Array2, ArrayView1, Axis};
 use std::iter::FromIterator;
 
+/// Cells hold the summed sample weight of each (true, predicted) pair, which is a plain count
+/// when samples are added without a weight.
 #[derive(Debug)]
 pub struct ConfusionMatrix<T: Debug + Eq> {
     labels: Array1<T>,
-    arr: Array2<u64>,
+    arr: Array2<f64>,
 }
 
 impl<T: Eq + Debug> ConfusionMatrix<T> {
-    fn new(labels: Array1<T>, arr: Array2<u64>) -> Self {
+    fn new(labels: Array1<T>, arr: Array2<f64>) -> Self {
         ConfusionMatrix { labels, arr }
     }
 
+    /// An empty matrix whose rows and columns follow the order of `labels`.
+    pub fn with_labels(labels: Array1<T>) -> ConfusionMatrix<T> {
+        let number_of_distinct_values = labels.len();
+        let arr = Array2::zeros((number_of_distinct_values, number_of_distinct_values));
+
+        ConfusionMatrix::new(
+            labels,
+            arr,
+        )
+    }
+
     pub fn from_labels(labels: ArrayView1<T>) -> ConfusionMatrix<T> where T: Copy + Hash {
         let itter = labels.iter().unique().map(|v| *v);
 
@@ -34,10 +47,14 @@ impl<T: Eq + Debug> ConfusionMatrix<T> {
     }
 
     pub fn add(&mut self, y_true: &T, y_pred: &T) {
+        self.add_weighted(y_true, y_pred, 1.);
+    }
+
+    pub fn add_weighted(&mut self, y_true: &T, y_pred: &T, weight: f64) {
         let x = self.labels.iter().position(|p| p == y_true).unwrap();
         let y = self.labels.iter().position(|p| p == y_pred).unwrap();
 
-        self.arr[[x, y]] += 1;
+        self.arr[[x, y]] += weight;
     }
 
     pub fn add_all(&mut self, y_true: ArrayView1<T>, y_pred: ArrayView1<T>) {
@@ -46,24 +63,45 @@ impl<T: Eq + Debug> ConfusionMatrix<T> {
         }
     }
 
-    pub fn false_positive(&self) -> Array1<u64> {
+    pub fn add_all_weighted(&mut self, y_true: ArrayView1<T>, y_pred: ArrayView1<T>, sample_weight: ArrayView1<f64>) {
+        for ((prediction, target), weight) in y_pred.iter().zip(y_true.iter()).zip(sample_weight.iter()) {
+            self.add_weighted(target, prediction, *weight);
+        }
+    }
+
+    pub fn false_positive(&self) -> Array1<f64> {
         self.arr.sum_axis(Axis(0)) - self.arr.diag()
     }
 
-    pub fn false_negative(&self) -> Array1<u64> {
+    pub fn false_negative(&self) -> Array1<f64> {
         self.arr.sum_axis(Axis(1)) - self.arr.diag()
     }
 
-    pub fn true_positive(&self) -> Array1<u64> {
+    pub fn true_positive(&self) -> Array1<f64> {
         self.arr.diag().into_owned()
     }
 
+    pub fn true_negative(&self) -> Array1<f64> {
+        self.arr.sum() - self.true_positive() - self.false_positive() - self.false_negative()
+    }
+
+    /// Fraction of all samples that sit on the diagonal.
+    pub fn accuracy(&self) -> f64 {
+        self.arr.diag().sum() / self.arr.sum()
+    }
+
     pub fn recall(&self) -> Array1<f64> {
-        self.true_positive().mapv(|x| x as f64) / (self.false_negative().mapv(|x| x as f64) + self.true_positive().mapv(|x| x as f64))
+        self.true_positive() / (self.false_negative() + self.true_positive())
     }
 
     pub fn precision(&self) -> Array1<f64> {
-        self.true_positive().mapv(|x| x as f64) / (self.false_positive().mapv(|x| x as f64) + self.true_positive().mapv(|x| x as f64))
+        self.true_positive() / (self.false_positive() + self.true_positive())
+    }
+
+    /// Weighted harmonic mean of precision and recall, where recall counts `beta` times as much.
+    pub fn fbeta(&self, beta: f64) -> Array1<f64> {
+        let beta2 = beta * beta;
+        ((self.precision() * self.recall()) / (self.precision() * beta2 + self.recall())) * (1. + beta2)
     }
 
     pub fn f1(&self) -> Array1<f64> {
@@ -82,7 +120,7 @@ mod tests {
         let y_true = array![1.,0.,1.,0.];
         let y_pred = array![1.,0.,0.,1.];
 
-        let rc = recall(y_true.view(), y_pred.view());
+        let rc = recall(y_true.view(), y_pred.view(), &1., None);
         assert_eq!(rc, 0.5)
     }
 
@@ -91,7 +129,7 @@ mod tests {
         let y_true = array![1.,0.,1.,0.];
         let y_pred = array![1.,0.,0.,1.];
 
-        let rc = precision(y_true.view(), y_pred.view());
+        let rc = precision(y_true.view(), y_pred.view(), &1., None);
         assert_eq!(rc, 0.5)
     }
 
@@ -100,7 +138,7 @@ mod tests {
         let y_true = array![1.,0.,1.,0.];
         let y_pred = array![1.,0.,0.,1.];
 
-        let rc = f1(y_true.view(), y_pred.view());
+        let rc = f1(y_true.view(), y_pred.view(), &1., None);
         assert_eq!(rc, 0.5)
     }
 }
\ No newline at end of file
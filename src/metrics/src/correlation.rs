@@ -0,0 +1,93 @@
+use std::cmp::Ordering;
+
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Correlation {
+    /// The linear correlation of the values.
+    Pearson,
+    /// The Pearson correlation of the ranks of the values, which measures how monotonic rather
+    /// than how linear the relationship is. Ties get the mean of their ranks.
+    Spearman,
+}
+
+/// The correlation between every pair of columns of `x`, a symmetric matrix with ones on the
+/// diagonal. Pairs involving a constant column are NaN, as the
+/// correlation is undefined.
+pub fn correlation_matrix(x: ArrayView2<f64>, method: Correlation) -> Array2<f64> {
+    let columns: Vec<Array1<f64>> = x.axis_iter(Axis(1))
+        .map(|column| match method {
+            Correlation::Pearson => column.to_owned(),
+            Correlation::Spearman => rank(column),
+        })
+        .collect();
+
+    let mut correlations = Array2::from_elem((x.ncols(), x.ncols()), 1.);
+    for i in 0..columns.len() {
+        for j in (i + 1)..columns.len() {
+            let correlation = pearson(columns[i].view(), columns[j].view());
+            correlations[[i, j]] = correlation;
+            correlations[[j, i]] = correlation;
+        }
+    }
+
+    correlations
+}
+
+pub fn pearson(a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64 {
+    let (mean_a, mean_b) = (a.mean().unwrap_or(f64::NAN), b.mean().unwrap_or(f64::NAN));
+
+    let (covariance, variance_a, variance_b) = a.iter()
+        .zip(b.iter())
+        .fold((0., 0., 0.), |(covariance, variance_a, variance_b), (&a, &b)| {
+            let (da, db) = (a - mean_a, b - mean_b);
+            (covariance + da * db, variance_a + da * da, variance_b + db * db)
+        });
+
+    covariance / (variance_a * variance_b).sqrt()
+}
+
+pub fn spearman(a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64 {
+    pearson(rank(a).view(), rank(b).view())
+}
+
+/// The rank of every value, from 1 for the smallest, with tied values sharing the mean of the
+/// ranks they span.
+pub fn rank(values: ArrayView1<f64>) -> Array1<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&l, &r| values[l].partial_cmp(&values[r]).unwrap_or(Ordering::Equal));
+
+    let mut ranks = Array1::zeros(values.len());
+    let mut start = 0;
+    while start < order.len() {
+        let end = (start..order.len()).find(|&i| values[order[i]] != values[order[start]]).unwrap_or(order.len());
+        let rank = (start + end + 1) as f64 / 2.;
+        for &index in &order[start..end] {
+            ranks[index] = rank;
+        }
+        start = end;
+    }
+
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::correlation::{correlation_matrix, rank, Correlation};
+
+    #[test]
+    fn test_spearman_measures_monotonicity() {
+        let x = array![[1., 1., 4.], [2., 8., 3.], [3., 27., 2.], [4., 64., 1.]];
+
+        let pearson = correlation_matrix(x.view(), Correlation::Pearson);
+        let spearman = correlation_matrix(x.view(), Correlation::Spearman);
+
+        assert!(pearson[[0, 1]] < 1. - 1e-3);
+        assert!((spearman[[0, 1]] - 1.).abs() < 1e-12);
+        assert!((spearman[[0, 2]] + 1.).abs() < 1e-12);
+        assert_eq!(spearman, spearman.t());
+        assert_eq!(rank(array![3., 1., 3., 2.].view()), array![3.5, 1., 3.5, 2.]);
+    }
+}
@@ -0,0 +1,103 @@
+//! Univariate scores of how much each feature tells about a class label, for screening
+//! features one at a time, e.g. with `SelectKBest`. Higher scores are more informative.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use ndarray::{Array1, ArrayView1, ArrayView2};
+
+/// The ANOVA F-statistic of every column of `x` against the classes `y`: the variance of the
+/// class means over the variance within the classes. Columns that are constant within every
+/// class score infinity.
+pub fn f_classif<T: Copy + Eq + Hash>(x: ArrayView2<f64>, y: ArrayView1<T>) -> Array1<f64> {
+    x.gencolumns().into_iter().map(|column| f_score(column, y)).collect()
+}
+
+/// The mutual information, in nats, between every column of `x`, binned into `n_bins` bins of
+/// equal width, and the classes `y`.
+pub fn mutual_info_classif<T: Copy + Eq + Hash>(x: ArrayView2<f64>, y: ArrayView1<T>, n_bins: usize) -> Array1<f64> {
+    assert!(n_bins > 0, "mutual information needs at least one bin");
+
+    x.gencolumns().into_iter().map(|column| mutual_info(column, y, n_bins)).collect()
+}
+
+fn f_score<T: Copy + Eq + Hash>(column: ArrayView1<f64>, y: ArrayView1<T>) -> f64 {
+    let mut groups: HashMap<T, (f64, f64)> = HashMap::new();
+
+    for (&v, &label) in column.iter().zip(y.iter()) {
+        let entry = groups.entry(label).or_insert((0., 0.));
+        entry.0 += v;
+        entry.1 += 1.;
+    }
+
+    let n = column.len() as f64;
+    let k = groups.len() as f64;
+    let mean = column.sum() / n;
+
+    let between: f64 = groups.values()
+        .map(|&(sum, count)| count * (sum / count - mean).powi(2))
+        .sum();
+
+    let within: f64 = column.iter()
+        .zip(y.iter())
+        .map(|(&v, label)| {
+            let (sum, count) = groups[label];
+            (v - sum / count).powi(2)
+        })
+        .sum();
+
+    if within == 0. {
+        return f64::INFINITY;
+    }
+
+    (between / (k - 1.)) / (within / (n - k))
+}
+
+fn mutual_info<T: Copy + Eq + Hash>(column: ArrayView1<f64>, y: ArrayView1<T>, n_bins: usize) -> f64 {
+    let min = column.fold(f64::INFINITY, |m, &v| m.min(v));
+    let max = column.fold(f64::NEG_INFINITY, |m, &v| m.max(v));
+    let width = (max - min) / n_bins as f64;
+
+    let bin = |v: f64| if width == 0. { 0 } else { (((v - min) / width) as usize).min(n_bins - 1) };
+
+    let mut joint: HashMap<(usize, T), f64> = HashMap::new();
+    let mut bins: HashMap<usize, f64> = HashMap::new();
+    let mut labels: HashMap<T, f64> = HashMap::new();
+
+    for (&v, &label) in column.iter().zip(y.iter()) {
+        let b = bin(v);
+        *joint.entry((b, label)).or_insert(0.) += 1.;
+        *bins.entry(b).or_insert(0.) += 1.;
+        *labels.entry(label).or_insert(0.) += 1.;
+    }
+
+    let n = column.len() as f64;
+
+    joint.iter()
+        .map(|((b, label), &count)| {
+            let p_joint = count / n;
+            p_joint * (p_joint / ((bins[b] / n) * (labels[label] / n))).ln()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::feature_scoring::{f_classif, mutual_info_classif};
+
+    #[test]
+    fn test_informative_feature_scores_higher() {
+        let x = array![[0., 5.], [0.1, 1.], [1., 4.], [1.1, 2.]];
+        let y = array![0, 0, 1, 1];
+
+        let f = f_classif(x.view(), y.view());
+        assert!(f[0] > f[1]);
+        assert!((f[1] - 0.).abs() < 1e-12);
+
+        let mi = mutual_info_classif(x.view(), y.view(), 2);
+        assert!((mi[0] - 2f64.ln()).abs() < 1e-12);
+        assert!(mi[0] > mi[1]);
+    }
+}
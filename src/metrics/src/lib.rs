@@ -1,2 +1,10 @@
+pub mod calibration;
+pub mod clustering;
 pub mod confusion_matrix;
-pub mod regression;
\ No newline at end of file
+pub mod correlation;
+pub mod feature_scoring;
+pub mod metrics;
+pub mod pairwise;
+pub mod regression;
+mod sample_weight;
+pub mod threshold;
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use ndarray::{array, ArrayView1, Zip};
+
+use crate::confusion_matrix::ConfusionMatrix;
+use crate::sample_weight;
+
+/// Confusion matrix of "is `positive_label`" for both arrays; index 0 is the positive class.
+fn binary_confusion_matrix<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, positive_label: &T, sample_weight: Option<ArrayView1<f64>>) -> ConfusionMatrix<bool> {
+    let mut cm = ConfusionMatrix::with_labels(array![true, false]);
+
+    cm.add_all_weighted(
+        y_true.map(|label| label == positive_label).view(),
+        y_pred.map(|label| label == positive_label).view(),
+        sample_weight::resolve(sample_weight, y_true.len()).view(),
+    );
+
+    cm
+}
+
+pub fn true_positive<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, positive_label: &T) -> u64 {
+    binary_confusion_matrix(y_true, y_pred, positive_label, None).true_positive()[0] as u64
+}
+
+pub fn false_positive<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, positive_label: &T) -> u64 {
+    binary_confusion_matrix(y_true, y_pred, positive_label, None).false_positive()[0] as u64
+}
+
+pub fn true_negative<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, positive_label: &T) -> u64 {
+    binary_confusion_matrix(y_true, y_pred, positive_label, None).true_negative()[0] as u64
+}
+
+pub fn false_negative<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, positive_label: &T) -> u64 {
+    binary_confusion_matrix(y_true, y_pred, positive_label, None).false_negative()[0] as u64
+}
+
+/// Every label other than `positive_label` counts as negative.
+pub fn precision<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, positive_label: &T, sample_weight: Option<ArrayView1<f64>>) -> f64 {
+    binary_confusion_matrix(y_true, y_pred, positive_label, sample_weight).precision()[0]
+}
+
+/// Every label other than `positive_label` counts as negative.
+pub fn recall<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, positive_label: &T, sample_weight: Option<ArrayView1<f64>>) -> f64 {
+    binary_confusion_matrix(y_true, y_pred, positive_label, sample_weight).recall()[0]
+}
+
+/// Every label other than `positive_label` counts as negative.
+pub fn f1<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, positive_label: &T, sample_weight: Option<ArrayView1<f64>>) -> f64 {
+    binary_confusion_matrix(y_true, y_pred, positive_label, sample_weight).f1()[0]
+}
+
+/// Every label other than `positive_label` counts as negative.
+pub fn fbeta_score<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, positive_label: &T, beta: f64, sample_weight: Option<ArrayView1<f64>>) -> f64 {
+    binary_confusion_matrix(y_true, y_pred, positive_label, sample_weight).fbeta(beta)[0]
+}
+
+/// Fraction of predictions that exactly match the true label, or the fraction of the total
+/// weight they carry when `sample_weight` is given.
+pub fn accuracy_score<T: PartialEq>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>, sample_weight: Option<ArrayView1<f64>>) -> f64 {
+    let weights = sample_weight::resolve(sample_weight, y_true.len());
+
+    let correct = Zip::from(&y_true)
+        .and(&y_pred)
+        .and(&weights)
+        .fold(0., |acc, y_true, y_pred, weight| if y_true == y_pred { acc + weight } else { acc });
+
+    correct / weights.sum()
+}
+
+/// Mean recall over the classes present in `y_true`, so every class weighs the same regardless
+/// of how many samples it has.
+pub fn balanced_accuracy_score<T: Eq + Hash>(y_true: ArrayView1<T>, y_pred: ArrayView1<T>) -> f64 {
+    let mut per_class: HashMap<&T, (usize, usize)> = HashMap::new();
+
+    for (y_true, y_pred) in y_true.iter().zip(y_pred.iter()) {
+        let entry = per_class.entry(y_true).or_insert((0, 0));
+        entry.1 += 1;
+        if y_true == y_pred {
+            entry.0 += 1;
+        }
+    }
+
+    let recall_sum: f64 = per_class.values()
+        .map(|&(correct, total)| correct as f64 / total as f64)
+        .sum();
+
+    recall_sum / per_class.len() as f64
+}
+
+/// Mean negative log-likelihood of the true labels under the predicted probabilities of the
+/// positive class. Probabilities are clipped to `[eps, 1 - eps]` so a confident mistake costs a
+/// large but finite amount.
+pub fn log_loss(y_true: ArrayView1<bool>, y_prob: ArrayView1<f64>, eps: f64) -> f64 {
+    let loss = Zip::from(&y_true)
+        .and(&y_prob)
+        .fold(0., |acc, &y_true, &y_prob| {
+            let p = y_prob.clamp(eps, 1. - eps);
+            acc - if y_true { p.ln() } else { (1. - p).ln() }
+        });
+
+    loss / y_true.len() as f64
+}
+
+/// Mean squared difference between the predicted probability of the positive class and the
+/// 0/1 outcome.
+pub fn brier_score_loss(y_true: ArrayView1<bool>, y_prob: ArrayView1<f64>) -> f64 {
+    let loss = Zip::from(&y_true)
+        .and(&y_prob)
+        .fold(0., |acc, &y_true, &y_prob| {
+            let outcome = if y_true { 1. } else { 0. };
+            acc + (y_prob - outcome).powi(2)
+        });
+
+    loss / y_true.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::metrics::{accuracy_score, balanced_accuracy_score, brier_score_loss, false_positive, fbeta_score, log_loss, precision, recall, true_negative};
+
+    #[test]
+    fn test_accuracy_score() {
+        let y_true = array!["setosa", "virginica", "setosa", "versicolor"];
+        let y_pred = array!["setosa", "setosa", "setosa", "versicolor"];
+
+        assert_eq!(accuracy_score(y_true.view(), y_pred.view(), None), 0.75);
+    }
+
+    #[test]
+    fn test_weighted_metrics() {
+        let y_true = array![1, 0, 1, 0];
+        let y_pred = array![1, 1, 0, 0];
+        let weights = array![3., 1., 1., 1.];
+
+        assert_eq!(accuracy_score(y_true.view(), y_pred.view(), Some(weights.view())), 4. / 6.);
+        assert_eq!(precision(y_true.view(), y_pred.view(), &1, Some(weights.view())), 0.75);
+        assert_eq!(recall(y_true.view(), y_pred.view(), &1, Some(weights.view())), 0.75);
+    }
+
+    #[test]
+    fn test_balanced_accuracy_score() {
+        let y_true = array![true, true, true, false];
+        let y_pred = array![true, true, true, true];
+
+        assert_eq!(balanced_accuracy_score(y_true.view(), y_pred.view()), 0.5);
+    }
+
+    #[test]
+    fn test_log_loss() {
+        let y_true = array![true, false];
+        let y_prob = array![0.5, 0.];
+
+        assert!((log_loss(y_true.view(), y_prob.view(), 1e-15) - 0.5f64.ln().abs() / 2.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_brier_score_loss() {
+        let y_true = array![true, false, true, false];
+        let y_prob = array![0.9, 0.1, 0.6, 0.4];
+
+        assert!((brier_score_loss(y_true.view(), y_prob.view()) - 0.085).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_binary_metrics_with_string_labels() {
+        let y_true = array!["spam", "ham", "spam", "eggs"];
+        let y_pred = array!["spam", "spam", "ham", "eggs"];
+
+        assert_eq!(false_positive(y_true.view(), y_pred.view(), &"spam"), 1);
+        assert_eq!(true_negative(y_true.view(), y_pred.view(), &"spam"), 1);
+        assert_eq!(precision(y_true.view(), y_pred.view(), &"spam", None), 0.5);
+    }
+
+    #[test]
+    fn test_fbeta_score() {
+        let y_true = array![1, 1, 1, 0];
+        let y_pred = array![1, 0, 0, 0];
+
+        // precision = 1, recall = 1/3
+        assert!((fbeta_score(y_true.view(), y_pred.view(), &1, 2., None) - 5. / 13.).abs() < 1e-12);
+        assert!((fbeta_score(y_true.view(), y_pred.view(), &1, 0.5, None) - 5. / 7.).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,147 @@
+use ndarray::{s, Array2, ArrayView1, ArrayView2, Axis, Zip};
+
+/// Rows of `x` processed per block by `pairwise_distances`, bounding the size of the temporary
+/// dot-product matrices.
+const BLOCK_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distance {
+    Euclidean,
+    SquaredEuclidean,
+    Manhattan,
+    Chebyshev,
+    Minkowski(f64),
+    /// `1 - cos(a, b)`; zero vectors are treated as orthogonal to everything.
+    Cosine,
+}
+
+impl Distance {
+    pub fn compute(&self, a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64 {
+        match *self {
+            Distance::Euclidean => euclidean_distance(a, b),
+            Distance::SquaredEuclidean => squared_euclidean_distance(a, b),
+            Distance::Manhattan => manhattan_distance(a, b),
+            Distance::Chebyshev => chebyshev_distance(a, b),
+            Distance::Minkowski(p) => minkowski_distance(a, b, p),
+            Distance::Cosine => cosine_distance(a, b),
+        }
+    }
+}
+
+pub fn squared_euclidean_distance(a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64 {
+    Zip::from(&a).and(&b).fold(0., |acc, a, b| acc + (a - b).powi(2))
+}
+
+pub fn euclidean_distance(a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64 {
+    squared_euclidean_distance(a, b).sqrt()
+}
+
+pub fn manhattan_distance(a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64 {
+    Zip::from(&a).and(&b).fold(0., |acc, a, b| acc + (a - b).abs())
+}
+
+pub fn chebyshev_distance(a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64 {
+    Zip::from(&a).and(&b).fold(0., |acc: f64, a, b| acc.max((a - b).abs()))
+}
+
+pub fn minkowski_distance(a: ArrayView1<f64>, b: ArrayView1<f64>, p: f64) -> f64 {
+    assert!(p >= 1., "the Minkowski distance requires p >= 1, got {}", p);
+
+    Zip::from(&a).and(&b).fold(0., |acc, a, b| acc + (a - b).abs().powf(p)).powf(1. / p)
+}
+
+pub fn cosine_distance(a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64 {
+    let norms = a.dot(&a).sqrt() * b.dot(&b).sqrt();
+
+    if norms == 0. {
+        return 1.;
+    }
+
+    1. - a.dot(&b) / norms
+}
+
+/// Distance between every row of `x` and every row of `y`, as an `(x.nrows(), y.nrows())` matrix.
+///
+/// Euclidean and cosine distances are computed from matrix products one block of `x` rows at a
+/// time, which is much faster than comparing row pairs one by one.
+pub fn pairwise_distances(x: ArrayView2<f64>, y: ArrayView2<f64>, metric: Distance) -> Array2<f64> {
+    assert_eq!(x.ncols(), y.ncols(), "x and y must have the same number of features");
+
+    let mut distances = Array2::zeros((x.nrows(), y.nrows()));
+
+    match metric {
+        Distance::Euclidean | Distance::SquaredEuclidean => {
+            let y_norms = y.map_axis(Axis(1), |row| row.dot(&row));
+
+            for start in (0..x.nrows()).step_by(BLOCK_SIZE) {
+                let end = (start + BLOCK_SIZE).min(x.nrows());
+                let block = x.slice(s![start..end, ..]);
+                let x_norms = block.map_axis(Axis(1), |row| row.dot(&row));
+
+                let mut target = distances.slice_mut(s![start..end, ..]);
+                target.assign(&(block.dot(&y.t()) * -2.));
+
+                for ((row, column), d) in target.indexed_iter_mut() {
+                    // rounding can push tiny distances slightly below zero
+                    let squared = (*d + x_norms[row] + y_norms[column]).max(0.);
+                    *d = if metric == Distance::Euclidean { squared.sqrt() } else { squared };
+                }
+            }
+        }
+        Distance::Cosine => {
+            let normalize = |m: ArrayView2<f64>| {
+                let mut m = m.to_owned();
+                for mut row in m.genrows_mut() {
+                    let norm = row.dot(&row).sqrt();
+                    if norm > 0. {
+                        row /= norm;
+                    }
+                }
+                m
+            };
+
+            let x = normalize(x);
+            let y = normalize(y);
+
+            for start in (0..x.nrows()).step_by(BLOCK_SIZE) {
+                let end = (start + BLOCK_SIZE).min(x.nrows());
+                let similarity = x.slice(s![start..end, ..]).dot(&y.t());
+                distances.slice_mut(s![start..end, ..]).assign(&similarity.mapv(|s| 1. - s));
+            }
+        }
+        _ => {
+            for (row, x_row) in x.genrows().into_iter().enumerate() {
+                for (column, y_row) in y.genrows().into_iter().enumerate() {
+                    distances[[row, column]] = metric.compute(x_row, y_row);
+                }
+            }
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::pairwise::{pairwise_distances, Distance};
+
+    #[test]
+    fn test_pairwise_distances_match_row_by_row() {
+        let x = array![[0., 0.], [3., 4.], [1., -1.]];
+        let y = array![[0., 0.], [1., 1.]];
+
+        for &metric in &[Distance::Euclidean, Distance::SquaredEuclidean, Distance::Manhattan, Distance::Chebyshev, Distance::Minkowski(3.), Distance::Cosine] {
+            let distances = pairwise_distances(x.view(), y.view(), metric);
+
+            for i in 0..x.nrows() {
+                for j in 0..y.nrows() {
+                    assert!((distances[[i, j]] - metric.compute(x.row(i), y.row(j))).abs() < 1e-9, "{:?} at ({}, {})", metric, i, j);
+                }
+            }
+        }
+
+        assert_eq!(pairwise_distances(x.view(), y.view(), Distance::Euclidean)[[1, 0]], 5.);
+    }
+}
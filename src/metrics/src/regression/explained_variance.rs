@@ -0,0 +1,11 @@
+use ndarray::{ArrayView1, Axis};
+
+/// `1 - Var(y_true - y_pred) / Var(y_true)`; unlike R² it ignores a constant bias in the predictions.
+pub fn explained_variance(y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>) -> f64 {
+    let residuals = &y_true - &y_pred;
+
+    let residual_variance = residuals.var_axis(Axis(0), 0.).into_scalar();
+    let variance = y_true.var_axis(Axis(0), 0.).into_scalar();
+
+    1. - (residual_variance / variance)
+}
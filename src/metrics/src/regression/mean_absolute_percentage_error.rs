@@ -0,0 +1,13 @@
+use ndarray::{ArrayView1, Zip};
+
+/// Mean of `|y_true - y_pred| / |y_true|`, returned as a fraction rather than a percentage.
+/// Targets of zero are replaced by `f64::EPSILON` to keep the result finite.
+pub fn mean_absolute_percentage_error(y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>) -> f64 {
+    let mape = Zip::from(&y_true)
+        .and(&y_pred)
+        .fold(0., |acc, y_true, y_pred| {
+            acc + (y_true - y_pred).abs() / y_true.abs().max(f64::EPSILON)
+        });
+
+    mape / y_true.len() as f64
+}
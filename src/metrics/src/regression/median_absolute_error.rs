@@ -0,0 +1,21 @@
+use std::cmp::Ordering;
+
+use ndarray::{ArrayView1, Zip};
+
+/// Median of the absolute residuals, robust to a handful of wild predictions.
+pub fn median_absolute_error(y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>) -> f64 {
+    let mut errors = Vec::with_capacity(y_true.len());
+
+    Zip::from(&y_true)
+        .and(&y_pred)
+        .apply(|y_true, y_pred| errors.push((y_true - y_pred).abs()));
+
+    errors.sort_by(|l, r| l.partial_cmp(r).unwrap_or(Ordering::Equal));
+
+    let middle = errors.len() / 2;
+    if errors.len() % 2 == 0 {
+        (errors[middle - 1] + errors[middle]) / 2.
+    } else {
+        errors[middle]
+    }
+}
@@ -1,2 +1,7 @@
+pub mod explained_variance;
+pub mod mean_absolute_error;
+pub mod mean_absolute_percentage_error;
+pub mod mean_squared_error;
+pub mod median_absolute_error;
 pub mod r2;
-pub mod root_mean_squared_error;
\ No newline at end of file
+pub mod root_mean_squared_error;
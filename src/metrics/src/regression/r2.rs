@@ -1,12 +1,25 @@
 use ndarray::{ArrayView1, Zip};
 
-pub fn r2(y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>) -> f64 {
-    let mean_y = y_true.mean().unwrap();
+use crate::sample_weight;
+
+pub fn r2(y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>, sample_weight: Option<ArrayView1<f64>>) -> f64 {
+    let weights = sample_weight::resolve(sample_weight, y_true.len());
+    let mean_y = y_true.dot(&weights) / weights.sum();
 
     let (ss_t, ss_r) = Zip::from(&y_true)
         .and(&y_pred)
-        .fold((0., 0.), |(ss_t, ss_r), y_true, y_pred| {
-            (ss_t + (y_true - mean_y).powf(2.), ss_r + (y_true - y_pred).powf(2.))
+        .and(&weights)
+        .fold((0., 0.), |(ss_t, ss_r), y_true, y_pred, weight| {
+            (ss_t + weight * (y_true - mean_y).powf(2.), ss_r + weight * (y_true - y_pred).powf(2.))
         });
     1. - (ss_r / ss_t)
-}
\ No newline at end of file
+}
+
+/// R² penalised for the number of features used by the model, so adding uninformative features
+/// no longer increases the score.
+pub fn adjusted_r2(y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>, n_features: usize) -> f64 {
+    let n = y_true.len() as f64;
+    let p = n_features as f64;
+
+    1. - (1. - r2(y_true, y_pred, None)) * (n - 1.) / (n - p - 1.)
+}
@@ -1,11 +1,16 @@
 use ndarray::{ArrayView1, Zip};
 
-pub fn root_mean_squared_error(y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>) -> f64 {
+use crate::sample_weight;
+
+pub fn root_mean_squared_error(y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>, sample_weight: Option<ArrayView1<f64>>) -> f64 {
+    let weights = sample_weight::resolve(sample_weight, y_true.len());
+
     let rmse = Zip::from(&y_true)
         .and(&y_pred)
-        .fold(0., |acc, y_true, y_pred| {
-            acc + (y_true - y_pred).powf(2.)
+        .and(&weights)
+        .fold(0., |acc, y_true, y_pred, weight| {
+            acc + weight * (y_true - y_pred).powf(2.)
         });
 
-    (rmse / y_true.len() as f64).sqrt()
-}
\ No newline at end of file
+    (rmse / weights.sum()).sqrt()
+}
@@ -0,0 +1,12 @@
+use ndarray::{Array1, ArrayView1};
+
+/// The given per-sample weights, or a weight of one for each of the `n_samples` samples.
+pub(crate) fn resolve(sample_weight: Option<ArrayView1<f64>>, n_samples: usize) -> Array1<f64> {
+    match sample_weight {
+        Some(weights) => {
+            assert_eq!(weights.len(), n_samples, "sample_weight must have one weight per sample");
+            weights.to_owned()
+        }
+        None => Array1::ones(n_samples),
+    }
+}
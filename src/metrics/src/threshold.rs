@@ -0,0 +1,47 @@
+use std::cmp::Ordering;
+
+use log::debug;
+use ndarray::ArrayView1;
+
+/// Tries every distinct score as the cut-off for predicting the positive class
+/// (`y_score >= threshold`) and returns the `(threshold, score)` pair maximising
+/// `metric(y_true, y_pred)`. Thresholds for which the metric is undefined (NaN) are skipped.
+pub fn tune_threshold<M>(y_true: ArrayView1<bool>, y_score: ArrayView1<f64>, metric: M) -> (f64, f64)
+    where M: Fn(ArrayView1<bool>, ArrayView1<bool>) -> f64 {
+    let mut thresholds = y_score.to_vec();
+    thresholds.sort_by(|l, r| l.partial_cmp(r).unwrap_or(Ordering::Equal));
+    thresholds.dedup();
+
+    let mut best = (f64::NAN, f64::NEG_INFINITY);
+
+    for threshold in thresholds {
+        let y_pred = y_score.mapv(|score| score >= threshold);
+        let score = metric(y_true, y_pred.view());
+        debug!("threshold {}: {}", threshold, score);
+
+        if !score.is_nan() && score > best.1 {
+            best = (threshold, score);
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::metrics::f1;
+    use crate::threshold::tune_threshold;
+
+    #[test]
+    fn test_tune_threshold() {
+        let y_true = array![false, false, true, true, true];
+        let y_score = array![0.1, 0.4, 0.35, 0.8, 0.9];
+
+        let (threshold, score) = tune_threshold(y_true.view(), y_score.view(), |t, p| f1(t, p, &true, None));
+
+        assert_eq!(threshold, 0.35);
+        assert!((score - 6. / 7.).abs() < 1e-12);
+    }
+}
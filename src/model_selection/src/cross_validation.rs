@@ -0,0 +1,197 @@
+use log::debug;
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
+use rune_core::parallel::parallel_map;
+use rune_core::Result;
+use rune_core::estimator::{Fit, Predictor};
+
+use crate::splitting::CrossValidator;
+use crate::splitting::k_fold::Fold;
+
+/// Fits `estimator` on the training part of `fold` and returns `(train_score, test_score)`; the
+/// training score is only computed when `return_train_score` is set.
+pub(crate) fn score_fold<E, M, Y, P, S>(estimator: &E, x: ArrayView2<f64>, y: ArrayView1<Y>, fold: &Fold, scoring: &S, return_train_score: bool) -> Result<(Option<f64>, f64)>
+    where
+        E: for<'a> Fit<ArrayView2<'a, f64>, M, Y>,
+        M: for<'a> Predictor<ArrayView2<'a, f64>, Array1<P>>,
+        Y: Copy,
+        S: Fn(ArrayView1<Y>, ArrayView1<P>) -> f64 {
+    let (train, test) = fold;
+
+    let x_train = x.select(Axis(0), train);
+    let y_train = y.select(Axis(0), train);
+
+    let model = estimator.fit(x_train.view(), y_train.view())?;
+
+    let train_score = if return_train_score {
+        Some(scoring(y_train.view(), model.predict(x_train.view()).view()))
+    } else {
+        None
+    };
+
+    let y_pred = model.predict(x.select(Axis(0), test).view());
+    let test_score = scoring(y.select(Axis(0), test).view(), y_pred.view());
+
+    debug!("fold score: {}", test_score);
+    Ok((train_score, test_score))
+}
+
+/// Fits `estimator` on the training part of every fold and scores its predictions on the held
+/// out part with `scoring(y_true, y_pred)`, returning one score per fold. `Y` is the label type
+/// the estimator fits on, e.g. `f64` for regressors. Folds are evaluated concurrently on `n_jobs`
+/// worker threads.
+pub fn cross_val_score<E, M, Y, P, C, S>(estimator: &E, x: ArrayView2<f64>, y: ArrayView1<Y>, cv: &C, scoring: S, n_jobs: usize) -> Result<Array1<f64>>
+    where
+        E: for<'a> Fit<ArrayView2<'a, f64>, M, Y> + Sync,
+        M: for<'a> Predictor<ArrayView2<'a, f64>, Array1<P>>,
+        Y: Copy + Sync,
+        C: CrossValidator<Y>,
+        S: Fn(ArrayView1<Y>, ArrayView1<P>) -> f64 + Sync {
+    let folds = cv.folds(y)?;
+
+    parallel_map(&folds, n_jobs, |fold| score_fold(estimator, x, y, fold, &scoring, false).map(|(_, test_score)| test_score))
+        .into_iter()
+        .collect::<Result<Vec<f64>>>()
+        .map(Array1::from)
+}
+
+/// Cross-validates the estimator built by `build` for each value of a single hyperparameter,
+/// e.g. the `alpha` of a ridge regressor fitted on `f64` targets, returning
+/// `(train_scores, test_scores)` with one row per value and one column per fold.
+pub fn validation_curve<B, V, E, M, Y, P, C, S>(build: B, param_values: &[V], x: ArrayView2<f64>, y: ArrayView1<Y>, cv: &C, scoring: S, n_jobs: usize) -> Result<(Array2<f64>, Array2<f64>)>
+    where
+        B: Fn(&V) -> E + Sync,
+        V: Sync,
+        E: for<'a> Fit<ArrayView2<'a, f64>, M, Y>,
+        M: for<'a> Predictor<ArrayView2<'a, f64>, Array1<P>>,
+        Y: Copy + Sync,
+        C: CrossValidator<Y>,
+        S: Fn(ArrayView1<Y>, ArrayView1<P>) -> f64 + Sync {
+    let folds = cv.folds(y)?;
+
+    let tasks: Vec<(usize, usize)> = (0..param_values.len())
+        .flat_map(|row| (0..folds.len()).map(move |fold| (row, fold)))
+        .collect();
+
+    let scores = parallel_map(&tasks, n_jobs, |&(row, fold)| {
+        score_fold(&build(&param_values[row]), x, y, &folds[fold], &scoring, true)
+    });
+
+    let mut train_scores = Array2::zeros((param_values.len(), folds.len()));
+    let mut test_scores = Array2::zeros((param_values.len(), folds.len()));
+
+    for (&(row, fold), scores) in tasks.iter().zip(scores) {
+        let (train_score, test_score) = scores?;
+        train_scores[[row, fold]] = train_score.unwrap();
+        test_scores[[row, fold]] = test_score;
+    }
+
+    Ok((train_scores, test_scores))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    use ndarray::{array, Array1, Array2, ArrayView1, ArrayView2};
+    use rune_core::estimator::{Fit, Predictor};
+    use rune_core::{Error, Result};
+
+    use crate::cross_validation::{cross_val_score, validation_curve};
+    use crate::splitting::k_fold::KFold;
+    use crate::splitting::stratified_k_fold::StratifiedKFold;
+
+    /// Least squares through the origin on the first feature, shrunk by `alpha` as in ridge.
+    pub(crate) struct ShrunkSlope {
+        pub(crate) alpha: f64,
+    }
+
+    pub(crate) struct SlopeModel(f64);
+
+    impl Fit<ArrayView2<'_, f64>, SlopeModel, f64> for ShrunkSlope {
+        fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<SlopeModel> {
+            let column = x.column(0);
+            Ok(SlopeModel(column.dot(&y) / (column.dot(&column) + self.alpha)))
+        }
+    }
+
+    impl Predictor<ArrayView2<'_, f64>, Array1<f64>> for SlopeModel {
+        fn predict(&self, x: ArrayView2<f64>) -> Array1<f64> {
+            x.column(0).mapv(|v| v * self.0)
+        }
+    }
+
+    /// Predicts the most common training label for every row.
+    struct Majority;
+
+    struct MajorityModel<Y>(Y);
+
+    impl<Y: Copy + Eq + Hash> Fit<ArrayView2<'_, f64>, MajorityModel<Y>, Y> for Majority {
+        fn fit(&self, _x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<MajorityModel<Y>> {
+            let mut counts = HashMap::new();
+            for &label in y {
+                *counts.entry(label).or_insert(0) += 1;
+            }
+            let (&label, _) = counts.iter().max_by_key(|&(_, count)| *count)
+                .ok_or_else(|| Error::InvalidInput("no labels".to_owned()))?;
+            Ok(MajorityModel(label))
+        }
+    }
+
+    impl<Y: Copy> Predictor<ArrayView2<'_, f64>, Array1<Y>> for MajorityModel<Y> {
+        fn predict(&self, x: ArrayView2<f64>) -> Array1<Y> {
+            Array1::from_elem(x.nrows(), self.0)
+        }
+    }
+
+    pub(crate) fn negative_mse(y_true: ArrayView1<f64>, y_pred: ArrayView1<f64>) -> f64 {
+        -(&y_true - &y_pred).mapv(|r| r * r).mean().unwrap()
+    }
+
+    pub(crate) fn linear_data() -> (Array2<f64>, Array1<f64>) {
+        let x = Array2::from_shape_fn((12, 1), |(i, _)| i as f64 + 1.);
+        let y = x.column(0).mapv(|v| 3. * v);
+        (x, y)
+    }
+
+    #[test]
+    fn test_cross_val_score_regressor() {
+        let (x, y) = linear_data();
+
+        let scores = cross_val_score(&ShrunkSlope { alpha: 0. }, x.view(), y.view(), &KFold::new(3), negative_mse, 2).unwrap();
+
+        assert_eq!(scores.len(), 3);
+        assert!(scores.iter().all(|&score| score.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_cross_val_score_multi_class() {
+        let x = Array2::zeros((9, 1));
+        let y = array!["a", "b", "a", "c", "a", "b", "a", "c", "a"];
+        let accuracy = |y_true: ArrayView1<&str>, y_pred: ArrayView1<&str>| {
+            y_true.iter().zip(y_pred.iter()).filter(|(t, p)| t == p).count() as f64 / y_true.len() as f64
+        };
+
+        let scores = cross_val_score(&Majority, x.view(), y.view(), &StratifiedKFold::new(3), accuracy, 1).unwrap();
+
+        // "a" is the majority of every training set, and the folds get 2, 2 and 1 of its rows.
+        assert_eq!(scores, array![2. / 3., 2. / 3., 1. / 3.]);
+    }
+
+    #[test]
+    fn test_validation_curve_regressor() {
+        let (x, y) = linear_data();
+        let alphas = [0., 10., 100.];
+
+        let (train_scores, test_scores) = validation_curve(|&alpha| ShrunkSlope { alpha }, &alphas, x.view(), y.view(), &KFold::new(4), negative_mse, 2).unwrap();
+
+        assert_eq!(train_scores.dim(), (3, 4));
+        assert_eq!(test_scores.dim(), (3, 4));
+        assert!(train_scores.row(0).iter().chain(test_scores.row(0).iter()).all(|&score| score.abs() < 1e-12));
+        // More shrinkage fits the training folds worse.
+        for fold in 0..4 {
+            assert!(train_scores[[0, fold]] > train_scores[[1, fold]]);
+            assert!(train_scores[[1, fold]] > train_scores[[2, fold]]);
+        }
+    }
+}
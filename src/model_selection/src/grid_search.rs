@@ -0,0 +1,229 @@
+use std::time::Instant;
+
+use log::info;
+use ndarray::{Array1, ArrayView1, ArrayView2};
+use rune_core::callback::{Callbacks, FitCallback};
+use rune_core::parallel::parallel_map;
+use rune_core::validation::check_x_y;
+use rune_core::{Error, Result};
+pub use rune_pipeline::params::{ParamValue, Params};
+use rune_core::estimator::{Fit, Predictor};
+use serde::{Deserialize, Serialize};
+
+use crate::cross_validation::score_fold;
+use crate::splitting::CrossValidator;
+
+/// The cartesian product of candidate values for each named hyperparameter.
+#[derive(Debug, Clone, Default)]
+pub struct ParamGrid {
+    params: Vec<(String, Vec<ParamValue>)>,
+}
+
+impl ParamGrid {
+    pub fn new() -> Self {
+        ParamGrid { params: Vec::new() }
+    }
+
+    pub fn add<V: Into<ParamValue>>(mut self, name: &str, values: Vec<V>) -> Self {
+        self.params.push((name.to_owned(), values.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    pub fn combinations(&self) -> Vec<Params> {
+        self.params.iter().fold(vec![Params::new()], |combinations, (name, values)| {
+            combinations.iter()
+                .flat_map(|params| values.iter().map(move |value| {
+                    let mut params = params.clone();
+                    params.insert(name.clone(), value.clone());
+                    params
+                }))
+                .collect()
+        })
+    }
+}
+
+/// Exhaustively cross-validates an estimator built by `build` for every combination in a
+/// `ParamGrid`, then refits the best combination on the full training data. `build` fails for
+/// combinations it can't apply, e.g. by setting them with `Parameterized::set_params`, which
+/// fails the search:
+///
+/// `|params| { let mut tree = tree.clone(); tree.set_params(params)?; Ok(tree) }`
+pub struct GridSearchCV<B, C, S> {
+    build: B,
+    param_grid: ParamGrid,
+    cv: C,
+    scoring: S,
+    n_jobs: usize,
+    callbacks: Callbacks,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GridSearchModel<M> {
+    best_params: Params,
+    best_score: f64,
+    best_model: M,
+    cv_results: Vec<(Params, f64)>,
+}
+
+impl<B, C, S> GridSearchCV<B, C, S> {
+    pub fn new(build: B, param_grid: ParamGrid, cv: C, scoring: S) -> Self {
+        GridSearchCV {
+            build,
+            param_grid,
+            cv,
+            scoring,
+            n_jobs: 1,
+            callbacks: Callbacks::new(),
+        }
+    }
+
+    /// Number of worker threads sharing the (combination, fold) fits.
+    pub fn with_n_jobs(self, n_jobs: usize) -> Self {
+        GridSearchCV { n_jobs, ..self }
+    }
+
+    /// Calls `callback` as every (combination, fold) fit is scored, numbered combination by
+    /// combination, with the negated test score as the loss. With several jobs the calls come
+    /// from the worker threads in the order the fits finish.
+    pub fn with_callback<F: FitCallback + 'static>(mut self, callback: F) -> Self {
+        self.callbacks.push(callback);
+        self
+    }
+
+    /// Searches on the rows of `x` labelled `y`, of any label type `Y` the estimator fits on and
+    /// the splitter can split.
+    pub fn fit<E, M, Y, P>(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<GridSearchModel<M>>
+        where
+            B: Fn(&Params) -> Result<E> + Sync,
+            E: for<'a> Fit<ArrayView2<'a, f64>, M, Y>,
+            M: for<'a> Predictor<ArrayView2<'a, f64>, Array1<P>>,
+            Y: Copy + Sync,
+            C: CrossValidator<Y> + Sync,
+            S: Fn(ArrayView1<Y>, ArrayView1<P>) -> f64 + Sync {
+        check_x_y(x, y, 1)?;
+
+        let combinations = self.param_grid.combinations();
+        let folds = self.cv.folds(y)?;
+        info!("Evaluating {} parameter combinations on {} folds", combinations.len(), folds.len());
+
+        let tasks: Vec<(usize, usize)> = (0..combinations.len())
+            .flat_map(|combination| (0..folds.len()).map(move |fold| (combination, fold)))
+            .collect();
+
+        let start = Instant::now();
+        let fold_scores = parallel_map(&tasks, self.n_jobs, |&(combination, fold)| -> Result<f64> {
+            let estimator = (self.build)(&combinations[combination])?;
+            let (_, test_score) = score_fold(&estimator, x, y, &folds[fold], &self.scoring, false)?;
+            self.callbacks.on_iteration(combination * folds.len() + fold, -test_score, start.elapsed());
+            Ok(test_score)
+        });
+
+        let mut totals = vec![0.; combinations.len()];
+        for (&(combination, _), score) in tasks.iter().zip(fold_scores) {
+            totals[combination] += score?;
+        }
+
+        let cv_results: Vec<(Params, f64)> = combinations.into_iter()
+            .zip(totals)
+            .map(|(params, total)| (params, total / folds.len() as f64))
+            .collect();
+
+        GridSearchModel::from_cv_results(cv_results, |params| (self.build)(params)?.fit(x, y))
+    }
+}
+
+impl<M> GridSearchModel<M> {
+    /// Picks the best scoring combination of `cv_results`, the first of equals, and fits the
+    /// model for it with `fit`, e.g. for searches that cross-validate by themselves.
+    pub fn from_cv_results<F: FnOnce(&Params) -> Result<M>>(cv_results: Vec<(Params, f64)>, fit: F) -> Result<Self> {
+        let (best_params, best_score) = cv_results.iter()
+            .filter(|(_, score)| !score.is_nan())
+            .fold(None, |best: Option<&(Params, f64)>, candidate| match best {
+                Some(best) if best.1 >= candidate.1 => Some(best),
+                _ => Some(candidate)
+            })
+            .cloned()
+            .ok_or_else(|| Error::InvalidInput("no parameter combination produced a score".to_owned()))?;
+
+        info!("Best score {} with {:?}", best_score, best_params);
+
+        let best_model = fit(&best_params)?;
+
+        Ok(GridSearchModel {
+            best_params,
+            best_score,
+            best_model,
+            cv_results,
+        })
+    }
+
+    pub fn best_params(&self) -> &Params {
+        &self.best_params
+    }
+
+    /// Mean cross-validated score of the best combination.
+    pub fn best_score(&self) -> f64 {
+        self.best_score
+    }
+
+    /// The estimator built with `best_params`, refitted on all the training data.
+    pub fn best_model(&self) -> &M {
+        &self.best_model
+    }
+
+    /// Mean cross-validated score of every combination, in grid order.
+    pub fn cv_results(&self) -> &[(Params, f64)] {
+        &self.cv_results
+    }
+}
+
+impl<'a, M, Out> Predictor<ArrayView2<'a, f64>, Out> for GridSearchModel<M> where M: Predictor<ArrayView2<'a, f64>, Out> {
+    fn predict(&self, x: ArrayView2<'a, f64>) -> Out {
+        self.best_model.predict(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rune_core::Error;
+
+    use crate::cross_validation::tests::{linear_data, negative_mse, ShrunkSlope};
+    use crate::grid_search::{GridSearchCV, ParamGrid, ParamValue, Params};
+    use crate::splitting::k_fold::KFold;
+
+    #[test]
+    fn test_param_grid_combinations() {
+        let combinations = ParamGrid::new()
+            .add("max_depth", vec![2usize, 4])
+            .add("criterion", vec!["entropy", "gini"])
+            .combinations();
+
+        assert_eq!(combinations.len(), 4);
+        assert_eq!(combinations[1]["max_depth"], ParamValue::Int(2));
+        assert_eq!(combinations[1]["criterion"], ParamValue::from("gini"));
+    }
+
+    #[test]
+    fn test_grid_search_regressor() {
+        let (x, y) = linear_data();
+        let grid = ParamGrid::new().add("alpha", vec![100., 0., 10.]);
+        let build = |params: &Params| Ok(ShrunkSlope { alpha: params["alpha"].as_f64()? });
+
+        let model = GridSearchCV::new(build, grid, KFold::new(4), negative_mse).fit(x.view(), y.view()).unwrap();
+
+        assert_eq!(model.best_params()["alpha"], ParamValue::Float(0.));
+        assert!(model.best_score().abs() < 1e-12);
+        assert_eq!(model.cv_results().len(), 3);
+    }
+
+    #[test]
+    fn test_grid_search_fails_on_a_parameter_it_cannot_build() {
+        let (x, y) = linear_data();
+        let grid = ParamGrid::new().add("alpha", vec!["none"]);
+        let build = |params: &Params| Ok(ShrunkSlope { alpha: params["alpha"].as_f64()? });
+
+        let result = GridSearchCV::new(build, grid, KFold::new(4), negative_mse).fit(x.view(), y.view());
+
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    }
+}
@@ -1 +1,3 @@
+pub mod cross_validation;
+pub mod grid_search;
 pub mod splitting;
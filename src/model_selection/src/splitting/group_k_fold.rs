@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use ndarray::Array1;
+use rune_core::{Error, Result};
+
+use crate::splitting::k_fold::{folds_from_test_sets, Fold};
+
+/// K-fold variant where all rows sharing a group id land in the same fold, so correlated rows
+/// (e.g. repeated measurements of one subject) never straddle the train/test boundary. Groups are
+/// assigned largest first to the currently smallest fold to keep the folds balanced.
+#[derive(Debug, Clone)]
+pub struct GroupKFold<G> {
+    n_splits: usize,
+    pub(crate) groups: Array1<G>,
+}
+
+impl<G: Eq + Hash> GroupKFold<G> {
+    /// `groups[i]` is the group id of row `i`.
+    pub fn new(n_splits: usize, groups: Array1<G>) -> Self {
+        GroupKFold {
+            n_splits,
+            groups,
+        }
+    }
+
+    pub fn n_splits(&self) -> usize {
+        self.n_splits
+    }
+
+    pub fn split(&self) -> Result<Vec<Fold>> {
+        if self.n_splits < 2 {
+            return Err(Error::InvalidParameter(format!("GroupKFold requires at least 2 splits, got {}", self.n_splits)));
+        }
+
+        let mut group_order: Vec<&G> = Vec::new();
+        let mut members: HashMap<&G, Vec<usize>> = HashMap::new();
+
+        for (index, group) in self.groups.iter().enumerate() {
+            members.entry(group)
+                .or_insert_with(|| {
+                    group_order.push(group);
+                    Vec::new()
+                })
+                .push(index);
+        }
+
+        if group_order.len() < self.n_splits {
+            return Err(Error::InvalidInput(format!("Cannot split {} groups into {} folds", group_order.len(), self.n_splits)));
+        }
+
+        group_order.sort_by_key(|group| std::cmp::Reverse(members[group].len()));
+
+        let mut test_sets: Vec<Vec<usize>> = vec![Vec::new(); self.n_splits];
+
+        for group in group_order {
+            let smallest = (0..self.n_splits).min_by_key(|&fold| test_sets[fold].len()).unwrap();
+            test_sets[smallest].extend_from_slice(&members[group]);
+        }
+
+        for test in test_sets.iter_mut() {
+            test.sort_unstable();
+        }
+
+        Ok(folds_from_test_sets(self.groups.len(), test_sets))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1};
+    use rune_core::Error;
+
+    use crate::splitting::group_k_fold::GroupKFold;
+    use crate::splitting::CrossValidator;
+
+    #[test]
+    fn test_groups_never_straddle_folds() {
+        let groups = array![1, 1, 1, 2, 2, 3, 3, 4, 5, 5];
+        let folds = GroupKFold::new(3, groups.clone()).split().unwrap();
+
+        assert_eq!(folds.len(), 3);
+        for (train, test) in folds {
+            for &i in &test {
+                assert!(train.iter().all(|&j| groups[j] != groups[i]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_too_few_groups_is_an_error() {
+        let result = GroupKFold::new(3, array![1, 1, 2, 2]).split();
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+
+        let result = GroupKFold::new(1, array![1, 2, 3]).split();
+        assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_one_group_per_sample_is_required() {
+        let y = Array1::from(vec![0; 5]);
+        let result = GroupKFold::new(2, array![1, 1, 2, 2]).folds(y.view());
+        assert!(matches!(result, Err(Error::ShapeMismatch(_))));
+    }
+}
@@ -0,0 +1,118 @@
+use rand::prelude::*;
+use rune_core::random::RngSource;
+use rune_core::{Error, Result};
+
+pub type TrainIndexes = Vec<usize>;
+pub type TestIndexes = Vec<usize>;
+
+pub type Fold = (TrainIndexes, TestIndexes);
+
+/// Splits `0..n_samples` into `n_splits` consecutive folds, each one used once as the test set
+/// while the remaining folds form the training set. The first `n_samples % n_splits` folds get
+/// one extra sample.
+#[derive(Debug, Clone)]
+pub struct KFold {
+    n_splits: usize,
+    shuffle: Option<RngSource>,
+}
+
+impl KFold {
+    pub fn new(n_splits: usize) -> Self {
+        KFold {
+            n_splits,
+            shuffle: None,
+        }
+    }
+
+    /// Shuffles the sample indexes with `rng` before cutting them into folds.
+    pub fn shuffled(n_splits: usize, rng: RngSource) -> Self {
+        KFold {
+            shuffle: Some(rng),
+            ..KFold::new(n_splits)
+        }
+    }
+
+    pub fn n_splits(&self) -> usize {
+        self.n_splits
+    }
+
+    pub fn split(&self, n_samples: usize) -> Result<Vec<Fold>> {
+        check_n_splits("KFold", self.n_splits, n_samples)?;
+
+        let mut indexes: Vec<usize> = (0..n_samples).collect();
+
+        if let Some(rng) = self.shuffle {
+            indexes.shuffle(&mut rng.rng());
+        }
+
+        let fold_size = n_samples / self.n_splits;
+        let remainder = n_samples % self.n_splits;
+
+        let mut test_sets = Vec::with_capacity(self.n_splits);
+        let mut start = 0;
+
+        for fold in 0..self.n_splits {
+            let end = start + fold_size + if fold < remainder { 1 } else { 0 };
+            test_sets.push(indexes[start..end].to_vec());
+            start = end;
+        }
+
+        Ok(folds_from_test_sets(n_samples, test_sets))
+    }
+}
+
+/// Checks that a splitter asks for at least 2 folds, each with at least one of the `n_samples`.
+pub(crate) fn check_n_splits(splitter: &str, n_splits: usize, n_samples: usize) -> Result<()> {
+    if n_splits < 2 {
+        return Err(Error::InvalidParameter(format!("{} requires at least 2 splits, got {}", splitter, n_splits)));
+    }
+    if n_samples < n_splits {
+        return Err(Error::InvalidInput(format!("Cannot split {} samples into {} folds", n_samples, n_splits)));
+    }
+    Ok(())
+}
+
+/// Pairs every test set with the training set made of all the samples it does not contain.
+pub(crate) fn folds_from_test_sets(n_samples: usize, test_sets: Vec<TestIndexes>) -> Vec<Fold> {
+    test_sets.into_iter()
+        .map(|test| {
+            let mut in_test = vec![false; n_samples];
+            for &index in &test {
+                in_test[index] = true;
+            }
+
+            let train = (0..n_samples).filter(|&index| !in_test[index]).collect();
+            (train, test)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rune_core::random::RngSource;
+    use rune_core::Error;
+
+    use crate::splitting::k_fold::KFold;
+
+    #[test]
+    fn test_every_sample_is_tested_once() {
+        let folds = KFold::shuffled(3, RngSource::Seed(7)).split(10).unwrap();
+
+        let mut tested: Vec<usize> = folds.iter().flat_map(|(_, test)| test.clone()).collect();
+        tested.sort();
+
+        assert_eq!(tested, (0..10).collect::<Vec<usize>>());
+        assert_eq!(folds.iter().map(|(_, test)| test.len()).collect::<Vec<usize>>(), vec![4, 3, 3]);
+
+        for (train, test) in folds {
+            assert_eq!(train.len() + test.len(), 10);
+            assert!(train.iter().all(|i| !test.contains(i)));
+        }
+    }
+
+    #[test]
+    fn test_invalid_splits_are_errors() {
+        assert!(matches!(KFold::new(5).split(4), Err(Error::InvalidInput(_))));
+        assert!(matches!(KFold::new(1).split(10), Err(Error::InvalidParameter(_))));
+    }
+}
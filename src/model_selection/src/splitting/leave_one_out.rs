@@ -0,0 +1,46 @@
+use rune_core::{Error, Result};
+
+use crate::splitting::k_fold::Fold;
+
+/// One fold per sample, testing on that single sample and training on all the others.
+#[derive(Debug, Clone, Default)]
+pub struct LeaveOneOut {}
+
+impl LeaveOneOut {
+    pub fn new() -> Self {
+        LeaveOneOut {}
+    }
+
+    pub fn split(&self, n_samples: usize) -> Result<Vec<Fold>> {
+        if n_samples < 2 {
+            return Err(Error::InvalidInput(format!("LeaveOneOut requires at least 2 samples, got {}", n_samples)));
+        }
+
+        Ok((0..n_samples)
+            .map(|test| {
+                let train = (0..n_samples).filter(|&index| index != test).collect();
+                (train, vec![test])
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rune_core::Error;
+
+    use crate::splitting::leave_one_out::LeaveOneOut;
+
+    #[test]
+    fn test_every_sample_is_tested_alone() {
+        let folds = LeaveOneOut::new().split(4).unwrap();
+
+        assert_eq!(folds.len(), 4);
+        assert_eq!(folds[2], (vec![0, 1, 3], vec![2]));
+    }
+
+    #[test]
+    fn test_too_few_samples_is_an_error() {
+        assert!(matches!(LeaveOneOut::new().split(1), Err(Error::InvalidInput(_))));
+    }
+}
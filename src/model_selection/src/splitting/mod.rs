@@ -1 +1,55 @@
-pub mod train_test_split;
\ No newline at end of file
+use std::hash::Hash;
+
+use ndarray::ArrayView1;
+use rune_core::{Error, Result};
+
+use crate::splitting::group_k_fold::GroupKFold;
+use crate::splitting::k_fold::{Fold, KFold};
+use crate::splitting::leave_one_out::LeaveOneOut;
+use crate::splitting::stratified_k_fold::StratifiedKFold;
+
+pub mod group_k_fold;
+pub mod k_fold;
+pub mod leave_one_out;
+pub mod stratified_k_fold;
+pub mod train_test_split;
+
+/// Produces the `(train, test)` folds used to cross-validate an estimator on the targets `y`.
+/// Splitters that only look at the number of samples, such as `KFold`, do so for targets of any
+/// type `Y`, e.g. the `f64` targets of a regressor; `StratifiedKFold` needs discrete labels.
+pub trait CrossValidator<Y> {
+    fn folds(&self, y: ArrayView1<Y>) -> Result<Vec<Fold>>;
+}
+
+impl<Y, C: CrossValidator<Y> + ?Sized> CrossValidator<Y> for &C {
+    fn folds(&self, y: ArrayView1<Y>) -> Result<Vec<Fold>> {
+        (**self).folds(y)
+    }
+}
+
+impl<Y> CrossValidator<Y> for KFold {
+    fn folds(&self, y: ArrayView1<Y>) -> Result<Vec<Fold>> {
+        self.split(y.len())
+    }
+}
+
+impl<Y: Eq + Hash> CrossValidator<Y> for StratifiedKFold {
+    fn folds(&self, y: ArrayView1<Y>) -> Result<Vec<Fold>> {
+        self.split(y)
+    }
+}
+
+impl<Y> CrossValidator<Y> for LeaveOneOut {
+    fn folds(&self, y: ArrayView1<Y>) -> Result<Vec<Fold>> {
+        self.split(y.len())
+    }
+}
+
+impl<G: Eq + Hash, Y> CrossValidator<Y> for GroupKFold<G> {
+    fn folds(&self, y: ArrayView1<Y>) -> Result<Vec<Fold>> {
+        if y.len() != self.groups.len() {
+            return Err(Error::ShapeMismatch(format!("one group id is required per sample, got {} for {} samples", self.groups.len(), y.len())));
+        }
+        self.split()
+    }
+}
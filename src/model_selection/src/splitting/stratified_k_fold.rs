@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use ndarray::ArrayView1;
+use rand::prelude::*;
+use rune_core::random::RngSource;
+use rune_core::Result;
+
+use crate::splitting::k_fold::{check_n_splits, folds_from_test_sets, Fold};
+
+/// Like `KFold`, but deals the samples of each class out across the folds so every fold keeps
+/// (within one sample) the class proportions of the whole dataset.
+#[derive(Debug, Clone)]
+pub struct StratifiedKFold {
+    n_splits: usize,
+    shuffle: Option<RngSource>,
+}
+
+impl StratifiedKFold {
+    pub fn new(n_splits: usize) -> Self {
+        StratifiedKFold {
+            n_splits,
+            shuffle: None,
+        }
+    }
+
+    /// Shuffles the samples within each class with `rng` before dealing them out.
+    pub fn shuffled(n_splits: usize, rng: RngSource) -> Self {
+        StratifiedKFold {
+            shuffle: Some(rng),
+            ..StratifiedKFold::new(n_splits)
+        }
+    }
+
+    pub fn n_splits(&self) -> usize {
+        self.n_splits
+    }
+
+    pub fn split<T: Eq + Hash>(&self, y: ArrayView1<T>) -> Result<Vec<Fold>> {
+        check_n_splits("StratifiedKFold", self.n_splits, y.len())?;
+
+        let mut class_order: Vec<&T> = Vec::new();
+        let mut classes: HashMap<&T, Vec<usize>> = HashMap::new();
+
+        for (index, label) in y.iter().enumerate() {
+            classes.entry(label)
+                .or_insert_with(|| {
+                    class_order.push(label);
+                    Vec::new()
+                })
+                .push(index);
+        }
+
+        let mut rng = self.shuffle.map(RngSource::rng);
+        let mut test_sets = vec![Vec::new(); self.n_splits];
+        let mut dealt = 0;
+
+        for label in class_order {
+            let indexes = classes.get_mut(label).unwrap();
+
+            if let Some(rng) = rng.as_mut() {
+                indexes.shuffle(rng);
+            }
+
+            for &index in indexes.iter() {
+                test_sets[dealt % self.n_splits].push(index);
+                dealt += 1;
+            }
+        }
+
+        for test in test_sets.iter_mut() {
+            test.sort_unstable();
+        }
+
+        Ok(folds_from_test_sets(y.len(), test_sets))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use rune_core::random::RngSource;
+
+    use crate::splitting::stratified_k_fold::StratifiedKFold;
+
+    #[test]
+    fn test_class_proportions_are_preserved() {
+        let y = array!["a", "a", "a", "a", "a", "a", "b", "b", "b", "c", "c", "c"];
+
+        for (_, test) in StratifiedKFold::shuffled(3, RngSource::Seed(42)).split(y.view()).unwrap() {
+            let labels: Vec<&str> = test.iter().map(|&i| y[i]).collect();
+
+            assert_eq!(labels.iter().filter(|&&l| l == "a").count(), 2);
+            assert_eq!(labels.iter().filter(|&&l| l == "b").count(), 1);
+            assert_eq!(labels.iter().filter(|&&l| l == "c").count(), 1);
+        }
+    }
+}
@@ -1,32 +1,129 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
 use ndarray::{Array1, Array2, ArrayView1, ArrayView2, Axis};
 use rand::prelude::*;
+use rune_core::random::RngSource;
+
+fn select_split<X: Copy, Y: Copy>(x: ArrayView2<X>, y: ArrayView1<Y>, left: &[usize], right: &[usize]) -> (Array2<X>, Array2<X>, Array1<Y>, Array1<Y>) {
+    (
+        x.select(Axis(0), left),
+        x.select(Axis(0), right),
+        y.select(Axis(0), left),
+        y.select(Axis(0), right)
+    )
+}
+
+/// Shuffles the rows and puts exactly `round(n * ratio)` of them in the training set. Passing a
+/// seeded `rng` makes the split reproducible.
+pub fn train_test_split<X: Copy, Y: Copy>(x: ArrayView2<X>, y: ArrayView1<Y>, ratio: f32, rng: RngSource) -> (Array2<X>, Array2<X>, Array1<Y>, Array1<Y>) {
+    let mut rng = rng.rng();
+
+    let mut vec: Vec<usize> = (0..x.nrows()).collect();
+    vec.shuffle(&mut rng);
+
+    let cut = (x.nrows() as f32 * ratio).round() as usize;
+    let (left, right) = vec.split_at(cut.min(x.nrows()));
+
+    select_split(x, y, left, right)
+}
+
+pub type TrainValTestSplit<X, Y> = (Array2<X>, Array2<X>, Array2<X>, Array1<Y>, Array1<Y>, Array1<Y>);
+
+/// Shuffles the rows once and cuts them into training, validation and test sets sized by the
+/// `(train, validation, test)` fractions in `ratios`, which must sum to one. Returns
+/// `(x_train, x_val, x_test, y_train, y_val, y_test)`.
+pub fn train_val_test_split<X: Copy, Y: Copy>(x: ArrayView2<X>, y: ArrayView1<Y>, ratios: (f32, f32, f32), rng: RngSource) -> TrainValTestSplit<X, Y> {
+    let (train_ratio, val_ratio, test_ratio) = ratios;
+    assert!((train_ratio + val_ratio + test_ratio - 1.).abs() < 1e-6, "split ratios must sum to 1, got {:?}", ratios);
+
+    let mut rng = rng.rng();
+
+    let mut vec: Vec<usize> = (0..x.nrows()).collect();
+    vec.shuffle(&mut rng);
+
+    let train_end = ((x.nrows() as f32 * train_ratio).round() as usize).min(x.nrows());
+    let val_end = ((x.nrows() as f32 * (train_ratio + val_ratio)).round() as usize).min(x.nrows());
+
+    let (train, rest) = vec.split_at(train_end);
+    let (val, test) = rest.split_at(val_end - train_end);
+
+    (
+        x.select(Axis(0), train),
+        x.select(Axis(0), val),
+        x.select(Axis(0), test),
+        y.select(Axis(0), train),
+        y.select(Axis(0), val),
+        y.select(Axis(0), test)
+    )
+}
+
+/// Like `train_test_split`, but cuts each class separately so the training and test sets keep
+/// the label proportions of `y`.
+pub fn stratified_train_test_split<X: Copy, Y: Copy + Eq + Hash>(x: ArrayView2<X>, y: ArrayView1<Y>, ratio: f32, rng: RngSource) -> (Array2<X>, Array2<X>, Array1<Y>, Array1<Y>) {
+    let mut rng = rng.rng();
+
+    let mut class_order: Vec<Y> = Vec::new();
+    let mut classes: HashMap<Y, Vec<usize>> = HashMap::new();
+
+    for (index, &label) in y.iter().enumerate() {
+        classes.entry(label)
+            .or_insert_with(|| {
+                class_order.push(label);
+                Vec::new()
+            })
+            .push(index);
+    }
 
-pub fn train_test_split<X: Copy, Y: Copy>(x: ArrayView2<X>, y: ArrayView1<Y>, ratio: f32) -> (Array2<X>, Array2<X>, Array1<Y>, Array1<Y>) {
-    let mut rng = rand::thread_rng();
     let mut left = Vec::new();
     let mut right = Vec::new();
 
-    let mut vec: Vec<usize> = (0..x.nrows()).collect();
-    vec.shuffle(&mut thread_rng());
+    for label in class_order {
+        let indexes = classes.get_mut(&label).unwrap();
+        indexes.shuffle(&mut rng);
+
+        let cut = ((indexes.len() as f32 * ratio).round() as usize).min(indexes.len());
+        left.extend_from_slice(&indexes[..cut]);
+        right.extend_from_slice(&indexes[cut..]);
+    }
+
+    left.shuffle(&mut rng);
+    right.shuffle(&mut rng);
+
+    select_split(x, y, &left, &right)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{Array1, Array2};
+    use rune_core::random::RngSource;
 
+    use crate::splitting::train_test_split::{stratified_train_test_split, train_test_split};
 
-    for idx in vec {
-        let n1: f32 = rng.gen();
+    #[test]
+    fn test_seeded_split_is_exact_and_reproducible() {
+        let x = Array2::from_shape_fn((10, 2), |(i, j)| i * 2 + j);
+        let y = Array1::from_shape_fn(10, |i| i);
 
-        if n1 < ratio {
-            left.push(idx)
-        } else {
-            right.push(idx)
-        }
+        let (x_train, x_test, y_train, y_test) = train_test_split(x.view(), y.view(), 0.7, RngSource::Seed(3));
+        let (x_train_again, _, _, _) = train_test_split(x.view(), y.view(), 0.7, RngSource::Seed(3));
+
+        assert_eq!(x_train.nrows(), 7);
+        assert_eq!(x_test.nrows(), 3);
+        assert_eq!(x_train, x_train_again);
+        assert_eq!(x_train.column(0).mapv(|v| v / 2), y_train);
+        assert_eq!(x_test.column(0).mapv(|v| v / 2), y_test);
     }
 
-    let left_indexes = left.as_slice();
-    let right_indexes = right.as_slice();
+    #[test]
+    fn test_stratified_split_keeps_proportions() {
+        let x = Array2::from_shape_fn((20, 1), |(i, _)| i);
+        let y = Array1::from_shape_fn(20, |i| i < 5);
+
+        let (_, _, y_train, y_test) = stratified_train_test_split(x.view(), y.view(), 0.8, RngSource::Seed(1));
 
-    return (
-        x.select(Axis(0), left_indexes),
-        x.select(Axis(0), right_indexes),
-        y.select(Axis(0), left_indexes),
-        y.select(Axis(0), right_indexes)
-    );
-}
\ No newline at end of file
+        assert_eq!(y_train.iter().filter(|&&label| label).count(), 4);
+        assert_eq!(y_test.iter().filter(|&&label| label).count(), 1);
+        assert_eq!(y_test.len(), 4);
+    }
+}
@@ -0,0 +1,111 @@
+//! Saving fitted models and loading them back, so a model trained in one process can serve
+//! predictions in another. Every fitted model in rune implements serde's `Serialize` and
+//! `Deserialize`, and so does a `PipelineModel` of them.
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use rune_core::{Error, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+#[cfg(feature = "fs")]
+use {
+    log::info,
+    std::fs::File,
+    std::io::{BufReader, BufWriter},
+};
+
+/// How a model is encoded: JSON is readable and portable, bincode is compact and fast to load.
+/// Bincode isn't self-describing, so the model type read must be exactly the type written.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Bincode,
+}
+
+impl Format {
+    /// `Bincode` for paths ending in `.bin` or `.bincode`, `Json` for any other.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Format {
+        match path.as_ref().extension().and_then(|extension| extension.to_str()) {
+            Some("bin") | Some("bincode") => Format::Bincode,
+            _ => Format::Json,
+        }
+    }
+}
+
+pub fn write_model<M: Serialize, W: Write>(model: &M, writer: W, format: Format) -> Result<()> {
+    match format {
+        Format::Json => serde_json::to_writer(writer, model).map_err(|e| Error::Serialization(e.to_string())),
+        Format::Bincode => bincode::serialize_into(writer, model).map_err(|e| Error::Serialization(e.to_string())),
+    }
+}
+
+pub fn read_model<M: DeserializeOwned, R: Read>(reader: R, format: Format) -> Result<M> {
+    match format {
+        Format::Json => serde_json::from_reader(reader).map_err(|e| Error::Serialization(e.to_string())),
+        Format::Bincode => bincode::deserialize_from(reader).map_err(|e| Error::Serialization(e.to_string())),
+    }
+}
+
+/// Writes `model` to `path`, in the format its extension calls for, see `Format::from_path`.
+#[cfg(feature = "fs")]
+pub fn save_model<M: Serialize, P: AsRef<Path>>(model: &M, path: P) -> Result<()> {
+    let format = Format::from_path(&path);
+    info!("Saving model to {} as {:?}", path.as_ref().display(), format);
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_model(model, &mut writer, format)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a model written by `save_model`. `M` must be the type that was saved.
+#[cfg(feature = "fs")]
+pub fn load_model<M: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<M> {
+    let format = Format::from_path(&path);
+    info!("Loading model from {} as {:?}", path.as_ref().display(), format);
+
+    read_model(BufReader::new(File::open(path)?), format)
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1};
+    use serde::{Deserialize, Serialize};
+
+    use crate::{read_model, write_model, Format};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Model {
+        beta: Array1<f64>,
+        intercept: f64,
+    }
+
+    #[test]
+    fn test_models_round_trip_in_both_formats() {
+        let model = Model { beta: array![0.1, -2.5, 1e-300], intercept: 3. };
+
+        for format in [Format::Json, Format::Bincode].iter() {
+            let mut bytes = vec![];
+            write_model(&model, &mut bytes, *format).unwrap();
+            assert_eq!(read_model::<Model, _>(&bytes[..], *format).unwrap(), model);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn test_save_model_picks_the_format_from_the_extension() {
+        let model = Model { beta: array![1., 2.], intercept: 0.5 };
+        let dir = std::env::temp_dir();
+
+        for name in ["rune-persist-test.json", "rune-persist-test.bin"].iter() {
+            let path = dir.join(name);
+            crate::save_model(&model, &path).unwrap();
+            assert_eq!(crate::load_model::<Model, _>(&path).unwrap(), model);
+            std::fs::remove_file(path).unwrap();
+        }
+
+        assert_eq!(Format::from_path("model.bincode"), Format::Bincode);
+        assert_eq!(Format::from_path("model"), Format::Json);
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use log::{debug, warn};
+use ndarray::{Array2, ArrayBase, ArrayView1, Data, Ix2};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use rune_core::{Error, Result};
+
+use crate::params::{ParamValue, Parameterized, Params};
+use crate::pipeline::{Fit, FitTransform, Transformer};
+
+/// Where `Cached` steps keep their fitted transformers together with the transformed training
+/// data. Entries are stored as JSON under the SHA-256 digest of everything they were computed
+/// from, so a directory cache can be reused by later runs and builds.
+pub enum StepCache {
+    Memory(Mutex<HashMap<String, String>>),
+    Directory(PathBuf),
+}
+
+impl StepCache {
+    pub fn in_memory() -> Self {
+        StepCache::Memory(Mutex::new(HashMap::new()))
+    }
+
+    pub fn directory<P: Into<PathBuf>>(path: P) -> Result<Self> {
+        let path = path.into();
+        fs::create_dir_all(&path)?;
+        Ok(StepCache::Directory(path))
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>> {
+        match self {
+            StepCache::Memory(entries) => Ok(entries.lock().unwrap().get(key).cloned()),
+            StepCache::Directory(path) => {
+                let file = path.join(format!("{}.json", key));
+                if file.exists() {
+                    Ok(Some(fs::read_to_string(file)?))
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    fn insert(&self, key: &str, entry: String) -> Result<()> {
+        match self {
+            StepCache::Memory(entries) => {
+                entries.lock().unwrap().insert(key.to_owned(), entry);
+                Ok(())
+            }
+            StepCache::Directory(path) => Ok(fs::write(path.join(format!("{}.json", key)), entry)?),
+        }
+    }
+}
+
+/// Inputs a `Cached` step can key its entries on, by writing out every byte that identifies
+/// them.
+pub trait CacheInput {
+    fn write_input<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+}
+
+impl<S: Data<Elem = f64>> CacheInput for ArrayBase<S, Ix2> {
+    fn write_input<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let (rows, columns) = self.dim();
+        writer.write_all(&(rows as u64).to_le_bytes())?;
+        writer.write_all(&(columns as u64).to_le_bytes())?;
+        for value in self.iter() {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// A cached fit, with the digest it was stored under and what that digest covers besides the
+/// data, so an entry is only ever loaded for the step it was computed by.
+#[derive(Serialize, Deserialize)]
+struct Entry<T, X> {
+    key: String,
+    tag: String,
+    params: Params,
+    transformer: T,
+    transformed: X,
+}
+
+/// Wraps a step so fitting it on inputs, labels and parameters it has seen before loads the
+/// fitted transformer and its transformed output from `cache` instead of recomputing them, e.g.
+/// to share the scaler of every `GridSearchCV` candidate that only differs in later steps:
+///
+/// `PipelineBuilder::new(Cached::new("scaler", StandardScaler::new(), cache.clone())).then(...)`
+pub struct Cached<F> {
+    tag: String,
+    step: F,
+    cache: Arc<StepCache>,
+}
+
+impl<F> Cached<F> {
+    /// `tag` names the kind of step in the cache keys, in place of its Rust type name, which
+    /// isn't stable between compilers. Steps sharing a cache need distinct tags.
+    pub fn new(tag: &str, step: F, cache: Arc<StepCache>) -> Self {
+        Cached { tag: tag.to_owned(), step, cache }
+    }
+
+    /// The hex SHA-256 digest of the tag, parameters, `x` and `y`, each length-prefixed or
+    /// fixed-size so that no two different inputs write the same bytes.
+    fn key<In: CacheInput, Y: Serialize>(&self, x: &In, y: ArrayView1<Y>) -> Result<String> where F: Parameterized {
+        let mut hasher = Sha256::new();
+
+        for part in [to_json(&self.tag)?, to_json(&self.step.get_params())?, to_json(&y)?] {
+            hasher.update((part.len() as u64).to_le_bytes());
+            hasher.update(&part);
+        }
+        x.write_input(&mut hasher)?;
+
+        Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+}
+
+impl<F: Parameterized> Parameterized for Cached<F> {
+    fn get_params(&self) -> Params {
+        self.step.get_params()
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        self.step.set_param(name, value)
+    }
+}
+
+impl<F, In, T, Y> Fit<In, T, Y> for Cached<F>
+    where
+        F: FitTransform<In, T, Array2<f64>, Y> + Parameterized,
+        In: Clone + CacheInput,
+        T: Transformer<In, Array2<f64>> + Serialize + DeserializeOwned,
+        Y: Serialize {
+    fn fit(&self, x: In, y: ArrayView1<Y>) -> Result<T> {
+        self.fit_transform(x, y).map(|(transformer, _)| transformer)
+    }
+}
+
+impl<F, In, T, Y> FitTransform<In, T, Array2<f64>, Y> for Cached<F>
+    where
+        F: FitTransform<In, T, Array2<f64>, Y> + Parameterized,
+        In: Clone + CacheInput,
+        T: Transformer<In, Array2<f64>> + Serialize + DeserializeOwned,
+        Y: Serialize {
+    fn fit_transform(&self, x: In, y: ArrayView1<Y>) -> Result<(T, Array2<f64>)> {
+        let key = self.key(&x, y)?;
+
+        if let Some(entry) = self.cache.get(&key)? {
+            let entry: Entry<T, Array2<f64>> = serde_json::from_str(&entry).map_err(|e| Error::Serialization(e.to_string()))?;
+            if entry.key == key {
+                debug!("Reusing cached {} ({})", self.tag, key);
+                return Ok((entry.transformer, entry.transformed));
+            }
+            warn!("Ignoring the cache entry for {} stored under {}, which was computed for {}", self.tag, key, entry.key);
+        }
+
+        let (transformer, transformed) = self.step.fit_transform(x, y)?;
+        let entry = Entry { key: key.clone(), tag: self.tag.clone(), params: self.step.get_params(), transformer: &transformer, transformed: &transformed };
+        self.cache.insert(&key, serde_json::to_string(&entry).map_err(|e| Error::Serialization(e.to_string()))?)?;
+
+        Ok((transformer, transformed))
+    }
+}
+
+fn to_json<V: Serialize + ?Sized>(value: &V) -> Result<Vec<u8>> {
+    serde_json::to_vec(value).map_err(|e| Error::Serialization(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use ndarray::{array, Array2, ArrayView1, ArrayView2};
+    use serde::{Deserialize, Serialize};
+
+    use rune_core::{Error, Result};
+
+    use crate::cache::{Cached, StepCache};
+    use crate::params::{ParamValue, Parameterized, Params};
+    use crate::pipeline::{Fit, FitTransform, Transformer};
+
+    /// Adds `offset` to every value, counting its fits.
+    struct Shift {
+        offset: f64,
+        fits: Arc<AtomicUsize>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ShiftTransformer {
+        offset: f64,
+    }
+
+    impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for ShiftTransformer {
+        fn transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+            &x + self.offset
+        }
+    }
+
+    impl<'a, Y> Fit<ArrayView2<'a, f64>, ShiftTransformer, Y> for Shift {
+        fn fit(&self, _x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<ShiftTransformer> {
+            self.fits.fetch_add(1, Ordering::SeqCst);
+            Ok(ShiftTransformer { offset: self.offset })
+        }
+    }
+
+    impl<'a, Y> FitTransform<ArrayView2<'a, f64>, ShiftTransformer, Array2<f64>, Y> for Shift {}
+
+    impl Parameterized for Shift {
+        fn get_params(&self) -> Params {
+            let mut params = Params::new();
+            params.insert("offset".to_owned(), self.offset.into());
+            params
+        }
+
+        fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+            match name {
+                "offset" => self.offset = value.as_f64()?,
+                _ => return Err(Error::InvalidParameter(format!("Shift has no parameter {}", name))),
+            }
+            Ok(())
+        }
+    }
+
+    fn cached(offset: f64, cache: &Arc<StepCache>) -> (Cached<Shift>, Arc<AtomicUsize>) {
+        let fits = Arc::new(AtomicUsize::new(0));
+        (Cached::new("shift", Shift { offset, fits: fits.clone() }, cache.clone()), fits)
+    }
+
+    #[test]
+    fn test_cached_hits_on_identical_input_and_misses_on_changes() {
+        let cache = Arc::new(StepCache::in_memory());
+        let (step, fits) = cached(1., &cache);
+        let x = array![[1., 2.], [3., 4.]];
+        let y = array![true, false];
+
+        let (first, transformed): (ShiftTransformer, _) = step.fit_transform(x.view(), y.view()).unwrap();
+        let (second, cached_transformed): (ShiftTransformer, _) = step.fit_transform(x.view(), y.view()).unwrap();
+        assert_eq!(fits.load(Ordering::SeqCst), 1);
+        assert_eq!(first, second);
+        assert_eq!(transformed, cached_transformed);
+
+        let _: ShiftTransformer = step.fit(array![[1., 2.], [3., 5.]].view(), y.view()).unwrap();
+        let _: ShiftTransformer = step.fit(x.view(), array![true, true].view()).unwrap();
+        assert_eq!(fits.load(Ordering::SeqCst), 3);
+
+        let (mut step, fits) = cached(1., &cache);
+        step.set_param("offset", 2.0.into()).unwrap();
+        let (transformer, _): (ShiftTransformer, _) = step.fit_transform(x.view(), y.view()).unwrap();
+        assert_eq!(fits.load(Ordering::SeqCst), 1);
+        assert_eq!(transformer.offset, 2.);
+    }
+
+    #[test]
+    fn test_cached_directory_round_trip() {
+        let path = std::env::temp_dir().join(format!("rune-step-cache-{}", std::process::id()));
+        let x = array![[0.5, -1.], [2., 0.]];
+        let y = array![1., 2.];
+
+        let (step, _) = cached(3., &Arc::new(StepCache::directory(&path).unwrap()));
+        let (_, transformed): (ShiftTransformer, _) = step.fit_transform(x.view(), y.view()).unwrap();
+
+        // A fresh cache over the same directory, as a later run would open.
+        let (step, fits) = cached(3., &Arc::new(StepCache::directory(&path).unwrap()));
+        let (transformer, reloaded): (ShiftTransformer, _) = step.fit_transform(x.view(), y.view()).unwrap();
+        assert_eq!(fits.load(Ordering::SeqCst), 0);
+        assert_eq!(transformer, ShiftTransformer { offset: 3. });
+        assert_eq!(reloaded, transformed);
+
+        // An entry whose stored key doesn't match the file it's in is refitted, not loaded.
+        let file = std::fs::read_dir(&path).unwrap().next().unwrap().unwrap().path();
+        let entry = std::fs::read_to_string(&file).unwrap();
+        let key = file.file_stem().unwrap().to_str().unwrap();
+        std::fs::write(&file, entry.replace(key, &"0".repeat(64))).unwrap();
+        let (step, fits) = cached(3., &Arc::new(StepCache::directory(&path).unwrap()));
+        let _: ShiftTransformer = step.fit(x.view(), y.view()).unwrap();
+        assert_eq!(fits.load(Ordering::SeqCst), 1);
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+use std::marker::PhantomData;
+
+use log::debug;
+use ndarray::{stack, Array2, ArrayView1, ArrayView2, Axis};
+
+use rune_core::parallel::parallel_map;
+use rune_core::{Error, Result};
+
+use crate::pipeline::{Fit, FitTransform, Transformer};
+
+/// Object-safe view of a `Fit` whose fitted transformer outputs `f64` features, so branches with
+/// different transformer types can live in the same `ColumnTransformer`. Every branch receives
+/// the labels, so supervised steps such as `TargetEncoder` can be used on their columns.
+pub trait ColumnFit<A, Y = bool>: Send + Sync {
+    fn fit_columns(&self, x: ArrayView2<A>, y: ArrayView1<Y>) -> Result<Box<dyn ColumnTransform<A>>>;
+}
+
+pub trait ColumnTransform<A>: Send + Sync {
+    fn transform_columns(&self, x: ArrayView2<A>) -> Array2<f64>;
+}
+
+struct Branch<F, T> {
+    fit: F,
+    _transformer: PhantomData<T>,
+}
+
+impl<A, F, T, Y> ColumnFit<A, Y> for Branch<F, T>
+    where
+        F: for<'a> Fit<ArrayView2<'a, A>, T, Y> + Send + Sync,
+        T: for<'a> Transformer<ArrayView2<'a, A>, Array2<f64>> + Send + Sync + 'static {
+    fn fit_columns(&self, x: ArrayView2<A>, y: ArrayView1<Y>) -> Result<Box<dyn ColumnTransform<A>>> {
+        Ok(Box::new(BranchTransform { transformer: self.fit.fit(x, y)? }))
+    }
+}
+
+struct BranchTransform<T> {
+    transformer: T,
+}
+
+impl<A, T> ColumnTransform<A> for BranchTransform<T> where T: for<'a> Transformer<ArrayView2<'a, A>, Array2<f64>> + Send + Sync {
+    fn transform_columns(&self, x: ArrayView2<A>) -> Array2<f64> {
+        self.transformer.transform(x)
+    }
+}
+
+/// Casts the selected columns to `f64` before handing them to a numeric transformer, e.g. to
+/// scale the measurement columns of an `Array2<Scalar>`.
+struct NumericBranch<F, T> {
+    fit: F,
+    _transformer: PhantomData<T>,
+}
+
+impl<A, F, T, Y> ColumnFit<A, Y> for NumericBranch<F, T>
+    where
+        A: Clone,
+        f64: From<A>,
+        F: for<'a> Fit<ArrayView2<'a, f64>, T, Y> + Send + Sync,
+        T: for<'a> Transformer<ArrayView2<'a, f64>, Array2<f64>> + Send + Sync + 'static {
+    fn fit_columns(&self, x: ArrayView2<A>, y: ArrayView1<Y>) -> Result<Box<dyn ColumnTransform<A>>> {
+        let x = x.mapv(f64::from);
+        Ok(Box::new(NumericBranchTransform { transformer: self.fit.fit(x.view(), y)? }))
+    }
+}
+
+struct NumericBranchTransform<T> {
+    transformer: T,
+}
+
+impl<A, T> ColumnTransform<A> for NumericBranchTransform<T>
+    where
+        A: Clone,
+        f64: From<A>,
+        T: for<'a> Transformer<ArrayView2<'a, f64>, Array2<f64>> + Send + Sync {
+    fn transform_columns(&self, x: ArrayView2<A>) -> Array2<f64> {
+        self.transformer.transform(x.mapv(f64::from).view())
+    }
+}
+
+type ColumnFits<A, Y> = Vec<(Vec<usize>, Box<dyn ColumnFit<A, Y>>)>;
+
+/// Fits a separate transformer on each set of column indexes and horizontally stacks their
+/// outputs in the order the branches were added. Columns not claimed by any branch are dropped.
+/// Fitting fails with `InvalidParameter` without any branch or if two branches share a column.
+/// The branches are independent, so they are fitted and transformed on `n_jobs` worker threads.
+pub struct ColumnTransformer<A, Y = bool> {
+    branches: ColumnFits<A, Y>,
+    n_jobs: usize,
+}
+
+pub struct ColumnTransformerModel<A> {
+    branches: Vec<(Vec<usize>, Box<dyn ColumnTransform<A>>)>,
+    n_jobs: usize,
+}
+
+impl<A: Clone + 'static, Y> ColumnTransformer<A, Y> {
+    pub fn new() -> Self {
+        ColumnTransformer { branches: Vec::new(), n_jobs: 1 }
+    }
+
+    /// Number of worker threads sharing the branches, for both fitting and transforming.
+    pub fn with_n_jobs(self, n_jobs: usize) -> Self {
+        ColumnTransformer { n_jobs, ..self }
+    }
+
+    fn push(mut self, columns: Vec<usize>, fit: Box<dyn ColumnFit<A, Y>>) -> Self {
+        self.branches.push((columns, fit));
+        self
+    }
+
+    pub fn add<F, T>(self, columns: Vec<usize>, fit: F) -> Self
+        where
+            F: for<'a> Fit<ArrayView2<'a, A>, T, Y> + Send + Sync + 'static,
+            T: for<'a> Transformer<ArrayView2<'a, A>, Array2<f64>> + Send + Sync + 'static {
+        self.push(columns, Box::new(Branch { fit, _transformer: PhantomData }))
+    }
+
+    pub fn add_numeric<F, T>(self, columns: Vec<usize>, fit: F) -> Self
+        where
+            f64: From<A>,
+            F: for<'a> Fit<ArrayView2<'a, f64>, T, Y> + Send + Sync + 'static,
+            T: for<'a> Transformer<ArrayView2<'a, f64>, Array2<f64>> + Send + Sync + 'static {
+        self.push(columns, Box::new(NumericBranch { fit, _transformer: PhantomData }))
+    }
+}
+
+impl<A: Clone + 'static, Y> Default for ColumnTransformer<A, Y> {
+    fn default() -> Self {
+        ColumnTransformer::new()
+    }
+}
+
+impl<A, Y> ColumnTransformer<A, Y> {
+    fn check_branches(&self, n_columns: usize) -> Result<()> {
+        if self.branches.is_empty() {
+            return Err(Error::InvalidParameter("a column transformer needs at least one branch".to_owned()));
+        }
+
+        for (i, (columns, _)) in self.branches.iter().enumerate() {
+            if let Some(column) = columns.iter().find(|&&column| column >= n_columns) {
+                return Err(Error::ShapeMismatch(format!("branch {} selects column {} of {}", i, column, n_columns)));
+            }
+            for (existing, _) in &self.branches[..i] {
+                if columns.iter().any(|column| existing.contains(column)) {
+                    return Err(Error::InvalidParameter(format!("column sets must be disjoint, {:?} overlaps {:?}", columns, existing)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn select_columns<A: Clone>(x: ArrayView2<A>, columns: &[usize]) -> Array2<A> {
+    Array2::from_shape_fn((x.nrows(), columns.len()), |(row, column)| x[[row, columns[column]]].clone())
+}
+
+impl<A: Clone + Sync, Y: Sync> Fit<ArrayView2<'_, A>, ColumnTransformerModel<A>, Y> for ColumnTransformer<A, Y> {
+    fn fit(&self, x: ArrayView2<A>, y: ArrayView1<Y>) -> Result<ColumnTransformerModel<A>> {
+        self.check_branches(x.ncols())?;
+
+        let branches = parallel_map(&self.branches, self.n_jobs, |(columns, fit)| {
+            debug!("Fitting column branch on columns {:?}", columns);
+            Ok((columns.clone(), fit.fit_columns(select_columns(x, columns).view(), y)?))
+        });
+
+        Ok(ColumnTransformerModel {
+            branches: branches.into_iter().collect::<Result<_>>()?,
+            n_jobs: self.n_jobs,
+        })
+    }
+}
+
+impl<'a, A: Clone + Sync, Y: Sync> FitTransform<ArrayView2<'a, A>, ColumnTransformerModel<A>, Array2<f64>, Y> for ColumnTransformer<A, Y> {}
+
+impl<A: Clone + Sync> Transformer<ArrayView2<'_, A>, Array2<f64>> for ColumnTransformerModel<A> {
+    fn transform(&self, x: ArrayView2<A>) -> Array2<f64> {
+        let outputs = parallel_map(&self.branches, self.n_jobs, |(columns, transformer)| {
+            transformer.transform_columns(select_columns(x, columns).view())
+        });
+
+        let views: Vec<ArrayView2<f64>> = outputs.iter().map(|o| o.view()).collect();
+
+        // Fitting checked there is at least one branch, so this only fails for transformers that
+        // change the number of rows.
+        stack(Axis(1), &views).expect("column branches returned a different number of rows")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array2, ArrayView1, ArrayView2, Axis};
+
+    use rune_core::{Error, Result};
+
+    use crate::column_transformer::{ColumnTransformer, ColumnTransformerModel};
+    use crate::pipeline::{Fit, Transformer};
+
+    /// Multiplies every column by `factor`.
+    struct Scale {
+        factor: f64,
+    }
+
+    impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for Scale {
+        fn transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+            &x * self.factor
+        }
+    }
+
+    impl<Y> Fit<ArrayView2<'_, f64>, Scale, Y> for Scale {
+        fn fit(&self, _x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<Scale> {
+            Ok(Scale { factor: self.factor })
+        }
+    }
+
+    /// Replaces its columns by their sum.
+    struct Sum;
+
+    impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for Sum {
+        fn transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+            x.sum_axis(Axis(1)).insert_axis(Axis(1))
+        }
+    }
+
+    impl<Y> Fit<ArrayView2<'_, f64>, Sum, Y> for Sum {
+        fn fit(&self, _x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<Sum> {
+            Ok(Sum)
+        }
+    }
+
+    #[test]
+    fn test_routes_columns_and_stacks_in_branch_order() {
+        let x = array![[1., 2., 3., 4.], [5., 6., 7., 8.]];
+        let y = array![true, false];
+
+        for &n_jobs in [1, 2].iter() {
+            let transformer = ColumnTransformer::new()
+                .add(vec![3], Scale { factor: 10. })
+                .add(vec![2, 0], Sum)
+                .with_n_jobs(n_jobs);
+            let model: ColumnTransformerModel<f64> = transformer.fit(x.view(), y.view()).unwrap();
+
+            // Column 1 isn't claimed, so it's dropped.
+            assert_eq!(model.transform(x.view()), array![[40., 4.], [80., 12.]]);
+        }
+    }
+
+    #[test]
+    fn test_invalid_branches_fail_to_fit() {
+        let x = array![[1., 2.], [3., 4.]];
+        let y = array![true, false];
+        let fit = |transformer: ColumnTransformer<f64>| -> Result<ColumnTransformerModel<f64>> { transformer.fit(x.view(), y.view()) };
+
+        assert!(matches!(fit(ColumnTransformer::new()), Err(Error::InvalidParameter(_))));
+        assert!(matches!(fit(ColumnTransformer::new().add(vec![0, 1], Sum).add(vec![1], Sum)), Err(Error::InvalidParameter(_))));
+        assert!(matches!(fit(ColumnTransformer::new().add(vec![2], Sum)), Err(Error::ShapeMismatch(_))));
+    }
+}
@@ -0,0 +1,480 @@
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Instant;
+
+use log::debug;
+use ndarray::{Array2, ArrayView1, ArrayView2};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use rune_core::{Error, Result};
+
+use crate::instrument::{Phase, PipelineReport};
+use crate::params::{ParamValue, Parameterized, Params};
+use crate::pipeline::{Fit, FitTransform, Transformer};
+
+/// Object-safe view of a `Fit` over feature matrices, so steps with different transformer types
+/// can be chosen at runtime and live in the same `DynPipeline`.
+pub trait Step<Y = bool>: Parameterized {
+    fn fit_step(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<Box<dyn FittedStep>>;
+}
+
+pub trait FittedStep: Send + Sync {
+    fn transform_step(&self, x: ArrayView2<f64>) -> Array2<f64>;
+
+    fn inverse_transform_step(&self, x: ArrayView2<f64>) -> Array2<f64>;
+
+    /// The fitted transformer, so a `StepRegistry` can find out how to save it.
+    fn as_any(&self) -> &dyn Any;
+}
+
+struct BoxedStep<F, T> {
+    fit: F,
+    _transformer: PhantomData<T>,
+}
+
+impl<F: Parameterized, T> Parameterized for BoxedStep<F, T> {
+    fn get_params(&self) -> Params {
+        self.fit.get_params()
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        self.fit.set_param(name, value)
+    }
+}
+
+impl<F, T, Y> Step<Y> for BoxedStep<F, T>
+    where
+        F: for<'a> Fit<ArrayView2<'a, f64>, T, Y> + Parameterized,
+        T: for<'a> Transformer<ArrayView2<'a, f64>, Array2<f64>> + Send + Sync + 'static {
+    fn fit_step(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<Box<dyn FittedStep>> {
+        Ok(Box::new(BoxedFittedStep { transformer: self.fit.fit(x, y)? }))
+    }
+}
+
+struct BoxedFittedStep<T> {
+    transformer: T,
+}
+
+impl<T> FittedStep for BoxedFittedStep<T> where T: for<'a> Transformer<ArrayView2<'a, f64>, Array2<f64>> + Send + Sync + 'static {
+    fn transform_step(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.transformer.transform(x)
+    }
+
+    fn inverse_transform_step(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.transformer.inverse_transform(x)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        &self.transformer
+    }
+}
+
+/// Erases the types of a fitter, e.g. so a configuration parser can return whichever step a
+/// spec names: `step(StandardScaler::new())`.
+pub fn step<F, T, Y>(fit: F) -> Box<dyn Step<Y>>
+    where
+        F: for<'a> Fit<ArrayView2<'a, f64>, T, Y> + Parameterized + 'static,
+        T: for<'a> Transformer<ArrayView2<'a, f64>, Array2<f64>> + Send + Sync + 'static {
+    Box::new(BoxedStep { fit, _transformer: PhantomData })
+}
+
+/// A pipeline whose steps are only known at runtime. Every step maps a feature matrix to a
+/// feature matrix; the fitted `DynPipelineModel` is itself a `Transformer`, so it can feed a
+/// statically typed estimator.
+///
+/// Steps are named, and their parameters are exposed as `<step>__<param>`, e.g.
+/// `set_param("pca__n_components", 2.into())`. Since the builder validates names, it returns
+/// `Result`: `DynPipeline::new().then("scaler", StandardScaler::new())?.then("pca", pca)?`.
+pub struct DynPipeline<Y = bool> {
+    steps: Vec<(String, Box<dyn Step<Y>>)>,
+    report: Option<Arc<PipelineReport>>,
+}
+
+pub struct DynPipelineModel {
+    steps: Vec<(String, Box<dyn FittedStep>)>,
+    report: Option<Arc<PipelineReport>>,
+}
+
+impl<Y: 'static> DynPipeline<Y> {
+    pub fn new() -> Self {
+        DynPipeline { steps: Vec::new(), report: None }
+    }
+
+    /// Records the time and output shape of every step in `report`, both while fitting and when
+    /// the fitted model transforms.
+    pub fn instrument(self, report: Arc<PipelineReport>) -> Self {
+        DynPipeline { report: Some(report), ..self }
+    }
+
+    /// Appends a step. Fails with `InvalidParameter` if `name` contains `__`, which would make
+    /// its parameters ambiguous, or if a step of that name already exists.
+    pub fn then<F, T>(self, name: &str, fit: F) -> Result<Self>
+        where
+            F: for<'a> Fit<ArrayView2<'a, f64>, T, Y> + Parameterized + 'static,
+            T: for<'a> Transformer<ArrayView2<'a, f64>, Array2<f64>> + Send + Sync + 'static {
+        self.then_boxed(name, step(fit))
+    }
+
+    pub fn then_boxed(mut self, name: &str, step: Box<dyn Step<Y>>) -> Result<Self> {
+        if name.contains("__") {
+            return Err(Error::InvalidParameter(format!("step name {} must not contain '__'", name)));
+        }
+        if self.steps.iter().any(|(existing, _)| existing == name) {
+            return Err(Error::InvalidParameter(format!("step name {} is used twice", name)));
+        }
+
+        self.steps.push((name.to_owned(), step));
+        Ok(self)
+    }
+
+    pub fn step_names(&self) -> Vec<&str> {
+        self.steps.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl<Y: 'static> Default for DynPipeline<Y> {
+    fn default() -> Self {
+        DynPipeline::new()
+    }
+}
+
+impl<Y> Parameterized for DynPipeline<Y> {
+    fn get_params(&self) -> Params {
+        self.steps.iter()
+            .flat_map(|(step_name, step)| {
+                step.get_params().into_iter().map(move |(name, value)| (format!("{}__{}", step_name, name), value))
+            })
+            .collect()
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        let (step_name, param) = name.split_once("__")
+            .ok_or_else(|| Error::InvalidParameter(format!("pipeline parameter {} is not of the form <step>__<param>", name)))?;
+
+        let (_, step) = self.steps.iter_mut()
+            .find(|(existing, _)| existing == step_name)
+            .ok_or_else(|| Error::InvalidParameter(format!("pipeline has no step named {}", step_name)))?;
+
+        step.set_param(param, value)
+    }
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, DynPipelineModel, Y> for DynPipeline<Y> {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<DynPipelineModel> {
+        self.fit_transform(x, y).map(|(model, _)| model)
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, f64>, DynPipelineModel, Array2<f64>, Y> for DynPipeline<Y> {
+    fn fit_transform(&self, x: ArrayView2<'a, f64>, y: ArrayView1<Y>) -> Result<(DynPipelineModel, Array2<f64>)> {
+        let mut steps = Vec::with_capacity(self.steps.len());
+        let mut current = x.to_owned();
+
+        for (name, step) in &self.steps {
+            debug!("Fitting pipeline step {}", name);
+            let start = Instant::now();
+            let fitted = step.fit_step(current.view(), y)?;
+            current = fitted.transform_step(current.view());
+
+            if let Some(report) = &self.report {
+                report.record(name, Phase::Fit, start.elapsed(), Some(current.dim()));
+            }
+            steps.push((name.clone(), fitted));
+        }
+
+        Ok((DynPipelineModel { steps, report: self.report.clone() }, current))
+    }
+}
+
+impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for DynPipelineModel {
+    fn transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.steps.iter().fold(x.to_owned(), |current, (name, step)| {
+            let start = Instant::now();
+            let transformed = step.transform_step(current.view());
+
+            if let Some(report) = &self.report {
+                report.record(name, Phase::Transform, start.elapsed(), Some(transformed.dim()));
+            }
+            transformed
+        })
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.steps.iter().rev().fold(x.to_owned(), |current, (_, step)| step.inverse_transform_step(current.view()))
+    }
+}
+
+/// The transformers a `DynPipelineModel` may hold, each under a name that's written with it when
+/// the model is saved. Boxed steps forget their types, so saving and loading go through the
+/// registry: `StepRegistry::new().register::<StandardScalerTransformer>("standard_scaler")`.
+#[derive(Default)]
+pub struct StepRegistry {
+    entries: Vec<RegisteredStep>,
+}
+
+struct RegisteredStep {
+    kind: String,
+    type_id: TypeId,
+    save: fn(&dyn Any) -> Result<String>,
+    load: fn(&str) -> Result<Box<dyn FittedStep>>,
+}
+
+/// The serializable form of a `DynPipelineModel`, made by `DynPipelineModel::save`. Each
+/// transformer is stored as JSON, so the whole can be written in any `rune_persist` format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedDynPipeline {
+    steps: Vec<SavedStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SavedStep {
+    name: String,
+    kind: String,
+    transformer: String,
+}
+
+impl StepRegistry {
+    pub fn new() -> Self {
+        StepRegistry { entries: Vec::new() }
+    }
+
+    /// Registers `T` under `kind`. Kinds are what saved models refer to, so they must be unique
+    /// and shouldn't change between versions.
+    pub fn register<T>(mut self, kind: &str) -> Self
+        where T: for<'a> Transformer<ArrayView2<'a, f64>, Array2<f64>> + Serialize + DeserializeOwned + Send + Sync + 'static {
+        self.entries.push(RegisteredStep { kind: kind.to_owned(), type_id: TypeId::of::<T>(), save: save_step::<T>, load: load_step::<T> });
+        self
+    }
+}
+
+fn save_step<T: Serialize + 'static>(transformer: &dyn Any) -> Result<String> {
+    let transformer = transformer.downcast_ref::<T>().expect("registered under the TypeId of T");
+    serde_json::to_string(transformer).map_err(|e| Error::Serialization(e.to_string()))
+}
+
+fn load_step<T>(json: &str) -> Result<Box<dyn FittedStep>>
+    where T: for<'a> Transformer<ArrayView2<'a, f64>, Array2<f64>> + DeserializeOwned + Send + Sync + 'static {
+    let transformer: T = serde_json::from_str(json).map_err(|e| Error::Serialization(e.to_string()))?;
+    Ok(Box::new(BoxedFittedStep { transformer }))
+}
+
+impl DynPipelineModel {
+    pub fn step_names(&self) -> Vec<&str> {
+        self.steps.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Fails with `Serialization` if a step's transformer isn't in `registry`.
+    pub fn save(&self, registry: &StepRegistry) -> Result<SavedDynPipeline> {
+        let steps = self.steps.iter()
+            .map(|(name, step)| {
+                let transformer = step.as_any();
+                let entry = registry.entries.iter()
+                    .find(|entry| entry.type_id == transformer.type_id())
+                    .ok_or_else(|| Error::Serialization(format!("the transformer of step {} isn't registered", name)))?;
+
+                Ok(SavedStep { name: name.clone(), kind: entry.kind.clone(), transformer: (entry.save)(transformer)? })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(SavedDynPipeline { steps })
+    }
+
+    /// Restores a model written by `save`, with `registry` knowing every kind it names. The model
+    /// isn't instrumented.
+    pub fn load(saved: &SavedDynPipeline, registry: &StepRegistry) -> Result<DynPipelineModel> {
+        let steps = saved.steps.iter()
+            .map(|step| {
+                let entry = registry.entries.iter()
+                    .find(|entry| entry.kind == step.kind)
+                    .ok_or_else(|| Error::Serialization(format!("step {} has unknown kind {}", step.name, step.kind)))?;
+
+                Ok((step.name.clone(), (entry.load)(&step.transformer)?))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(DynPipelineModel { steps, report: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, s, Array1, Array2, ArrayView1, ArrayView2, Axis};
+    use serde::{Deserialize, Serialize};
+
+    use rune_core::{Error, Result};
+
+    use crate::dyn_pipeline::{step, DynPipeline, DynPipelineModel, StepRegistry};
+    use crate::params::{ParamValue, Parameterized, Params};
+    use crate::pipeline::{Fit, FitTransform, Transformer};
+
+    /// Subtracts the column means, optionally scaled by `factor`.
+    struct Center {
+        factor: f64,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct CenterTransformer {
+        means: Array1<f64>,
+        factor: f64,
+    }
+
+    impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for CenterTransformer {
+        fn transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+            (&x - &self.means) * self.factor
+        }
+
+        fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+            &x / self.factor + &self.means
+        }
+    }
+
+    impl<Y> Fit<ArrayView2<'_, f64>, CenterTransformer, Y> for Center {
+        fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<CenterTransformer> {
+            Ok(CenterTransformer { means: x.mean_axis(Axis(0)).unwrap(), factor: self.factor })
+        }
+    }
+
+    impl Parameterized for Center {
+        fn get_params(&self) -> Params {
+            let mut params = Params::new();
+            params.insert("factor".to_owned(), self.factor.into());
+            params
+        }
+
+        fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+            match name {
+                "factor" => self.factor = value.as_f64()?,
+                _ => return Err(Error::InvalidParameter(format!("Center has no parameter {}", name))),
+            }
+            Ok(())
+        }
+    }
+
+    /// Keeps the first `n_columns` columns.
+    struct Keep {
+        n_columns: usize,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct KeepTransformer {
+        n_columns: usize,
+    }
+
+    impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for KeepTransformer {
+        fn transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+            x.slice(s![.., ..self.n_columns]).to_owned()
+        }
+    }
+
+    impl<Y> Fit<ArrayView2<'_, f64>, KeepTransformer, Y> for Keep {
+        fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<KeepTransformer> {
+            if self.n_columns > x.ncols() {
+                return Err(Error::InvalidParameter(format!("cannot keep {} of {} columns", self.n_columns, x.ncols())));
+            }
+            Ok(KeepTransformer { n_columns: self.n_columns })
+        }
+    }
+
+    impl Parameterized for Keep {
+        fn get_params(&self) -> Params {
+            let mut params = Params::new();
+            params.insert("n_columns".to_owned(), self.n_columns.into());
+            params
+        }
+
+        fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+            match name {
+                "n_columns" => self.n_columns = value.as_usize()?,
+                _ => return Err(Error::InvalidParameter(format!("Keep has no parameter {}", name))),
+            }
+            Ok(())
+        }
+    }
+
+    fn pipeline() -> DynPipeline {
+        DynPipeline::new()
+            .then("center", Center { factor: 1. }).unwrap()
+            .then_boxed("keep", step(Keep { n_columns: 2 })).unwrap()
+    }
+
+    #[test]
+    fn test_fits_a_chain_of_different_step_types() {
+        let x = array![[1., 10., 5.], [3., 20., 5.], [5., 30., 5.]];
+        let y = array![true, false, true];
+
+        let (model, transformed): (DynPipelineModel, _) = pipeline().fit_transform(x.view(), y.view()).unwrap();
+        let expected = array![[-2., -10.], [0., 0.], [2., 10.]];
+        assert_eq!(transformed, expected);
+        assert_eq!(model.transform(x.view()), expected);
+        assert_eq!(model.transform(array![[4., 40., 0.]].view()), array![[1., 20.]]);
+    }
+
+    #[test]
+    fn test_routes_step_params_by_name() {
+        let mut pipeline = pipeline();
+        assert_eq!(pipeline.step_names(), vec!["center", "keep"]);
+        assert_eq!(pipeline.get_params().len(), 2);
+
+        pipeline.set_param("center__factor", 2.0.into()).unwrap();
+        pipeline.set_param("keep__n_columns", 1usize.into()).unwrap();
+        let params = pipeline.get_params();
+        assert_eq!(params["center__factor"].as_f64().unwrap(), 2.);
+        assert_eq!(params["keep__n_columns"].as_usize().unwrap(), 1);
+
+        let x = array![[1., 10.], [3., 20.]];
+        let model: DynPipelineModel = pipeline.fit(x.view(), array![true, false].view()).unwrap();
+        assert_eq!(model.transform(x.view()), array![[-2.], [2.]]);
+
+        for name in &["factor", "scale__factor", "center__offset"] {
+            match pipeline.set_param(name, 1.0.into()) {
+                Err(Error::InvalidParameter(_)) => {}
+                other => panic!("expected InvalidParameter for {}, got {:?}", name, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_step_errors_surface_from_fit() {
+        let mut pipeline = pipeline();
+        pipeline.set_param("keep__n_columns", 4usize.into()).unwrap();
+        let fitted: Result<DynPipelineModel> = pipeline.fit(array![[1., 2.]].view(), array![true].view());
+        assert!(fitted.is_err());
+    }
+
+    #[test]
+    fn test_rejects_ambiguous_and_duplicate_names() {
+        let nested = DynPipeline::<bool>::new().then("center__inner", Center { factor: 1. });
+        assert!(matches!(nested, Err(Error::InvalidParameter(_))));
+
+        let duplicate = pipeline().then("center", Center { factor: 1. });
+        assert!(matches!(duplicate, Err(Error::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_saved_models_transform_like_the_original() {
+        let x = array![[1., 10., 5.], [3., 20., 5.], [5., 30., 5.]];
+        let model: DynPipelineModel = pipeline().fit(x.view(), array![true, false, true].view()).unwrap();
+        let registry = StepRegistry::new()
+            .register::<CenterTransformer>("center")
+            .register::<KeepTransformer>("keep");
+
+        let saved = serde_json::to_string(&model.save(&registry).unwrap()).unwrap();
+        let loaded = DynPipelineModel::load(&serde_json::from_str(&saved).unwrap(), &registry).unwrap();
+        assert_eq!(loaded.step_names(), vec!["center", "keep"]);
+        assert_eq!(loaded.transform(x.view()), model.transform(x.view()));
+
+        let partial = StepRegistry::new().register::<CenterTransformer>("center");
+        assert!(matches!(model.save(&partial), Err(Error::Serialization(_))));
+        assert!(matches!(DynPipelineModel::load(&model.save(&registry).unwrap(), &partial), Err(Error::Serialization(_))));
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ndarray::{Array2, ArrayView1, ArrayView2};
+use serde::{Deserialize, Serialize};
+
+use rune_core::Result;
+
+use crate::params::{ParamValue, Parameterized, Params};
+use crate::pipeline::{Fit, FitTransform, Transformer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Fit,
+    Transform,
+}
+
+/// Wall-clock time of one step running one phase, with the `(rows, columns)` it produced. Steps
+/// that were only fitted, without transforming their training data, have no output shape.
+#[derive(Debug, Clone)]
+pub struct StepRecord {
+    pub step: String,
+    pub phase: Phase,
+    pub elapsed: Duration,
+    pub output_shape: Option<(usize, usize)>,
+}
+
+/// Collects a `StepRecord` every time an instrumented step is fitted or transforms, in the order
+/// they ran. Shared between steps (and threads) through an `Arc`.
+#[derive(Debug, Default)]
+pub struct PipelineReport {
+    records: Mutex<Vec<StepRecord>>,
+}
+
+impl PipelineReport {
+    pub fn new() -> Self {
+        PipelineReport::default()
+    }
+
+    pub fn record(&self, step: &str, phase: Phase, elapsed: Duration, output_shape: Option<(usize, usize)>) {
+        self.records.lock().unwrap().push(StepRecord {
+            step: step.to_owned(),
+            phase,
+            elapsed,
+            output_shape,
+        });
+    }
+
+    pub fn records(&self) -> Vec<StepRecord> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// Time spent by `step` in `phase` over every run recorded so far.
+    pub fn total(&self, step: &str, phase: Phase) -> Duration {
+        self.records.lock().unwrap().iter()
+            .filter(|record| record.step == step && record.phase == phase)
+            .map(|record| record.elapsed)
+            .sum()
+    }
+
+    pub fn clear(&self) {
+        self.records.lock().unwrap().clear();
+    }
+}
+
+impl fmt::Display for PipelineReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<24} {:<10} {:>12} {:>14}", "step", "phase", "elapsed (ms)", "output")?;
+
+        for record in self.records.lock().unwrap().iter() {
+            let shape = match record.output_shape {
+                Some((rows, columns)) => format!("{} x {}", rows, columns),
+                None => "-".to_owned(),
+            };
+            writeln!(f, "{:<24} {:<10} {:>12.3} {:>14}", record.step, format!("{:?}", record.phase), record.elapsed.as_secs_f64() * 1000., shape)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Wraps a step of a statically typed pipeline so its fits and transforms are recorded in
+/// `report` under `name`:
+///
+/// `PipelineBuilder::new(Instrumented::new("scaler", StandardScaler::new(), report.clone()))`
+pub struct Instrumented<F> {
+    name: String,
+    step: F,
+    report: Arc<PipelineReport>,
+}
+
+/// The fitted counterpart of `Instrumented`. Only the wrapped transformer is saved with a
+/// `PipelineModel`; a loaded model records into a fresh report.
+#[derive(Serialize, Deserialize)]
+pub struct InstrumentedTransformer<T> {
+    name: String,
+    transformer: T,
+    #[serde(skip)]
+    report: Arc<PipelineReport>,
+}
+
+impl<F> Instrumented<F> {
+    pub fn new(name: &str, step: F, report: Arc<PipelineReport>) -> Self {
+        Instrumented {
+            name: name.to_owned(),
+            step,
+            report,
+        }
+    }
+
+    fn wrap<T>(&self, transformer: T) -> InstrumentedTransformer<T> {
+        InstrumentedTransformer {
+            name: self.name.clone(),
+            transformer,
+            report: self.report.clone(),
+        }
+    }
+}
+
+impl<T> InstrumentedTransformer<T> {
+    pub fn report(&self) -> &Arc<PipelineReport> {
+        &self.report
+    }
+}
+
+impl<F: Parameterized> Parameterized for Instrumented<F> {
+    fn get_params(&self) -> Params {
+        self.step.get_params()
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        self.step.set_param(name, value)
+    }
+}
+
+impl<F, In, T, Y> Fit<In, InstrumentedTransformer<T>, Y> for Instrumented<F> where F: Fit<In, T, Y> {
+    fn fit(&self, x: In, y: ArrayView1<Y>) -> Result<InstrumentedTransformer<T>> {
+        let start = Instant::now();
+        let transformer = self.step.fit(x, y)?;
+        self.report.record(&self.name, Phase::Fit, start.elapsed(), None);
+
+        Ok(self.wrap(transformer))
+    }
+}
+
+impl<F, In, T, Y> FitTransform<In, InstrumentedTransformer<T>, Array2<f64>, Y> for Instrumented<F>
+    where
+        F: FitTransform<In, T, Array2<f64>, Y>,
+        In: Clone,
+        T: Transformer<In, Array2<f64>> {
+    fn fit_transform(&self, x: In, y: ArrayView1<Y>) -> Result<(InstrumentedTransformer<T>, Array2<f64>)> {
+        let start = Instant::now();
+        let (transformer, transformed) = self.step.fit_transform(x, y)?;
+        self.report.record(&self.name, Phase::Fit, start.elapsed(), Some(transformed.dim()));
+
+        Ok((self.wrap(transformer), transformed))
+    }
+}
+
+impl<T, In> Transformer<In, Array2<f64>> for InstrumentedTransformer<T> where T: Transformer<In, Array2<f64>> {
+    fn transform(&self, x: In) -> Array2<f64> {
+        let start = Instant::now();
+        let transformed = self.transformer.transform(x);
+        self.report.record(&self.name, Phase::Transform, start.elapsed(), Some(transformed.dim()));
+
+        transformed
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.transformer.inverse_transform(x)
+    }
+}
@@ -1 +1,7 @@
-pub mod pipeline;
\ No newline at end of file
+#[cfg(feature = "fs")]
+pub mod cache;
+pub mod column_transformer;
+pub mod dyn_pipeline;
+pub mod instrument;
+pub mod params;
+pub mod pipeline;
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rune_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParamValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+}
+
+impl ParamValue {
+    pub fn as_f64(&self) -> Result<f64> {
+        match self {
+            ParamValue::Int(i) => Ok(*i as f64),
+            ParamValue::Float(f) => Ok(*f),
+            _ => Err(Error::InvalidParameter(format!("{} is not numeric", self)))
+        }
+    }
+
+    pub fn as_usize(&self) -> Result<usize> {
+        match self {
+            ParamValue::Int(i) if *i >= 0 => Ok(*i as usize),
+            _ => Err(Error::InvalidParameter(format!("{} is not a non-negative integer", self)))
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool> {
+        match self {
+            ParamValue::Bool(b) => Ok(*b),
+            _ => Err(Error::InvalidParameter(format!("{} is not a bool", self)))
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&str> {
+        match self {
+            ParamValue::Str(s) => Ok(s),
+            _ => Err(Error::InvalidParameter(format!("{} is not a string", self)))
+        }
+    }
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParamValue::Int(i) => write!(f, "{}", i),
+            ParamValue::Float(v) => write!(f, "{}", v),
+            ParamValue::Bool(b) => write!(f, "{}", b),
+            ParamValue::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<i64> for ParamValue {
+    fn from(value: i64) -> Self {
+        ParamValue::Int(value)
+    }
+}
+
+impl From<u32> for ParamValue {
+    fn from(value: u32) -> Self {
+        ParamValue::Int(value as i64)
+    }
+}
+
+impl From<usize> for ParamValue {
+    fn from(value: usize) -> Self {
+        ParamValue::Int(value as i64)
+    }
+}
+
+impl From<f64> for ParamValue {
+    fn from(value: f64) -> Self {
+        ParamValue::Float(value)
+    }
+}
+
+impl From<bool> for ParamValue {
+    fn from(value: bool) -> Self {
+        ParamValue::Bool(value)
+    }
+}
+
+impl From<&str> for ParamValue {
+    fn from(value: &str) -> Self {
+        ParamValue::Str(value.to_owned())
+    }
+}
+
+impl From<String> for ParamValue {
+    fn from(value: String) -> Self {
+        ParamValue::Str(value)
+    }
+}
+
+/// Hyperparameter values keyed by parameter name.
+pub type Params = BTreeMap<String, ParamValue>;
+
+/// Hyperparameters that can be read and changed by name, which lets a grid search or a
+/// `DynPipeline` tune a step without knowing its concrete type. Setting a name the type doesn't
+/// have, or a value of the wrong type, is an `Error::InvalidParameter`.
+pub trait Parameterized {
+    fn get_params(&self) -> Params;
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()>;
+
+    /// Sets every parameter in `params`, stopping at the first that can't be set.
+    fn set_params(&mut self, params: &Params) -> Result<()> {
+        for (name, value) in params {
+            self.set_param(name, value.clone())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rune_core::Error;
+
+    use crate::params::ParamValue;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(ParamValue::Int(3).as_f64().unwrap(), 3.);
+        assert_eq!(ParamValue::Int(3).as_usize().unwrap(), 3);
+        assert!(ParamValue::Bool(true).as_bool().unwrap());
+        assert_eq!(ParamValue::from("gini").as_str().unwrap(), "gini");
+    }
+
+    #[test]
+    fn test_wrong_types_are_errors() {
+        assert!(matches!(ParamValue::from("x").as_f64(), Err(Error::InvalidParameter(_))));
+        assert!(matches!(ParamValue::Int(-1).as_usize(), Err(Error::InvalidParameter(_))));
+        assert!(matches!(ParamValue::Float(2.).as_usize(), Err(Error::InvalidParameter(_))));
+        assert!(matches!(ParamValue::Int(1).as_bool(), Err(Error::InvalidParameter(_))));
+        assert!(matches!(ParamValue::Bool(false).as_str(), Err(Error::InvalidParameter(_))));
+    }
+}
@@ -1,28 +1,30 @@
 use ndarray::{ArrayView2, Array2, ArrayView1};
+use rune_core::Result;
+use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
+#[cfg(feature = "fs")]
+use {
+    rune_core::Error,
+    serde::de::DeserializeOwned,
+    std::fs::File,
+    std::io::{BufReader, BufWriter},
+    std::path::Path,
+};
 
-// pub struct Pipeline<In, Out, F, Tf> {
-//     _in: PhantomData<In>,
-//     _out: PhantomData<Out>,
-//     _tf: PhantomData<Tf>,
-//
-//     f: F,
-// }
-
-pub trait Transformer<In, Out> {
-    fn transform(&self, x: In) -> Out;
-}
-
-pub trait Fit<In, Out> {
-    fn fit(&self, x: In, y: ArrayView1<bool>) -> Out;
-}
+pub use rune_core::estimator::{Fit, FitTransform, IncrementalFit, InverseTransformer, Predictor, ProbabilisticPredictor, Score, Transformer, UnsupervisedFit};
 
+#[derive(Serialize, Deserialize)]
 pub struct ComposedTransform<In, F1Output, Out, F1Transformer, F2Transformer> {
+    #[serde(skip)]
     _in: PhantomData<In>,
+    #[serde(skip)]
     _out: PhantomData<Out>,
 
+    #[serde(skip)]
     _F1Transformer: PhantomData<F1Transformer>,
+    #[serde(skip)]
     _F1Output: PhantomData<F1Output>,
+    #[serde(skip)]
     _F2Transformer: PhantomData<F2Transformer>,
     t1: F1Transformer,
     t2: F2Transformer,
@@ -38,11 +40,17 @@ impl<In, F1Output, Out, F1Transformer, F2Transformer> Transformer<In, Out> for C
         let t2 = self.t2.transform(t1);
         t2
     }
+
+    /// Inverts the steps in reverse order, so every step must support `inverse_transform`.
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.t1.inverse_transform(self.t2.inverse_transform(x).view())
+    }
 }
 
-pub struct ComposedFit<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer> {
+pub struct ComposedFit<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer, Y = bool> {
     _in: PhantomData<In>,
     _out: PhantomData<Out>,
+    _y: PhantomData<Y>,
 
     _F1Transformer: PhantomData<F1Transformer>,
     _F1Output: PhantomData<F1Output>,
@@ -52,15 +60,15 @@ pub struct ComposedFit<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer>
     f2: F2,
 }
 
-impl<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer> ComposedFit<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer>
+impl<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer, Y> ComposedFit<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer, Y>
     where
-        F1: Fit<In, F1Transformer>,
+        F1: FitTransform<In, F1Transformer, F1Output, Y>,
         F1Transformer: Transformer<In, F1Output>,
-        F2: Fit<F1Output, F2Transformer>,
+        F2: Fit<F1Output, F2Transformer, Y>,
         F2Transformer: Transformer<F1Output, Out>,
         In: Copy {
     fn new(f1: F1, f2: F2) -> Self {
-        ComposedFit { _in: PhantomData, _out: PhantomData, _F1Transformer: PhantomData, _F1Output: PhantomData, _F2Transformer: PhantomData, f1, f2 }
+        ComposedFit { _in: PhantomData, _out: PhantomData, _y: PhantomData, _F1Transformer: PhantomData, _F1Output: PhantomData, _F2Transformer: PhantomData, f1, f2 }
     }
 
     pub fn compose(f1: F1, f2: F2) -> Self {
@@ -69,31 +77,219 @@ impl<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer> ComposedFit<F1, F2
 
 }
 
-impl<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer> Fit<In, ComposedTransform<In, F1Output, Out, F1Transformer, F2Transformer>> for ComposedFit<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer>
+impl<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer, Y> Fit<In, ComposedTransform<In, F1Output, Out, F1Transformer, F2Transformer>, Y> for ComposedFit<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer, Y>
+    where
+        F1: FitTransform<In, F1Transformer, F1Output, Y>,
+        F1Transformer: Transformer<In, F1Output>,
+        F2: Fit<F1Output, F2Transformer, Y>,
+        F2Transformer: Transformer<F1Output, Out>,
+        In: Copy {
+    fn fit(&self, x: In, y: ArrayView1<Y>) -> Result<ComposedTransform<In, F1Output, Out, F1Transformer, F2Transformer>> {
+        let (t1, x1) = self.f1.fit_transform(x, y)?;
+        let t2 = self.f2.fit(x1, y)?;
+        Ok(ComposedTransform { _in: PhantomData, _out: PhantomData, _F1Transformer: PhantomData, _F1Output: PhantomData, _F2Transformer: PhantomData, t1, t2 })
+    }
+}
+
+impl<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer, Y> FitTransform<In, ComposedTransform<In, F1Output, Out, F1Transformer, F2Transformer>, Out, Y> for ComposedFit<F1, F2, In, Out, F1Transformer, F1Output, F2Transformer, Y>
     where
-        F1: Fit<In, F1Transformer>,
+        F1: FitTransform<In, F1Transformer, F1Output, Y>,
         F1Transformer: Transformer<In, F1Output>,
-        F2: Fit<F1Output, F2Transformer>,
+        F2: FitTransform<F1Output, F2Transformer, Out, Y>,
         F2Transformer: Transformer<F1Output, Out>,
+        F1Output: Clone,
         In: Copy {
-    fn fit(&self, x: In, y: ArrayView1<bool>) -> ComposedTransform<In, F1Output, Out, F1Transformer, F2Transformer> {
-        let t1 = self.f1.fit(x, y);
-        let t2 = self.f2.fit(t1.transform(x), y);
-        ComposedTransform { _in: PhantomData, _out: PhantomData, _F1Transformer: PhantomData, _F1Output: PhantomData, _F2Transformer: PhantomData, t1, t2 }
-    }
-}
-
-//
-// impl<In, Out, F, Tf> Pipeline<In, Out, F, Tf> where Tf: Transformer<In, Out>, F: Fit<In, Tf> {
-//     pub fn new(f: F) -> Pipeline<In, Out, F, Tf> {
-//         Pipeline { _in: PhantomData, _out: PhantomData, _tf: PhantomData, f }
-//     }
-//
-//     pub fn then<IIn, NFit, Nout, NTf>(&self, f: NFit) -> Pipeline<In, Nout, NFit, NTf> where NTf: Transformer<IIn, Nout>, F: Fit<Out, Tf> {
-//         let t = Pipeline { _in: PhantomData, _out: PhantomData, _tf: PhantomData, f: f };
-//     }
-//
-//     // pub fn fit(&self, x: In) {
-//     //     self.f.fit(x)
-//     // }
-// }
\ No newline at end of file
+    fn fit_transform(&self, x: In, y: ArrayView1<Y>) -> Result<(ComposedTransform<In, F1Output, Out, F1Transformer, F2Transformer>, Out)> {
+        let (t1, x1) = self.f1.fit_transform(x, y)?;
+        let (t2, x2) = self.f2.fit_transform(x1, y)?;
+        Ok((ComposedTransform { _in: PhantomData, _out: PhantomData, _F1Transformer: PhantomData, _F1Output: PhantomData, _F2Transformer: PhantomData, t1, t2 }, x2))
+    }
+}
+
+/// The builder `PipelineBuilder::then` returns, with the new step composed after the others.
+type ChainedBuilder<F, G, In, Out, FTransformer, FOutput, GTransformer, Y> =
+    PipelineBuilder<ComposedFit<F, G, In, Out, FTransformer, FOutput, GTransformer, Y>>;
+
+/// Chains fitters one after another without spelling out the nested `ComposedFit` types, and
+/// terminates them in an estimator:
+///
+/// `PipelineBuilder::new(scaler).then(pca).finish(decision_tree)`
+pub struct PipelineBuilder<F> {
+    steps: F,
+}
+
+impl<F> PipelineBuilder<F> {
+    pub fn new(step: F) -> Self {
+        PipelineBuilder { steps: step }
+    }
+
+    /// Appends a step that is fitted on, and transforms, the output of the steps before it.
+    pub fn then<G, In, Out, FTransformer, FOutput, GTransformer, Y>(self, step: G) -> ChainedBuilder<F, G, In, Out, FTransformer, FOutput, GTransformer, Y>
+        where
+            F: FitTransform<In, FTransformer, FOutput, Y>,
+            FTransformer: Transformer<In, FOutput>,
+            G: Fit<FOutput, GTransformer, Y>,
+            GTransformer: Transformer<FOutput, Out>,
+            In: Copy {
+        PipelineBuilder { steps: ComposedFit::new(self.steps, step) }
+    }
+
+    /// Ends the pipeline with the estimator that is fitted on the transformed features and makes
+    /// the predictions.
+    pub fn finish<E>(self, estimator: E) -> Pipeline<F, E> {
+        Pipeline { steps: self.steps, estimator }
+    }
+}
+
+pub struct Pipeline<F, E> {
+    steps: F,
+    estimator: E,
+}
+
+impl<F, E, In, T, Features, M, Y> Fit<In, PipelineModel<T, M, Features>, Y> for Pipeline<F, E>
+    where
+        F: FitTransform<In, T, Features, Y>,
+        T: Transformer<In, Features>,
+        E: Fit<Features, M, Y>,
+        In: Clone {
+    fn fit(&self, x: In, y: ArrayView1<Y>) -> Result<PipelineModel<T, M, Features>> {
+        let (steps, features) = self.steps.fit_transform(x, y)?;
+        let estimator = self.estimator.fit(features, y)?;
+        Ok(PipelineModel { _features: PhantomData, steps, estimator })
+    }
+}
+
+/// A fitted `Pipeline`; predicting runs the input through every fitted step in order and hands
+/// the resulting `Features` to the fitted estimator.
+#[derive(Serialize, Deserialize)]
+pub struct PipelineModel<T, M, Features> {
+    #[serde(skip)]
+    _features: PhantomData<Features>,
+
+    steps: T,
+    estimator: M,
+}
+
+#[cfg(feature = "fs")]
+impl<T, M, Features> PipelineModel<T, M, Features> {
+    /// Writes the fitted state of every step as JSON, so the model can be loaded by an inference
+    /// process with `PipelineModel::load`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> where T: Serialize, M: Serialize {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self).map_err(|e| Error::Serialization(e.to_string()))
+    }
+
+    /// Reads a model written by `save`. The step types must match the pipeline that was saved.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> where T: DeserializeOwned, M: DeserializeOwned {
+        let reader = BufReader::new(File::open(path)?);
+        serde_json::from_reader(reader).map_err(|e| Error::Serialization(e.to_string()))
+    }
+}
+
+impl<T, M, Features, In, Out> Predictor<In, Out> for PipelineModel<T, M, Features>
+    where
+        T: Transformer<In, Features>,
+        M: Predictor<Features, Out> {
+    fn predict(&self, x: In) -> Out {
+        self.estimator.predict(self.steps.transform(x))
+    }
+}
+
+impl<T, M, Features, In, P> ProbabilisticPredictor<In, P> for PipelineModel<T, M, Features>
+    where
+        T: Transformer<In, Features>,
+        M: ProbabilisticPredictor<Features, P> {
+    fn predict_proba(&self, x: In) -> P {
+        self.estimator.predict_proba(self.steps.transform(x))
+    }
+}
+
+/// Runs the input through the fitted steps only, giving the features the estimator predicts from.
+impl<T, M, Features, In> Transformer<In, Features> for PipelineModel<T, M, Features>
+    where T: Transformer<In, Features> {
+    fn transform(&self, x: In) -> Features {
+        self.steps.transform(x)
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        self.steps.inverse_transform(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2, ArrayView1, ArrayView2, Axis};
+
+    use rune_core::Result;
+
+    use crate::pipeline::{Fit, FitTransform, PipelineBuilder, PipelineModel, Predictor, Transformer};
+
+    /// Adds `offset` to every value.
+    #[derive(Clone, Copy)]
+    struct Shift {
+        offset: f64,
+    }
+
+    impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for Shift {
+        fn transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+            &x + self.offset
+        }
+
+        fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+            &x - self.offset
+        }
+    }
+
+    impl Transformer<Array2<f64>, Array2<f64>> for Shift {
+        fn transform(&self, x: Array2<f64>) -> Array2<f64> {
+            x + self.offset
+        }
+
+        fn inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+            &x - self.offset
+        }
+    }
+
+    impl<'a, Y> Fit<ArrayView2<'a, f64>, Shift, Y> for Shift {
+        fn fit(&self, _x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<Shift> {
+            Ok(*self)
+        }
+    }
+
+    impl<'a, Y> FitTransform<ArrayView2<'a, f64>, Shift, Array2<f64>, Y> for Shift {}
+
+    impl<Y> Fit<Array2<f64>, Shift, Y> for Shift {
+        fn fit(&self, _x: Array2<f64>, _y: ArrayView1<Y>) -> Result<Shift> {
+            Ok(*self)
+        }
+    }
+
+    impl<Y> FitTransform<Array2<f64>, Shift, Array2<f64>, Y> for Shift {}
+
+    /// Predicts the sum of each row.
+    struct RowSum;
+
+    impl<Y> Fit<Array2<f64>, RowSum, Y> for RowSum {
+        fn fit(&self, _x: Array2<f64>, _y: ArrayView1<Y>) -> Result<RowSum> {
+            Ok(RowSum)
+        }
+    }
+
+    impl Predictor<Array2<f64>, Array1<f64>> for RowSum {
+        fn predict(&self, x: Array2<f64>) -> Array1<f64> {
+            x.sum_axis(Axis(1))
+        }
+    }
+
+    #[test]
+    fn test_fitted_pipelines_transform_with_their_steps_only() {
+        let x = array![[1., 2.], [3., 4.]];
+        let pipeline = PipelineBuilder::new(Shift { offset: 1. }).then(Shift { offset: 10. }).finish(RowSum);
+
+        let model: PipelineModel<_, RowSum, Array2<f64>> = pipeline.fit(x.view(), array![true, false].view()).unwrap();
+        let transformed: Array2<f64> = model.transform(x.view());
+        assert_eq!(transformed, array![[12., 13.], [14., 15.]]);
+        assert_eq!(model.inverse_transform(transformed.view()), x);
+        assert_eq!(model.predict(x.view()), array![25., 29.]);
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use log::info;
+use ndarray::prelude::*;
+use ndarray_heterogeneous::Scalar;
+use rune_core::{Error, Result};
+use rune_core::estimator::{Fit, FitTransform, Transformer};
+use serde::{Deserialize, Serialize};
+
+/// The features every datetime column expands into, in order.
+pub const DATETIME_FEATURES: [&str; 4] = ["year", "month", "day_of_week", "hour"];
+
+/// Expands each datetime column into its year, month (1 to 12), day of the week (0 for Monday)
+/// and hour (0 to 23), so models can pick up trends and seasonality. Columns may hold
+/// `Scalar::DATETIME`s or strings `Scalar::parse_datetime` understands; empty strings are missing
+/// values and expand into NaN.
+#[derive(Debug, Default)]
+pub struct DatetimeFeatures;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatetimeFeaturesTransformer {
+    n_columns: usize,
+}
+
+fn is_missing(value: &Scalar) -> bool {
+    match value {
+        Scalar::STRING(value) => value.is_empty(),
+        _ => false,
+    }
+}
+
+fn features(datetime: Option<NaiveDateTime>) -> [f64; 4] {
+    match datetime {
+        Some(datetime) => [
+            datetime.year() as f64,
+            datetime.month() as f64,
+            datetime.weekday().num_days_from_monday() as f64,
+            datetime.hour() as f64,
+        ],
+        None => [f64::NAN; 4],
+    }
+}
+
+impl Transformer<ArrayView2<'_, Scalar>, Array2<f64>> for DatetimeFeaturesTransformer {
+    fn transform(&self, x: ArrayView2<'_, Scalar>) -> Array2<f64> {
+        self.internal_transform(x)
+    }
+}
+
+impl DatetimeFeaturesTransformer {
+    /// Names the expanded features after their input columns, e.g. `created_year`.
+    pub fn feature_names(&self, input_names: &[String]) -> Vec<String> {
+        input_names.iter()
+            .flat_map(|name| DATETIME_FEATURES.iter().map(move |feature| format!("{}_{}", name, feature)))
+            .collect()
+    }
+
+    /// Values that aren't datetimes, which `fit` only lets through as missing values, expand into
+    /// NaN.
+    pub fn internal_transform(&self, x: ArrayView2<Scalar>) -> Array2<f64> {
+        assert_eq!(x.ncols(), self.n_columns, "expected {} columns", self.n_columns);
+
+        let mut expanded = Array2::zeros((x.nrows(), x.ncols() * DATETIME_FEATURES.len()));
+
+        for ((row, column), value) in x.indexed_iter() {
+            let start = column * DATETIME_FEATURES.len();
+            expanded.slice_mut(s![row, start..start + DATETIME_FEATURES.len()]).assign(&aview1(&features(value.as_datetime())));
+        }
+
+        expanded
+    }
+}
+
+impl<Y> Fit<ArrayView2<'_, Scalar>, DatetimeFeaturesTransformer, Y> for DatetimeFeatures {
+    fn fit(&self, x: ArrayView2<Scalar>, _y: ArrayView1<Y>) -> Result<DatetimeFeaturesTransformer> {
+        self.internal_fit(x)
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, Scalar>, DatetimeFeaturesTransformer, Array2<f64>, Y> for DatetimeFeatures {}
+
+impl DatetimeFeatures {
+    pub fn new() -> Self {
+        DatetimeFeatures
+    }
+
+    pub fn internal_fit(&self, x: ArrayView2<Scalar>) -> Result<DatetimeFeaturesTransformer> {
+        for ((row, column), value) in x.indexed_iter() {
+            if value.as_datetime().is_none() && !is_missing(value) {
+                return Err(Error::InvalidInput(format!("{:?} in row {}, column {} is not a datetime", value, row, column)));
+            }
+        }
+
+        info!("expanding {} datetime columns into {} features", x.ncols(), x.ncols() * DATETIME_FEATURES.len());
+
+        Ok(DatetimeFeaturesTransformer { n_columns: x.ncols() })
+    }
+}
@@ -0,0 +1,32 @@
+use ndarray::prelude::*;
+use rune_core::estimator::Transformer;
+use serde::{Deserialize, Serialize};
+
+pub mod select_k_best;
+pub mod variance_threshold;
+
+/// Keeps only the columns chosen by a feature selector, in their original order.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeatureSelectionTransformer {
+    selected_columns: Vec<usize>,
+}
+
+impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for FeatureSelectionTransformer {
+    fn transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_transform(x)
+    }
+}
+
+impl FeatureSelectionTransformer {
+    pub fn new(selected_columns: Vec<usize>) -> Self {
+        FeatureSelectionTransformer { selected_columns }
+    }
+
+    pub fn selected_columns(&self) -> &[usize] {
+        &self.selected_columns
+    }
+
+    pub fn internal_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        x.select(Axis(1), &self.selected_columns)
+    }
+}
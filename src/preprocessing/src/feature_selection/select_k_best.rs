@@ -0,0 +1,83 @@
+use std::cmp::Ordering;
+use std::hash::Hash;
+
+use log::info;
+use ndarray::prelude::*;
+use rune_core::validation::check_x_y;
+use rune_core::{Error, Result};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::estimator::{Fit, FitTransform};
+use rune_metrics::feature_scoring::{f_classif, mutual_info_classif};
+
+use crate::feature_selection::FeatureSelectionTransformer;
+
+const MUTUAL_INFO_BINS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoreFunction {
+    /// ANOVA F-statistic between the feature and the class label.
+    FClassif,
+    /// Mutual information between the (equal-width binned) feature and the class label.
+    MutualInfo,
+}
+
+/// Keeps the `k` features scoring highest against the target.
+#[derive(Debug)]
+pub struct SelectKBest {
+    k: usize,
+    score_function: ScoreFunction,
+}
+
+impl<Y: Copy + Eq + Hash> Fit<ArrayView2<'_, f64>, FeatureSelectionTransformer, Y> for SelectKBest {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<FeatureSelectionTransformer> {
+        check_x_y(x, y, 1)?;
+        Ok(self.internal_fit(x, y))
+    }
+}
+
+impl<'a, Y: Copy + Eq + Hash> FitTransform<ArrayView2<'a, f64>, FeatureSelectionTransformer, Array2<f64>, Y> for SelectKBest {}
+
+impl SelectKBest {
+    pub fn new(k: usize, score_function: ScoreFunction) -> Self {
+        SelectKBest {
+            k,
+            score_function,
+        }
+    }
+
+    pub fn scores<T: Copy + Eq + Hash>(&self, x: ArrayView2<f64>, y: ArrayView1<T>) -> Array1<f64> {
+        match self.score_function {
+            ScoreFunction::FClassif => f_classif(x, y),
+            ScoreFunction::MutualInfo => mutual_info_classif(x, y, MUTUAL_INFO_BINS),
+        }
+    }
+
+    pub fn internal_fit<T: Copy + Eq + Hash>(&self, x: ArrayView2<f64>, y: ArrayView1<T>) -> FeatureSelectionTransformer {
+        let scores = self.scores(x, y);
+        info!("feature scores: {}", scores);
+
+        let mut ranked: Vec<usize> = (0..scores.len()).collect();
+        ranked.sort_by(|&l, &r| scores[r].partial_cmp(&scores[l]).unwrap_or(Ordering::Equal));
+
+        let mut selected_columns: Vec<usize> = ranked.into_iter().take(self.k).collect();
+        selected_columns.sort_unstable();
+
+        FeatureSelectionTransformer::new(selected_columns)
+    }
+}
+
+impl Parameterized for SelectKBest {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("k".to_owned(), self.k.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "k" => self.k = value.as_usize()?,
+            _ => return Err(Error::InvalidParameter(format!("SelectKBest has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use log::info;
+use ndarray::prelude::*;
+use rune_core::validation::check_x;
+use rune_core::{Error, Result};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::estimator::{Fit, FitTransform};
+
+use crate::feature_selection::FeatureSelectionTransformer;
+
+/// Drops every feature whose (population) variance does not exceed `threshold`; the default of
+/// zero removes constant columns.
+#[derive(Debug, Default)]
+pub struct VarianceThreshold {
+    threshold: f64,
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, FeatureSelectionTransformer, Y> for VarianceThreshold {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<FeatureSelectionTransformer> {
+        check_x(x, 1)?;
+        Ok(self.internal_fit(x))
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, f64>, FeatureSelectionTransformer, Array2<f64>, Y> for VarianceThreshold {}
+
+impl VarianceThreshold {
+    pub fn new(threshold: f64) -> Self {
+        VarianceThreshold { threshold }
+    }
+
+    pub fn internal_fit(&self, x: ArrayView2<f64>) -> FeatureSelectionTransformer {
+        let variances = x.var_axis(Axis(0), 0.);
+        info!("variances: {}", variances);
+
+        let selected_columns = variances.iter()
+            .enumerate()
+            .filter(|(_, &variance)| variance > self.threshold)
+            .map(|(column, _)| column)
+            .collect();
+
+        FeatureSelectionTransformer::new(selected_columns)
+    }
+}
+
+impl Parameterized for VarianceThreshold {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("threshold".to_owned(), self.threshold.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "threshold" => self.threshold = value.as_f64()?,
+            _ => return Err(Error::InvalidParameter(format!("VarianceThreshold has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use crate::feature_selection::variance_threshold::VarianceThreshold;
+
+    #[test]
+    fn test_constant_columns_are_dropped() {
+        let x = array![[1., 5., 0.], [2., 5., 0.], [3., 5., 0.1]];
+
+        let selector = VarianceThreshold::new(0.).internal_fit(x.view());
+
+        assert_eq!(selector.selected_columns(), &[0, 2]);
+        assert_eq!(selector.internal_transform(x.view()), array![[1., 0.], [2., 0.], [3., 0.1]]);
+    }
+}
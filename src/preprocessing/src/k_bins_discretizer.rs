@@ -0,0 +1,230 @@
+use std::cmp::Ordering;
+
+use log::{debug, info};
+use ndarray::prelude::*;
+use rune_core::math::binning::{bin_index, quantile_edges};
+use rune_core::validation::check_x;
+use rune_core::{Error, Result};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::estimator::{Fit, FitTransform, Transformer};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinStrategy {
+    /// Bins of equal width between the column's minimum and maximum.
+    Uniform,
+    /// Bins holding (roughly) the same number of samples.
+    Quantile,
+    /// Bins centred on the clusters found by a 1-D k-means.
+    KMeans,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BinEncoding {
+    /// A single column per feature holding the bin index.
+    Ordinal,
+    /// One indicator column per bin of every feature.
+    OneHot,
+}
+
+#[derive(Debug)]
+pub struct KBinsDiscretizer {
+    n_bins: usize,
+    strategy: BinStrategy,
+    encoding: BinEncoding,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KBinsDiscretizerTransformer {
+    bin_edges: Vec<Array1<f64>>,
+    encoding: BinEncoding,
+}
+
+impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for KBinsDiscretizerTransformer {
+    fn transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_transform(x)
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
+}
+
+impl KBinsDiscretizerTransformer {
+    /// Edges of every feature, including the outer minimum and maximum.
+    pub fn bin_edges(&self) -> &[Array1<f64>] {
+        &self.bin_edges
+    }
+
+    pub fn n_bins(&self) -> Vec<usize> {
+        self.bin_edges.iter().map(|edges| edges.len() - 1).collect()
+    }
+
+    fn bin_centre(edges: &Array1<f64>, bin: usize) -> f64 {
+        (edges[bin] + edges[bin + 1]) / 2.
+    }
+
+    pub fn internal_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let n_bins = self.n_bins();
+
+        match self.encoding {
+            BinEncoding::Ordinal => {
+                Array2::from_shape_fn(x.dim(), |(row, column)| bin_index(self.bin_edges[column].view(), x[[row, column]]) as f64)
+            }
+            BinEncoding::OneHot => {
+                let mut encoded = Array2::zeros((x.nrows(), n_bins.iter().sum()));
+                let mut offset = 0;
+
+                for (column, edges) in self.bin_edges.iter().enumerate() {
+                    for row in 0..x.nrows() {
+                        encoded[[row, offset + bin_index(edges.view(), x[[row, column]])]] = 1.;
+                    }
+                    offset += n_bins[column];
+                }
+
+                encoded
+            }
+        }
+    }
+
+    /// Replaces every bin with the centre of its edges; for one-hot input the most active column
+    /// of each feature's block picks the bin.
+    pub fn internal_inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let n_bins = self.n_bins();
+        let mut original = Array2::zeros((x.nrows(), self.bin_edges.len()));
+
+        match self.encoding {
+            BinEncoding::Ordinal => {
+                for (column, edges) in self.bin_edges.iter().enumerate() {
+                    for row in 0..x.nrows() {
+                        let bin = x[[row, column]].round().max(0.).min((n_bins[column] - 1) as f64) as usize;
+                        original[[row, column]] = Self::bin_centre(edges, bin);
+                    }
+                }
+            }
+            BinEncoding::OneHot => {
+                let mut offset = 0;
+
+                for (column, edges) in self.bin_edges.iter().enumerate() {
+                    for row in 0..x.nrows() {
+                        let block = x.slice(s![row, offset..offset + n_bins[column]]);
+                        let (bin, _) = block.iter()
+                            .enumerate()
+                            .fold((0, f64::NEG_INFINITY), |(best, best_value), (bin, &value)| {
+                                if value > best_value { (bin, value) } else { (best, best_value) }
+                            });
+                        original[[row, column]] = Self::bin_centre(edges, bin);
+                    }
+                    offset += n_bins[column];
+                }
+            }
+        }
+
+        original
+    }
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, KBinsDiscretizerTransformer, Y> for KBinsDiscretizer {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<KBinsDiscretizerTransformer> {
+        check_x(x, 1)?;
+        Ok(self.internal_fit(x))
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, f64>, KBinsDiscretizerTransformer, Array2<f64>, Y> for KBinsDiscretizer {}
+
+impl KBinsDiscretizer {
+    pub fn new(n_bins: usize, strategy: BinStrategy, encoding: BinEncoding) -> Self {
+        assert!(n_bins >= 2, "at least 2 bins are required");
+
+        KBinsDiscretizer {
+            n_bins,
+            strategy,
+            encoding,
+        }
+    }
+
+    pub fn internal_fit(&self, x: ArrayView2<f64>) -> KBinsDiscretizerTransformer {
+        let bin_edges: Vec<Array1<f64>> = x.gencolumns()
+            .into_iter()
+            .map(|column| {
+                let mut sorted = column.to_vec();
+                sorted.sort_by(|l, r| l.partial_cmp(r).unwrap_or(Ordering::Equal));
+
+                let mut edges = match self.strategy {
+                    BinStrategy::Uniform => Array1::linspace(sorted[0], sorted[sorted.len() - 1], self.n_bins + 1).to_vec(),
+                    BinStrategy::Quantile => quantile_edges(&sorted, self.n_bins),
+                    BinStrategy::KMeans => k_means_edges(&sorted, self.n_bins),
+                };
+
+                edges.dedup_by(|l, r| (*l - *r).abs() < 1e-8);
+                if edges.len() < 2 {
+                    debug!("Constant feature collapsed into a single bin");
+                    edges = vec![sorted[0], sorted[0]];
+                }
+
+                Array1::from(edges)
+            })
+            .collect();
+
+        info!("bin edges: {:?}", bin_edges);
+
+        KBinsDiscretizerTransformer {
+            bin_edges,
+            encoding: self.encoding,
+        }
+    }
+}
+
+fn k_means_edges(sorted: &[f64], n_bins: usize) -> Vec<f64> {
+    const MAX_ITERATIONS: usize = 100;
+
+    let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+    let uniform = Array1::linspace(min, max, n_bins + 1);
+    let mut centers: Vec<f64> = (0..n_bins).map(|i| (uniform[i] + uniform[i + 1]) / 2.).collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut sums = vec![0.; n_bins];
+        let mut counts = vec![0usize; n_bins];
+
+        for &v in sorted {
+            let nearest = (0..n_bins)
+                .min_by(|&l, &r| (v - centers[l]).abs().partial_cmp(&(v - centers[r]).abs()).unwrap_or(Ordering::Equal))
+                .unwrap();
+            sums[nearest] += v;
+            counts[nearest] += 1;
+        }
+
+        let updated: Vec<f64> = (0..n_bins)
+            .map(|i| if counts[i] > 0 { sums[i] / counts[i] as f64 } else { centers[i] })
+            .collect();
+
+        if updated == centers {
+            break;
+        }
+        centers = updated;
+    }
+
+    centers.sort_by(|l, r| l.partial_cmp(r).unwrap_or(Ordering::Equal));
+
+    let mut edges = vec![min];
+    edges.extend(centers.windows(2).map(|pair| (pair[0] + pair[1]) / 2.));
+    edges.push(max);
+    edges
+}
+
+impl Parameterized for KBinsDiscretizer {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("n_bins".to_owned(), self.n_bins.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "n_bins" => self.n_bins = value.as_usize()?,
+            _ => return Err(Error::InvalidParameter(format!("KBinsDiscretizer has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use itertools::Itertools;
+use log::info;
+use ndarray::prelude::*;
+use rune_core::estimator::{InverseTransformer, Transformer};
+use rune_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Maps arbitrary labels to the contiguous codes `0..n_classes`, in order of first appearance.
+/// Labels not seen during fit, and codes of no class, fail with `InvalidInput`.
+#[derive(Debug, Default)]
+pub struct LabelEncoder {}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LabelEncoderTransformer<T: Eq + Hash> {
+    classes: Array1<T>,
+    codes: HashMap<T, usize>,
+}
+
+impl<T: Clone + Eq + Hash> Transformer<ArrayView1<'_, T>, Result<Array1<usize>>> for LabelEncoderTransformer<T> {
+    fn transform(&self, y: ArrayView1<'_, T>) -> Result<Array1<usize>> {
+        self.internal_transform(y)
+    }
+}
+
+impl<T: Clone + Eq + Hash> InverseTransformer<ArrayView1<'_, usize>, Array1<T>> for LabelEncoderTransformer<T> {
+    fn inverse_transform(&self, codes: ArrayView1<'_, usize>) -> Result<Array1<T>> {
+        LabelEncoderTransformer::inverse_transform(self, codes)
+    }
+}
+
+impl<T: Clone + Eq + Hash> LabelEncoderTransformer<T> {
+    pub fn new(classes: Array1<T>) -> Self {
+        let codes = classes.iter()
+            .cloned()
+            .enumerate()
+            .map(|(code, class)| (class, code))
+            .collect();
+
+        LabelEncoderTransformer {
+            classes,
+            codes,
+        }
+    }
+
+    pub fn classes(&self) -> ArrayView1<'_, T> {
+        self.classes.view()
+    }
+
+    pub fn internal_transform(&self, y: ArrayView1<T>) -> Result<Array1<usize>> {
+        let codes = y.iter()
+            .enumerate()
+            .map(|(row, label)| self.codes.get(label).copied().ok_or_else(|| Error::InvalidInput(format!("the label of row {} was not seen during fit", row))))
+            .collect::<Result<Vec<usize>>>()?;
+
+        Ok(Array1::from(codes))
+    }
+
+    pub fn inverse_transform(&self, codes: ArrayView1<usize>) -> Result<Array1<T>> {
+        if let Some(&code) = codes.iter().find(|&&code| code >= self.classes.len()) {
+            return Err(Error::InvalidInput(format!("code {} is not one of the {} classes", code, self.classes.len())));
+        }
+
+        Ok(codes.map(|&code| self.classes[code].clone()))
+    }
+}
+
+impl LabelEncoder {
+    pub fn new() -> Self {
+        LabelEncoder {}
+    }
+
+    pub fn fit<T: Clone + Eq + Hash>(&self, y: ArrayView1<T>) -> LabelEncoderTransformer<T> {
+        let classes: Array1<T> = y.iter().unique().cloned().collect();
+
+        info!("classes: {}", classes.len());
+
+        LabelEncoderTransformer::new(classes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use rune_core::estimator::{InverseTransformer, Transformer};
+    use rune_core::Error;
+
+    use crate::label_encoder::{LabelEncoder, LabelEncoderTransformer};
+
+    #[test]
+    fn test_codes_follow_first_appearance_and_round_trip() {
+        let y = array!["cat", "dog", "cat", "bird"];
+        let encoder = LabelEncoder::new().fit(y.view());
+
+        assert_eq!(encoder.classes(), array!["cat", "dog", "bird"].view());
+        let codes = encoder.transform(y.view()).unwrap();
+        assert_eq!(codes, array![0, 1, 0, 2]);
+        assert_eq!(InverseTransformer::inverse_transform(&encoder, codes.view()).unwrap(), y);
+    }
+
+    #[test]
+    fn test_unseen_labels_and_codes_are_errors() {
+        let encoder: LabelEncoderTransformer<&str> = LabelEncoder::new().fit(array!["cat", "dog"].view());
+
+        assert!(matches!(encoder.internal_transform(array!["dog", "fish"].view()), Err(Error::InvalidInput(_))));
+        assert!(matches!(encoder.inverse_transform(array![1, 2].view()), Err(Error::InvalidInput(_))));
+    }
+}
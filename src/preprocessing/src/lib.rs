@@ -1 +1,9 @@
+pub mod datetime_features;
+pub mod feature_selection;
+pub mod k_bins_discretizer;
+pub mod label_encoder;
+pub mod ordinal_encoder;
+pub mod power_transformer;
+pub mod quantile_transformer;
 pub mod standard_scaler;
+pub mod target_encoder;
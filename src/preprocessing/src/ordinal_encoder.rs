@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use log::info;
+use ndarray::prelude::*;
+use rune_core::{Error, Result};
+use rune_core::estimator::{Fit, InverseTransformer, Transformer};
+use serde::{Deserialize, Serialize};
+
+/// Encodes each categorical column as the position of its value in a user-supplied ordering,
+/// e.g. `["poor", "fair", "good"]` becomes `0., 1., 2.`. Transforming fails with `InvalidInput`
+/// on a value missing from its column's ordering, and inverting on a code of no category.
+#[derive(Debug)]
+pub struct OrdinalEncoder<T> {
+    categories: Vec<Vec<T>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrdinalEncoderTransformer<T: Eq + Hash> {
+    categories: Vec<Vec<T>>,
+    codes: Vec<HashMap<T, usize>>,
+}
+
+impl<T: Clone + Eq + Hash> Transformer<ArrayView2<'_, T>, Result<Array2<f64>>> for OrdinalEncoderTransformer<T> {
+    fn transform(&self, x: ArrayView2<'_, T>) -> Result<Array2<f64>> {
+        self.internal_transform(x)
+    }
+}
+
+impl<T: Clone + Eq + Hash> InverseTransformer<ArrayView2<'_, f64>, Array2<T>> for OrdinalEncoderTransformer<T> {
+    fn inverse_transform(&self, x: ArrayView2<'_, f64>) -> Result<Array2<T>> {
+        OrdinalEncoderTransformer::inverse_transform(self, x)
+    }
+}
+
+impl<T: Clone + Eq + Hash> OrdinalEncoderTransformer<T> {
+    pub fn new(categories: Vec<Vec<T>>) -> Self {
+        let codes = categories.iter()
+            .map(|column| column.iter().cloned().enumerate().map(|(code, category)| (category, code)).collect())
+            .collect();
+
+        OrdinalEncoderTransformer {
+            categories,
+            codes,
+        }
+    }
+
+    pub fn categories(&self) -> &[Vec<T>] {
+        &self.categories
+    }
+
+    pub fn internal_transform(&self, x: ArrayView2<T>) -> Result<Array2<f64>> {
+        self.check_columns(x.ncols())?;
+
+        let mut encoded = Array2::zeros(x.dim());
+
+        for ((row, column), value) in x.indexed_iter() {
+            let code = self.codes[column].get(value)
+                .ok_or_else(|| Error::InvalidInput(format!("row {} of column {} is missing from the column's ordering", row, column)))?;
+            encoded[[row, column]] = *code as f64;
+        }
+
+        Ok(encoded)
+    }
+
+    pub fn inverse_transform(&self, x: ArrayView2<f64>) -> Result<Array2<T>> {
+        self.check_columns(x.ncols())?;
+
+        for ((row, column), &code) in x.indexed_iter() {
+            if code.fract() != 0. || code < 0. || code >= self.categories[column].len() as f64 {
+                return Err(Error::InvalidInput(format!("row {} of column {} is {}, not the code of a category", row, column, code)));
+            }
+        }
+
+        Ok(Array2::from_shape_fn(x.dim(), |(row, column)| self.categories[column][x[[row, column]] as usize].clone()))
+    }
+
+    fn check_columns(&self, n_columns: usize) -> Result<()> {
+        if n_columns != self.codes.len() {
+            return Err(Error::ShapeMismatch(format!("expected {} columns, got {}", self.codes.len(), n_columns)));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone + Eq + Hash, Y> Fit<ArrayView2<'_, T>, OrdinalEncoderTransformer<T>, Y> for OrdinalEncoder<T> {
+    fn fit(&self, x: ArrayView2<T>, _y: ArrayView1<Y>) -> Result<OrdinalEncoderTransformer<T>> {
+        self.internal_fit(x)
+    }
+}
+
+impl<T: Clone + Eq + Hash> OrdinalEncoder<T> {
+    /// `categories[i]` lists every value of column `i` from lowest to highest.
+    pub fn new(categories: Vec<Vec<T>>) -> Self {
+        OrdinalEncoder { categories }
+    }
+
+    pub fn internal_fit(&self, x: ArrayView2<T>) -> Result<OrdinalEncoderTransformer<T>> {
+        if x.ncols() != self.categories.len() {
+            return Err(Error::ShapeMismatch(format!("an ordering is required for each of the {} columns, got {}", x.ncols(), self.categories.len())));
+        }
+
+        for (index, (column, categories)) in x.gencolumns().into_iter().zip(self.categories.iter()).enumerate() {
+            if !column.iter().all(|value| categories.contains(value)) {
+                return Err(Error::InvalidInput(format!("column {} contains a category missing from its ordering", index)));
+            }
+        }
+
+        info!("categories per column: {:?}", self.categories.iter().map(|c| c.len()).collect::<Vec<usize>>());
+
+        Ok(OrdinalEncoderTransformer::new(self.categories.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use rune_core::estimator::{InverseTransformer, Transformer};
+    use rune_core::Error;
+
+    use crate::ordinal_encoder::OrdinalEncoder;
+
+    fn encoder() -> OrdinalEncoder<&'static str> {
+        OrdinalEncoder::new(vec![vec!["poor", "fair", "good"], vec!["s", "m", "l"]])
+    }
+
+    #[test]
+    fn test_codes_follow_the_ordering_and_round_trip() {
+        let x = array![["good", "s"], ["poor", "l"], ["fair", "m"]];
+        let transformer = encoder().internal_fit(x.view()).unwrap();
+
+        let encoded = transformer.transform(x.view()).unwrap();
+        assert_eq!(encoded, array![[2., 0.], [0., 2.], [1., 1.]]);
+        assert_eq!(InverseTransformer::inverse_transform(&transformer, encoded.view()).unwrap(), x);
+    }
+
+    #[test]
+    fn test_unknown_categories_codes_and_shapes_are_errors() {
+        let transformer = encoder().internal_fit(array![["good", "s"]].view()).unwrap();
+
+        assert!(matches!(transformer.internal_transform(array![["great", "s"]].view()), Err(Error::InvalidInput(_))));
+        assert!(matches!(transformer.internal_transform(array![["good"]].view()), Err(Error::ShapeMismatch(_))));
+        for &code in [3., -1., 0.5].iter() {
+            assert!(matches!(transformer.inverse_transform(array![[code, 0.]].view()), Err(Error::InvalidInput(_))));
+        }
+        assert!(matches!(encoder().internal_fit(array![["good", "xl"]].view()), Err(Error::InvalidInput(_))));
+    }
+}
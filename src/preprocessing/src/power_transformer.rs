@@ -0,0 +1,285 @@
+use log::info;
+use ndarray::prelude::*;
+use rune_core::validation::check_x;
+use rune_core::{Error, Result};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::estimator::{Fit, FitTransform, Transformer};
+use serde::{Deserialize, Serialize};
+
+use crate::standard_scaler::ZERO_VARIANCE_EPSILON;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PowerMethod {
+    /// Only defined for strictly positive data.
+    BoxCox,
+    /// Works with zero and negative values.
+    YeoJohnson,
+}
+
+/// Applies a per-feature power transform, with the exponent chosen by maximum likelihood, to
+/// make skewed features more Gaussian.
+#[derive(Debug)]
+pub struct PowerTransformer {
+    method: PowerMethod,
+    standardize: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PowerTransformerTransformer {
+    method: PowerMethod,
+    lambdas: Array1<f64>,
+    means: Option<Array1<f64>>,
+    std_dev: Option<Array1<f64>>,
+}
+
+impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for PowerTransformerTransformer {
+    fn transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_transform(x)
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
+}
+
+impl PowerTransformerTransformer {
+    pub fn lambdas(&self) -> ArrayView1<'_, f64> {
+        self.lambdas.view()
+    }
+
+    fn power_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let mut transformed = x.to_owned();
+
+        for (mut column, &lambda) in transformed.gencolumns_mut().into_iter().zip(self.lambdas.iter()) {
+            column.mapv_inplace(|v| apply(self.method, v, lambda));
+        }
+
+        transformed
+    }
+
+    pub fn internal_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let transformed = self.power_transform(x);
+
+        match (&self.means, &self.std_dev) {
+            (Some(means), Some(std_dev)) => (&transformed - means) / std_dev,
+            _ => transformed
+        }
+    }
+
+    pub fn internal_inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let mut original = match (&self.means, &self.std_dev) {
+            (Some(means), Some(std_dev)) => &x * std_dev + means,
+            _ => x.to_owned()
+        };
+
+        for (mut column, &lambda) in original.gencolumns_mut().into_iter().zip(self.lambdas.iter()) {
+            column.mapv_inplace(|v| invert(self.method, v, lambda));
+        }
+
+        original
+    }
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, PowerTransformerTransformer, Y> for PowerTransformer {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<PowerTransformerTransformer> {
+        check_x(x, 1)?;
+        self.internal_fit(x)
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, f64>, PowerTransformerTransformer, Array2<f64>, Y> for PowerTransformer {}
+
+impl PowerTransformer {
+    pub fn new(method: PowerMethod, standardize: bool) -> Self {
+        PowerTransformer {
+            method,
+            standardize,
+        }
+    }
+
+    pub fn internal_fit(&self, x: ArrayView2<f64>) -> Result<PowerTransformerTransformer> {
+        if self.method == PowerMethod::BoxCox && !x.iter().all(|&v| v > 0.) {
+            return Err(Error::InvalidInput("Box-Cox requires strictly positive data".to_owned()));
+        }
+
+        let lambdas: Array1<f64> = x.gencolumns()
+            .into_iter()
+            .map(|column| optimal_lambda(self.method, column))
+            .collect();
+
+        info!("lambdas: {}", lambdas);
+
+        let mut transformer = PowerTransformerTransformer {
+            method: self.method,
+            lambdas,
+            means: None,
+            std_dev: None,
+        };
+
+        if self.standardize {
+            let transformed = transformer.power_transform(x);
+            transformer.means = transformed.mean_axis(Axis(0));
+            // As in `StandardScaler`, constant columns are only centred.
+            transformer.std_dev = Some(transformed.std_axis(Axis(0), 0.).mapv(|s| if s < ZERO_VARIANCE_EPSILON { 1. } else { s }));
+        }
+
+        Ok(transformer)
+    }
+}
+
+fn apply(method: PowerMethod, v: f64, lambda: f64) -> f64 {
+    match method {
+        PowerMethod::BoxCox => {
+            if lambda.abs() < f64::EPSILON { v.ln() } else { (v.powf(lambda) - 1.) / lambda }
+        }
+        PowerMethod::YeoJohnson => {
+            if v >= 0. {
+                if lambda.abs() < f64::EPSILON { v.ln_1p() } else { ((v + 1.).powf(lambda) - 1.) / lambda }
+            } else if (lambda - 2.).abs() < f64::EPSILON {
+                -(-v).ln_1p()
+            } else {
+                -((1. - v).powf(2. - lambda) - 1.) / (2. - lambda)
+            }
+        }
+    }
+}
+
+fn invert(method: PowerMethod, v: f64, lambda: f64) -> f64 {
+    match method {
+        PowerMethod::BoxCox => {
+            if lambda.abs() < f64::EPSILON { v.exp() } else { (lambda * v + 1.).powf(1. / lambda) }
+        }
+        PowerMethod::YeoJohnson => {
+            if v >= 0. {
+                if lambda.abs() < f64::EPSILON { v.exp_m1() } else { (lambda * v + 1.).powf(1. / lambda) - 1. }
+            } else if (lambda - 2.).abs() < f64::EPSILON {
+                -(-v).exp_m1()
+            } else {
+                1. - (1. - (2. - lambda) * v).powf(1. / (2. - lambda))
+            }
+        }
+    }
+}
+
+fn log_likelihood(method: PowerMethod, column: ArrayView1<f64>, lambda: f64) -> f64 {
+    let n = column.len() as f64;
+    let transformed = column.mapv(|v| apply(method, v, lambda));
+    let variance = transformed.var_axis(Axis(0), 0.).into_scalar();
+
+    let jacobian: f64 = match method {
+        PowerMethod::BoxCox => column.iter().map(|v| v.ln()).sum(),
+        PowerMethod::YeoJohnson => column.iter().map(|v| v.signum() * v.abs().ln_1p()).sum(),
+    };
+
+    -n / 2. * variance.ln() + (lambda - 1.) * jacobian
+}
+
+/// Golden-section search for the lambda maximising the log-likelihood on `[-5, 5]`.
+fn optimal_lambda(method: PowerMethod, column: ArrayView1<f64>) -> f64 {
+    const TOLERANCE: f64 = 1e-6;
+    let ratio = (5f64.sqrt() - 1.) / 2.;
+
+    let (mut low, mut high) = (-5., 5.);
+    let mut c = high - ratio * (high - low);
+    let mut d = low + ratio * (high - low);
+
+    while (high - low).abs() > TOLERANCE {
+        if log_likelihood(method, column, c) > log_likelihood(method, column, d) {
+            high = d;
+        } else {
+            low = c;
+        }
+
+        c = high - ratio * (high - low);
+        d = low + ratio * (high - low);
+    }
+
+    (low + high) / 2.
+}
+
+impl Parameterized for PowerTransformer {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("standardize".to_owned(), self.standardize.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "standardize" => self.standardize = value.as_bool()?,
+            _ => return Err(Error::InvalidParameter(format!("PowerTransformer has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{Array1, Array2, Axis};
+
+    use crate::power_transformer::{apply, invert, PowerMethod, PowerTransformer};
+    use crate::quantile_transformer::normal_quantile;
+
+    /// Evenly spaced quantiles of the standard normal distribution, a sample without noise.
+    fn normal_sample() -> Array1<f64> {
+        (0..50).map(|i| normal_quantile((i as f64 + 0.5) / 50.)).collect()
+    }
+
+    fn assert_close(a: &Array2<f64>, b: &Array2<f64>, tolerance: f64) {
+        assert_eq!(a.dim(), b.dim());
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < tolerance, "{} != {}\n{}\n{}", x, y, a, b);
+        }
+    }
+
+    #[test]
+    fn test_lambdas_recover_the_transform_that_made_the_data_skewed() {
+        for &(method, lambda) in [(PowerMethod::BoxCox, 0.), (PowerMethod::BoxCox, 0.25), (PowerMethod::YeoJohnson, 2.), (PowerMethod::YeoJohnson, 0.5)].iter() {
+            let skewed = normal_sample().mapv(|z| invert(method, z, lambda)).insert_axis(Axis(1));
+
+            let transformer = PowerTransformer::new(method, false).internal_fit(skewed.view()).unwrap();
+            assert!((transformer.lambdas()[0] - lambda).abs() < 0.1, "{:?}: {} != {}", method, transformer.lambdas(), lambda);
+        }
+    }
+
+    #[test]
+    fn test_inverse_transform_round_trips() {
+        let x = Array2::from_shape_fn((20, 2), |(i, j)| (i as f64 + 1.).powi(j as i32 + 2) / 10.);
+
+        for &method in [PowerMethod::BoxCox, PowerMethod::YeoJohnson].iter() {
+            for &standardize in [false, true].iter() {
+                let transformer = PowerTransformer::new(method, standardize).internal_fit(x.view()).unwrap();
+                let transformed = transformer.internal_transform(x.view());
+                assert_close(&transformer.internal_inverse_transform(transformed.view()), &x, 1e-8);
+            }
+        }
+
+        let negative = x.mapv(|v| v - 5.);
+        let transformer = PowerTransformer::new(PowerMethod::YeoJohnson, true).internal_fit(negative.view()).unwrap();
+        assert_close(&transformer.internal_inverse_transform(transformer.internal_transform(negative.view()).view()), &negative, 1e-8);
+        assert!(PowerTransformer::new(PowerMethod::BoxCox, true).internal_fit(negative.view()).is_err());
+    }
+
+    #[test]
+    fn test_constant_columns_are_only_centred() {
+        let x = Array2::from_shape_fn((10, 2), |(i, j)| if j == 0 { 3. } else { i as f64 + 1. });
+
+        for &method in [PowerMethod::BoxCox, PowerMethod::YeoJohnson].iter() {
+            let transformer = PowerTransformer::new(method, true).internal_fit(x.view()).unwrap();
+            let transformed = transformer.internal_transform(x.view());
+
+            assert!(transformed.iter().all(|v| v.is_finite()), "{}", transformed);
+            assert!(transformed.column(0).iter().all(|v| v.abs() < 1e-12));
+            assert_close(&transformer.internal_inverse_transform(transformed.view()), &x, 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_yeo_johnson_matches_box_cox_on_shifted_positive_values() {
+        for &lambda in [-1., 0., 0.5, 2.].iter() {
+            for &v in [0., 0.5, 3.].iter() {
+                assert!((apply(PowerMethod::YeoJohnson, v, lambda) - apply(PowerMethod::BoxCox, v + 1., lambda)).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use log::info;
+use ndarray::prelude::*;
+use rune_core::math::quantile::{self, Interpolation};
+use rune_core::validation::check_x;
+use rune_core::{Error, Result};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::estimator::{Fit, FitTransform, Transformer};
+use serde::{Deserialize, Serialize};
+
+const BOUNDS_THRESHOLD: f64 = 1e-7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OutputDistribution {
+    Uniform,
+    Normal,
+}
+
+/// Maps every feature through its empirical CDF so the output follows a uniform or standard
+/// normal distribution, regardless of outliers or skew in the input.
+#[derive(Debug)]
+pub struct QuantileTransformer {
+    n_quantiles: usize,
+    output_distribution: OutputDistribution,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuantileTransformerTransformer {
+    output_distribution: OutputDistribution,
+    references: Array1<f64>,
+    quantiles: Array2<f64>,
+}
+
+impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for QuantileTransformerTransformer {
+    fn transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_transform(x)
+    }
+
+    fn inverse_transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
+}
+
+impl QuantileTransformerTransformer {
+    pub fn internal_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let mut transformed = x.to_owned();
+
+        for (mut column, quantiles) in transformed.gencolumns_mut().into_iter().zip(self.quantiles.gencolumns()) {
+            column.mapv_inplace(|v| {
+                let p = interpolate(quantiles, self.references.view(), v);
+
+                match self.output_distribution {
+                    OutputDistribution::Uniform => p,
+                    OutputDistribution::Normal => normal_quantile(p.clamp(BOUNDS_THRESHOLD, 1. - BOUNDS_THRESHOLD)),
+                }
+            });
+        }
+
+        transformed
+    }
+
+    pub fn internal_inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let mut original = x.to_owned();
+
+        for (mut column, quantiles) in original.gencolumns_mut().into_iter().zip(self.quantiles.gencolumns()) {
+            column.mapv_inplace(|v| {
+                let p = match self.output_distribution {
+                    OutputDistribution::Uniform => v,
+                    OutputDistribution::Normal => normal_cdf(v),
+                };
+
+                interpolate(self.references.view(), quantiles, p)
+            });
+        }
+
+        original
+    }
+}
+
+impl<Y> Fit<ArrayView2<'_, f64>, QuantileTransformerTransformer, Y> for QuantileTransformer {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<QuantileTransformerTransformer> {
+        check_x(x, 1)?;
+        Ok(self.internal_fit(x))
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, f64>, QuantileTransformerTransformer, Array2<f64>, Y> for QuantileTransformer {}
+
+impl QuantileTransformer {
+    pub fn new(n_quantiles: usize, output_distribution: OutputDistribution) -> Self {
+        assert!(n_quantiles >= 2, "at least 2 quantiles are required");
+
+        QuantileTransformer {
+            n_quantiles,
+            output_distribution,
+        }
+    }
+
+    pub fn internal_fit(&self, x: ArrayView2<f64>) -> QuantileTransformerTransformer {
+        let n_quantiles = self.n_quantiles.min(x.nrows());
+        let references = Array1::linspace(0., 1., n_quantiles);
+
+        let mut quantiles = Array2::zeros((n_quantiles, x.ncols()));
+
+        for (column, mut target) in x.gencolumns().into_iter().zip(quantiles.gencolumns_mut()) {
+            target.assign(&quantile::quantiles(column, references.view(), Interpolation::Linear));
+        }
+
+        info!("quantiles: {}", quantiles);
+
+        QuantileTransformerTransformer {
+            output_distribution: self.output_distribution,
+            references,
+            quantiles,
+        }
+    }
+}
+
+/// Piecewise-linear lookup of `v` in the non-decreasing `xs`, clipped to the ends of `ys`.
+fn interpolate(xs: ArrayView1<f64>, ys: ArrayView1<f64>, v: f64) -> f64 {
+    let n = xs.len();
+
+    if v <= xs[0] {
+        return ys[0];
+    }
+    if v >= xs[n - 1] {
+        return ys[n - 1];
+    }
+
+    let upper = xs.iter().position(|&x| x >= v).unwrap();
+    let (x0, x1) = (xs[upper - 1], xs[upper]);
+
+    if x1 == x0 {
+        ys[upper]
+    } else {
+        ys[upper - 1] + (ys[upper] - ys[upper - 1]) * (v - x0) / (x1 - x0)
+    }
+}
+
+/// Standard normal CDF, using the complementary error function approximation from Numerical
+/// Recipes (fractional error below 1.2e-7).
+fn normal_cdf(z: f64) -> f64 {
+    let x = z.abs() / 2f64.sqrt();
+    let t = 1. / (1. + 0.5 * x);
+    let erfc = t * (-x * x - 1.26551223 + t * (1.00002368 + t * (0.37409196 + t * (0.09678418 + t * (-0.18628806
+        + t * (0.27886807 + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))))).exp();
+
+    if z >= 0. { 1. - erfc / 2. } else { erfc / 2. }
+}
+
+/// Inverse of the standard normal CDF using Acklam's rational approximation.
+pub(crate) fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e+01, 2.209460984245205e+02, -2.759285104469687e+02, 1.38357751867269e+02, -3.066479806614716e+01, 2.506628277459239e+00];
+    const B: [f64; 5] = [-5.447609879822406e+01, 1.615858368580409e+02, -1.556989798598866e+02, 6.680131188771972e+01, -1.328068155288572e+01];
+    const C: [f64; 6] = [-7.784894002430293e-03, -3.223964580411365e-01, -2.400758277161838e+00, -2.549732539343734e+00, 4.374664141464968e+00, 2.938163982698783e+00];
+    const D: [f64; 4] = [7.784695709041462e-03, 3.224671290700398e-01, 2.445134137142996e+00, 3.754408661907416e+00];
+    const P_LOW: f64 = 0.02425;
+
+    if p < P_LOW {
+        let q = (-2. * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5]) / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.)
+    } else if p <= 1. - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.)
+    } else {
+        -normal_quantile(1. - p)
+    }
+}
+
+impl Parameterized for QuantileTransformer {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("n_quantiles".to_owned(), self.n_quantiles.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "n_quantiles" => self.n_quantiles = value.as_usize()?,
+            _ => return Err(Error::InvalidParameter(format!("QuantileTransformer has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, s, Array1, Array2};
+
+    use crate::quantile_transformer::{normal_cdf, normal_quantile, OutputDistribution, QuantileTransformer};
+
+    #[test]
+    fn test_uniform_output_is_the_rank_of_each_value() {
+        let x = array![[10.], [1000.], [1.], [2.], [100.]];
+        let transformer = QuantileTransformer::new(5, OutputDistribution::Uniform).internal_fit(x.view());
+
+        assert_eq!(transformer.internal_transform(x.view()), array![[0.5], [1.], [0.], [0.25], [0.75]]);
+        // Between two quantiles values are interpolated, and beyond them clipped.
+        assert_eq!(transformer.internal_transform(array![[6.], [-5.], [5000.]].view()), array![[0.375], [0.], [1.]]);
+        assert_eq!(transformer.internal_inverse_transform(array![[0.375], [0.75]].view()), array![[6.], [100.]]);
+    }
+
+    #[test]
+    fn test_normal_output_is_symmetric_and_round_trips() {
+        let x = Array2::from_shape_fn((101, 1), |(i, _)| (i as f64 / 10.).exp());
+        let transformer = QuantileTransformer::new(101, OutputDistribution::Normal).internal_fit(x.view());
+        let transformed = transformer.internal_transform(x.view());
+
+        assert!(transformed[[50, 0]].abs() < 1e-9);
+        assert!((transformed[[25, 0]] + transformed[[75, 0]]).abs() < 1e-6);
+        assert!(transformed.column(0).windows(2).into_iter().all(|pair| pair[0] < pair[1]));
+        assert!(transformed.iter().all(|v| v.is_finite()));
+
+        let inner = x.slice(s![1..100, ..]);
+        let round_trip = transformer.internal_inverse_transform(transformer.internal_transform(inner).view());
+        for (original, recovered) in inner.iter().zip(round_trip.iter()) {
+            assert!((original - recovered).abs() / original < 1e-5, "{} != {}", original, recovered);
+        }
+    }
+
+    #[test]
+    fn test_normal_quantile_inverts_the_cdf() {
+        let z: Array1<f64> = Array1::linspace(-4., 4., 33);
+
+        for &z in z.iter() {
+            assert!((normal_quantile(normal_cdf(z)) - z).abs() < 1e-5, "{}", z);
+        }
+        assert!((normal_cdf(1.959964) - 0.975).abs() < 1e-6);
+    }
+}
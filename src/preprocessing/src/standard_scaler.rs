@@ -1,21 +1,39 @@
-use ndarray::{Axis, ArrayView2};
+use ndarray::ArrayView2;
 
 use log::info;
 use ndarray::prelude::*;
-use rune_pipeline::pipeline::{Transformer, Fit};
+use rune_core::math::stats::RunningStats;
+use rune_core::validation::{check_finite, check_x};
+use rune_core::{Error, Result};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::estimator::{Transformer, Fit, FitTransform, IncrementalFit};
+use serde::{Deserialize, Serialize};
+
+/// Columns whose standard deviation falls below this are left unscaled instead of being divided
+/// by (almost) zero.
+pub(crate) const ZERO_VARIANCE_EPSILON: f64 = 1e-12;
 
 #[derive(Debug)]
-pub struct StandardScaler {}
+pub struct StandardScaler {
+    with_mean: bool,
+    with_std: bool,
+}
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StandardScalerTransformer {
     means: Array1<f64>,
     std_dev: Array1<f64>,
+    moments: Option<RunningStats>,
 }
 
 impl Transformer<ArrayView2<'_, f64>, Array2<f64>> for StandardScalerTransformer {
     fn transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
         self.internal_transform(x)
     }
+
+    fn inverse_transform(&self, x: ArrayView2<'_, f64>) -> Array2<f64> {
+        self.internal_inverse_transform(x)
+    }
 }
 
 impl StandardScalerTransformer {
@@ -23,41 +41,143 @@ impl StandardScalerTransformer {
         StandardScalerTransformer {
             means,
             std_dev,
+            moments: None,
         }
     }
 
+    /// The mean subtracted from every column, zero without `with_mean`.
+    pub fn mean(&self) -> ArrayView1<'_, f64> {
+        self.means.view()
+    }
+
+    /// What every column is divided by after centring, one without `with_std` and for constant
+    /// columns.
+    pub fn std_dev(&self) -> ArrayView1<'_, f64> {
+        self.std_dev.view()
+    }
+
     pub fn internal_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
         let xo = x.to_owned();
 
         (&xo - &self.means) / &self.std_dev
     }
+
+    pub fn internal_inverse_transform(&self, x: ArrayView2<f64>) -> Array2<f64> {
+        let xo = x.to_owned();
+
+        &xo * &self.std_dev + &self.means
+    }
 }
 
 
-impl Fit<ArrayView2<'_, f64>, StandardScalerTransformer> for StandardScaler {
-    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<bool>) -> StandardScalerTransformer {
-        self.internal_fit(x)
+impl<Y> Fit<ArrayView2<'_, f64>, StandardScalerTransformer, Y> for StandardScaler {
+    fn fit(&self, x: ArrayView2<f64>, _y: ArrayView1<Y>) -> Result<StandardScalerTransformer> {
+        check_x(x, 1)?;
+        Ok(self.internal_fit(x))
+    }
+}
+
+impl<'a, Y> FitTransform<ArrayView2<'a, f64>, StandardScalerTransformer, Array2<f64>, Y> for StandardScaler {
+    fn fit_transform(&self, x: ArrayView2<'a, f64>, _y: ArrayView1<Y>) -> Result<(StandardScalerTransformer, Array2<f64>)> {
+        check_x(x, 1)?;
+        Ok(self.internal_fit_transform(x))
+    }
+}
+
+/// Merges the running mean and variance of each batch into the model, so every batch after the
+/// first can be discarded once seen.
+impl<'a, Y> IncrementalFit<ArrayView2<'a, f64>, StandardScalerTransformer, Y> for StandardScaler {
+    fn partial_fit(&self, model: &mut StandardScalerTransformer, x: ArrayView2<'a, f64>, _y: ArrayView1<Y>) -> Result<()> {
+        check_finite("x", x)?;
+
+        let mut moments = model.moments.clone()
+            .ok_or_else(|| Error::InvalidInput("only a scaler fitted on data can be updated with partial_fit".to_owned()))?;
+
+        if moments.mean().len() != x.ncols() {
+            return Err(Error::ShapeMismatch(format!("scaler was fitted on {} columns, got {}", moments.mean().len(), x.ncols())));
+        }
+
+        moments.update(x);
+        *model = self.transformer_for(moments);
+        Ok(())
+    }
+}
+
+impl Default for StandardScaler {
+    fn default() -> Self {
+        StandardScaler::new()
     }
 }
 
 impl StandardScaler {
     pub fn new() -> Self {
-        StandardScaler {}
+        StandardScaler {
+            with_mean: true,
+            with_std: true,
+        }
+    }
+
+    /// Whether to centre the data on the mean before scaling.
+    pub fn with_mean(self, with_mean: bool) -> Self {
+        StandardScaler { with_mean, ..self }
+    }
+
+    /// Whether to scale the data to unit standard deviation.
+    pub fn with_std(self, with_std: bool) -> Self {
+        StandardScaler { with_std, ..self }
     }
 
     pub fn internal_fit(&self, x: ArrayView2<f64>) -> StandardScalerTransformer {
-        let xo = x.to_owned();
-        let mean: &Array1<f64> = &xo.mean_axis(Axis(0)).unwrap();
-        let std_dev: &Array1<f64> = &xo.std_axis(Axis(0), 1.);
-        let std_scale = (&xo - mean) / std_dev;
+        self.internal_fit_transform(x).0
+    }
+
+    /// Fits the scaler and returns the scaled `x`, which is computed while fitting anyway.
+    pub fn internal_fit_transform(&self, x: ArrayView2<f64>) -> (StandardScalerTransformer, Array2<f64>) {
+        let transformer = self.transformer_for(RunningStats::of(x));
+        let std_scale = transformer.internal_transform(x);
 
-        info!("mean: {}", mean);
-        info!("std_dev: {}", std_dev);
+        info!("mean: {}", transformer.means);
+        info!("std_dev: {}", transformer.std_dev);
         info!("std_scale: {}", std_scale);
 
-        StandardScalerTransformer::new(
-            mean.to_owned(),
-            std_dev.to_owned(),
-        )
+        (transformer, std_scale)
     }
-}
\ No newline at end of file
+
+    fn transformer_for(&self, moments: RunningStats) -> StandardScalerTransformer {
+        let mean = if self.with_mean {
+            moments.mean().to_owned()
+        } else {
+            Array1::zeros(moments.mean().len())
+        };
+
+        let std_dev = if self.with_std {
+            moments.std_dev(1.).mapv(|s| if s < ZERO_VARIANCE_EPSILON { 1. } else { s })
+        } else {
+            Array1::ones(moments.mean().len())
+        };
+
+        StandardScalerTransformer {
+            means: mean,
+            std_dev,
+            moments: Some(moments),
+        }
+    }
+}
+
+impl Parameterized for StandardScaler {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("with_mean".to_owned(), self.with_mean.into());
+        params.insert("with_std".to_owned(), self.with_std.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "with_mean" => self.with_mean = value.as_bool()?,
+            "with_std" => self.with_std = value.as_bool()?,
+            _ => return Err(Error::InvalidParameter(format!("StandardScaler has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
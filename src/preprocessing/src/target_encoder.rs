@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use log::{debug, info};
+use ndarray::prelude::*;
+use rune_core::validation::check_n_samples;
+use rune_core::{Error, Result};
+use rune_model_selection::splitting::k_fold::KFold;
+use rune_core::estimator::{Fit, FitTransform, Transformer};
+use serde::{Deserialize, Serialize};
+
+/// Replaces each category with the mean target of the rows sharing it, shrunk towards the global
+/// mean by `smoothing` pseudo-counts so rare categories are not trusted blindly.
+///
+/// Encoding the training rows with statistics that include their own targets leaks the label, so
+/// `fit_transform` encodes every training row with statistics computed on the other folds only.
+#[derive(Debug)]
+pub struct TargetEncoder {
+    smoothing: f64,
+    cv: KFold,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TargetEncoderTransformer<T: Eq + Hash> {
+    prior: f64,
+    encodings: Vec<HashMap<T, f64>>,
+}
+
+impl<T: Eq + Hash> Transformer<ArrayView2<'_, T>, Array2<f64>> for TargetEncoderTransformer<T> {
+    fn transform(&self, x: ArrayView2<'_, T>) -> Array2<f64> {
+        self.internal_transform(x)
+    }
+}
+
+impl<T: Eq + Hash> TargetEncoderTransformer<T> {
+    pub fn prior(&self) -> f64 {
+        self.prior
+    }
+
+    /// Categories not seen during fit are encoded with the global target mean.
+    pub fn internal_transform(&self, x: ArrayView2<T>) -> Array2<f64> {
+        Array2::from_shape_fn(x.dim(), |(row, column)| {
+            *self.encodings[column].get(&x[[row, column]]).unwrap_or(&self.prior)
+        })
+    }
+}
+
+impl<T: Clone + Eq + Hash> Fit<ArrayView2<'_, T>, TargetEncoderTransformer<T>> for TargetEncoder {
+    fn fit(&self, x: ArrayView2<T>, y: ArrayView1<bool>) -> Result<TargetEncoderTransformer<T>> {
+        self.internal_fit(x, y.mapv(|label| if label { 1. } else { 0. }).view())
+    }
+}
+
+/// Regression targets are encoded as their mean per category.
+impl<T: Clone + Eq + Hash> Fit<ArrayView2<'_, T>, TargetEncoderTransformer<T>, f64> for TargetEncoder {
+    fn fit(&self, x: ArrayView2<T>, y: ArrayView1<f64>) -> Result<TargetEncoderTransformer<T>> {
+        self.internal_fit(x, y)
+    }
+}
+
+/// Returns the out-of-fold encoding of the training rows rather than `transform(x)`, see
+/// `TargetEncoder::fit_transform`.
+impl<'a, T: Clone + Eq + Hash> FitTransform<ArrayView2<'a, T>, TargetEncoderTransformer<T>, Array2<f64>> for TargetEncoder {
+    fn fit_transform(&self, x: ArrayView2<'a, T>, y: ArrayView1<bool>) -> Result<(TargetEncoderTransformer<T>, Array2<f64>)> {
+        TargetEncoder::fit_transform(self, x, y.mapv(|label| if label { 1. } else { 0. }).view())
+    }
+}
+
+impl<'a, T: Clone + Eq + Hash> FitTransform<ArrayView2<'a, T>, TargetEncoderTransformer<T>, Array2<f64>, f64> for TargetEncoder {
+    fn fit_transform(&self, x: ArrayView2<'a, T>, y: ArrayView1<f64>) -> Result<(TargetEncoderTransformer<T>, Array2<f64>)> {
+        TargetEncoder::fit_transform(self, x, y)
+    }
+}
+
+impl TargetEncoder {
+    pub fn new(smoothing: f64, cv: KFold) -> Self {
+        TargetEncoder {
+            smoothing,
+            cv,
+        }
+    }
+
+    pub fn internal_fit<T: Clone + Eq + Hash>(&self, x: ArrayView2<T>, y: ArrayView1<f64>) -> Result<TargetEncoderTransformer<T>> {
+        check_n_samples(x.nrows(), y.len())?;
+        let prior = y.mean().ok_or_else(|| Error::InvalidInput("cannot fit a target encoder on an empty target".to_owned()))?;
+
+        let encodings = x.gencolumns()
+            .into_iter()
+            .map(|column| {
+                let mut statistics: HashMap<T, (f64, f64)> = HashMap::new();
+
+                for (category, &target) in column.iter().zip(y.iter()) {
+                    let entry = statistics.entry(category.clone()).or_insert((0., 0.));
+                    entry.0 += target;
+                    entry.1 += 1.;
+                }
+
+                statistics.into_iter()
+                    .map(|(category, (sum, count))| (category, (sum + self.smoothing * prior) / (count + self.smoothing)))
+                    .collect()
+            })
+            .collect();
+
+        Ok(TargetEncoderTransformer {
+            prior,
+            encodings,
+        })
+    }
+
+    /// Fits on all rows and returns the out-of-fold encoding of `x` to train downstream models on.
+    pub fn fit_transform<T: Clone + Eq + Hash>(&self, x: ArrayView2<T>, y: ArrayView1<f64>) -> Result<(TargetEncoderTransformer<T>, Array2<f64>)> {
+        let mut encoded = Array2::zeros(x.dim());
+
+        for (fold, (train, test)) in self.cv.split(x.nrows())?.into_iter().enumerate() {
+            debug!("Encoding fold {} from {} training rows", fold, train.len());
+
+            let train_x = Array2::from_shape_fn((train.len(), x.ncols()), |(row, column)| x[[train[row], column]].clone());
+            let test_x = Array2::from_shape_fn((test.len(), x.ncols()), |(row, column)| x[[test[row], column]].clone());
+
+            let fold_encoded = self.internal_fit(train_x.view(), y.select(Axis(0), &train).view())?
+                .internal_transform(test_x.view());
+
+            for (row, &index) in test.iter().enumerate() {
+                encoded.row_mut(index).assign(&fold_encoded.row(row));
+            }
+        }
+
+        let transformer = self.internal_fit(x, y)?;
+        info!("prior: {}", transformer.prior);
+
+        Ok((transformer, encoded))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use rune_model_selection::splitting::k_fold::KFold;
+
+    use crate::target_encoder::TargetEncoder;
+
+    #[test]
+    fn test_training_rows_are_encoded_without_their_own_fold() {
+        let x = array![["a"], ["a"], ["a"], ["a"]];
+        let y = array![1., 1., 0., 0.];
+
+        let (transformer, encoded) = TargetEncoder::new(0., KFold::new(2)).fit_transform(x.view(), y.view()).unwrap();
+
+        // Rows 0 and 1 only see the targets of rows 2 and 3, and the other way around.
+        assert_eq!(encoded, array![[0.], [0.], [1.], [1.]]);
+        assert_eq!(transformer.internal_transform(x.view()), array![[0.5], [0.5], [0.5], [0.5]]);
+    }
+
+    #[test]
+    fn test_unseen_categories_fall_back_to_the_global_mean() {
+        let x = array![["a"], ["a"], ["b"], ["b"]];
+        let y = array![1., 0., 1., 1.];
+
+        let (transformer, encoded) = TargetEncoder::new(0., KFold::new(2)).fit_transform(x.view(), y.view()).unwrap();
+
+        // Neither fold sees the other's category, so each falls back to its own training mean.
+        assert_eq!(encoded, array![[1.], [1.], [0.5], [0.5]]);
+        assert_eq!(transformer.prior(), 0.75);
+        assert_eq!(transformer.internal_transform(array![["a"], ["b"], ["c"]].view()), array![[0.5], [1.], [0.75]]);
+
+        // Smoothing pulls categories towards the global mean by as many pseudo-counts.
+        let smoothed = TargetEncoder::new(2., KFold::new(2)).internal_fit(x.view(), y.view()).unwrap();
+        assert_eq!(smoothed.internal_transform(array![["a"], ["c"]].view()), array![[(1. + 1.5) / 4.], [0.75]]);
+    }
+}
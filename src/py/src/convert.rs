@@ -0,0 +1,38 @@
+//! Copies between numpy arrays and rune's ndarray arrays, which are different versions of
+//! ndarray, and maps rune's errors onto Python exceptions.
+
+use ndarray::{Array1, Array2};
+use numpy::{Element, IntoPyArray, PyArray1, PyArray2, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use rune_core::Error;
+
+pub(crate) fn from_numpy2(x: PyReadonlyArray2<f64>) -> Array2<f64> {
+    let x = x.as_array();
+    Array2::from_shape_vec(x.dim(), x.iter().cloned().collect()).unwrap()
+}
+
+pub(crate) fn from_numpy1<T: Element + Clone>(y: PyReadonlyArray1<T>) -> Array1<T> {
+    y.as_array().iter().cloned().collect()
+}
+
+pub(crate) fn to_numpy2(py: Python<'_>, x: Array2<f64>) -> Bound<'_, PyArray2<f64>> {
+    numpy::ndarray::Array2::from_shape_vec(x.dim(), x.iter().cloned().collect()).unwrap().into_pyarray(py)
+}
+
+pub(crate) fn to_numpy1<T: Element>(py: Python<'_>, y: Array1<T>) -> Bound<'_, PyArray1<T>> {
+    PyArray1::from_vec(py, y.into_raw_vec())
+}
+
+/// Bad parameters or data raise `ValueError`, as they do in scikit-learn; anything else
+/// `RuntimeError`.
+pub(crate) fn to_py_err(error: Error) -> PyErr {
+    match error {
+        Error::InvalidParameter(_) | Error::InvalidInput(_) | Error::ShapeMismatch(_) | Error::Shape(_) => PyValueError::new_err(error.to_string()),
+        _ => PyRuntimeError::new_err(error.to_string()),
+    }
+}
+
+pub(crate) fn fitted<'a, M>(model: &'a Option<M>, name: &str) -> PyResult<&'a M> {
+    model.as_ref().ok_or_else(|| PyRuntimeError::new_err(format!("this {} is not fitted yet, call fit first", name)))
+}
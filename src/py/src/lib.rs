@@ -0,0 +1,250 @@
+//! Python bindings, built into a `rune` extension module with maturin. The estimators follow
+//! scikit-learn's conventions: they take and return numpy arrays, `fit` returns the estimator,
+//! and the fitted state lives on it.
+
+mod convert;
+
+use ndarray::Array1;
+use numpy::{PyArray1, PyArray2, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rune_linear::linear_model::LinearOptions;
+use rune_linear::multiple_linear_regression::{MultipleLinearRegression, MultipleLinearRegressionModel};
+use rune_core::estimator::{Fit, Predictor, UnsupervisedFit};
+use rune_preprocessing::standard_scaler::{StandardScaler, StandardScalerTransformer};
+use rune_tree::feature_selector::greedy_feature_selector::GreedyFeatureSelector;
+use rune_tree::measures::entropy::EntropySelectionMeasure;
+use rune_tree::{DecisionTreeClassifier, DecisionTreeModel};
+
+use crate::convert::{fitted, from_numpy1, from_numpy2, to_numpy1, to_numpy2, to_py_err};
+
+#[pyclass(name = "StandardScaler")]
+struct PyStandardScaler {
+    scaler: StandardScaler,
+    model: Option<StandardScalerTransformer>,
+}
+
+#[pymethods]
+impl PyStandardScaler {
+    #[new]
+    #[pyo3(signature = (with_mean = true, with_std = true))]
+    fn new(with_mean: bool, with_std: bool) -> Self {
+        PyStandardScaler {
+            scaler: StandardScaler::new().with_mean(with_mean).with_std(with_std),
+            model: None,
+        }
+    }
+
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: PyReadonlyArray2<'py, f64>) -> PyResult<PyRefMut<'py, Self>> {
+        let x = from_numpy2(x);
+        slf.model = Some(slf.scaler.fit_unsupervised(x.view()).map_err(to_py_err)?);
+        Ok(slf)
+    }
+
+    fn transform<'py>(&self, py: Python<'py>, x: PyReadonlyArray2<'py, f64>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        let model = fitted(&self.model, "StandardScaler")?;
+        Ok(to_numpy2(py, model.internal_transform(from_numpy2(x).view())))
+    }
+
+    fn fit_transform<'py>(mut slf: PyRefMut<'py, Self>, py: Python<'py>, x: PyReadonlyArray2<'py, f64>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        let (model, transformed) = slf.scaler.internal_fit_transform(from_numpy2(x).view());
+        slf.model = Some(model);
+        Ok(to_numpy2(py, transformed))
+    }
+
+    fn inverse_transform<'py>(&self, py: Python<'py>, x: PyReadonlyArray2<'py, f64>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+        let model = fitted(&self.model, "StandardScaler")?;
+        Ok(to_numpy2(py, model.internal_inverse_transform(from_numpy2(x).view())))
+    }
+}
+
+/// An entropy-based decision tree over integer class labels.
+#[pyclass(name = "DecisionTreeClassifier")]
+struct PyDecisionTreeClassifier {
+    classifier: DecisionTreeClassifier<GreedyFeatureSelector<EntropySelectionMeasure>>,
+    model: Option<DecisionTreeModel<i64>>,
+}
+
+#[pymethods]
+impl PyDecisionTreeClassifier {
+    #[new]
+    #[pyo3(signature = (max_depth = 10, min_size = 1))]
+    fn new(max_depth: u32, min_size: usize) -> Self {
+        PyDecisionTreeClassifier {
+            classifier: DecisionTreeClassifier::new(max_depth, min_size, GreedyFeatureSelector::new(EntropySelectionMeasure::new())),
+            model: None,
+        }
+    }
+
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: PyReadonlyArray2<'py, f64>, y: PyReadonlyArray1<'py, i64>) -> PyResult<PyRefMut<'py, Self>> {
+        let (x, y) = (from_numpy2(x), from_numpy1(y));
+        slf.model = Some(slf.classifier.fit(x.view(), y.view()).map_err(to_py_err)?);
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: PyReadonlyArray2<'py, f64>) -> PyResult<Bound<'py, PyArray1<i64>>> {
+        let model = fitted(&self.model, "DecisionTreeClassifier")?;
+        Ok(to_numpy1(py, model.predict(from_numpy2(x).view())))
+    }
+}
+
+/// Least-squares linear regression fitted by gradient descent.
+#[pyclass(name = "LinearRegression")]
+struct PyLinearRegression {
+    regressor: MultipleLinearRegression,
+    model: Option<MultipleLinearRegressionModel>,
+}
+
+#[pymethods]
+impl PyLinearRegression {
+    #[new]
+    #[pyo3(signature = (alpha = 0.01, iterations = 1000, fit_intercept = true))]
+    fn new(alpha: f64, iterations: usize, fit_intercept: bool) -> Self {
+        PyLinearRegression {
+            regressor: MultipleLinearRegression::new(alpha, iterations).options(LinearOptions::new().fit_intercept(fit_intercept)),
+            model: None,
+        }
+    }
+
+    fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: PyReadonlyArray2<'py, f64>, y: PyReadonlyArray1<'py, f64>) -> PyResult<PyRefMut<'py, Self>> {
+        let (x, y): (_, Array1<f64>) = (from_numpy2(x), from_numpy1(y));
+        slf.model = Some(slf.regressor.fit(x.view(), y.view()).map_err(to_py_err)?);
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: PyReadonlyArray2<'py, f64>) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        let model = fitted(&self.model, "LinearRegression")?;
+        Ok(to_numpy1(py, model.predict(from_numpy2(x).view())))
+    }
+
+    #[getter]
+    fn coef_<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        let model = fitted(&self.model, "LinearRegression")?;
+        Ok(to_numpy1(py, model.coef().to_owned()))
+    }
+
+    #[getter]
+    fn intercept_(&self) -> PyResult<f64> {
+        Ok(fitted(&self.model, "LinearRegression")?.intercept())
+    }
+}
+
+#[cfg(feature = "decomposition")]
+mod decomposition {
+    use numpy::{PyArray1, PyArray2, PyReadonlyArray2};
+    use pyo3::prelude::*;
+    use rune_decomposition::principal_component_analysis::{PrincipalComponentAnalysis, PrincipalComponentAnalysisTransformer};
+    use rune_core::estimator::UnsupervisedFit;
+
+    use crate::convert::{fitted, from_numpy2, to_numpy1, to_numpy2, to_py_err};
+
+    #[pyclass(name = "PCA")]
+    pub(crate) struct PyPrincipalComponentAnalysis {
+        pca: PrincipalComponentAnalysis,
+        model: Option<PrincipalComponentAnalysisTransformer>,
+    }
+
+    #[pymethods]
+    impl PyPrincipalComponentAnalysis {
+        #[new]
+        #[pyo3(signature = (n_components, whiten = false))]
+        fn new(n_components: usize, whiten: bool) -> Self {
+            PyPrincipalComponentAnalysis {
+                pca: PrincipalComponentAnalysis::new(n_components).whiten(whiten),
+                model: None,
+            }
+        }
+
+        fn fit<'py>(mut slf: PyRefMut<'py, Self>, x: PyReadonlyArray2<'py, f64>) -> PyResult<PyRefMut<'py, Self>> {
+            let x = from_numpy2(x);
+            slf.model = Some(slf.pca.fit_unsupervised(x.view()).map_err(to_py_err)?);
+            Ok(slf)
+        }
+
+        fn transform<'py>(&self, py: Python<'py>, x: PyReadonlyArray2<'py, f64>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+            let model = fitted(&self.model, "PCA")?;
+            Ok(to_numpy2(py, model.internal_transform(from_numpy2(x).view())))
+        }
+
+        fn fit_transform<'py>(mut slf: PyRefMut<'py, Self>, py: Python<'py>, x: PyReadonlyArray2<'py, f64>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+            let x = from_numpy2(x);
+            let model = slf.pca.fit_unsupervised(x.view()).map_err(to_py_err)?;
+            let transformed = model.internal_transform(x.view());
+            slf.model = Some(model);
+            Ok(to_numpy2(py, transformed))
+        }
+
+        fn inverse_transform<'py>(&self, py: Python<'py>, x: PyReadonlyArray2<'py, f64>) -> PyResult<Bound<'py, PyArray2<f64>>> {
+            let model = fitted(&self.model, "PCA")?;
+            Ok(to_numpy2(py, model.internal_inverse_transform(from_numpy2(x).view())))
+        }
+
+        #[getter]
+        fn explained_variance_ratio_<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<f64>>> {
+            let model = fitted(&self.model, "PCA")?;
+            Ok(to_numpy1(py, model.explained_variance_ratio().to_owned()))
+        }
+    }
+}
+
+/// Chains steps like scikit-learn's `Pipeline`: every step but the last is fitted with
+/// `fit_transform` and feeds its output to the next, and the last is fitted with `fit` and
+/// answers `predict` or `transform`. Any Python objects with those methods can be steps, so rune
+/// and scikit-learn estimators mix.
+#[pyclass(name = "Pipeline")]
+struct PyPipeline {
+    steps: Vec<Py<PyAny>>,
+}
+
+#[pymethods]
+impl PyPipeline {
+    #[new]
+    fn new(steps: Vec<Py<PyAny>>) -> PyResult<Self> {
+        if steps.is_empty() {
+            return Err(PyValueError::new_err("a pipeline needs at least one step"));
+        }
+
+        Ok(PyPipeline { steps })
+    }
+
+    #[pyo3(signature = (x, y = None))]
+    fn fit<'py>(slf: PyRef<'py, Self>, py: Python<'py>, x: Bound<'py, PyAny>, y: Option<Bound<'py, PyAny>>) -> PyResult<PyRef<'py, Self>> {
+        let (last, transformers) = slf.steps.split_last().unwrap();
+
+        let mut x = x;
+        for step in transformers {
+            x = step.bind(py).call_method1("fit_transform", (x,))?;
+        }
+
+        match y {
+            Some(y) => last.bind(py).call_method1("fit", (x, y))?,
+            None => last.bind(py).call_method1("fit", (x,))?,
+        };
+
+        Ok(slf)
+    }
+
+    fn predict<'py>(&self, py: Python<'py>, x: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let (last, transformers) = self.steps.split_last().unwrap();
+        last.bind(py).call_method1("predict", (transform_through(py, transformers, x)?,))
+    }
+
+    fn transform<'py>(&self, py: Python<'py>, x: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        transform_through(py, &self.steps, x)
+    }
+}
+
+fn transform_through<'py>(py: Python<'py>, steps: &[Py<PyAny>], x: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    steps.iter().try_fold(x, |x, step| step.bind(py).call_method1("transform", (x,)))
+}
+
+#[pymodule]
+fn rune(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyStandardScaler>()?;
+    m.add_class::<PyDecisionTreeClassifier>()?;
+    m.add_class::<PyLinearRegression>()?;
+    m.add_class::<PyPipeline>()?;
+    #[cfg(feature = "decomposition")]
+    m.add_class::<decomposition::PyPrincipalComponentAnalysis>()?;
+    Ok(())
+}
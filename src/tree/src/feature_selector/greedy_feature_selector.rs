@@ -1,6 +1,3 @@
-use std::fmt::Debug;
-use std::hash::Hash;
-
 use log::*;
 use ndarray::{ArrayView1, ArrayView2};
 
@@ -12,7 +9,7 @@ pub struct GreedyFeatureSelector<SM: SelectionMeasure> {
     selection_measure: SM,
 }
 
-impl<SM: SelectionMeasure + Debug> GreedyFeatureSelector<SM> {
+impl<SM: SelectionMeasure> GreedyFeatureSelector<SM> {
     pub fn new(selection_measure: SM) -> Self {
         GreedyFeatureSelector {
             selection_measure
@@ -35,8 +32,8 @@ impl<SM: SelectionMeasure + Debug> GreedyFeatureSelector<SM> {
     }
 }
 
-impl<SM: SelectionMeasure + Debug> FeatureSelector for GreedyFeatureSelector<SM> {
-    fn apply<T: Copy + Eq + Hash>(&self, x: ArrayView2<f64>, y: ArrayView1<T>) -> SplitResult {
+impl<SM: SelectionMeasure> FeatureSelector for GreedyFeatureSelector<SM> {
+    fn apply(&self, x: ArrayView2<f64>, y: ArrayView1<usize>) -> SplitResult {
         let rows = x.nrows();
 
         let mut best_score = -1.;
@@ -1,4 +1,5 @@
-use std::hash::Hash;
+use std::fmt::Debug;
+
 use ndarray::{ArrayView2, ArrayView1};
 
 
@@ -14,6 +15,14 @@ type FeatureIndex = IndexSelector;
 
 type SplitResult = (LeftIndexes, RightIndexes, SplitThreshold, FeatureIndex);
 
-pub trait FeatureSelector {
-    fn apply<T: Copy + Eq + Hash>(&self, x: ArrayView2<f64>, y: ArrayView1<T>) -> SplitResult;
+/// Chooses the split of the rows of `x` given `y`, their class indexes. Object safe, so trees can
+/// take `Box<dyn FeatureSelector>` and differently split trees share one type.
+pub trait FeatureSelector: Debug {
+    fn apply(&self, x: ArrayView2<f64>, y: ArrayView1<usize>) -> SplitResult;
+}
+
+impl<FS: FeatureSelector + ?Sized> FeatureSelector for Box<FS> {
+    fn apply(&self, x: ArrayView2<f64>, y: ArrayView1<usize>) -> SplitResult {
+        (**self).apply(x, y)
+    }
 }
\ No newline at end of file
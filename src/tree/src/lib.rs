@@ -1,38 +1,83 @@
 pub mod feature_selector;
 pub mod measures;
 pub mod math;
+pub mod regressor;
+pub mod rules;
+pub mod sklearn;
+pub mod stump;
 
-use std::fmt::Debug;
+use std::collections::HashMap;
 use std::hash::Hash;
+use std::time::Instant;
 
 use log::*;
 use ndarray::{Array1, ArrayView1, ArrayView2, Axis, Array2};
+use rand::seq::index::sample;
+use rand::Rng;
 use crate::feature_selector::FeatureSelector;
-use crate::math::histogram::histogram;
 use crate::measures::entropy::entropy;
-use rune_pipeline::pipeline::{Fit, Transformer};
+use rune_core::callback::{Callbacks, FitCallback};
+use rune_core::random::RngSource;
+use rune_core::validation::check_x_y;
+use rune_core::{Error, Result};
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use rune_core::cost_matrix::CostMatrix;
+use rune_core::estimator::{Fit, Predictor, ProbabilisticPredictor, Score};
+use rune_metrics::metrics::accuracy_score;
+use rune_model_selection::grid_search::{GridSearchCV, GridSearchModel, ParamGrid};
+use rune_model_selection::splitting::CrossValidator;
+use serde::{Deserialize, Serialize};
 
 
-impl<FS: FeatureSelector + Debug> Fit<Array2<f64>, DecisionTreeModel<bool>> for DecisionTreeClassifier<FS> {
-    fn fit(&self, x: Array2<f64>, y: ArrayView1<bool>) -> DecisionTreeModel<bool> {
-        self.fit_internal(x.view(), y)
+impl<FS: FeatureSelector, Y: Copy + Hash + Eq> Fit<Array2<f64>, DecisionTreeModel<Y>, Y> for DecisionTreeClassifier<FS> {
+    fn fit(&self, x: Array2<f64>, y: ArrayView1<Y>) -> Result<DecisionTreeModel<Y>> {
+        check_x_y(x.view(), y, 1)?;
+        Ok(self.fit_internal(x.view(), y))
     }
 }
 
-impl Transformer<Array2<f64>, Array1<bool>> for DecisionTreeModel<bool> {
-    fn transform(&self, x: Array2<f64>) -> Array1<bool> {
-        self.predict(x.view())
+impl<FS: FeatureSelector, Y: Copy + Hash + Eq> Fit<ArrayView2<'_, f64>, DecisionTreeModel<Y>, Y> for DecisionTreeClassifier<FS> {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<DecisionTreeModel<Y>> {
+        check_x_y(x, y, 1)?;
+        Ok(self.fit_internal(x, y))
     }
 }
 
+impl<T: Eq + Hash + Default + Copy> Predictor<Array2<f64>, Array1<T>> for DecisionTreeModel<T> {
+    fn predict(&self, x: Array2<f64>) -> Array1<T> {
+        self.predict_rows(x.view())
+    }
+}
+
+impl<T: Eq + Hash + Default + Copy> Predictor<ArrayView2<'_, f64>, Array1<T>> for DecisionTreeModel<T> {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<T> {
+        self.predict_rows(x)
+    }
+}
+
+/// The fraction of rows whose class is predicted correctly.
+impl<T: Eq + Hash + Default + Copy> Score<ArrayView2<'_, f64>, T> for DecisionTreeModel<T> {
+    fn score(&self, x: ArrayView2<f64>, y: ArrayView1<T>) -> f64 {
+        accuracy_score(y, self.predict_rows(x).view(), None)
+    }
+}
+
+/// The `max_depth` values `DecisionTreeClassifier::fit_auto` tries.
+pub const AUTO_MAX_DEPTHS: [u32; 6] = [2, 3, 4, 6, 8, 12];
+
+/// The `min_size` values `DecisionTreeClassifier::fit_auto` tries with each depth.
+pub const AUTO_MIN_SIZES: [usize; 4] = [1, 5, 10, 20];
+
 #[derive(Debug)]
 pub struct DecisionTreeClassifier<FS> {
     max_depth: u32,
     min_size: usize,
     feature_selector: FS,
+    split_subsample: Option<(usize, RngSource)>,
+    callbacks: Callbacks,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 enum DecisionTreeNode<T> {
     Interior {
         feature: usize,
@@ -42,6 +87,15 @@ enum DecisionTreeNode<T> {
     },
     Leaf {
         probability: T,
+        /// How many training rows reached the leaf.
+        #[serde(default)]
+        support: usize,
+        /// The fraction of those rows in the predicted class.
+        #[serde(default)]
+        confidence: f64,
+        /// The fraction of those rows in each of the model's classes.
+        #[serde(default)]
+        proportions: Vec<f64>,
     },
 }
 
@@ -60,18 +114,6 @@ impl<T: Copy + Eq + Hash> DecisionTreeNode<T> {
         }
     }
 
-    fn new_leaf_node(y: ArrayView1<T>) -> DecisionTreeNode<T> {
-        let distribution = histogram(y);
-
-        let (key, _) = distribution
-            .iter()
-            .max_by_key(|&(_, value)| {
-                value
-            }).unwrap();
-
-        DecisionTreeNode::Leaf { probability: *key }
-    }
-
     pub fn predict(&self, x: ArrayView1<f64>) -> T {
         return match *self {
             DecisionTreeNode::Interior { feature, threshold, ref left, ref right } => {
@@ -81,18 +123,103 @@ impl<T: Copy + Eq + Hash> DecisionTreeNode<T> {
                     right.predict(x)
                 }
             }
-            DecisionTreeNode::Leaf { probability } => { return probability; }
+            DecisionTreeNode::Leaf { probability, .. } => { return probability; }
         };
     }
 }
 
-#[derive(Debug)]
+impl<T> DecisionTreeNode<T> {
+    /// The class proportions of the leaf `x` falls in.
+    fn proportions(&self, x: ArrayView1<f64>) -> &[f64] {
+        match self {
+            DecisionTreeNode::Interior { feature, threshold, left, right } => {
+                if x[*feature] < *threshold { left.proportions(x) } else { right.proportions(x) }
+            }
+            DecisionTreeNode::Leaf { proportions, .. } => proportions,
+        }
+    }
+}
+
+impl DecisionTreeNode<usize> {
+    fn new_leaf_node(y: ArrayView1<usize>) -> DecisionTreeNode<usize> {
+        let mut counts = vec![0; y.iter().max().map_or(0, |&max| max + 1)];
+        for &label in y {
+            counts[label] += 1;
+        }
+        let best = (0..counts.len()).fold(0, |best, class| if counts[class] > counts[best] { class } else { best });
+
+        DecisionTreeNode::Leaf {
+            probability: best,
+            support: y.len(),
+            confidence: counts[best] as f64 / y.len() as f64,
+            proportions: counts.iter().map(|&count| count as f64 / y.len() as f64).collect(),
+        }
+    }
+
+    /// Swaps the class index in every leaf for the class it indexes.
+    fn map_classes<T: Copy>(self, classes: &[T]) -> DecisionTreeNode<T> {
+        match self {
+            DecisionTreeNode::Interior { feature, threshold, left, right } => DecisionTreeNode::Interior {
+                feature,
+                threshold,
+                left: Box::new(left.map_classes(classes)),
+                right: Box::new(right.map_classes(classes)),
+            },
+            DecisionTreeNode::Leaf { probability, support, confidence, mut proportions } => {
+                // Leaves only count the classes up to the last one among their rows.
+                proportions.resize(classes.len(), 0.);
+                DecisionTreeNode::Leaf { probability: classes[probability], support, confidence, proportions }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DecisionTreeModel<T> {
-    tree: DecisionTreeNode<T>
+    tree: DecisionTreeNode<T>,
+    #[serde(default = "Vec::new")]
+    classes: Vec<T>,
+}
+
+/// The fraction of the training rows in the leaf each row falls in that are of each class, one
+/// column per class in the order of `classes`. Fails with `InvalidInput` for models saved before
+/// trees kept their class proportions.
+impl<T> ProbabilisticPredictor<ArrayView2<'_, f64>, Result<Array2<f64>>> for DecisionTreeModel<T> {
+    fn predict_proba(&self, x: ArrayView2<f64>) -> Result<Array2<f64>> {
+        if self.classes.is_empty() {
+            return Err(Error::InvalidInput("the model was saved without class proportions; refit it to predict probabilities".to_owned()));
+        }
+
+        let mut probabilities = Array2::zeros((x.nrows(), self.classes.len()));
+        for (row, mut out) in x.genrows().into_iter().zip(probabilities.genrows_mut()) {
+            out.assign(&ArrayView1::from(self.tree.proportions(row)));
+        }
+        Ok(probabilities)
+    }
+}
+
+impl<T: Copy> DecisionTreeModel<T> {
+    /// The classes the tree was fitted on, in the order of the columns of `predict_proba` and of
+    /// the rows and columns of cost matrices.
+    pub fn classes(&self) -> &[T] {
+        &self.classes
+    }
+
+    /// Predicts the class with the least expected cost under `costs` and the probabilities of
+    /// `predict_proba`, rather than the most probable one. Fails as `predict_proba` does, and
+    /// with `ShapeMismatch` unless `costs` has a row and a column per class.
+    pub fn predict_with_costs(&self, x: ArrayView2<f64>, costs: &CostMatrix) -> Result<Array1<T>> {
+        let probabilities = self.predict_proba(x)?;
+        if costs.n_classes() != self.classes.len() {
+            return Err(Error::ShapeMismatch(format!("the cost matrix is for {} classes, the tree has {}", costs.n_classes(), self.classes.len())));
+        }
+
+        Ok(costs.decide_rows(probabilities.view()).mapv(|class| self.classes[class]))
+    }
 }
 
 impl<T: Eq + Hash + Default + Copy> DecisionTreeModel<T> {
-    pub fn predict(&self, x: ArrayView2<f64>) -> Array1<T> {
+    fn predict_rows(&self, x: ArrayView2<f64>) -> Array1<T> {
         let mut results = Array1::<T>::default(x.nrows());
 
         for row_index in 0..x.nrows() {
@@ -105,26 +232,82 @@ impl<T: Eq + Hash + Default + Copy> DecisionTreeModel<T> {
     }
 }
 
-impl<FS> DecisionTreeClassifier<FS> where FS: FeatureSelector + Debug {
+impl<FS> DecisionTreeClassifier<FS> where FS: FeatureSelector {
     pub fn new(max_depth: u32, min_size: usize, feature_selector: FS) -> Self {
         DecisionTreeClassifier {
             max_depth,
             min_size,
             feature_selector,
+            split_subsample: None,
+            callbacks: Callbacks::new(),
         }
     }
 
+    /// Calls `callback` after every node is split.
+    pub fn with_callback<C: FitCallback + 'static>(mut self, callback: C) -> Self {
+        self.callbacks.push(callback);
+        self
+    }
+
+    /// Has nodes of more than `max_rows` rows choose their split from `max_rows` of them drawn
+    /// from `rng`, then split all of them, so that the cost of a node no longer grows with its
+    /// size. Smaller nodes are split exactly. The leaves still count every row.
+    pub fn split_subsample(mut self, max_rows: usize, rng: RngSource) -> Self {
+        assert!(max_rows >= 2, "splits need at least 2 rows to choose from");
+        self.split_subsample = Some((max_rows, rng));
+        self
+    }
+
+    /// Cross-validates the accuracy of every `max_depth` in `AUTO_MAX_DEPTHS` with every
+    /// `min_size` in `AUTO_MIN_SIZES` on the folds of `cv` with `GridSearchCV`, then refits the
+    /// best on all of `x`. Everything else is as configured on this classifier, whose own limits
+    /// are ignored. The returned model has the best tree and the mean score of every combination.
+    pub fn fit_auto<Y, C>(&self, x: ArrayView2<f64>, y: ArrayView1<Y>, cv: &C) -> Result<GridSearchModel<DecisionTreeModel<Y>>>
+        where
+            FS: Sync,
+            Y: Copy + Hash + Eq + Default + Sync,
+            C: CrossValidator<Y> + Sync {
+        let grid = ParamGrid::new()
+            .add("max_depth", AUTO_MAX_DEPTHS.to_vec())
+            .add("min_size", AUTO_MIN_SIZES.to_vec());
+        let build = |params: &Params| -> Result<LimitedTree<FS>> {
+            Ok(LimitedTree {
+                classifier: self,
+                limits: (params["max_depth"].as_usize()? as u32, params["min_size"].as_usize()?),
+            })
+        };
+        let accuracy = |y_true: ArrayView1<Y>, y_pred: ArrayView1<Y>| accuracy_score(y_true, y_pred, None);
+
+        GridSearchCV::new(build, grid, cv, accuracy).fit(x, y)
+    }
+
     pub fn fit_internal<Y: Copy + Hash + Eq>(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> DecisionTreeModel<Y> {
+        self.fit_limited(x, y, (self.max_depth, self.min_size))
+    }
+
+    /// Fits a tree limited to `(max_depth, min_size)` rather than this classifier's own limits.
+    fn fit_limited<Y: Copy + Hash + Eq>(&self, x: ArrayView2<f64>, y: ArrayView1<Y>, limits: (u32, usize)) -> DecisionTreeModel<Y> {
+        // The tree is grown on class indexes, which is all the feature selector needs to see.
+        let mut classes: Vec<Y> = vec![];
+        let mut class_indexes: HashMap<Y, usize> = HashMap::new();
+        let labels = y.mapv(|label| *class_indexes.entry(label).or_insert_with(|| {
+            classes.push(label);
+            classes.len() - 1
+        }));
+        let mut rng = self.split_subsample.map_or(RngSource::Entropy, |(_, rng)| rng).rng();
+
         DecisionTreeModel {
-            tree: self.build_tree(x, y, 0)
+            tree: self.build_tree(x, labels.view(), limits, 0, &mut rng, Instant::now()).map_classes(&classes),
+            classes,
         }
     }
 
-    fn build_tree<Y: Copy + Hash + Eq>(&self, x: ArrayView2<f64>, y: ArrayView1<Y>, depth: u32) -> DecisionTreeNode<Y> {
+    fn build_tree<R: Rng>(&self, x: ArrayView2<f64>, y: ArrayView1<usize>, limits: (u32, usize), depth: u32, rng: &mut R, start: Instant) -> DecisionTreeNode<usize> {
         let current_entropy = entropy(y);
         info!("Current entropy of split: {:.5}", current_entropy);
 
-        if y.len() <= self.min_size || depth > self.max_depth || current_entropy == 0. {
+        let (max_depth, min_size) = limits;
+        if y.len() <= min_size || depth > max_depth || current_entropy == 0. {
             info!("Terminating branch with a leaf");
             return DecisionTreeNode::new_leaf_node(y);
         }
@@ -132,16 +315,29 @@ impl<FS> DecisionTreeClassifier<FS> where FS: FeatureSelector + Debug {
         let (left_indexes,
             right_indexes,
             threshold,
-            feature) = self.feature_selector.apply(x, y);
+            feature) = match self.split_subsample {
+            Some((max_rows, _)) if y.len() > max_rows => {
+                let candidates = sample(rng, y.len(), max_rows).into_vec();
+                let (_, _, threshold, feature) = self.feature_selector.apply(x.select(Axis(0), &candidates).view(), y.select(Axis(0), &candidates).view());
+                let (left, right) = (0..y.len()).partition(|&row| x[[row, feature]] < threshold);
+                (left, right, threshold, feature)
+            }
+            _ => self.feature_selector.apply(x, y),
+        };
+        if left_indexes.is_empty() || right_indexes.is_empty() {
+            info!("No split separates the rows, terminating branch with a leaf");
+            return DecisionTreeNode::new_leaf_node(y);
+        }
+        self.callbacks.on_split(depth as usize, feature, threshold, current_entropy, start.elapsed());
 
 
         let left_y = y.select(Axis(0), left_indexes.as_ref());
         info!("Current depth of: {:} and drafting left side of node", depth);
-        let left = self.build_tree(x.select(Axis(0), left_indexes.as_ref()).view(), left_y.view(), depth + 1);
+        let left = self.build_tree(x.select(Axis(0), left_indexes.as_ref()).view(), left_y.view(), limits, depth + 1, rng, start);
 
         let right_y = y.select(Axis(0), right_indexes.as_ref());
         info!("Current depth of: {:} and drafting right side of node", depth);
-        let right = self.build_tree(x.select(Axis(0), right_indexes.as_ref()).view(), right_y.view(), depth + 1);
+        let right = self.build_tree(x.select(Axis(0), right_indexes.as_ref()).view(), right_y.view(), limits, depth + 1, rng, start);
 
         return DecisionTreeNode::new_interior(
             feature,
@@ -150,4 +346,94 @@ impl<FS> DecisionTreeClassifier<FS> where FS: FeatureSelector + Debug {
             right,
         );
     }
-}
\ No newline at end of file
+}
+
+/// A classifier fitting trees limited to `limits` rather than its own, one candidate of
+/// `DecisionTreeClassifier::fit_auto`.
+struct LimitedTree<'a, FS> {
+    classifier: &'a DecisionTreeClassifier<FS>,
+    limits: (u32, usize),
+}
+
+impl<FS: FeatureSelector, Y: Copy + Hash + Eq> Fit<ArrayView2<'_, f64>, DecisionTreeModel<Y>, Y> for LimitedTree<'_, FS> {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<DecisionTreeModel<Y>> {
+        check_x_y(x, y, 1)?;
+        Ok(self.classifier.fit_limited(x, y, self.limits))
+    }
+}
+
+impl<FS> Parameterized for DecisionTreeClassifier<FS> {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("max_depth".to_owned(), self.max_depth.into());
+        params.insert("min_size".to_owned(), self.min_size.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "max_depth" => self.max_depth = value.as_usize()? as u32,
+            "min_size" => self.min_size = value.as_usize()?,
+            _ => return Err(Error::InvalidParameter(format!("DecisionTreeClassifier has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{array, Array1, Array2};
+    use rune_core::cost_matrix::CostMatrix;
+    use rune_core::estimator::{Fit, Predictor, ProbabilisticPredictor};
+    use rune_core::random::RngSource;
+    use rune_core::Error;
+    use rune_model_selection::splitting::k_fold::KFold;
+    use rune_pipeline::params::{ParamValue, Parameterized};
+
+    use crate::feature_selector::greedy_feature_selector::GreedyFeatureSelector;
+    use crate::measures::entropy::EntropySelectionMeasure;
+    use crate::{DecisionTreeClassifier, DecisionTreeModel, AUTO_MAX_DEPTHS, AUTO_MIN_SIZES};
+
+    #[test]
+    fn test_fit_auto_cross_validates_every_size() {
+        let x = Array2::from_shape_fn((60, 1), |(i, _)| i as f64);
+        let y: Array1<usize> = (0..60).map(|i| i / 20).collect();
+        let classifier = DecisionTreeClassifier::new(0, 100, GreedyFeatureSelector::new(EntropySelectionMeasure::new()));
+
+        let model = classifier.fit_auto(x.view(), y.view(), &KFold::shuffled(3, RngSource::Seed(5))).unwrap();
+
+        assert_eq!(model.cv_results().len(), AUTO_MAX_DEPTHS.len() * AUTO_MIN_SIZES.len());
+        assert!(model.best_score() > 0.95, "{}", model.best_score());
+        assert_eq!(model.predict(x.view()), y);
+    }
+
+    #[test]
+    fn test_set_param_rejects_unknown_names_and_wrong_types() {
+        let mut classifier = DecisionTreeClassifier::new(3, 1, GreedyFeatureSelector::new(EntropySelectionMeasure::new()));
+
+        classifier.set_param("max_depth", 5usize.into()).unwrap();
+        assert_eq!(classifier.get_params()["max_depth"], ParamValue::Int(5));
+        assert!(matches!(classifier.set_param("max_dept", 5usize.into()), Err(Error::InvalidParameter(_))));
+        assert!(matches!(classifier.set_param("min_size", "big".into()), Err(Error::InvalidParameter(_))));
+    }
+
+    #[test]
+    fn test_probabilities_and_costs_fail_instead_of_panicking() {
+        let x = array![[0.], [1.], [2.], [3.], [4.], [5.]];
+        let y = array![0usize, 0, 1, 1, 2, 2];
+        let classifier = DecisionTreeClassifier::new(3, 1, GreedyFeatureSelector::new(EntropySelectionMeasure::new()));
+        let model: DecisionTreeModel<usize> = classifier.fit(x.view(), y.view()).unwrap();
+
+        let probabilities = model.predict_proba(x.view()).unwrap();
+        assert_eq!(probabilities.dim(), (6, 3));
+        assert_eq!(model.predict_with_costs(x.view(), &CostMatrix::zero_one(3)).unwrap(), y);
+        assert!(matches!(model.predict_with_costs(x.view(), &CostMatrix::zero_one(2)), Err(Error::ShapeMismatch(_))));
+
+        let mut saved = serde_json::to_value(&model).unwrap();
+        saved.as_object_mut().unwrap().remove("classes");
+        let old: DecisionTreeModel<usize> = serde_json::from_value(saved).unwrap();
+        assert_eq!(old.predict(x.view()), y);
+        assert!(matches!(old.predict_proba(x.view()), Err(Error::InvalidInput(_))));
+        assert!(matches!(old.predict_with_costs(x.view(), &CostMatrix::zero_one(3)), Err(Error::InvalidInput(_))));
+    }
+}
@@ -1 +1,9 @@
-pub mod histogram;
\ No newline at end of file
+pub mod histogram;
+
+/// The threshold a split between the distinct feature values `value < next` is made at, rows
+/// below it going left: their midpoint, or `next` when that isn't above `value`, as for adjacent
+/// floats, where the midpoint rounds down to `value` and would send every row right.
+pub(crate) fn split_threshold(value: f64, next: f64) -> f64 {
+    let middle = value + (next - value) / 2.;
+    if value < middle && middle <= next { middle } else { next }
+}
@@ -9,7 +9,7 @@ use crate::measures::SelectionMeasure;
 pub struct EntropySelectionMeasure {}
 
 impl SelectionMeasure for EntropySelectionMeasure {
-    fn apply<T: Copy + Eq + Hash>(&self, dataset: ArrayView1<T>, left_indexes: &[usize], right_indexes: &[usize]) -> f64 {
+    fn apply(&self, dataset: ArrayView1<usize>, left_indexes: &[usize], right_indexes: &[usize]) -> f64 {
         let total_entropy = entropy(dataset);
         let left_entropy = entropy(dataset.select(Axis(0), left_indexes).view());
         let right_entropy = entropy(dataset.select(Axis(0), right_indexes).view());
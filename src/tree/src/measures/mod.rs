@@ -1,8 +1,17 @@
 use ndarray::ArrayView1;
-use std::hash::Hash;
+use std::fmt::Debug;
 
 pub mod entropy;
 
-pub trait SelectionMeasure {
-    fn apply<T: Copy + Eq + Hash>(&self, dataset: ArrayView1<T>, left_indexes: &[usize], right_indexes: &[usize]) -> f64;
+/// Scores splitting `dataset`, class indexes, into the rows at `left_indexes` and the rows at
+/// `right_indexes`; higher is better. Object safe, so measures can be chosen at runtime as
+/// `Box<dyn SelectionMeasure>`.
+pub trait SelectionMeasure: Debug {
+    fn apply(&self, dataset: ArrayView1<usize>, left_indexes: &[usize], right_indexes: &[usize]) -> f64;
+}
+
+impl<SM: SelectionMeasure + ?Sized> SelectionMeasure for Box<SM> {
+    fn apply(&self, dataset: ArrayView1<usize>, left_indexes: &[usize], right_indexes: &[usize]) -> f64 {
+        (**self).apply(dataset, left_indexes, right_indexes)
+    }
 }
\ No newline at end of file
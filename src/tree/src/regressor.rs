@@ -0,0 +1,445 @@
+use std::cmp::Ordering;
+use std::time::Instant;
+
+use log::*;
+use rand::seq::index::sample;
+use rand::Rng;
+use ndarray::{Array1, ArrayView1, ArrayView2};
+use rune_core::callback::{Callbacks, FitCallback};
+use rune_core::estimator::{Fit, Predictor, Score};
+use rune_core::math::binning::{BinEdges, BinIndex, Binner};
+use rune_core::random::RngSource;
+use rune_core::validation::{check_finite, check_x_y};
+use rune_core::{Error, Result};
+use rune_metrics::regression::r2::r2;
+use rune_pipeline::params::{ParamValue, Parameterized, Params};
+use serde::{Deserialize, Serialize};
+
+use crate::math::split_threshold;
+
+/// A regression tree, splitting each node where it most reduces the squared error of predicting
+/// the mean target on each side. By default every feature's values are sorted once per node, so
+/// finding a split is exact and takes `O(n log n)` per feature. With `histogram`, features are
+/// binned into quantiles once and splits are only considered between bins, which takes `O(n)`.
+#[derive(Debug, Clone)]
+pub struct DecisionTreeRegressor {
+    max_depth: u32,
+    min_size: usize,
+    max_bins: Option<usize>,
+    monotonic_constraints: Vec<i8>,
+    split_subsample: Option<(usize, RngSource)>,
+    callbacks: Callbacks,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum RegressionNode {
+    Interior {
+        feature: usize,
+        threshold: f64,
+        left: Box<RegressionNode>,
+        right: Box<RegressionNode>,
+    },
+    Leaf {
+        id: usize,
+        value: f64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionTreeModel {
+    tree: RegressionNode,
+    n_leaves: usize,
+    #[serde(default)]
+    monotonic_constraints: Vec<i8>,
+}
+
+/// Finds the best split of the first rows given, the candidates, and splits the second by it.
+type FindSplit<'a> = dyn Fn(&[usize], &[usize]) -> Option<Split> + 'a;
+
+struct Split {
+    feature: usize,
+    threshold: f64,
+    left: Vec<usize>,
+    right: Vec<usize>,
+}
+
+impl Fit<ArrayView2<'_, f64>, RegressionTreeModel, f64> for DecisionTreeRegressor {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> Result<RegressionTreeModel> {
+        check_x_y(x, y, 1)?;
+        check_finite("y", y)?;
+        self.check_constraints(x.ncols())?;
+        Ok(self.fit_internal(x, y))
+    }
+}
+
+impl Predictor<ArrayView2<'_, f64>, Array1<f64>> for RegressionTreeModel {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<f64> {
+        x.genrows().into_iter().map(|row| self.tree.leaf(row).1).collect()
+    }
+}
+
+/// The coefficient of determination of the predictions.
+impl Score<ArrayView2<'_, f64>, f64> for RegressionTreeModel {
+    fn score(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> f64 {
+        r2(y, self.predict(x).view(), None)
+    }
+}
+
+impl DecisionTreeRegressor {
+    /// A tree at most `max_depth` splits deep, which stops splitting nodes of `min_size` rows or
+    /// fewer.
+    pub fn new(max_depth: u32, min_size: usize) -> Self {
+        DecisionTreeRegressor {
+            max_depth,
+            min_size,
+            max_bins: None,
+            monotonic_constraints: vec![],
+            split_subsample: None,
+            callbacks: Callbacks::new(),
+        }
+    }
+
+    /// Finds splits between at most `max_bins` quantile bins of every feature, up to 256.
+    pub fn histogram(mut self, max_bins: usize) -> Self {
+        assert!((2..=u8::MAX_BINS).contains(&max_bins), "histogram trees need between 2 and 256 bins");
+        self.max_bins = Some(max_bins);
+        self
+    }
+
+    /// Constrains the prediction to never decrease (`1`), never increase (`-1`) or change freely
+    /// (`0`) as each feature increases, the others held fixed. Splits on a constrained feature
+    /// whose sides' means go the wrong way are never chosen, and leaves that would still break
+    /// the constraint, e.g. after their values are replaced, are clipped to meet in the middle.
+    pub fn monotonic_constraints(mut self, constraints: Vec<i8>) -> Self {
+        self.monotonic_constraints = constraints;
+        self
+    }
+
+    /// Has nodes of more than `max_rows` rows choose their split from `max_rows` of them drawn
+    /// from `rng`, then split all of them, so that the cost of a node no longer grows with its
+    /// size. Smaller nodes are split exactly. The leaves are still estimated from every row.
+    pub fn split_subsample(mut self, max_rows: usize, rng: RngSource) -> Self {
+        assert!(max_rows >= 2, "splits need at least 2 rows to choose from");
+        self.split_subsample = Some((max_rows, rng));
+        self
+    }
+
+    /// Checks the monotonic constraints suit data of `n_features` features.
+    pub fn check_constraints(&self, n_features: usize) -> Result<()> {
+        if self.monotonic_constraints.is_empty() {
+            return Ok(());
+        }
+        if self.monotonic_constraints.len() != n_features {
+            return Err(Error::InvalidParameter(format!("{} monotonic constraints for {} features", self.monotonic_constraints.len(), n_features)));
+        }
+        if let Some(constraint) = self.monotonic_constraints.iter().find(|constraint| !(-1..=1).contains(*constraint)) {
+            return Err(Error::InvalidParameter(format!("monotonic constraints must be -1, 0 or 1, got {}", constraint)));
+        }
+        Ok(())
+    }
+
+    /// Calls `callback` after every node is split.
+    pub fn with_callback<C: FitCallback + 'static>(mut self, callback: C) -> Self {
+        self.callbacks.push(callback);
+        self
+    }
+
+    pub fn fit_internal(&self, x: ArrayView2<f64>, y: ArrayView1<f64>) -> RegressionTreeModel {
+        match self.max_bins {
+            Some(max_bins) => {
+                let edges = Binner::new(max_bins).fit(x);
+                self.fit_binned(edges.transform::<u8>(x).view(), &edges, y)
+            }
+            None => self.fit_with(y, &|candidates, rows| best_split(x, y, candidates, rows, &self.monotonic_constraints)),
+        }
+    }
+
+    /// Fits the tree to data already binned by `edges`, splitting between bins whatever the
+    /// `histogram` setting, e.g. for an ensemble to bin its training data only once.
+    pub fn fit_binned<B: BinIndex>(&self, binned: ArrayView2<B>, edges: &BinEdges, y: ArrayView1<f64>) -> RegressionTreeModel {
+        self.fit_with(y, &|candidates, rows| best_binned_split(binned, edges, y, candidates, rows, &self.monotonic_constraints))
+    }
+
+    fn fit_with(&self, y: ArrayView1<f64>, find_split: &FindSplit) -> RegressionTreeModel {
+        let rows: Vec<usize> = (0..y.len()).collect();
+        let mut n_leaves = 0;
+        let mut rng = self.split_subsample.map_or(RngSource::Entropy, |(_, rng)| rng).rng();
+        let mut tree = self.build_tree(y, find_split, &rows, 0, &mut n_leaves, &mut rng, Instant::now());
+        if !self.monotonic_constraints.is_empty() {
+            tree.enforce_monotonic(&self.monotonic_constraints, (f64::NEG_INFINITY, f64::INFINITY));
+        }
+
+        RegressionTreeModel { tree, n_leaves, monotonic_constraints: self.monotonic_constraints.clone() }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build_tree<R: Rng>(&self, y: ArrayView1<f64>, find_split: &FindSplit, rows: &[usize], depth: u32, n_leaves: &mut usize, rng: &mut R, start: Instant) -> RegressionNode {
+        let mean = rows.iter().map(|&row| y[row]).sum::<f64>() / rows.len() as f64;
+        let error: f64 = rows.iter().map(|&row| (y[row] - mean).powi(2)).sum();
+
+        let split = if rows.len() <= self.min_size || depth >= self.max_depth || error == 0. {
+            None
+        } else {
+            match self.split_subsample {
+                Some((max_rows, _)) if rows.len() > max_rows => {
+                    let candidates: Vec<usize> = sample(rng, rows.len(), max_rows).into_iter().map(|i| rows[i]).collect();
+                    find_split(&candidates, rows)
+                }
+                _ => find_split(rows, rows),
+            }
+        };
+
+        match split {
+            None => {
+                debug!("Terminating branch of {} rows with a leaf of {:.5}", rows.len(), mean);
+                *n_leaves += 1;
+                RegressionNode::Leaf { id: *n_leaves - 1, value: mean }
+            }
+            Some(Split { feature, threshold, left, right }) => {
+                self.callbacks.on_split(depth as usize, feature, threshold, error / rows.len() as f64, start.elapsed());
+                debug!("Split {} rows at depth {}: [X{} < {:.5}]", rows.len(), depth, feature, threshold);
+
+                RegressionNode::Interior {
+                    feature,
+                    threshold,
+                    left: Box::new(self.build_tree(y, find_split, &left, depth + 1, n_leaves, rng, start)),
+                    right: Box::new(self.build_tree(y, find_split, &right, depth + 1, n_leaves, rng, start)),
+                }
+            }
+        }
+    }
+}
+
+/// The split of `candidates` leaving the least squared error about the mean of each side,
+/// halfway between two consecutive distinct values of a feature, applied to `rows`, or none when
+/// every feature is constant.
+fn best_split(x: ArrayView2<f64>, y: ArrayView1<f64>, candidates: &[usize], rows: &[usize], constraints: &[i8]) -> Option<Split> {
+    let n = candidates.len() as f64;
+    let total: f64 = candidates.iter().map(|&row| y[row]).sum();
+
+    // Minimizing the squared error is maximizing sum_left² / n_left + sum_right² / n_right.
+    let mut best: Option<(f64, usize, f64)> = None;
+    let mut sorted = candidates.to_vec();
+
+    for feature in 0..x.ncols() {
+        let column = x.column(feature);
+        sorted.sort_by(|&l, &r| column[l].partial_cmp(&column[r]).unwrap_or(Ordering::Equal));
+
+        let mut left_sum = 0.;
+        for (i, window) in sorted.windows(2).enumerate() {
+            left_sum += y[window[0]];
+
+            let (value, next) = (column[window[0]], column[window[1]]);
+            if value == next {
+                continue;
+            }
+
+            let n_left = (i + 1) as f64;
+            if !respects_constraint(constraints, feature, left_sum / n_left, (total - left_sum) / (n - n_left)) {
+                continue;
+            }
+
+            let gain = left_sum * left_sum / n_left + (total - left_sum).powi(2) / (n - n_left);
+
+            let better = match best {
+                Some((best_gain, _, _)) => gain > best_gain,
+                None => true,
+            };
+            if better {
+                best = Some((gain, feature, split_threshold(value, next)));
+            }
+        }
+    }
+
+    best.map(|(_, feature, threshold)| {
+        let (left, right) = rows.iter().partition(|&&row| x[[row, feature]] < threshold);
+        Split { feature, threshold, left, right }
+    })
+}
+
+/// `best_split` between the bins of `binned`, from the sum of the targets in every bin.
+fn best_binned_split<B: BinIndex>(binned: ArrayView2<B>, edges: &BinEdges, y: ArrayView1<f64>, candidates: &[usize], rows: &[usize], constraints: &[i8]) -> Option<Split> {
+    let n = candidates.len();
+    let total: f64 = candidates.iter().map(|&row| y[row]).sum();
+
+    let mut best: Option<(f64, usize, usize)> = None;
+
+    for feature in 0..binned.ncols() {
+        let n_bins = edges.n_bins(feature);
+        let mut sums = vec![0.; n_bins];
+        let mut counts = vec![0; n_bins];
+
+        for &row in candidates {
+            let bin = binned[[row, feature]].bin();
+            sums[bin] += y[row];
+            counts[bin] += 1;
+        }
+
+        let (mut left_sum, mut n_left) = (0., 0);
+        for bin in 0..n_bins - 1 {
+            left_sum += sums[bin];
+            n_left += counts[bin];
+
+            if n_left == 0 || n_left == n || counts[bin] == 0 {
+                continue;
+            }
+            if !respects_constraint(constraints, feature, left_sum / n_left as f64, (total - left_sum) / (n - n_left) as f64) {
+                continue;
+            }
+
+            let gain = left_sum * left_sum / n_left as f64 + (total - left_sum).powi(2) / (n - n_left) as f64;
+
+            let better = match best {
+                Some((best_gain, _, _)) => gain > best_gain,
+                None => true,
+            };
+            if better {
+                best = Some((gain, feature, bin));
+            }
+        }
+    }
+
+    best.map(|(_, feature, bin)| {
+        let (left, right) = rows.iter().partition(|&&row| binned[[row, feature]].bin() <= bin);
+        Split { feature, threshold: edges.upper_edge(feature, bin), left, right }
+    })
+}
+
+/// Whether the mean target below a split on `feature` and the one above it go the way the
+/// feature's monotonic constraint allows.
+fn respects_constraint(constraints: &[i8], feature: usize, left_mean: f64, right_mean: f64) -> bool {
+    match constraints.get(feature).copied().unwrap_or(0) {
+        0 => true,
+        constraint if constraint > 0 => left_mean <= right_mean,
+        _ => left_mean >= right_mean,
+    }
+}
+
+impl RegressionNode {
+    /// The id and value of the leaf `x` falls in.
+    fn leaf(&self, x: ArrayView1<f64>) -> (usize, f64) {
+        match *self {
+            RegressionNode::Interior { feature, threshold, ref left, ref right } => {
+                if x[feature] < threshold {
+                    left.leaf(x)
+                } else {
+                    right.leaf(x)
+                }
+            }
+            RegressionNode::Leaf { id, value } => (id, value),
+        }
+    }
+
+    /// The smallest and largest leaf values of the subtree.
+    fn value_range(&self) -> (f64, f64) {
+        match self {
+            RegressionNode::Interior { left, right, .. } => {
+                let ((left_min, left_max), (right_min, right_max)) = (left.value_range(), right.value_range());
+                (left_min.min(right_min), left_max.max(right_max))
+            }
+            RegressionNode::Leaf { value, .. } => (*value, *value),
+        }
+    }
+
+    /// Clips the leaves to `bounds` and, below every split on a constrained feature whose sides'
+    /// values overlap the wrong way, to either side of the middle of the overlap. Clipping only
+    /// ever narrows a subtree's range, so splits further down can't undo the order above them.
+    fn enforce_monotonic(&mut self, constraints: &[i8], bounds: (f64, f64)) {
+        match self {
+            RegressionNode::Interior { feature, left, right, .. } => {
+                let constraint = constraints.get(*feature).copied().unwrap_or(0);
+                let (low, high) = if constraint > 0 { (&**left, &**right) } else { (&**right, &**left) };
+                let (low_max, high_min) = (low.value_range().1.max(bounds.0).min(bounds.1), high.value_range().0.max(bounds.0).min(bounds.1));
+
+                let (left_bounds, right_bounds) = if constraint == 0 || low_max <= high_min {
+                    (bounds, bounds)
+                } else {
+                    let middle = (low_max + high_min) / 2.;
+                    let (low_bounds, high_bounds) = ((bounds.0, middle), (middle, bounds.1));
+                    if constraint > 0 { (low_bounds, high_bounds) } else { (high_bounds, low_bounds) }
+                };
+
+                left.enforce_monotonic(constraints, left_bounds);
+                right.enforce_monotonic(constraints, right_bounds);
+            }
+            RegressionNode::Leaf { value, .. } => *value = value.max(bounds.0).min(bounds.1),
+        }
+    }
+
+    fn set_leaf_values(&mut self, values: &[f64]) {
+        match self {
+            RegressionNode::Interior { left, right, .. } => {
+                left.set_leaf_values(values);
+                right.set_leaf_values(values);
+            }
+            RegressionNode::Leaf { id, value } => *value = values[*id],
+        }
+    }
+}
+
+impl RegressionTreeModel {
+    pub fn n_leaves(&self) -> usize {
+        self.n_leaves
+    }
+
+    /// The id, from 0 to `n_leaves`, of the leaf each row of `x` falls in.
+    pub fn apply(&self, x: ArrayView2<f64>) -> Array1<usize> {
+        x.genrows().into_iter().map(|row| self.tree.leaf(row).0).collect()
+    }
+
+    /// Replaces the value of every leaf with the one at its id in `values`, e.g. for gradient
+    /// boosting to re-estimate the leaves of a tree fitted to gradients under its own loss. The
+    /// values are then clipped to meet the tree's monotonic constraints.
+    pub fn set_leaf_values(&mut self, values: &[f64]) {
+        assert_eq!(values.len(), self.n_leaves, "one value is needed per leaf");
+
+        self.tree.set_leaf_values(values);
+        if !self.monotonic_constraints.is_empty() {
+            self.tree.enforce_monotonic(&self.monotonic_constraints, (f64::NEG_INFINITY, f64::INFINITY));
+        }
+    }
+}
+
+impl Parameterized for DecisionTreeRegressor {
+    fn get_params(&self) -> Params {
+        let mut params = Params::new();
+        params.insert("max_depth".to_owned(), self.max_depth.into());
+        params.insert("min_size".to_owned(), self.min_size.into());
+        params
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<()> {
+        match name {
+            "max_depth" => self.max_depth = value.as_usize()? as u32,
+            "min_size" => self.min_size = value.as_usize()?,
+            _ => return Err(Error::InvalidParameter(format!("DecisionTreeRegressor has no parameter {}", name))),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2};
+    use rune_core::estimator::Predictor;
+
+    use crate::regressor::DecisionTreeRegressor;
+
+    #[test]
+    fn test_splits_between_adjacent_floats() {
+        let next = f64::from_bits(1f64.to_bits() + 1);
+        let x = arr2(&[[1.], [1.], [next], [next]]);
+        let model = DecisionTreeRegressor::new(1, 1).fit_internal(x.view(), arr1(&[0., 0., 1., 1.]).view());
+
+        assert_eq!(model.n_leaves(), 2);
+        assert_eq!(model.predict(x.view()), arr1(&[0., 0., 1., 1.]));
+    }
+
+    #[test]
+    fn test_splits_at_the_midpoint() {
+        let x = arr2(&[[0.], [1.], [3.], [4.]]);
+        let model = DecisionTreeRegressor::new(1, 1).fit_internal(x.view(), arr1(&[5., 5., 9., 9.]).view());
+
+        assert_eq!(model.predict(arr2(&[[1.9], [2.1]]).view()), arr1(&[5., 9.]));
+    }
+}
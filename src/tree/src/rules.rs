@@ -0,0 +1,101 @@
+use std::fmt;
+
+use crate::{DecisionTreeModel, DecisionTreeNode};
+
+/// One test on the path to a leaf: the feature is below the threshold, or at or above it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Condition {
+    pub feature: usize,
+    pub threshold: f64,
+    pub below: bool,
+}
+
+/// The path from the root of a tree to one of its leaves, as the conditions a row must meet to
+/// reach it and the class it then gets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule<T> {
+    pub conditions: Vec<Condition>,
+    pub class: T,
+    /// How many training rows reached the leaf.
+    pub support: usize,
+    /// The fraction of those rows in `class`.
+    pub confidence: f64,
+}
+
+impl<T: Clone> DecisionTreeModel<T> {
+    /// One rule per leaf, left to right. A feature tested more than once in the same direction
+    /// on a path keeps only its tightest threshold.
+    pub fn to_rules(&self) -> Vec<Rule<T>> {
+        let mut rules = vec![];
+        collect_rules(&self.tree, &[], &mut rules);
+        rules
+    }
+}
+
+fn collect_rules<T: Clone>(node: &DecisionTreeNode<T>, conditions: &[Condition], rules: &mut Vec<Rule<T>>) {
+    match node {
+        DecisionTreeNode::Interior { feature, threshold, left, right } => {
+            for &(below, child) in &[(true, left), (false, right)] {
+                let condition = Condition { feature: *feature, threshold: *threshold, below };
+                let mut path = conditions.to_vec();
+                tighten(&mut path, condition);
+                collect_rules(child, &path, rules);
+            }
+        }
+        DecisionTreeNode::Leaf { probability, support, confidence, .. } => rules.push(Rule {
+            conditions: conditions.to_vec(),
+            class: probability.clone(),
+            support: *support,
+            confidence: *confidence,
+        }),
+    }
+}
+
+fn tighten(conditions: &mut Vec<Condition>, condition: Condition) {
+    match conditions.iter_mut().find(|c| c.feature == condition.feature && c.below == condition.below) {
+        Some(existing) if condition.below => existing.threshold = existing.threshold.min(condition.threshold),
+        Some(existing) => existing.threshold = existing.threshold.max(condition.threshold),
+        None => conditions.push(condition),
+    }
+}
+
+impl Condition {
+    /// The condition with its threshold to four decimals, e.g. `petal_width < 1.75`.
+    pub fn render(&self, feature_names: &[String]) -> String {
+        let operator = if self.below { "<" } else { ">=" };
+        let threshold = format!("{:.4}", self.threshold);
+        let threshold = if threshold.contains('.') { threshold.trim_end_matches('0').trim_end_matches('.') } else { &threshold };
+
+        format!("{} {} {}", feature_names[self.feature], operator, threshold)
+    }
+}
+
+impl<T: fmt::Display> Rule<T> {
+    /// The rule as `IF petal_width < 1.75 AND ... THEN class=1 (support=54, confidence=0.91)`,
+    /// naming the features from `feature_names`, e.g. those of the dataset the tree was fitted
+    /// on.
+    pub fn render(&self, feature_names: &[String]) -> String {
+        let conditions = if self.conditions.is_empty() {
+            "TRUE".to_owned()
+        } else {
+            self.conditions.iter().map(|condition| condition.render(feature_names)).collect::<Vec<String>>().join(" AND ")
+        };
+
+        format!("IF {} THEN class={} (support={}, confidence={:.2})", conditions, self.class, self.support, self.confidence)
+    }
+}
+
+/// Features are named `feature_0`, `feature_1` and so on.
+impl<T: fmt::Display> fmt::Display for Rule<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let n_features = self.conditions.iter().map(|condition| condition.feature + 1).max().unwrap_or(0);
+        let names: Vec<String> = (0..n_features).map(|feature| format!("feature_{}", feature)).collect();
+
+        write!(f, "{}", self.render(&names))
+    }
+}
+
+/// Every rule rendered with `feature_names`, one per line.
+pub fn render_rules<T: fmt::Display>(rules: &[Rule<T>], feature_names: &[String]) -> String {
+    rules.iter().map(|rule| rule.render(feature_names)).collect::<Vec<String>>().join("\n")
+}
@@ -0,0 +1,135 @@
+use std::convert::TryFrom;
+use std::io::Read;
+
+use log::debug;
+use rune_core::{Error, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::{DecisionTreeModel, DecisionTreeNode};
+
+/// The arrays of a fitted scikit-learn tree, `model.tree_`, as lists.
+#[derive(Deserialize)]
+struct SklearnTree {
+    children_left: Vec<i64>,
+    children_right: Vec<i64>,
+    feature: Vec<i64>,
+    threshold: Vec<f64>,
+    value: Vec<Vec<Vec<f64>>>,
+    /// How many training rows reached each node, for the support of leaves when exported.
+    #[serde(default)]
+    n_node_samples: Option<Vec<i64>>,
+}
+
+#[derive(Deserialize)]
+struct SklearnTreeExport<T> {
+    classes: Vec<T>,
+    #[serde(flatten)]
+    tree: SklearnTree,
+}
+
+#[derive(Deserialize)]
+struct SklearnForestExport<T> {
+    classes: Vec<T>,
+    estimators: Vec<SklearnTree>,
+}
+
+/// Reads a scikit-learn `DecisionTreeClassifier` exported as JSON, with its `classes_` under
+/// `classes` and the `children_left`, `children_right`, `feature`, `threshold` and `value`
+/// arrays of its `tree_`, e.g. from Python:
+///
+/// `json.dump({"classes": m.classes_.tolist(), **{k: getattr(m.tree_, k).tolist() for k in
+/// ["children_left", "children_right", "feature", "threshold", "value"]}}, f)`
+///
+/// Each leaf predicts the class with the largest value, as scikit-learn does. Leaves only know
+/// their support when `n_node_samples` is exported too.
+pub fn read_sklearn_tree<T: DeserializeOwned + Clone, R: Read>(reader: R) -> Result<DecisionTreeModel<T>> {
+    let export: SklearnTreeExport<T> = serde_json::from_reader(reader).map_err(|e| Error::Serialization(e.to_string()))?;
+
+    convert_tree(&export.tree, &export.classes)
+}
+
+/// Reads the trees of a scikit-learn `RandomForestClassifier` exported as JSON, with its
+/// `classes_` under `classes` and each of its `estimators_` under `estimators`, laid out as for
+/// `read_sklearn_tree`. The forest predicts the majority vote of the trees' predictions.
+pub fn read_sklearn_forest<T: DeserializeOwned + Clone, R: Read>(reader: R) -> Result<Vec<DecisionTreeModel<T>>> {
+    let export: SklearnForestExport<T> = serde_json::from_reader(reader).map_err(|e| Error::Serialization(e.to_string()))?;
+
+    export.estimators.iter()
+        .map(|tree| convert_tree(tree, &export.classes))
+        .collect()
+}
+
+fn convert_tree<T: Clone>(tree: &SklearnTree, classes: &[T]) -> Result<DecisionTreeModel<T>> {
+    let n_nodes = tree.children_left.len();
+    if [tree.children_right.len(), tree.feature.len(), tree.threshold.len(), tree.value.len()].iter().any(|&len| len != n_nodes) {
+        return Err(Error::InvalidInput("scikit-learn tree arrays should all have one entry per node".to_owned()));
+    }
+    if n_nodes == 0 {
+        return Err(Error::InvalidInput("scikit-learn tree has no nodes".to_owned()));
+    }
+    debug!("Converting scikit-learn tree of {} nodes", n_nodes);
+
+    Ok(DecisionTreeModel { tree: convert_node(tree, classes, 0)?, classes: classes.to_vec() })
+}
+
+fn convert_node<T: Clone>(tree: &SklearnTree, classes: &[T], node: usize) -> Result<DecisionTreeNode<T>> {
+    let invalid = |message: String| Error::InvalidInput(format!("scikit-learn tree node {} {}", node, message));
+
+    let (left, right) = (tree.children_left[node], tree.children_right[node]);
+
+    // Leaves have -1 for both children.
+    if left < 0 {
+        let values = tree.value[node].first().ok_or_else(|| invalid("has no values".to_owned()))?;
+        if values.len() != classes.len() {
+            return Err(invalid(format!("has {} class values for {} classes", values.len(), classes.len())));
+        }
+
+        let best = values.iter()
+            .enumerate()
+            .fold(0, |best, (class, &value)| if value > values[best] { class } else { best });
+        // Newer scikit-learn versions store class fractions rather than counts; the confidence
+        // comes out the same from either.
+        let total: f64 = values.iter().sum();
+        let support = tree.n_node_samples.as_ref().and_then(|samples| samples.get(node)).map_or(0, |&n| n.max(0) as usize);
+
+        return Ok(DecisionTreeNode::Leaf {
+            probability: classes[best].clone(),
+            support,
+            confidence: if total > 0. { values[best] / total } else { 0. },
+            proportions: values.iter().map(|&value| if total > 0. { value / total } else { 0. }).collect(),
+        });
+    }
+
+    // Nodes are numbered depth first, so children always come after their parent; anything else
+    // would be a cycle.
+    let child = |index: i64| {
+        if index as usize > node && (index as usize) < tree.children_left.len() {
+            Ok(index as usize)
+        } else {
+            Err(invalid(format!("has out of order child {}", index)))
+        }
+    };
+    let feature = usize::try_from(tree.feature[node]).map_err(|_| invalid(format!("splits on feature {}", tree.feature[node])))?;
+
+    Ok(DecisionTreeNode::Interior {
+        feature,
+        threshold: next_up(tree.threshold[node]),
+        left: Box::new(convert_node(tree, classes, child(left)?)?),
+        right: Box::new(convert_node(tree, classes, child(right)?)?),
+    })
+}
+
+/// The smallest float above `value`. scikit-learn sends `x <= threshold` left where rune's trees
+/// send `x < threshold`, and the two agree once the threshold is moved up by one step.
+fn next_up(value: f64) -> f64 {
+    if value.is_nan() || value == f64::INFINITY {
+        value
+    } else if value == 0. {
+        f64::from_bits(1)
+    } else if value > 0. {
+        f64::from_bits(value.to_bits() + 1)
+    } else {
+        f64::from_bits(value.to_bits() - 1)
+    }
+}
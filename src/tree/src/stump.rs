@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use log::*;
+use ndarray::{Array1, ArrayView1, ArrayView2};
+use rune_core::estimator::{Fit, Predictor, Score};
+use rune_core::validation::{check_finite, check_x_y};
+use rune_core::{Error, Result};
+use rune_metrics::metrics::accuracy_score;
+use serde::{Deserialize, Serialize};
+
+use crate::math::split_threshold;
+
+/// A depth-1 classification tree: the single threshold on a single feature with the highest
+/// weighted information gain, and the weighted majority class on each side. Every feature is
+/// sorted once and its thresholds swept with running class weights, so fitting takes
+/// `O(n log n)` per feature, cheap enough to fit thousands as the weak learners of a boosting
+/// ensemble.
+#[derive(Debug, Clone, Default)]
+pub struct DecisionStump {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionStumpModel<T> {
+    feature: usize,
+    threshold: f64,
+    left: T,
+    right: T,
+}
+
+impl<Y: Copy + Hash + Eq> Fit<ArrayView2<'_, f64>, DecisionStumpModel<Y>, Y> for DecisionStump {
+    fn fit(&self, x: ArrayView2<f64>, y: ArrayView1<Y>) -> Result<DecisionStumpModel<Y>> {
+        check_x_y(x, y, 1)?;
+        Ok(self.fit_internal(x, y, None))
+    }
+}
+
+impl<T: Copy> Predictor<ArrayView2<'_, f64>, Array1<T>> for DecisionStumpModel<T> {
+    fn predict(&self, x: ArrayView2<f64>) -> Array1<T> {
+        x.column(self.feature).mapv(|v| if v < self.threshold { self.left } else { self.right })
+    }
+}
+
+/// The fraction of rows whose class is predicted correctly.
+impl<T: Copy + PartialEq> Score<ArrayView2<'_, f64>, T> for DecisionStumpModel<T> {
+    fn score(&self, x: ArrayView2<f64>, y: ArrayView1<T>) -> f64 {
+        accuracy_score(y, self.predict(x).view(), None)
+    }
+}
+
+impl DecisionStump {
+    pub fn new() -> Self {
+        DecisionStump {}
+    }
+
+    /// Fits the stump with every row counting as `sample_weight` rows, e.g. the weights AdaBoost
+    /// puts on the rows earlier learners got wrong.
+    pub fn fit_weighted<Y: Copy + Hash + Eq>(&self, x: ArrayView2<f64>, y: ArrayView1<Y>, sample_weight: ArrayView1<f64>) -> Result<DecisionStumpModel<Y>> {
+        check_x_y(x, y, 1)?;
+        check_finite("sample_weight", sample_weight)?;
+        if sample_weight.len() != y.len() {
+            return Err(Error::ShapeMismatch(format!("{} sample weights for {} samples", sample_weight.len(), y.len())));
+        }
+        if sample_weight.iter().any(|&w| w < 0.) {
+            return Err(Error::InvalidInput("sample weights must not be negative".to_owned()));
+        }
+
+        Ok(self.fit_internal(x, y, Some(sample_weight)))
+    }
+
+    fn fit_internal<Y: Copy + Hash + Eq>(&self, x: ArrayView2<f64>, y: ArrayView1<Y>, sample_weight: Option<ArrayView1<f64>>) -> DecisionStumpModel<Y> {
+        let mut classes: Vec<Y> = vec![];
+        let mut class_indexes: HashMap<Y, usize> = HashMap::new();
+        let labels: Vec<usize> = y.iter()
+            .map(|&label| *class_indexes.entry(label).or_insert_with(|| {
+                classes.push(label);
+                classes.len() - 1
+            }))
+            .collect();
+        let weight = |row: usize| sample_weight.map_or(1., |weights| weights[row]);
+
+        let mut totals = vec![0.; classes.len()];
+        for (row, &label) in labels.iter().enumerate() {
+            totals[label] += weight(row);
+        }
+        let total_weight: f64 = totals.iter().sum();
+
+        // Maximizing the information gain is minimizing the weighted entropy of the sides.
+        let mut best: Option<(f64, usize, f64)> = None;
+        let mut sorted: Vec<usize> = (0..x.nrows()).collect();
+
+        for feature in 0..x.ncols() {
+            let column = x.column(feature);
+            sorted.sort_by(|&l, &r| column[l].partial_cmp(&column[r]).unwrap_or(Ordering::Equal));
+
+            let mut left = vec![0.; classes.len()];
+            for window in sorted.windows(2) {
+                left[labels[window[0]]] += weight(window[0]);
+
+                let (value, next) = (column[window[0]], column[window[1]]);
+                if value == next {
+                    continue;
+                }
+
+                let left_weight: f64 = left.iter().sum();
+                let right: Vec<f64> = totals.iter().zip(left.iter()).map(|(total, left)| total - left).collect();
+                let impurity = left_weight * entropy(&left, left_weight) + (total_weight - left_weight) * entropy(&right, total_weight - left_weight);
+
+                let better = match best {
+                    Some((best_impurity, _, _)) => impurity < best_impurity,
+                    None => true,
+                };
+                if better {
+                    best = Some((impurity, feature, split_threshold(value, next)));
+                }
+            }
+        }
+
+        match best {
+            Some((impurity, feature, threshold)) => {
+                let mut left = vec![0.; classes.len()];
+                for (row, &label) in labels.iter().enumerate() {
+                    if x[[row, feature]] < threshold {
+                        left[label] += weight(row);
+                    }
+                }
+                let right: Vec<f64> = totals.iter().zip(left.iter()).map(|(total, left)| total - left).collect();
+
+                info!("Found stump split: [X{} < {:.5}] leaving an entropy of {:.5}", feature, threshold, impurity / total_weight);
+
+                DecisionStumpModel {
+                    feature,
+                    threshold,
+                    left: classes[majority(&left)],
+                    right: classes[majority(&right)],
+                }
+            }
+            None => {
+                info!("Every feature is constant, so the stump predicts the majority class");
+                let class = classes[majority(&totals)];
+
+                DecisionStumpModel { feature: 0, threshold: f64::INFINITY, left: class, right: class }
+            }
+        }
+    }
+}
+
+impl<T> DecisionStumpModel<T> {
+    pub fn feature(&self) -> usize {
+        self.feature
+    }
+
+    /// Rows whose feature is below the threshold get the left class, the others the right.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+}
+
+fn entropy(weights: &[f64], total: f64) -> f64 {
+    if total <= 0. {
+        return 0.;
+    }
+
+    -weights.iter()
+        .filter(|&&w| w > 0.)
+        .map(|&w| w / total * (w / total).log2())
+        .sum::<f64>()
+}
+
+fn majority(weights: &[f64]) -> usize {
+    (0..weights.len()).fold(0, |best, class| if weights[class] > weights[best] { class } else { best })
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr1, arr2};
+    use rune_core::estimator::{Fit, Predictor};
+
+    use crate::stump::DecisionStump;
+
+    #[test]
+    fn test_splits_separable_data() {
+        let x = arr2(&[[5., 0.], [1., 1.], [4., 2.], [2., 3.], [3., 4.], [0., 5.]]);
+        let y = arr1(&['a', 'a', 'b', 'b', 'b', 'b']);
+        let model = DecisionStump::new().fit(x.view(), y.view()).unwrap();
+
+        assert_eq!(model.feature(), 1);
+        assert_eq!(model.threshold(), 1.5);
+        assert_eq!(model.predict(x.view()), y);
+    }
+
+    #[test]
+    fn test_splits_between_adjacent_floats() {
+        let next = f64::from_bits(1f64.to_bits() + 1);
+        let x = arr2(&[[1.], [next]]);
+        let model = DecisionStump::new().fit(x.view(), arr1(&[false, true]).view()).unwrap();
+
+        assert_eq!(model.predict(x.view()), arr1(&[false, true]));
+    }
+
+    #[test]
+    fn test_fit_weighted_follows_the_weights() {
+        // Unweighted, splitting off the first row or the last leaves the same entropy, and the
+        // first is kept; weighting the last row up makes splitting it off the better split.
+        let x = arr2(&[[0.], [1.], [2.], [3.]]);
+        let y = arr1(&[false, true, true, false]);
+
+        let model = DecisionStump::new().fit_weighted(x.view(), y.view(), arr1(&[1., 1., 1., 1.]).view()).unwrap();
+        assert_eq!(model.threshold(), 0.5);
+        assert_eq!(model.predict(x.view()), arr1(&[false, true, true, true]));
+
+        let model = DecisionStump::new().fit_weighted(x.view(), y.view(), arr1(&[1., 1., 1., 5.]).view()).unwrap();
+        assert_eq!(model.threshold(), 2.5);
+        assert_eq!(model.predict(x.view()), arr1(&[true, true, true, false]));
+    }
+
+    #[test]
+    fn test_predicts_the_majority_class_when_every_feature_is_constant() {
+        let x = arr2(&[[1., 2.], [1., 2.], [1., 2.]]);
+        let y = arr1(&[3, 7, 7]);
+        let model = DecisionStump::new().fit(x.view(), y.view()).unwrap();
+
+        assert_eq!(model.threshold(), f64::INFINITY);
+        assert_eq!(model.predict(arr2(&[[0., 0.], [9., 9.]]).view()), arr1(&[7, 7]));
+
+        let weighted = DecisionStump::new().fit_weighted(x.view(), y.view(), arr1(&[5., 1., 1.]).view()).unwrap();
+        assert_eq!(weighted.predict(x.view()), arr1(&[3, 3, 3]));
+    }
+}